@@ -0,0 +1,33 @@
+//! The `Cell` lesson, run: mutating fields of a struct that is not `mut`.
+//!
+//! ```text
+//! cargo run --example cell_basics
+//! ```
+
+use learning_cell::Cell::Immutable;
+
+fn main() {
+    // `a` is not `mut`, yet two of its fields are about to change.
+    let a = Immutable::default();
+    let before = a.snapshot();
+    println!("start:            {before:?}");
+
+    // `set` works for any `T`: it moves the new value in and drops the old one.
+    a.special.set(2);
+    a.special_nocopy.set("bye".to_string());
+    println!("after set:        special = {}", a.special.get());
+
+    // `get` copies the value out, so it only exists for `T: Copy`. For a `String`, `replace`
+    // hands back the old value while putting a new one in, so the cell is never empty.
+    let old = a.special_nocopy.replace("HI!".to_string());
+    println!("replace returned: {old:?}");
+
+    // `take` is `replace` with `Default::default()`: the cell is left with an empty `String`.
+    let taken = a.special_nocopy.take();
+    println!("take returned:    {taken:?}");
+
+    println!("\nwhat changed, through a shared reference only:");
+    for change in a.diff(&before) {
+        println!("  {change}");
+    }
+}
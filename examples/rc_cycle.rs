@@ -0,0 +1,57 @@
+//! The `Rc` chapter, run: shared ownership, a reference cycle that leaks, and `Weak` fixing it.
+//!
+//! ```text
+//! cargo run --example rc_cycle
+//! ```
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use learning_cell::leakcheck::{LeakTracker, Tracked};
+
+/// A node pointing at its neighbour, strongly or weakly.
+struct Node {
+    next: RefCell<Option<Rc<Node>>>,
+    previous: RefCell<Weak<Node>>,
+    _token: Tracked,
+}
+
+fn node(tracker: &LeakTracker) -> Rc<Node> {
+    Rc::new(Node {
+        next: RefCell::new(None),
+        previous: RefCell::new(Weak::new()),
+        _token: tracker.track(),
+    })
+}
+
+fn main() {
+    // Cloning an `Rc` copies a pointer and bumps a counter; the value is shared.
+    let shared = Rc::new(String::from("shared"));
+    let other = Rc::clone(&shared);
+    println!("strong count with two owners: {}", Rc::strong_count(&shared));
+    drop(other);
+    println!("after dropping one:           {}", Rc::strong_count(&shared));
+
+    // Two nodes owning each other keep each other alive: neither count ever reaches zero.
+    let tracker = LeakTracker::new();
+    {
+        let a = node(&tracker);
+        let b = node(&tracker);
+        *a.next.borrow_mut() = Some(Rc::clone(&b));
+        *b.next.borrow_mut() = Some(Rc::clone(&a));
+        println!("\ncycle: a has {} strong references", Rc::strong_count(&a));
+    }
+    println!("after the scope, nodes still alive: {}", tracker.live());
+
+    // With the back edge as a `Weak`, only one direction owns, and both nodes are freed.
+    let tracker = LeakTracker::new();
+    {
+        let a = node(&tracker);
+        let b = node(&tracker);
+        *a.next.borrow_mut() = Some(Rc::clone(&b));
+        *b.previous.borrow_mut() = Rc::downgrade(&a);
+        let previous = b.previous.borrow().upgrade();
+        println!("\nweak back edge: b can still reach a: {}", previous.is_some());
+    }
+    println!("after the scope, nodes still alive: {}", tracker.live());
+}
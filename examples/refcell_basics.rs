@@ -0,0 +1,47 @@
+//! The `RefCell` lesson, run: borrows are checked while the program runs.
+//!
+//! ```text
+//! cargo run --example refcell_basics
+//! ```
+
+use learning_cell::diagnostics;
+use learning_cell::impls::TracedRefCell;
+use learning_cell::RefCell::Immutable;
+
+fn main() {
+    let a = Immutable::default();
+
+    // Any number of shared borrows can be alive at once.
+    {
+        let first = a.special.borrow();
+        let second = a.special.borrow();
+        println!("two shared borrows: {} and {}", *first, *second);
+    }
+
+    // An exclusive borrow gives `&mut` access, even though `a` is not `mut`.
+    a.special_nocopy.borrow_mut().push_str(", world");
+    println!("after borrow_mut:   {:?}", a.special_nocopy.borrow());
+
+    // While a shared borrow is alive, an exclusive one is refused. `borrow_mut` would panic;
+    // `try_borrow_mut` reports it instead.
+    let reader = a.special.borrow();
+    match a.special.try_borrow_mut() {
+        Ok(_) => println!("try_borrow_mut:     granted"),
+        Err(err) => println!("try_borrow_mut:     refused ({err}) while `reader` is alive"),
+    }
+    drop(reader);
+    println!("after drop(reader): {}", a.special.try_borrow_mut().is_ok());
+
+    // The traced version of `RefCell` reports each borrow as it happens.
+    println!("\nthe same borrows, traced:");
+    let cell = TracedRefCell::with_label(42, "special");
+    let ((), events) = diagnostics::record(|| {
+        let reader = cell.borrow();
+        assert!(cell.try_borrow_mut().is_err());
+        drop(reader);
+        *cell.borrow_mut() += 1;
+    });
+    for event in events {
+        println!("  {event}");
+    }
+}
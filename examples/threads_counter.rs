@@ -0,0 +1,59 @@
+//! The threads chapter, run: `Arc` shares across threads, `Mutex` and atomics make it mutable.
+//!
+//! ```text
+//! cargo run --example threads_counter
+//! ```
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use learning_cell::diagnostics;
+use learning_cell::impls::TracedMutex;
+
+const THREADS: usize = 4;
+const INCREMENTS: usize = 1000;
+
+fn main() {
+    // `Rc` and `RefCell` are not thread-safe; their `Sync` counterparts are `Arc` and `Mutex`.
+    let counter = Arc::new(Mutex::new(0));
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || {
+                for _ in 0..INCREMENTS {
+                    *counter.lock().unwrap() += 1;
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!("Mutex counter:  {} (expected {})", counter.lock().unwrap(), THREADS * INCREMENTS);
+
+    // For a plain number, an atomic does the same without a lock.
+    let atomic = Arc::new(AtomicUsize::new(0));
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..INCREMENTS {
+                    atomic.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    println!("atomic counter: {}", atomic.load(Ordering::Relaxed));
+
+    // A traced mutex shows that each lock is held for a moment, then released.
+    println!("\ntwo locks of a traced mutex:");
+    let traced = TracedMutex::with_label(0, "counter");
+    let ((), events) = diagnostics::record(|| {
+        for _ in 0..2 {
+            *traced.lock().unwrap() += 1;
+        }
+    });
+    for event in events {
+        println!("  {event}");
+    }
+}
@@ -0,0 +1,47 @@
+//! The `UnsafeCell` chapter, run: a `Cell` of our own, built on the primitive under all of them.
+//!
+//! ```text
+//! cargo run --example unsafe_cell
+//! cargo +nightly miri run --example unsafe_cell
+//! ```
+//! Miri checks the `unsafe` blocks for undefined behavior while the example runs.
+
+use std::cell::UnsafeCell;
+
+/// A minimal `Cell`: values go in and out whole, and no reference to the inside escapes.
+struct TinyCell<T> {
+    value: UnsafeCell<T>,
+}
+
+impl<T> TinyCell<T> {
+    fn new(value: T) -> Self {
+        Self { value: UnsafeCell::new(value) }
+    }
+
+    fn replace(&self, value: T) -> T {
+        // SAFETY: `TinyCell` is not `Sync` (because `UnsafeCell` is not), so no other thread can
+        // access the value, and no reference into it exists while we write.
+        unsafe { std::mem::replace(&mut *self.value.get(), value) }
+    }
+}
+
+impl<T: Copy> TinyCell<T> {
+    fn get(&self) -> T {
+        // SAFETY: as in `replace`; we only copy the value out.
+        unsafe { *self.value.get() }
+    }
+}
+
+fn main() {
+    let cell = TinyCell::new(1);
+    let shared = &cell;
+    let old = shared.replace(2);
+    println!("replaced {old} through a shared reference, now {}", cell.get());
+
+    let text = TinyCell::new(String::from("hi"));
+    println!("replace hands back the old String: {:?}", text.replace(String::from("bye")));
+
+    // Handing out `&T` to the inside would be unsound: a later `replace` would drop the value
+    // the reference points to. That is why `Cell` has no `borrow`, and why `RefCell` counts.
+    println!("\nsee the my_cell and my_refcell exercises to build the real thing");
+}
@@ -0,0 +1,14 @@
+//! Runs arbitrary operations on the borrow simulator and on a real `RefCell`, and fails as soon
+//! as they disagree. Run with `cargo +nightly fuzz run simulator` from the repository root.
+
+#![no_main]
+
+use learning_cell::simulator::{self, Op};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    let ops = Op::decode(bytes);
+    if let Err(divergence) = simulator::compare_with_std(&ops) {
+        panic!("{divergence}\noperations: {ops:?}");
+    }
+});
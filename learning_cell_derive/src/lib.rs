@@ -0,0 +1,337 @@
+//! Derive macros for the `learning_cell` course, re-exported by it behind the `derive` feature.
+//!
+//! The struct is read with `syn` and the twin written with `quote`. Mistakes in the input are
+//! reported as errors pointing at them, not as panics of the derive.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, Ident, Type, Visibility,
+};
+
+/// Generates `<Name>Shared`, a twin of a plain struct whose fields are wrapped in cells, so
+/// they can change through a shared reference.
+///
+/// Each field chooses how it is wrapped with a `#[shared(...)]` attribute:
+/// - `cell`: a `Cell<T>`, with a getter copying the value out (so `T` must be `Copy`) and a
+///   `set_<field>` setter.
+/// - `refcell` (the default): a `RefCell<T>`, with a getter returning a `Ref<'_, T>`, a
+///   `<field>_mut` returning a `RefMut<'_, T>` and a `set_<field>` setter.
+/// - `atomic`: the atomic type of `T` (see `learning_cell::util::AtomicValue`), with a getter
+///   and a `set_<field>` setter.
+/// - `plain`: `T` itself, with no accessors.
+///
+/// The twin converts from and into the plain struct, with `new` and `into_inner` or with `From`.
+/// Other attributes of the fields, like doc comments, are copied over.
+/// ```
+/// use learning_cell::InteriorMutable;
+///
+/// #[derive(InteriorMutable)]
+/// struct Player {
+///     #[shared(plain)]
+///     name: String,
+///     #[shared(cell)]
+///     health: i32,
+///     inventory: Vec<String>,
+///     #[shared(atomic)]
+///     score: u64,
+/// }
+///
+/// let player = PlayerShared::new(Player {
+///     name: "ferris".to_string(),
+///     health: 10,
+///     inventory: Vec::new(),
+///     score: 0,
+/// });
+/// player.set_health(player.health() - 3);
+/// player.inventory_mut().push("sword".to_string());
+/// player.set_score(player.score() + 100);
+///
+/// let player = player.into_inner();
+/// assert_eq!((player.health, player.score), (7, 100));
+/// assert_eq!(player.inventory, ["sword"]);
+/// ```
+#[proc_macro_derive(InteriorMutable, attributes(shared))]
+pub fn derive_interior_mutable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match Struct::parse(input) {
+        Ok(item) => item.shared_twin().into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// How a field of the twin wraps the field of the plain struct.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Cell,
+    RefCell,
+    Atomic,
+    Plain,
+}
+
+impl Kind {
+    fn parse(ident: &Ident) -> syn::Result<Kind> {
+        match ident.to_string().as_str() {
+            "cell" => Ok(Kind::Cell),
+            "refcell" => Ok(Kind::RefCell),
+            "atomic" => Ok(Kind::Atomic),
+            "plain" => Ok(Kind::Plain),
+            other => Err(Error::new(
+                ident.span(),
+                format!(
+                    "unknown cell type `{other}`, expected `cell`, `refcell`, `atomic` or `plain`"
+                ),
+            )),
+        }
+    }
+}
+
+struct Field {
+    /// The attributes to copy over, such as doc comments.
+    attributes: Vec<Attribute>,
+    visibility: Visibility,
+    name: Ident,
+    /// The type, whose tokens keep their spans so errors about it point at the field.
+    ty: Type,
+    kind: Kind,
+}
+
+struct Struct {
+    visibility: Visibility,
+    name: Ident,
+    fields: Vec<Field>,
+}
+
+impl Struct {
+    fn parse(input: DeriveInput) -> syn::Result<Struct> {
+        let fields = match input.data {
+            Data::Struct(data) => data.fields,
+            Data::Enum(data) => {
+                return Err(Error::new(
+                    data.enum_token.span,
+                    "`InteriorMutable` can only be derived for structs",
+                ))
+            }
+            Data::Union(data) => {
+                return Err(Error::new(
+                    data.union_token.span,
+                    "`InteriorMutable` can only be derived for structs",
+                ))
+            }
+        };
+        let generics = &input.generics;
+        if !generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                generics,
+                "`InteriorMutable` does not support generic structs",
+            ));
+        }
+        if let Some(clause) = &generics.where_clause {
+            return Err(Error::new_spanned(
+                clause,
+                "`InteriorMutable` does not support generic structs",
+            ));
+        }
+        let fields = match fields {
+            Fields::Named(fields) => fields.named,
+            Fields::Unnamed(fields) => {
+                return Err(Error::new_spanned(
+                    fields,
+                    "`InteriorMutable` needs a struct with named fields",
+                ))
+            }
+            Fields::Unit => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "`InteriorMutable` needs a struct with named fields",
+                ))
+            }
+        };
+        Ok(Struct {
+            visibility: input.vis,
+            name: input.ident,
+            fields: fields.into_iter().map(Field::parse).collect::<syn::Result<_>>()?,
+        })
+    }
+
+    /// The twin struct, its accessors and the conversions to and from the plain struct.
+    fn shared_twin(&self) -> TokenStream {
+        let Struct { visibility, name, fields } = self;
+        let twin = format_ident!("{}Shared", name, span = name.span());
+        let doc = format!("A [`{name}`] whose fields can change through a shared reference.");
+        let definitions = fields.iter().map(Field::definition);
+        let wrap = fields.iter().map(Field::wrap);
+        let unwrap = fields.iter().map(Field::unwrap);
+        let accessors = fields.iter().map(|field| field.accessors(visibility));
+        quote! {
+            #[doc = #doc]
+            #visibility struct #twin {
+                #(#definitions,)*
+            }
+
+            #[allow(dead_code)]
+            impl #twin {
+                /// Wraps every field of `plain`.
+                #visibility fn new(plain: #name) -> Self {
+                    Self { #(#wrap,)* }
+                }
+
+                /// Unwraps every field, giving back the plain struct.
+                #visibility fn into_inner(self) -> #name {
+                    #name { #(#unwrap,)* }
+                }
+
+                #(#accessors)*
+            }
+
+            impl ::std::convert::From<#name> for #twin {
+                fn from(plain: #name) -> Self {
+                    Self::new(plain)
+                }
+            }
+
+            impl ::std::convert::From<#twin> for #name {
+                fn from(shared: #twin) -> Self {
+                    shared.into_inner()
+                }
+            }
+        }
+    }
+}
+
+impl Field {
+    fn parse(field: syn::Field) -> syn::Result<Field> {
+        let mut kind = None;
+        let mut attributes = Vec::new();
+        for attribute in field.attrs {
+            if !attribute.path().is_ident("shared") {
+                attributes.push(attribute);
+                continue;
+            }
+            if kind.is_some() {
+                return Err(Error::new_spanned(attribute.path(), "duplicate `shared` attribute"));
+            }
+            kind = Some(Field::parse_kind(&attribute)?);
+        }
+        Ok(Field {
+            attributes,
+            visibility: field.vis,
+            name: field.ident.expect("the fields are named"),
+            ty: field.ty,
+            kind: kind.unwrap_or(Kind::RefCell),
+        })
+    }
+
+    /// Reads the `(kind)` of a `#[shared(kind)]` attribute.
+    fn parse_kind(attribute: &Attribute) -> syn::Result<Kind> {
+        let kind: Ident = attribute.parse_args().map_err(|_| {
+            Error::new_spanned(
+                attribute,
+                "expected `#[shared(cell)]`, `#[shared(refcell)]`, `#[shared(atomic)]` or \
+                 `#[shared(plain)]`",
+            )
+        })?;
+        Kind::parse(&kind)
+    }
+
+    /// The field of the twin.
+    fn definition(&self) -> TokenStream {
+        let Field { attributes, visibility, name, ty, kind } = self;
+        let wrapped = match kind {
+            Kind::Cell => quote!(::std::cell::Cell<#ty>),
+            Kind::RefCell => quote!(::std::cell::RefCell<#ty>),
+            Kind::Atomic => quote!(<#ty as ::learning_cell::util::AtomicValue>::Atomic),
+            Kind::Plain => quote!(#ty),
+        };
+        quote!(#(#attributes)* #visibility #name: #wrapped)
+    }
+
+    /// The initializer of the twin's field from `plain`.
+    fn wrap(&self) -> TokenStream {
+        let name = &self.name;
+        match self.kind {
+            Kind::Cell => quote!(#name: ::std::cell::Cell::new(plain.#name)),
+            Kind::RefCell => quote!(#name: ::std::cell::RefCell::new(plain.#name)),
+            Kind::Atomic => {
+                quote!(#name: ::learning_cell::util::AtomicValue::into_atomic(plain.#name))
+            }
+            Kind::Plain => quote!(#name: plain.#name),
+        }
+    }
+
+    /// The initializer of the plain struct's field from the twin's.
+    fn unwrap(&self) -> TokenStream {
+        let name = &self.name;
+        match self.kind {
+            Kind::Cell | Kind::RefCell | Kind::Atomic => quote!(#name: self.#name.into_inner()),
+            Kind::Plain => quote!(#name: self.#name),
+        }
+    }
+
+    /// The getter and setters of the field.
+    fn accessors(&self, visibility: &Visibility) -> TokenStream {
+        let Field { name, ty, .. } = self;
+        let name_mut = format_ident!("{}_mut", name);
+        let set = format_ident!("set_{}", name);
+        match self.kind {
+            Kind::Cell => {
+                let get_doc = format!("Copies `{name}` out of its cell.");
+                let set_doc = format!("Overwrites `{name}`.");
+                // The bound sits on the getter, so a non-`Copy` field is reported at its type.
+                let copy = quote_spanned!(ty.span()=> ::std::marker::Copy);
+                quote! {
+                    #[doc = #get_doc]
+                    #visibility fn #name(&self) -> #ty where #ty: #copy {
+                        self.#name.get()
+                    }
+
+                    #[doc = #set_doc]
+                    #visibility fn #set(&self, value: #ty) {
+                        self.#name.set(value);
+                    }
+                }
+            }
+            Kind::RefCell => {
+                let get_doc =
+                    format!("Borrows `{name}`.\n\nPanics if it is currently mutably borrowed.");
+                let mut_doc =
+                    format!("Mutably borrows `{name}`.\n\nPanics if it is currently borrowed.");
+                let set_doc =
+                    format!("Overwrites `{name}`.\n\nPanics if it is currently borrowed.");
+                quote! {
+                    #[doc = #get_doc]
+                    #visibility fn #name(&self) -> ::std::cell::Ref<'_, #ty> {
+                        self.#name.borrow()
+                    }
+
+                    #[doc = #mut_doc]
+                    #visibility fn #name_mut(&self) -> ::std::cell::RefMut<'_, #ty> {
+                        self.#name.borrow_mut()
+                    }
+
+                    #[doc = #set_doc]
+                    #visibility fn #set(&self, value: #ty) {
+                        self.#name.replace(value);
+                    }
+                }
+            }
+            Kind::Atomic => {
+                let get_doc = format!("Reads `{name}`.");
+                let set_doc = format!("Overwrites `{name}`.");
+                quote! {
+                    #[doc = #get_doc]
+                    #visibility fn #name(&self) -> #ty {
+                        ::learning_cell::util::AtomicValue::load(&self.#name)
+                    }
+
+                    #[doc = #set_doc]
+                    #visibility fn #set(&self, value: #ty) {
+                        ::learning_cell::util::AtomicValue::store(&self.#name, value);
+                    }
+                }
+            }
+            Kind::Plain => TokenStream::new(),
+        }
+    }
+}
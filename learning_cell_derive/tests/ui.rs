@@ -0,0 +1,10 @@
+//! Misuses of `#[derive(InteriorMutable)]`, with the compiler's diagnostics pinned by trybuild.
+//!
+//! Every `tests/ui/<name>.rs` is a program the derive should reject, and `tests/ui/<name>.stderr`
+//! what the compiler reports for it. Run with `TRYBUILD=overwrite` to write the files anew, and
+//! check the diff still points the user at the mistake.
+
+#[test]
+fn diagnostics_match_the_pinned_output() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
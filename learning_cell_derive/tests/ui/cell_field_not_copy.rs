@@ -0,0 +1,9 @@
+use learning_cell::InteriorMutable;
+
+#[derive(InteriorMutable)]
+struct Profile {
+    #[shared(cell)]
+    name: String,
+}
+
+fn main() {}
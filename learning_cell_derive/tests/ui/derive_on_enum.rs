@@ -0,0 +1,9 @@
+use learning_cell::InteriorMutable;
+
+#[derive(InteriorMutable)]
+enum Light {
+    On,
+    Off,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use learning_cell::InteriorMutable;
+
+#[derive(InteriorMutable)]
+struct Slot<T> {
+    value: T,
+}
+
+fn main() {}
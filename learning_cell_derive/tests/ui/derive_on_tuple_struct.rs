@@ -0,0 +1,6 @@
+use learning_cell::InteriorMutable;
+
+#[derive(InteriorMutable)]
+struct Meters(u32);
+
+fn main() {}
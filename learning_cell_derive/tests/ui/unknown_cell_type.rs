@@ -0,0 +1,9 @@
+use learning_cell::InteriorMutable;
+
+#[derive(InteriorMutable)]
+struct Counter {
+    #[shared(mutex)]
+    count: u32,
+}
+
+fn main() {}
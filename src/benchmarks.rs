@@ -0,0 +1,141 @@
+//! What the cells cost.
+//!
+//! Every check a cell does at run time (a borrow flag, a lock, an atomic instruction) has a
+//! price. The benchmarks here do the same small job through each type, so the numbers can be
+//! compared within a group: the first benchmark of a group is its baseline, usually the plain,
+//! unchecked version. They use the simple [`util::bench`](crate::util::bench) harness, which is
+//! fine for differences of a few times, not for a few percent.
+//! ```
+//! use learning_cell::benchmarks;
+//!
+//! for benchmark in benchmarks::all().iter().filter(|b| b.group == "read") {
+//!     let measurement = benchmark.run(1_000);
+//!     println!("{}: {:?}/op", benchmark.name, measurement.per_iteration());
+//! }
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::hint::black_box;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::util::{bench, Measurement};
+
+/// One operation measured through one type.
+#[derive(Debug, Clone, Copy)]
+pub struct Benchmark {
+    /// The job, shared by every benchmark it can be compared with.
+    pub group: &'static str,
+    /// The type doing the job, e.g. `RefCell::borrow`.
+    pub name: &'static str,
+    run: fn(u32) -> Measurement,
+}
+
+impl Benchmark {
+    /// Runs the benchmark `iterations` times.
+    pub fn run(&self, iterations: u32) -> Measurement {
+        (self.run)(iterations)
+    }
+}
+
+/// Every benchmark, grouped, with the baseline first in each group.
+pub fn all() -> &'static [Benchmark] {
+    &[
+        Benchmark { group: "read", name: "&i32", run: read_plain },
+        Benchmark { group: "read", name: "Cell::get", run: read_cell },
+        Benchmark { group: "read", name: "RefCell::borrow", run: read_refcell },
+        Benchmark { group: "read", name: "AtomicI32::load", run: read_atomic },
+        Benchmark { group: "read", name: "Mutex::lock", run: read_mutex },
+        Benchmark { group: "read", name: "RwLock::read", run: read_rwlock },
+        Benchmark { group: "increment", name: "&mut i32", run: increment_plain },
+        Benchmark { group: "increment", name: "Cell::set", run: increment_cell },
+        Benchmark { group: "increment", name: "RefCell::borrow_mut", run: increment_refcell },
+        Benchmark { group: "increment", name: "AtomicI32::fetch_add", run: increment_atomic },
+        Benchmark { group: "increment", name: "Mutex::lock", run: increment_mutex },
+        Benchmark { group: "increment", name: "RwLock::write", run: increment_rwlock },
+        Benchmark { group: "share", name: "&T", run: share_reference },
+        Benchmark { group: "share", name: "Rc::clone", run: share_rc },
+        Benchmark { group: "share", name: "Arc::clone", run: share_arc },
+    ]
+}
+
+// `black_box` around the receiver keeps the optimizer from hoisting the work out of the loop.
+
+fn read_plain(iterations: u32) -> Measurement {
+    let value = 42;
+    bench(iterations, || *black_box(&value))
+}
+
+fn read_cell(iterations: u32) -> Measurement {
+    let cell = Cell::new(42);
+    bench(iterations, || black_box(&cell).get())
+}
+
+fn read_refcell(iterations: u32) -> Measurement {
+    let cell = RefCell::new(42);
+    bench(iterations, || *black_box(&cell).borrow())
+}
+
+fn read_atomic(iterations: u32) -> Measurement {
+    let atomic = AtomicI32::new(42);
+    bench(iterations, || black_box(&atomic).load(Ordering::SeqCst))
+}
+
+fn read_mutex(iterations: u32) -> Measurement {
+    let mutex = Mutex::new(42);
+    bench(iterations, || *black_box(&mutex).lock().unwrap())
+}
+
+fn read_rwlock(iterations: u32) -> Measurement {
+    let lock = RwLock::new(42);
+    bench(iterations, || *black_box(&lock).read().unwrap())
+}
+
+fn increment_plain(iterations: u32) -> Measurement {
+    let mut value = 0i32;
+    bench(iterations, || *black_box(&mut value) += 1)
+}
+
+fn increment_cell(iterations: u32) -> Measurement {
+    let cell = Cell::new(0i32);
+    bench(iterations, || {
+        let cell = black_box(&cell);
+        cell.set(cell.get() + 1)
+    })
+}
+
+fn increment_refcell(iterations: u32) -> Measurement {
+    let cell = RefCell::new(0i32);
+    bench(iterations, || *black_box(&cell).borrow_mut() += 1)
+}
+
+fn increment_atomic(iterations: u32) -> Measurement {
+    let atomic = AtomicI32::new(0);
+    bench(iterations, || black_box(&atomic).fetch_add(1, Ordering::SeqCst))
+}
+
+fn increment_mutex(iterations: u32) -> Measurement {
+    let mutex = Mutex::new(0i32);
+    bench(iterations, || *black_box(&mutex).lock().unwrap() += 1)
+}
+
+fn increment_rwlock(iterations: u32) -> Measurement {
+    let lock = RwLock::new(0i32);
+    bench(iterations, || *black_box(&lock).write().unwrap() += 1)
+}
+
+fn share_reference(iterations: u32) -> Measurement {
+    let value = String::from("shared");
+    bench(iterations, || black_box(&value).len())
+}
+
+fn share_rc(iterations: u32) -> Measurement {
+    let rc = Rc::new(String::from("shared"));
+    bench(iterations, || Rc::clone(black_box(&rc)).len())
+}
+
+fn share_arc(iterations: u32) -> Measurement {
+    let arc = Arc::new(String::from("shared"));
+    bench(iterations, || Arc::clone(black_box(&arc)).len())
+}
@@ -0,0 +1,326 @@
+//! `learning_cell completions <shell>`: tab completion for bash, zsh and fish.
+//!
+//! The generated scripts complete command names and flags from [`COMMANDS`]. Lesson, exercise
+//! and type names are not baked in: the scripts ask the binary for them with the hidden
+//! `__complete <kind>` command, so registered exercises, and the course of a manifest named by
+//! `$LEARNING_CELL_MANIFEST`, show up without regenerating anything.
+//!
+//! ```text
+//! learning_cell completions bash > ~/.local/share/bash-completion/completions/learning_cell
+//! learning_cell completions zsh > ~/.zfunc/_learning_cell
+//! learning_cell completions fish > ~/.config/fish/completions/learning_cell.fish
+//! ```
+
+use std::collections::HashSet;
+use std::process::ExitCode;
+
+use learning_cell::comparison;
+use learning_cell::curriculum::Chapter;
+use learning_cell::{exercises, lessons};
+
+use super::{Args, Result};
+
+/// What the positional arguments of a command are.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    None,
+    Lesson,
+    Exercise,
+    Type,
+    Shell,
+    Directory,
+}
+
+impl Operand {
+    /// The argument of `__complete` listing the candidates, for the dynamic kinds.
+    fn kind(self) -> Option<&'static str> {
+        match self {
+            Operand::Lesson => Some("lessons"),
+            Operand::Exercise => Some("exercises"),
+            Operand::Type => Some("types"),
+            Operand::Shell => Some("shells"),
+            Operand::None | Operand::Directory => None,
+        }
+    }
+}
+
+struct Command {
+    name: &'static str,
+    about: &'static str,
+    flags: &'static [&'static str],
+    operand: Operand,
+}
+
+/// Every command of `USAGE`, in the same order.
+const COMMANDS: &[Command] = &[
+    Command { name: "run", about: "read a lesson", flags: &[], operand: Operand::Lesson },
+    Command {
+        name: "try",
+        about: "edit and re-run an example",
+        flags: &["--no-edit"],
+        operand: Operand::Lesson,
+    },
+    Command {
+        name: "list",
+        about: "list the lessons and exercises",
+        flags: &["--topic", "--status"],
+        operand: Operand::None,
+    },
+    Command {
+        name: "verify",
+        about: "run the hidden tests",
+        flags: &[],
+        operand: Operand::Exercise,
+    },
+    Command { name: "watch", about: "re-run tests on save", flags: &[], operand: Operand::None },
+    Command {
+        name: "next",
+        about: "recommend what to do next",
+        flags: &["--open"],
+        operand: Operand::None,
+    },
+    Command {
+        name: "reset",
+        about: "restore exercise scaffolds",
+        flags: &["--all", "--yes"],
+        operand: Operand::Exercise,
+    },
+    Command {
+        name: "hint",
+        about: "reveal the next hint",
+        flags: &["--level"],
+        operand: Operand::Exercise,
+    },
+    Command {
+        name: "quiz",
+        about: "answer a quiz",
+        flags: &["--topic", "--count", "--seed"],
+        operand: Operand::None,
+    },
+    Command {
+        name: "search",
+        about: "search the course",
+        flags: &["--limit"],
+        operand: Operand::None,
+    },
+    Command { name: "explain", about: "print a cheat sheet", flags: &[], operand: Operand::Type },
+    Command {
+        name: "bench",
+        about: "time each cell type",
+        flags: &["--filter", "--iterations"],
+        operand: Operand::None,
+    },
+    Command {
+        name: "simulate",
+        about: "borrow a virtual RefCell",
+        flags: &[],
+        operand: Operand::None,
+    },
+    Command {
+        name: "demo",
+        about: "run the examples of a lesson",
+        flags: &[],
+        operand: Operand::Lesson,
+    },
+    Command {
+        name: "diagram",
+        about: "draw the borrows of a lesson",
+        flags: &["--format", "--output"],
+        operand: Operand::Lesson,
+    },
+    Command {
+        name: "export",
+        about: "publish the course",
+        flags: &["--format", "--lesson"],
+        operand: Operand::Directory,
+    },
+    Command {
+        name: "progress",
+        about: "export or import your progress",
+        flags: &["--learner", "--yes"],
+        operand: Operand::None,
+    },
+    Command { name: "tui", about: "browse full-screen", flags: &[], operand: Operand::None },
+    Command {
+        name: "stats",
+        about: "show a progress dashboard",
+        flags: &[],
+        operand: Operand::None,
+    },
+    Command {
+        name: "certificate",
+        about: "write a completion certificate",
+        flags: &["--learner", "--output"],
+        operand: Operand::None,
+    },
+    Command { name: "doctor", about: "check optional tools", flags: &[], operand: Operand::None },
+    Command {
+        name: "completions",
+        about: "print a completion script",
+        flags: &[],
+        operand: Operand::Shell,
+    },
+    Command { name: "help", about: "show the usage", flags: &[], operand: Operand::None },
+];
+
+const SHELLS: [&str; 3] = ["bash", "zsh", "fish"];
+
+/// `completions <shell>`: prints the completion script for `shell`.
+pub fn completions(args: &mut Args) -> Result<ExitCode> {
+    let shell = args.positional().ok_or("missing shell, use `bash`, `zsh` or `fish`")?;
+    args.finish()?;
+    let script = match shell.as_str() {
+        "bash" => bash(),
+        "zsh" => zsh(),
+        "fish" => fish(),
+        other => return Err(format!("unknown shell `{other}`, use `bash`, `zsh` or `fish`").into()),
+    };
+    print!("{script}");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `__complete <kind>`: prints the candidates of a dynamic operand, one per line.
+pub fn complete(args: &mut Args) -> Result<ExitCode> {
+    let kind = args.positional().ok_or("missing kind")?;
+    args.finish()?;
+    let mut seen = HashSet::new();
+    let candidates: Vec<&str> = match kind.as_str() {
+        "lessons" => Chapter::ALL
+            .iter()
+            .map(|chapter| chapter.id())
+            .chain(lessons::all().into_iter().map(|lesson| lesson.id()))
+            .filter(|id| seen.insert(*id))
+            .collect(),
+        "exercises" => exercises::all().iter().map(|exercise| exercise.id()).collect(),
+        "types" => comparison::TYPES.iter().map(|info| info.name).collect(),
+        "shells" => SHELLS.to_vec(),
+        other => return Err(format!("unknown kind `{other}`").into()),
+    };
+    for candidate in candidates {
+        println!("{candidate}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn bash() -> String {
+    let names: Vec<_> = COMMANDS.iter().map(|command| command.name).collect();
+    let mut flags = String::new();
+    let mut operands = String::new();
+    for command in COMMANDS {
+        if !command.flags.is_empty() {
+            flags.push_str(&format!(
+                "        {}) flags=\"{}\" ;;\n",
+                command.name,
+                command.flags.join(" ")
+            ));
+        }
+        match (command.operand, command.operand.kind()) {
+            (_, Some(kind)) => operands.push_str(&format!(
+                "        {}) COMPREPLY=($(compgen -W \"$(\"${{COMP_WORDS[0]}}\" __complete {kind} 2>/dev/null)\" -- \"$cur\")) ;;\n",
+                command.name
+            )),
+            (Operand::Directory, None) => operands.push_str(&format!(
+                "        {}) COMPREPLY=($(compgen -d -- \"$cur\")) ;;\n",
+                command.name
+            )),
+            _ => {}
+        }
+    }
+    format!(
+        "# bash completion for learning_cell
+_learning_cell() {{
+    local cur=${{COMP_WORDS[COMP_CWORD]}}
+    if [ \"$COMP_CWORD\" -eq 1 ]; then
+        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))
+        return
+    fi
+    if [[ $cur == -* ]]; then
+        local flags=\"\"
+        case ${{COMP_WORDS[1]}} in
+{flags}        esac
+        COMPREPLY=($(compgen -W \"$flags\" -- \"$cur\"))
+        return
+    fi
+    case ${{COMP_WORDS[1]}} in
+{operands}    esac
+}}
+complete -F _learning_cell learning_cell
+",
+        names.join(" ")
+    )
+}
+
+fn zsh() -> String {
+    let mut descriptions = String::new();
+    let mut flags = String::new();
+    let mut operands = String::new();
+    for command in COMMANDS {
+        descriptions.push_str(&format!("        '{}:{}'\n", command.name, command.about));
+        if !command.flags.is_empty() {
+            flags.push_str(&format!(
+                "        {}) compadd -- {} ;;\n",
+                command.name,
+                command.flags.join(" ")
+            ));
+        }
+        match (command.operand, command.operand.kind()) {
+            (_, Some(kind)) => operands.push_str(&format!(
+                "        {}) compadd -- ${{(f)\"$($words[1] __complete {kind} 2>/dev/null)\"}} ;;\n",
+                command.name
+            )),
+            (Operand::Directory, None) => {
+                operands.push_str(&format!("        {}) _files -/ ;;\n", command.name))
+            }
+            _ => {}
+        }
+    }
+    format!(
+        "#compdef learning_cell
+_learning_cell() {{
+    local -a commands
+    commands=(
+{descriptions}    )
+    if (( CURRENT == 2 )); then
+        _describe command commands
+        return
+    fi
+    if [[ $words[CURRENT] == -* ]]; then
+        case $words[2] in
+{flags}        esac
+        return
+    fi
+    case $words[2] in
+{operands}    esac
+}}
+compdef _learning_cell learning_cell
+"
+    )
+}
+
+fn fish() -> String {
+    let mut script =
+        String::from("# fish completion for learning_cell\ncomplete -c learning_cell -f\n");
+    for command in COMMANDS {
+        script.push_str(&format!(
+            "complete -c learning_cell -n __fish_use_subcommand -a {} -d '{}'\n",
+            command.name, command.about
+        ));
+        let condition = format!("__fish_seen_subcommand_from {}", command.name);
+        for flag in command.flags {
+            script.push_str(&format!(
+                "complete -c learning_cell -n '{condition}' -l {}\n",
+                flag.trim_start_matches("--")
+            ));
+        }
+        match (command.operand, command.operand.kind()) {
+            (_, Some(kind)) => script.push_str(&format!(
+                "complete -c learning_cell -n '{condition}' -a '(learning_cell __complete {kind} 2>/dev/null)'\n"
+            )),
+            (Operand::Directory, None) => script.push_str(&format!(
+                "complete -c learning_cell -n '{condition}' -a '(__fish_complete_directories)'\n"
+            )),
+            _ => {}
+        }
+    }
+    script
+}
@@ -0,0 +1,1539 @@
+//! The `learning_cell` command line: the course, in a terminal.
+//!
+//! ```text
+//! learning_cell run <lesson>      read a lesson, run its demos and step through its examples
+//! learning_cell try <lesson> <n>  edit and re-run an example of a lesson
+//! learning_cell list              list the lessons and exercises
+//! learning_cell verify            run every exercise's hidden tests
+//! learning_cell hint <exercise>   reveal the next hint of an exercise
+//! learning_cell watch             re-run an exercise whenever its file is saved
+//! learning_cell next              recommend what to do next
+//! learning_cell reset <exercise>  start an exercise over
+//! learning_cell quiz              answer a quiz and see why each answer is right
+//! learning_cell search <query>    find where something is explained
+//! learning_cell explain <type>    print a cheat sheet for RefCell, Mutex, ...
+//! learning_cell bench             measure what each cell costs on this machine
+//! learning_cell simulate          borrow a virtual RefCell step by step
+//! learning_cell demo <lesson>     run a lesson's examples and show what each call did
+//! learning_cell diagram <lesson>  draw the borrows of a lesson's demo
+//! learning_cell export <dir>      publish the course as an mdBook, HTML or for rustlings
+//! learning_cell progress export   save your progress to a file for another machine or grading
+//! learning_cell tui               browse the course full-screen (`tui` feature)
+//! learning_cell stats             show a dashboard of your progress
+//! learning_cell certificate       write a completion certificate once the core chapters are done
+//! learning_cell doctor            check which optional tools and lessons work on this machine
+//! learning_cell completions <sh>  print tab completions for bash, zsh or fish
+//! ```
+//!
+//! `list`, `verify` and `run` accept a global `--json` flag that replaces their output with a
+//! single JSON document, for editors and grading scripts. A global `--lang <locale>` (or the
+//! `LEARNING_CELL_LANG` environment variable) shows the lessons in another
+//! [language](learning_cell::locale), and a global `--manifest <path>` (or the
+//! `LEARNING_CELL_MANIFEST` environment variable) replaces the course with the one described by
+//! an [exercise manifest](learning_cell::manifest).
+//!
+//! The exercises are compiled into the binary, so run it through cargo (`cargo run -- verify`)
+//! to pick up your latest edits.
+//!
+//! Arguments are parsed by hand (see [`Args`]) to keep the crate's dependencies few.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use learning_cell::benchmarks::{self, Benchmark};
+use learning_cell::certificate::{self, Certificate};
+use learning_cell::curriculum::{self, Chapter, Difficulty, Item};
+use learning_cell::demo::DemoOutcome;
+use learning_cell::diagnostics::BorrowKind;
+use learning_cell::diagram::{self, Format};
+use learning_cell::error::{Context, LearningCellError};
+use learning_cell::exercises::Report;
+use learning_cell::info::{size_report, Wrapper};
+use learning_cell::json::Json;
+use learning_cell::lessons::{self, Block, ExampleKind, LessonMeta, Page};
+use learning_cell::manifest::{self, Registry};
+use learning_cell::progress::{Badge, Export, Progress};
+use learning_cell::quiz::{self, option_letter};
+use learning_cell::runner::{Outcome, Runner};
+use learning_cell::simulator::{BorrowSimulator, Op, SimError, State};
+use learning_cell::{comparison, exercises, export, locale, search, snippets};
+
+mod completions;
+#[cfg(feature = "tui")]
+mod tui;
+
+type Result<T, E = LearningCellError> = std::result::Result<T, E>;
+
+const USAGE: &str = "\
+usage: learning_cell [--json] [--lang <locale>] [--manifest <path>] <command> [<args>]
+
+commands:
+    run <lesson>    read a lesson, run its demos and step through its examples
+    try [--no-edit] <lesson> <example>
+                    open example number <example> of a lesson in $EDITOR, then build and run
+                    it and show the compiler errors or the panic
+    list [--topic <chapter>] [--status todo|done]
+                    list the lessons and exercises
+    verify [<exercise>...]
+                    run the hidden tests of every (or the given) exercise
+    watch           re-run an exercise's tests whenever its file is saved
+    next [--open]   recommend what to do next (and start the lesson or open the exercise)
+    reset (<exercise>... | --all) [--yes]
+                    restore exercise scaffolds and forget their progress
+    hint <exercise> [--level <n>]
+                    reveal the next hint (or the first <n> hints) of an exercise
+    quiz [--topic <chapter>] [--count <n>] [--seed <n>]
+                    answer randomized questions, then see the explanations; the same seed
+                    gives the same quiz
+    search [--limit <n>] <query>...
+                    find the lesson paragraphs, examples and exercises mentioning every word
+    explain <type>  print a cheat sheet for a type: Cell, RefCell, Rc, Mutex, ...
+    bench [--filter <name>] [--iterations <n>]
+                    time the same operations through each cell type
+    simulate        type `borrow`, `borrow_mut`, `drop <n>`, `replace`, ... against a virtual
+                    RefCell and watch its borrow flag
+    demo <lesson>   run the operations of a lesson's examples and print what each returned and
+                    which fields it changed
+    diagram [--format mermaid|dot] [--output <file>] <lesson>
+                    write a sequence diagram of the borrows in a lesson's demo
+    export [--format mdbook|html|rustlings] <dir>
+                    write the course as an mdBook source tree, as HTML handouts or as a
+                    rustlings exercise set
+    export --lesson <lesson> [<file>]
+                    write one lesson as Markdown to <file>, or print it
+    progress export [--learner <name>] <file>
+                    save your progress to <file>, to import elsewhere or hand in
+    progress import [--yes] <file>
+                    replace your progress with the one exported to <file>
+    tui             browse the course full-screen (needs the `tui` feature)
+    stats           show chapters completed, time spent, exercises by difficulty, badges and
+                    your weakest quiz topics
+    certificate [--learner <name>] [--output <name>]
+                    once the core chapters are complete, write <name>.json and <name>.txt
+                    (default `certificate`) summarizing your progress for an instructor
+    doctor          check for Miri, nightly, cargo-fuzz and optional features, and show
+                    which advanced exercises can run here
+    completions bash|zsh|fish
+                    print a tab completion script for the shell
+    help            show this message
+
+options:
+    --json          print `list`, `verify` and `run` results as JSON
+    --lang <locale> show the lessons in another language: en (default) or tr; also read from
+                    $LEARNING_CELL_LANG
+    --manifest <path>
+                    use the exercises, order, hints and prerequisites of the exercise manifest
+                    at <path> instead of the built-in course; also read from
+                    $LEARNING_CELL_MANIFEST
+";
+
+fn main() -> ExitCode {
+    register_packs();
+    let mut args = Args::new(env::args().skip(1));
+    args.json = args.flag("json");
+    match dispatch(&mut args) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {}", err.report());
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Adds the [lesson packs](learning_cell::packs) shipped with this binary to the course.
+///
+/// A course built on third-party packs adds them as dependencies and calls their
+/// `register_pack` functions here, e.g. `weak_pack::register_pack();`.
+fn register_packs() {}
+
+fn dispatch(args: &mut Args) -> Result<ExitCode> {
+    let lang = args.value("lang")?.or_else(|| env::var(locale::LOCALE_VAR).ok());
+    if let Some(lang) = lang.filter(|lang| !lang.is_empty()) {
+        locale::set_locale(&lang)?;
+    }
+    let manifest = args.value("manifest")?.or_else(|| env::var(manifest::MANIFEST_VAR).ok());
+    if let Some(path) = manifest.filter(|path| !path.is_empty()) {
+        Registry::load(&path).context(format!("loading the manifest `{path}`"))?.install();
+    }
+    let Some(command) = args.positional() else {
+        print!("{USAGE}");
+        return Ok(ExitCode::SUCCESS);
+    };
+    if args.json && !["list", "verify", "run"].contains(&command.as_str()) {
+        return Err(format!("`{command}` has no JSON output").into());
+    }
+    match command.as_str() {
+        "run" => run(args),
+        "try" => try_example(args),
+        "list" => list(args),
+        "verify" => verify(args),
+        "hint" => hint(args),
+        "watch" => watch(args),
+        "next" => next(args),
+        "reset" => reset(args),
+        "quiz" => quiz(args),
+        "search" => search(args),
+        "explain" => explain(args),
+        "bench" => bench(args),
+        "simulate" => simulate(args),
+        "demo" => demo(args),
+        "diagram" => diagram(args),
+        "export" => export(args),
+        "progress" => progress(args),
+        #[cfg(feature = "tui")]
+        "tui" => tui::tui(args),
+        #[cfg(not(feature = "tui"))]
+        "tui" => Err("this build has no TUI, rebuild with `--features tui`".into()),
+        "stats" => stats(args),
+        "certificate" => certificate(args),
+        "doctor" => doctor(args),
+        "completions" => completions::completions(args),
+        "__complete" => completions::complete(args),
+        "help" | "--help" | "-h" => {
+            print!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        other => Err(format!("unknown command `{other}`, see `learning_cell help`").into()),
+    }
+}
+
+/// `run <lesson>`: prints the lesson, runs its demos next to the examples they perform, and lets
+/// the learner predict what the other examples do.
+fn run(args: &mut Args) -> Result<ExitCode> {
+    let id = args.positional().ok_or("missing lesson, e.g. `learning_cell run refcell`")?;
+    args.finish()?;
+    let (title, page) = lesson_page(&id)?;
+    let demos = lessons::find(&id).map(|lesson| lesson.run().demos).unwrap_or_default();
+    let placed: Vec<_> = demos.iter().map(|demo| (page.example_of(demo), demo)).collect();
+    let demos_of = |number| {
+        placed.iter().filter(move |(example, _)| *example == Some(number)).map(|(_, demo)| *demo)
+    };
+    let mut progress = Progress::load_default().context("reading the progress file")?;
+    if args.json {
+        let mut number = 0usize;
+        let blocks = page.blocks.iter().map(|block| match block {
+            Block::Text(text) => {
+                Json::object([("type", "text".into()), ("text", text.as_str().into())])
+            }
+            Block::Example(example) => {
+                number += 1;
+                Json::object([
+                    ("type", "example".into()),
+                    ("number", Json::from(number)),
+                    ("kind", example_kind_id(example.kind).into()),
+                    ("source", example.visible().into()),
+                    ("demos", Json::array(demos_of(number).map(demo_json))),
+                ])
+            }
+        });
+        let lesson = Json::object([
+            ("lesson", id.as_str().into()),
+            ("title", title.into()),
+            ("blocks", Json::array(blocks)),
+        ]);
+        println!("{lesson}");
+        progress.score.record_lesson(&id);
+        progress.save_default().context("saving the progress file")?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut prompt = Prompt::new();
+    let total = page.examples().count();
+    let (mut asked, mut right) = (0, 0);
+    println!("{title}\n{}\n", "=".repeat(title.len()));
+    let mut number = 0;
+    for block in &page.blocks {
+        let example = match block {
+            Block::Text(text) => {
+                println!("{text}\n");
+                continue;
+            }
+            Block::Example(example) => example,
+        };
+        number += 1;
+        println!("--- example {number}/{total} ---");
+        for line in example.visible().lines() {
+            println!("{}", format!("    {line}").trim_end());
+        }
+        // Shown after the prediction, since a demo gives away whether the example panics.
+        let show_demos = || {
+            for demo in demos_of(number) {
+                println!("Running it: {}", demo.title);
+                demo.steps.iter().for_each(|step| print!("{step}"));
+                println!();
+            }
+        };
+        let question = match example.kind {
+            ExampleKind::CompileFail => "Does this compile?",
+            ExampleKind::ShouldPanic => "Does this run without panicking?",
+            ExampleKind::Runs | ExampleKind::Ignored => {
+                println!("({})\n", example.kind);
+                show_demos();
+                continue;
+            }
+        };
+        if let Some(guess) = prompt.yes_no(question)? {
+            asked += 1;
+            // Neither kind of example gets through: one fails to compile, the other panics.
+            if !guess {
+                right += 1;
+                println!("Right: it {}.", example.kind);
+            } else {
+                println!("Not quite: it {}.", example.kind);
+            }
+        } else {
+            println!("{question} No: it {}.", example.kind);
+        }
+        println!("(Change it and see for yourself: `learning_cell try {id} {number}`.)\n");
+        show_demos();
+    }
+    if asked > 0 {
+        println!("You predicted {right} of {asked} examples correctly.");
+    }
+    println!(
+        "Every example above is checked by `cargo test --doc`, so it really behaves this way."
+    );
+
+    progress.score.record_lesson(&id);
+    progress.save_default().context("saving the progress file")?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// A demo's outcome as JSON: its title and, for each step, the code, what it returned or the
+/// panic message, and the fields it changed.
+fn demo_json(demo: &DemoOutcome) -> Json {
+    let steps = demo.steps.iter().map(|step| {
+        Json::object([
+            ("code", step.code.into()),
+            ("returned", step.returned.clone().into()),
+            ("panicked", step.panicked.clone().into()),
+            ("changes", Json::array(step.changes.iter().map(ToString::to_string))),
+        ])
+    });
+    Json::object([("title", demo.title.into()), ("steps", Json::array(steps))])
+}
+
+/// The title and page of the built-in or pack lesson `id`.
+fn lesson_page(id: &str) -> Result<(&'static str, Page)> {
+    let lesson = lessons::find(id).ok_or_else(|| match Chapter::from_id(id) {
+        Some(_) => format!("`{id}` has no lesson yet"),
+        None => format!("unknown lesson `{id}`"),
+    })?;
+    Ok((lesson.title(), lesson.page()))
+}
+
+/// `try <lesson> <example>`: lets the learner edit an example and shows what it does then.
+fn try_example(args: &mut Args) -> Result<ExitCode> {
+    let no_edit = args.flag("no-edit");
+    let id = args.positional().ok_or("missing lesson, e.g. `learning_cell try refcell 2`")?;
+    let number =
+        args.positional().ok_or("missing example number, e.g. `learning_cell try refcell 2`")?;
+    args.finish()?;
+    let (_, page) = lesson_page(&id)?;
+    let total = page.examples().count();
+    let example = number
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| page.examples().nth(number.checked_sub(1)?))
+        .ok_or_else(|| format!("`{id}` has examples 1 to {total}, not `{number}`"))?;
+    if example.kind == ExampleKind::Ignored {
+        return Err(format!("example {number} of `{id}` is an illustration, not code").into());
+    }
+
+    let dir = env::temp_dir().join("learning_cell-try");
+    fs::create_dir_all(&dir)?;
+    let file = dir.join(format!("{id}-{number}.rs"));
+    fs::write(&file, example.compiled())?;
+    let snippet = snippets::for_example(example);
+    let editor = env::var_os("VISUAL").or_else(|| env::var_os("EDITOR"));
+    let runner = Runner::in_temp_dir();
+    let style = Style::detect();
+    let mut prompt = Prompt::new();
+    println!("The lesson says it {}.", example.kind);
+    loop {
+        if let (false, Some(editor)) = (no_edit, &editor) {
+            Command::new(editor).arg(&file).status()?;
+        } else if !no_edit {
+            println!("Set `EDITOR` to edit the example; running {} as is.", file.display());
+        }
+        println!("Building...");
+        match runner.run(&fs::read_to_string(&file)?)? {
+            Outcome::Ran { stdout } => {
+                println!("{}", style.green("It compiled and ran to completion."));
+                print!("{stdout}");
+            }
+            Outcome::CompileError(message) => {
+                println!("{}\n{message}", style.red("It does not compile:"));
+                match snippet {
+                    Some(snippet) if message.contains(snippet.expected_error) => {
+                        println!("That is the error the lesson is about. {}", snippet.explanation);
+                    }
+                    Some(snippet) => println!(
+                        "That is not the error the lesson is about ({}), so your edit moved it.",
+                        snippet.expected_error
+                    ),
+                    None => {}
+                }
+            }
+            Outcome::Panicked(message) => println!("{} {message}", style.red("It panicked:")),
+            Outcome::TimedOut => println!("{}", style.red("It took too long and was stopped.")),
+        }
+        if no_edit || editor.is_none() || prompt.yes_no("Edit and run it again?")? != Some(true) {
+            return Ok(ExitCode::SUCCESS);
+        }
+    }
+}
+
+fn item_kind(item: Item) -> &'static str {
+    match item {
+        Item::Lesson(_) => "lesson",
+        Item::Exercise(_) => "exercise",
+    }
+}
+
+/// The metadata of a lesson, or `None` for an exercise.
+fn lesson_meta(item: Item) -> Option<LessonMeta> {
+    match item {
+        Item::Lesson(lesson) => Some(lesson.meta()),
+        Item::Exercise(_) => None,
+    }
+}
+
+/// The annotation of an example, as used in JSON output.
+fn example_kind_id(kind: ExampleKind) -> &'static str {
+    match kind {
+        ExampleKind::Runs => "runs",
+        ExampleKind::CompileFail => "compile_fail",
+        ExampleKind::ShouldPanic => "should_panic",
+        ExampleKind::Ignored => "ignore",
+    }
+}
+
+/// `list`: a table of the course's items, optionally filtered by chapter and completion.
+fn list(args: &mut Args) -> Result<ExitCode> {
+    let topic = match args.value("topic")? {
+        Some(id) => Some(Chapter::from_id(&id).ok_or_else(|| format!("unknown topic `{id}`"))?),
+        None => None,
+    };
+    let status = match args.value("status")?.as_deref() {
+        None => None,
+        Some("done") => Some(true),
+        Some("todo") => Some(false),
+        Some(other) => return Err(format!("unknown status `{other}`, use `todo` or `done`").into()),
+    };
+    args.finish()?;
+
+    let progress = Progress::load_default().context("reading the progress file")?;
+    let items: Vec<_> = curriculum::items()
+        .into_iter()
+        .filter(|item| topic.is_none_or(|topic| item.chapter() == topic))
+        .map(|item| (item, progress.score.is_completed(item.id())))
+        .filter(|(_, done)| status.is_none_or(|status| *done == status))
+        .collect();
+    if args.json {
+        let items = items.iter().map(|(item, done)| {
+            let meta = lesson_meta(*item);
+            Json::object([
+                ("id", item.id().into()),
+                ("kind", item_kind(*item).into()),
+                ("chapter", item.chapter().id().into()),
+                ("difficulty", item.difficulty().id().into()),
+                ("status", if *done { "done" } else { "todo" }.into()),
+                ("title", item.title().into()),
+                ("summary", meta.map(|meta| meta.summary).into()),
+                ("minutes", meta.map(|meta| u64::from(meta.minutes)).into()),
+            ])
+        });
+        println!("{}", Json::array(items));
+        return Ok(ExitCode::SUCCESS);
+    }
+    let rows: Vec<_> = items
+        .into_iter()
+        .map(|(item, done)| {
+            vec![
+                item.id().to_string(),
+                item_kind(item).to_string(),
+                item.chapter().id().to_string(),
+                item.difficulty().to_string(),
+                if done { "done" } else { "todo" }.to_string(),
+                lesson_meta(item).map_or(String::new(), |meta| format!("{} min", meta.minutes)),
+                item.title().to_string(),
+            ]
+        })
+        .collect();
+    if rows.is_empty() {
+        println!("Nothing to list.");
+    } else {
+        print_table(&["ID", "KIND", "CHAPTER", "DIFFICULTY", "STATUS", "TIME", "TITLE"], &rows);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `verify [<exercise>...]`: runs the exercises and fails unless all of them pass.
+fn verify(args: &mut Args) -> Result<ExitCode> {
+    let mut exercises = Vec::new();
+    while let Some(id) = args.positional() {
+        exercises.push(exercises::get(&id).ok_or_else(|| format!("unknown exercise `{id}`"))?);
+    }
+    args.finish()?;
+    // Only the exercises asked for by name count as attempts. A full run just records newly
+    // solved exercises; otherwise it would count a failure for every exercise not started yet.
+    let named = !exercises.is_empty();
+    if !named {
+        exercises = exercises::all();
+    }
+    let mut progress = Progress::load_default().context("reading the progress file")?;
+
+    let style = Style::detect();
+    let mut failing = Vec::new();
+    let mut results = Vec::new();
+    for exercise in &exercises {
+        let started = Instant::now();
+        let report = exercise.run();
+        let elapsed = started.elapsed();
+        let mut badges = Vec::new();
+        if named || (report.passed() && !progress.score.is_completed(exercise.id())) {
+            badges = progress.record_exercise(exercise.id(), report.passed(), SystemTime::now());
+        }
+        if args.json {
+            results.push(report_to_json(&report, elapsed, &badges));
+            if !report.passed() {
+                failing.push(report);
+            }
+            continue;
+        }
+        for badge in badges {
+            println!("  Badge unlocked: {badge}");
+        }
+        if report.passed() {
+            println!("  {}     {}", style.green("ok"), exercise.id());
+        } else {
+            println!("  {} {}", style.red("FAILED"), exercise.id());
+            failing.push(report);
+        }
+    }
+    progress.save_default().context("saving the progress file")?;
+    let passed = exercises.len() - failing.len();
+    let code = if failing.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE };
+    if args.json {
+        let summary = Json::object([
+            ("passed", Json::from(passed)),
+            ("total", Json::from(exercises.len())),
+            ("exercises", Json::Array(results)),
+        ]);
+        println!("{summary}");
+        return Ok(code);
+    }
+    let summary = format!("{passed}/{} exercises pass", exercises.len());
+    let summary = if failing.is_empty() { style.green(&summary) } else { style.red(&summary) };
+    println!("\n{summary}");
+    for report in &failing {
+        let text = style.diff(&report.to_string());
+        println!("\n{text}  -> edit src/exercises/{}.rs", report.exercise);
+    }
+    Ok(code)
+}
+
+/// One exercise's results for `verify --json`: the outcome, message and notes of every test.
+fn report_to_json(report: &Report, elapsed: Duration, badges: &[Badge]) -> Json {
+    let tests = report.results.iter().map(|result| {
+        Json::object([
+            ("name", result.name.into()),
+            ("passed", result.outcome.is_ok().into()),
+            ("message", result.outcome.clone().err().into()),
+            ("notes", Json::array(result.notes.iter().map(String::as_str))),
+        ])
+    });
+    Json::object([
+        ("id", report.exercise.into()),
+        ("passed", report.passed().into()),
+        ("duration_ms", Json::from(elapsed.as_secs_f64() * 1000.0)),
+        ("tests", Json::array(tests)),
+        ("badges", Json::array(badges.iter().map(|badge| badge.to_string()))),
+    ])
+}
+
+/// `watch`: polls the exercise sources and re-runs `verify` for every file that changes.
+///
+/// The exercises are compiled into this binary, so each run goes through `cargo run`, which
+/// rebuilds the crate with the saved edits first.
+fn watch(args: &mut Args) -> Result<ExitCode> {
+    args.finish()?;
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let dir = root.join("src").join("exercises");
+    let mut seen = modification_times(&dir)?;
+    println!("Watching {} for changes, press Ctrl-C to stop.", dir.display());
+    loop {
+        thread::sleep(Duration::from_millis(500));
+        let now = modification_times(&dir)?;
+        let changed: Vec<_> = now
+            .iter()
+            .filter(|(path, time)| seen.get(*path) != Some(time))
+            .filter_map(|(path, _)| path.file_stem()?.to_str().map(str::to_string))
+            .collect();
+        seen = now;
+        if changed.is_empty() {
+            continue;
+        }
+        // A change to anything but an exercise (like `mod.rs`) may affect all of them.
+        let ids: Vec<_> = if changed.iter().all(|id| exercises::get(id).is_some()) {
+            changed
+        } else {
+            Vec::new()
+        };
+        println!(
+            "\n--- {} ---",
+            if ids.is_empty() { "all exercises".into() } else { ids.join(", ") }
+        );
+        Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+            .args(["run", "--quiet", "--bin", "learning_cell", "--", "verify"])
+            .args(&ids)
+            .current_dir(root)
+            .status()?;
+    }
+}
+
+/// The last modification time of every file in `dir`.
+fn modification_times(dir: &Path) -> Result<BTreeMap<PathBuf, SystemTime>> {
+    let mut times = BTreeMap::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        times.insert(entry.path(), entry.metadata()?.modified()?);
+    }
+    Ok(times)
+}
+
+/// `hint <exercise>`: reveals one more hint than last time, or up to `--level`.
+fn hint(args: &mut Args) -> Result<ExitCode> {
+    let level = match args.value("level")? {
+        Some(level) => {
+            Some(level.parse::<usize>().map_err(|_| format!("invalid level `{level}`"))?)
+        }
+        None => None,
+    };
+    let id = args.positional().ok_or("missing exercise, e.g. `learning_cell hint memo_fib`")?;
+    args.finish()?;
+    let exercise = exercises::get(&id).ok_or_else(|| format!("unknown exercise `{id}`"))?;
+
+    let mut progress = Progress::load_default().context("reading the progress file")?;
+    let seen = progress.stats.exercise(&id).map_or(0, |stats| stats.hints as usize);
+    let hints = exercise.reveal(level.unwrap_or(seen + 1));
+    if hints.is_empty() {
+        println!("No hints revealed.");
+        return Ok(ExitCode::SUCCESS);
+    }
+    let total = exercise.hints().len();
+    for (number, hint) in hints.iter().enumerate() {
+        println!("Hint {}/{total}: {hint}", number + 1);
+    }
+    if hints.len() == total && level.is_none() {
+        println!("That was the last hint.");
+    }
+    progress.stats.record_hint(&id, hints.len());
+    progress.save_default().context("saving the progress file")?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `quiz`: asks generated questions one by one, then grades them all at once.
+fn quiz(args: &mut Args) -> Result<ExitCode> {
+    let topic = match args.value("topic")? {
+        Some(id) => Some(Chapter::from_id(&id).ok_or_else(|| format!("unknown topic `{id}`"))?),
+        None => None,
+    };
+    let count = match args.value("count")? {
+        Some(count) => count.parse::<usize>().map_err(|_| format!("invalid count `{count}`"))?,
+        None => 10,
+    };
+    let seed = match args.value("seed")? {
+        Some(seed) => seed.parse::<u64>().map_err(|_| format!("invalid seed `{seed}`"))?,
+        None => SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|err| err.to_string())?
+            .as_secs(),
+    };
+    args.finish()?;
+
+    let quiz = quiz::generate(seed, count, topic);
+    if quiz.questions().is_empty() {
+        return Err(match topic {
+            Some(topic) => format!("there are no questions about {topic} yet").into(),
+            None => "the quiz is empty".into(),
+        });
+    }
+    let mut prompt = Prompt::new();
+    if !prompt.interactive {
+        return Err("the quiz needs a terminal to answer in".into());
+    }
+    println!("Quiz with seed {seed}: answer with a letter, or press enter to skip.\n");
+    let total = quiz.questions().len();
+    let mut answers = Vec::new();
+    for (number, question) in quiz.questions().iter().enumerate() {
+        println!("{}/{total}. {question}", number + 1);
+        answers.push(prompt.choice(question.options.len())?);
+        println!();
+    }
+
+    let grade = quiz.grade(&answers);
+    println!("{grade}");
+    let mut progress = Progress::load_default().context("reading the progress file")?;
+    for badge in progress.record_quiz(&grade) {
+        println!("Badge unlocked: {badge}");
+    }
+    progress.save_default().context("saving the progress file")?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `next`: the first unlocked item that is still to do, per the curriculum.
+fn next(args: &mut Args) -> Result<ExitCode> {
+    let open = args.flag("open");
+    args.finish()?;
+    let progress = Progress::load_default().context("reading the progress file")?;
+    let Some(item) = curriculum::next(|id| progress.score.is_completed(id)) else {
+        println!("You have finished the whole course. Congratulations!");
+        return Ok(ExitCode::SUCCESS);
+    };
+    let chapter = item.chapter();
+    match item {
+        Item::Lesson(lesson) => {
+            let meta = lesson.meta();
+            if lesson.id() == chapter.id() {
+                println!("Next: the {chapter} lesson ({})", meta.difficulty);
+            } else {
+                println!("Next: {} ({chapter}, {})", meta.title, meta.difficulty);
+            }
+            println!("{} About {} minutes.", meta.summary, meta.minutes);
+            if open {
+                return run(&mut Args::new([lesson.id().to_string()]));
+            }
+            println!("Start it with `learning_cell run {}`.", lesson.id());
+        }
+        Item::Exercise(exercise) => {
+            println!("Next: {} ({chapter}, {})", exercise.title(), item.difficulty());
+            println!("{}", exercise.description());
+            let path = exercise_path(exercise.id());
+            if open {
+                let editor = env::var_os("VISUAL")
+                    .or_else(|| env::var_os("EDITOR"))
+                    .ok_or("set `EDITOR` to open exercises in your editor")?;
+                Command::new(editor).arg(&path).status()?;
+            } else {
+                println!(
+                    "Edit {}, then check it with `learning_cell verify {}`.",
+                    path.display(),
+                    exercise.id()
+                );
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `reset`: puts back the shipped scaffold of exercises and forgets their progress.
+fn reset(args: &mut Args) -> Result<ExitCode> {
+    let all = args.flag("all");
+    let yes = args.flag("yes");
+    let mut ids = Vec::new();
+    while let Some(id) = args.positional() {
+        exercises::get(&id).ok_or_else(|| format!("unknown exercise `{id}`"))?;
+        ids.push(id);
+    }
+    args.finish()?;
+    if all != ids.is_empty() {
+        return Err("name the exercises to reset, or pass `--all`".into());
+    }
+
+    let mut prompt = Prompt::new();
+    let question = if all {
+        "Reset every exercise and all of your progress?".to_string()
+    } else {
+        format!("Reset {}? Your changes will be lost.", ids.join(", "))
+    };
+    if !yes {
+        match prompt.yes_no(&question)? {
+            Some(true) => {}
+            Some(false) => return Ok(ExitCode::FAILURE),
+            None => return Err("not resetting without confirmation, pass `--yes`".into()),
+        }
+    }
+    if all {
+        ids = exercises::all().iter().map(|exercise| exercise.id().to_string()).collect();
+    }
+
+    let mut progress = Progress::load_default().context("reading the progress file")?;
+    for id in &ids {
+        // Exercises registered by other crates have no scaffold to restore.
+        if let Some(scaffold) = exercises::scaffold(id) {
+            let path = exercise_path(id);
+            let source = fs::read_to_string(&path)?;
+            let restored = exercises::restore_scaffold(&source, scaffold)
+                .ok_or_else(|| format!("cannot find the exercise markers in {}", path.display()))?;
+            fs::write(&path, restored)?;
+        }
+        progress.forget(id);
+        println!("Reset {id}.");
+    }
+    if all {
+        progress = Progress::default();
+    }
+    progress.save_default().context("saving the progress file")?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `search <query>...`: the best matching sections of the course, with their anchors.
+fn search(args: &mut Args) -> Result<ExitCode> {
+    let limit = match args.value("limit")? {
+        Some(limit) => limit.parse().map_err(|_| format!("invalid limit `{limit}`"))?,
+        None => 10,
+    };
+    let words: Vec<String> = std::iter::from_fn(|| args.positional()).collect();
+    args.finish()?;
+    if words.is_empty() {
+        return Err("missing query, e.g. `learning_cell search try_borrow`".into());
+    }
+    let hits = search::search(&words.join(" "));
+    if hits.is_empty() {
+        println!("Nothing matches `{}`.", words.join(" "));
+        return Ok(ExitCode::FAILURE);
+    }
+    let style = Style::detect();
+    for hit in hits.iter().take(limit) {
+        println!("{}  {}", style.green(&hit.section.anchor), hit.section.title);
+        println!("    {}", hit.snippet);
+    }
+    if hits.len() > limit {
+        println!("... and {} more, see `--limit`.", hits.len() - limit);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `explain <type>`: a cheat sheet for one row of the comparison table.
+fn explain(args: &mut Args) -> Result<ExitCode> {
+    let name = args.positional().ok_or("missing type, e.g. `learning_cell explain RefCell`")?;
+    args.finish()?;
+    let Some(info) = comparison::lookup(&name) else {
+        let names: Vec<_> = comparison::TYPES.iter().map(|info| info.name).collect();
+        return Err(format!("unknown type `{name}`, try one of: {}", names.join(", ")).into());
+    };
+    let style = Style::detect();
+    let heading = |text: &str| style.paint(text, "1");
+    println!("{} ({})\n{}\n", heading(info.name), info.path, info.summary);
+    println!("Send: {}    Sync: {}\n", info.send, info.sync);
+
+    println!("{}", heading("Key methods"));
+    let width = info.methods.iter().map(|(method, _)| method.len()).max().unwrap_or(0);
+    for (method, what) in info.methods {
+        println!("  {method:<width$}  {what}");
+    }
+    println!("\n{}", heading("How it fails"));
+    for failure in info.failure_modes {
+        println!("  - {failure}");
+    }
+    println!("\n{}", heading("Pitfalls"));
+    for pitfall in info.pitfalls {
+        println!("  - {pitfall}");
+    }
+
+    if let Some(wrapper) = Wrapper::lookup(info.name) {
+        println!("\n{}", heading("Memory (size / align in bytes)"));
+        let (plain, wrapped) = (Wrapper::Plain.to_string(), wrapper.to_string());
+        println!("  {:<8}  {plain:<8}  {wrapped}", "");
+        for row in size_report() {
+            let (plain, wrapped) = (row.layout(Wrapper::Plain), row.layout(wrapper));
+            println!("  {:<8}  {:<8}  {wrapped}", row.inner, plain.to_string());
+        }
+    }
+
+    println!("\n{}", heading("Learn more"));
+    if info.chapter.has_lesson() {
+        println!("  lesson    learning_cell run {}", info.chapter.id());
+    } else {
+        println!("  chapter   {} (no lesson yet)", info.chapter.title());
+    }
+    for exercise in exercises::all().into_iter().filter(|e| e.tags().contains(&info.tag)) {
+        println!("  exercise  {} ({})", exercise.id(), exercise.title());
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `bench`: runs the comparison benchmarks and prints each one relative to its group's baseline.
+fn bench(args: &mut Args) -> Result<ExitCode> {
+    let filter = args.value("filter")?.map(|filter| filter.to_lowercase());
+    let iterations = match args.value("iterations")? {
+        Some(n) => n.parse().map_err(|_| format!("invalid number of iterations `{n}`"))?,
+        None => 200_000,
+    };
+    args.finish()?;
+    let matches = |benchmark: &Benchmark| {
+        let name = format!("{} {}", benchmark.group, benchmark.name).to_lowercase();
+        filter.as_ref().is_none_or(|filter| name.contains(filter))
+    };
+    let all = benchmarks::all();
+    if !all.iter().any(matches) {
+        return Err(format!("no benchmark matches `{}`", filter.unwrap_or_default()).into());
+    }
+
+    let mut rows = Vec::new();
+    let mut baseline = 0.0;
+    for (index, benchmark) in all.iter().enumerate() {
+        let is_baseline = index == 0 || all[index - 1].group != benchmark.group;
+        let group_runs = all.iter().any(|b| b.group == benchmark.group && matches(b));
+        // The baseline runs whenever its group does, so every row can be compared.
+        if !(matches(benchmark) || is_baseline && group_runs) {
+            continue;
+        }
+        let measurement = benchmark.run(iterations);
+        let nanos = measurement.total.as_secs_f64() * 1e9 / f64::from(iterations.max(1));
+        let relative = if is_baseline {
+            baseline = nanos;
+            "baseline".to_string()
+        } else if baseline > 0.0 {
+            format!("{:.1}x", nanos / baseline)
+        } else {
+            "-".to_string()
+        };
+        rows.push(vec![
+            benchmark.group.to_string(),
+            benchmark.name.to_string(),
+            format!("{nanos:.2}"),
+            relative,
+        ]);
+    }
+    print_table(&["GROUP", "BENCHMARK", "NS/OP", "RELATIVE"], &rows);
+    if cfg!(debug_assertions) {
+        println!("\nThis is a debug build; use `cargo run --release -- bench` for real numbers.");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+const SIMULATE_HELP: &str = "\
+borrow            RefCell::borrow: a shared borrow, panics if mutably borrowed
+borrow_mut        RefCell::borrow_mut: an exclusive borrow, panics if borrowed at all
+try_borrow        like borrow, but returns an error instead of panicking
+try_borrow_mut    like borrow_mut, but returns an error instead of panicking
+replace           RefCell::replace: a borrow_mut that ends at once
+swap              RefCell::swap with an unborrowed cell: the same, as far as this cell goes
+drop <n>          drop guard <n>
+reset             start over with a fresh cell
+help              show this message
+quit              leave
+";
+
+/// `simulate`: a read-eval-print loop over a [`BorrowSimulator`].
+fn simulate(args: &mut Args) -> Result<ExitCode> {
+    args.finish()?;
+    let interactive = io::stdin().is_terminal();
+    let style = Style::detect();
+    let mut cell = BorrowSimulator::new();
+    if interactive {
+        println!("A virtual `RefCell` with nothing borrowed. Type `help` for the operations.");
+    }
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("> ");
+            io::stdout().flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(ExitCode::SUCCESS);
+        };
+        let words: Vec<_> = line.split_whitespace().collect();
+        // A real program would stop at a panic; here it is reported and the cell left as it was.
+        // `panics` says whether the failure is a panic or a returned error.
+        let (op, panics) = match words.as_slice() {
+            [] => continue,
+            ["borrow"] => (Op::Borrow, true),
+            ["borrow_mut"] => (Op::BorrowMut, true),
+            ["try_borrow"] => (Op::Borrow, false),
+            ["try_borrow_mut"] => (Op::BorrowMut, false),
+            ["replace"] => (Op::Replace, true),
+            ["swap"] => (Op::Swap, true),
+            ["drop", id] => match id.parse() {
+                Ok(id) => (Op::DropGuard(id), false),
+                Err(_) => {
+                    println!("`drop` takes a guard number, like `drop 1`");
+                    continue;
+                }
+            },
+            ["reset"] => {
+                cell = BorrowSimulator::new();
+                println!("fresh cell\n  {}", describe_cell(&cell));
+                continue;
+            }
+            ["help"] => {
+                print!("{SIMULATE_HELP}");
+                continue;
+            }
+            ["quit" | "exit"] => return Ok(ExitCode::SUCCESS),
+            _ => {
+                println!("unknown operation `{line}`, type `help` for the list");
+                continue;
+            }
+        };
+        match cell.apply(op) {
+            Ok(State { guard: Some(id), .. }) if panics => println!("guard {id}"),
+            Ok(State { guard: Some(id), .. }) => println!("Ok(guard {id})"),
+            Ok(_) => match op {
+                Op::DropGuard(id) => println!("dropped guard {id}"),
+                Op::Swap => println!("swapped"),
+                _ => println!("replaced"),
+            },
+            Err(err @ SimError::NoSuchGuard(_)) => println!("{err}"),
+            Err(err) if panics => println!("{}", style.red(&format!("panic: {err}"))),
+            Err(err) => println!("Err({err})"),
+        }
+        println!("  {}", describe_cell(&cell));
+    }
+}
+
+/// The borrow flag and state of `cell`, its live guards and the calls that would panic.
+fn describe_cell(cell: &BorrowSimulator) -> String {
+    let guards: Vec<_> = cell
+        .guards()
+        .iter()
+        .map(|(id, kind)| {
+            let kind = match kind {
+                BorrowKind::Shared => "shared",
+                BorrowKind::Exclusive => "exclusive",
+            };
+            format!("{id} ({kind})")
+        })
+        .collect();
+    let guards = if guards.is_empty() { "none".to_string() } else { guards.join(", ") };
+    let panicking = cell.panicking_calls();
+    let panicking = if panicking.is_empty() { "nothing".to_string() } else { panicking.join(", ") };
+    let (flag, state) = (cell.flag(), cell.state());
+    format!("flag {flag} ({state}), guards: {guards}; would panic: {panicking}")
+}
+
+/// `demo <lesson>`: runs the lesson's demos and prints their outcomes.
+fn demo(args: &mut Args) -> Result<ExitCode> {
+    let id = args.positional().ok_or("missing lesson, e.g. `learning_cell demo cell`")?;
+    args.finish()?;
+    let lesson = lessons::find(&id).ok_or_else(|| format!("unknown lesson `{id}`"))?;
+    let report = lesson.run();
+    if report.demos.is_empty() {
+        return Err(format!("`{id}` has no demos to run").into());
+    }
+    let style = Style::detect();
+    for (number, outcome) in report.demos.iter().enumerate() {
+        if number > 0 {
+            println!();
+        }
+        println!("{}", style.paint(outcome.title, "1"));
+        for step in &outcome.steps {
+            print!("{step}");
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `diagram <lesson>`: records the borrows of the lesson's demo and writes them as a diagram.
+fn diagram(args: &mut Args) -> Result<ExitCode> {
+    let format = match args.value("format")?.as_deref() {
+        None | Some("mermaid") => Format::Mermaid,
+        Some("dot") => Format::Dot,
+        Some(other) => {
+            return Err(format!("unknown format `{other}`, use `mermaid` or `dot`").into())
+        }
+    };
+    let output = args.value("output")?;
+    let id = args.positional().ok_or("missing lesson, e.g. `learning_cell diagram refcell`")?;
+    args.finish()?;
+    let chapter = Chapter::from_id(&id).ok_or_else(|| format!("unknown lesson `{id}`"))?;
+    let events = diagram::demo(chapter).ok_or_else(|| format!("`{id}` has no borrows to draw"))?;
+
+    let output = output.unwrap_or_else(|| format!("{id}-borrows.{}", format.extension()));
+    fs::write(&output, diagram::render(&events, format))?;
+    println!("Wrote {} borrow events to {output}.", events.len());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `export <dir>`: writes the course in another format.
+///
+/// `export --lesson <lesson> [<file>]`: writes a single lesson as Markdown.
+fn export(args: &mut Args) -> Result<ExitCode> {
+    if let Some(id) = args.value("lesson")? {
+        let lesson = Chapter::from_id(&id).ok_or_else(|| format!("unknown lesson `{id}`"))?;
+        let file = args.positional();
+        args.finish()?;
+        let text = export::lesson_to_markdown(lesson);
+        match file {
+            Some(file) => fs::write(file, text)?,
+            None => print!("{text}"),
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    let format = args.value("format")?.unwrap_or_else(|| "mdbook".to_string());
+    let dir =
+        args.positional().ok_or("missing output directory, e.g. `learning_cell export book`")?;
+    args.finish()?;
+    let files = match format.as_str() {
+        "mdbook" => export::mdbook(&dir)?,
+        "html" => export::html(&dir)?,
+        "rustlings" => export::rustlings(&dir)?,
+        other => {
+            return Err(
+                format!("unknown format `{other}`, use `mdbook`, `html` or `rustlings`").into()
+            )
+        }
+    };
+    println!("Wrote {} files to {dir}.", files.len());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `stats`: a dashboard of the progress store.
+fn stats(args: &mut Args) -> Result<ExitCode> {
+    args.finish()?;
+    let progress = Progress::load_default().context("reading the progress file")?;
+    let score = &progress.score;
+    let is_done = |id: &str| score.is_completed(id);
+    let style = Style::detect();
+    let heading = |text: &str| style.paint(text, "1");
+    let bar = |done: usize, total: usize| {
+        const WIDTH: usize = 20;
+        let filled = (done * WIDTH).checked_div(total).unwrap_or(0);
+        format!("[{}{}] {done:>2}/{total}", "#".repeat(filled), ".".repeat(WIDTH - filled))
+    };
+
+    println!(
+        "{} points, streak {} (best {})\n",
+        score.points(),
+        score.streak(),
+        score.best_streak()
+    );
+
+    println!("{}", heading("Chapters"));
+    let width = Chapter::ALL.iter().map(|chapter| chapter.title().len()).max().unwrap_or(0);
+    for chapter in Chapter::ALL {
+        let items = chapter.items();
+        let done = items.iter().filter(|item| is_done(item.id())).count();
+        let mark =
+            if chapter.is_complete(&is_done) { style.green(" complete") } else { String::new() };
+        println!("  {:width$}  {}{mark}", chapter.title(), bar(done, items.len()));
+    }
+
+    println!("\n{}", heading("Exercises by difficulty"));
+    let exercises = exercises::all();
+    for difficulty in Difficulty::ALL {
+        let of_difficulty: Vec<_> =
+            exercises.iter().filter(|exercise| exercise.difficulty() == difficulty).collect();
+        let done = of_difficulty.iter().filter(|exercise| is_done(exercise.id())).count();
+        println!("  {:width$}  {}", difficulty.id(), bar(done, of_difficulty.len()));
+    }
+
+    println!("\n{}", heading("Time spent"));
+    let times: Vec<_> =
+        progress.stats.iter().filter_map(|(_, stats)| stats.time_to_completion()).collect();
+    let total: Duration = times.iter().sum();
+    if times.is_empty() {
+        println!("  nothing solved yet");
+    } else {
+        println!(
+            "  {} from first attempt to success, over {} exercises",
+            format_duration(total),
+            times.len()
+        );
+    }
+    if let Some((id, stats)) = progress.stats.struggles().first() {
+        println!("  hardest so far: {id} ({} failed runs, {} hints)", stats.failures, stats.hints);
+    }
+
+    println!("\n{}", heading("Badges"));
+    if score.badges().is_empty() {
+        println!("  none yet");
+    }
+    for badge in score.badges() {
+        println!("  {badge}: {}", badge.description());
+    }
+
+    println!("\n{}", heading("Weakest quiz topics"));
+    let topics = progress.stats.weakest_topics();
+    if topics.is_empty() {
+        println!("  no quizzes taken yet, try `learning_cell quiz`");
+    }
+    for (topic, quiz) in topics.iter().take(3) {
+        let percent = u64::from(quiz.correct) * 100 / u64::from(quiz.asked.max(1));
+        println!(
+            "  {:width$}  {}/{} correct ({percent}%)",
+            topic.title(),
+            quiz.correct,
+            quiz.asked
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `duration` in hours and minutes, or seconds when shorter than a minute.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 3600, seconds / 60 % 60) {
+        (0, 0) => format!("{seconds}s"),
+        (0, minutes) => format!("{minutes}m"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
+
+/// `certificate`: writes the completion certificate as JSON and as text.
+fn certificate(args: &mut Args) -> Result<ExitCode> {
+    let learner = match args.value("learner")? {
+        Some(name) => Some(name),
+        None => env::var("USER").or_else(|_| env::var("USERNAME")).ok(),
+    };
+    let name = args.value("output")?.unwrap_or_else(|| "certificate".to_string());
+    args.finish()?;
+    let progress = Progress::load_default().context("reading the progress file")?;
+    let certificate = match Certificate::issue(&progress, learner.as_deref(), SystemTime::now()) {
+        Ok(certificate) => certificate,
+        Err(missing) => {
+            let chapters: Vec<_> =
+                certificate::CORE_CHAPTERS.iter().map(|chapter| chapter.id()).collect();
+            println!("A certificate needs the {} chapters completed.", chapters.join(", "));
+            println!("Still to do: {}", missing.join(", "));
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    fs::write(format!("{name}.json"), format!("{}\n", certificate.to_json()))?;
+    fs::write(format!("{name}.txt"), certificate.to_text())?;
+    print!("{}", certificate.to_text());
+    println!("\nWrote {name}.json and {name}.txt.");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `doctor`: finds out which optional tools are installed and what they make possible.
+fn doctor(args: &mut Args) -> Result<ExitCode> {
+    args.finish()?;
+    let cargo = tool_version("cargo", &["--version"]);
+    let nightly = tool_version("cargo", &["+nightly", "--version"]);
+    let miri =
+        nightly.as_ref().and_then(|_| tool_version("cargo", &["+nightly", "miri", "--version"]));
+    let fuzz = tool_version("cargo", &["fuzz", "--version"]);
+    // loom is an ordinary crate: usable once cargo has downloaded it.
+    let loom =
+        cargo_home().is_some_and(|home| has_crate(&home.join("registry").join("cache"), "loom-"));
+
+    let style = Style::detect();
+    let status = |found: &Option<String>| match found {
+        Some(version) => style.green(version),
+        None => style.red("not found"),
+    };
+    let feature =
+        |enabled: bool| if enabled { style.green("enabled") } else { "disabled".to_string() };
+    let rows = vec![
+        vec!["cargo".to_string(), status(&cargo)],
+        vec!["nightly toolchain".to_string(), status(&nightly)],
+        vec!["Miri".to_string(), status(&miri)],
+        vec!["cargo-fuzz".to_string(), status(&fuzz)],
+        vec![
+            "loom crate".to_string(),
+            if loom { style.green("downloaded") } else { style.red("not downloaded") },
+        ],
+        vec!["feature `tui`".to_string(), feature(cfg!(feature = "tui"))],
+        vec!["feature `solutions`".to_string(), feature(cfg!(feature = "solutions"))],
+    ];
+    print_table(&["CHECK", "STATUS"], &rows);
+
+    let lessons = [
+        (
+            "Miri checks of the unsafe exercises (my_cell, my_refcell)",
+            miri.is_some(),
+            if nightly.is_some() {
+                "rustup +nightly component add miri"
+            } else {
+                "rustup toolchain install nightly --component miri"
+            },
+        ),
+        (
+            "loom model checking of the threads chapter",
+            loom,
+            "cargo add --dev loom (needs network access once)",
+        ),
+        (
+            "fuzzing the cells you build with cargo-fuzz",
+            fuzz.is_some() && nightly.is_some(),
+            "rustup toolchain install nightly && cargo install cargo-fuzz",
+        ),
+        (
+            "the full-screen browser (`learning_cell tui`)",
+            cfg!(feature = "tui"),
+            "cargo run --features tui -- tui",
+        ),
+    ];
+    println!();
+    for (lesson, runnable, install) in lessons {
+        if runnable {
+            println!("{} {lesson}", style.green("ready  "));
+        } else {
+            println!("{} {lesson}\n        try: {install}", style.red("missing"));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The first line `program args` prints, or `None` if it cannot be run or fails.
+fn tool_version(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).lines().next().map(str::to_string)
+}
+
+/// Where cargo keeps its registry, per `CARGO_HOME` or its default.
+fn cargo_home() -> Option<PathBuf> {
+    env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cargo")))
+}
+
+/// Whether a `<prefix>*.crate` file was downloaded into any registry under `cache`.
+fn has_crate(cache: &Path, prefix: &str) -> bool {
+    let Ok(registries) = fs::read_dir(cache) else { return false };
+    registries.flatten().any(|registry| {
+        fs::read_dir(registry.path())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .any(|file| file.file_name().to_string_lossy().starts_with(prefix))
+    })
+}
+
+/// `progress export <file>`: writes the saved progress in the versioned export format.
+///
+/// `progress import <file>`: replaces the saved progress with an export.
+fn progress(args: &mut Args) -> Result<ExitCode> {
+    let action = args.positional().ok_or("missing action, use `export` or `import`")?;
+    match action.as_str() {
+        "export" => {
+            // Instructors collecting files want to know whose they are.
+            let learner = match args.value("learner")? {
+                Some(name) => Some(name),
+                None => env::var("USER").or_else(|_| env::var("USERNAME")).ok(),
+            };
+            let file = args.positional().ok_or("missing file, e.g. `progress.txt`")?;
+            args.finish()?;
+            let export = Export {
+                learner,
+                exported_at: SystemTime::now(),
+                progress: Progress::load_default().context("reading the progress file")?,
+            };
+            fs::write(&file, export.to_text())?;
+            println!("Exported your progress to {file}.");
+        }
+        "import" => {
+            let yes = args.flag("yes");
+            let file = args.positional().ok_or("missing file to import")?;
+            args.finish()?;
+            let export = Export::parse(&fs::read_to_string(&file)?)
+                .map_err(|err| format!("cannot import {file}: {err}"))?;
+            let score = &export.progress.score;
+            println!(
+                "{file}: {} points and {} completed items{}.",
+                score.points(),
+                score.completed().len(),
+                export.learner.as_ref().map_or(String::new(), |name| format!(" of {name}"))
+            );
+            if !yes {
+                match Prompt::new().yes_no("Replace your progress with it?")? {
+                    Some(true) => {}
+                    Some(false) => return Ok(ExitCode::FAILURE),
+                    None => return Err("not importing without confirmation, pass `--yes`".into()),
+                }
+            }
+            export.progress.save_default().context("saving the progress file")?;
+            println!("Imported.");
+        }
+        other => return Err(format!("unknown action `{other}`, use `export` or `import`").into()),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The source file of the built-in exercise `id`.
+fn exercise_path(id: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("exercises").join(format!("{id}.rs"))
+}
+
+/// Prints `rows` in aligned columns under `header`.
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<_> = header.iter().map(|title| title.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<_> =
+            cells.iter().zip(&widths).map(|(cell, width)| format!("{cell:width$}")).collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(header.to_vec());
+    for row in rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
+
+/// ANSI colors, used only when printing to a terminal and `NO_COLOR` is not set.
+struct Style {
+    enabled: bool,
+}
+
+impl Style {
+    fn detect() -> Style {
+        Style { enabled: io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none() }
+    }
+
+    fn paint(&self, text: &str, code: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn green(&self, text: &str) -> String {
+        self.paint(text, "32")
+    }
+
+    fn red(&self, text: &str) -> String {
+        self.paint(text, "31")
+    }
+
+    /// Colors the expected (`- `) and actual (`+ `) lines of the output diffs in `text`, see
+    /// [`learning_cell::diff`].
+    fn diff(&self, text: &str) -> String {
+        let mut in_diff = false;
+        let mut painted = String::new();
+        for line in text.lines() {
+            let content = line.trim_start();
+            if content.starts_with("first difference at line") {
+                in_diff = true;
+            } else if !line.starts_with("    ") {
+                in_diff = false;
+            }
+            let line = match content.get(..2) {
+                Some("- ") if in_diff => self.red(line),
+                Some("+ ") if in_diff => self.green(line),
+                _ => line.to_string(),
+            };
+            painted.push_str(&line);
+            painted.push('\n');
+        }
+        painted
+    }
+}
+
+/// Command line arguments, consumed piece by piece by the commands.
+///
+/// A command first takes its options (`flag`, `value`), then its positional arguments, then calls
+/// `finish` to reject anything it did not expect.
+struct Args {
+    words: Vec<String>,
+    /// The global `--json` flag: print a JSON document instead of text.
+    json: bool,
+}
+
+impl Args {
+    fn new(words: impl IntoIterator<Item = String>) -> Args {
+        Args { words: words.into_iter().collect(), json: false }
+    }
+
+    /// Removes `--name` and reports whether it was present.
+    fn flag(&mut self, name: &str) -> bool {
+        let before = self.words.len();
+        self.words.retain(|word| word.strip_prefix("--") != Some(name));
+        self.words.len() != before
+    }
+
+    /// Removes `--name <value>` or `--name=<value>` and returns the value.
+    fn value(&mut self, name: &str) -> Result<Option<String>> {
+        let Some(index) = self.words.iter().position(|word| {
+            word.strip_prefix("--").is_some_and(|rest| {
+                rest == name || rest.strip_prefix(name).is_some_and(|rest| rest.starts_with('='))
+            })
+        }) else {
+            return Ok(None);
+        };
+        let word = self.words.remove(index);
+        if let Some((_, value)) = word.split_once('=') {
+            return Ok(Some(value.to_string()));
+        }
+        if index < self.words.len() && !self.words[index].starts_with("--") {
+            return Ok(Some(self.words.remove(index)));
+        }
+        Err(format!("`--{name}` needs a value").into())
+    }
+
+    /// Removes and returns the first argument that is not an option.
+    fn positional(&mut self) -> Option<String> {
+        let index = self.words.iter().position(|word| !word.starts_with("--"))?;
+        Some(self.words.remove(index))
+    }
+
+    /// Fails if any argument was not consumed.
+    fn finish(&mut self) -> Result<()> {
+        match self.words.first() {
+            Some(word) => Err(format!("unexpected argument `{word}`").into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Asks the learner questions, if there is a learner at the terminal.
+struct Prompt {
+    interactive: bool,
+}
+
+impl Prompt {
+    fn new() -> Prompt {
+        Prompt { interactive: io::stdin().is_terminal() && io::stdout().is_terminal() }
+    }
+
+    /// Asks a yes/no question. Returns `None` when nobody can answer.
+    fn yes_no(&mut self, question: &str) -> Result<Option<bool>> {
+        if !self.interactive {
+            return Ok(None);
+        }
+        loop {
+            print!("{question} [y/n] ");
+            io::stdout().flush()?;
+            let mut answer = String::new();
+            if io::stdin().lock().read_line(&mut answer)? == 0 {
+                return Ok(None);
+            }
+            match answer.trim().to_lowercase().as_str() {
+                "y" | "yes" => return Ok(Some(true)),
+                "n" | "no" => return Ok(Some(false)),
+                _ => println!("Please answer `y` or `n`."),
+            }
+        }
+    }
+
+    /// Asks for one of `count` options by letter. Returns `None` when the question is skipped.
+    fn choice(&mut self, count: usize) -> Result<Option<usize>> {
+        let last = option_letter(count - 1);
+        loop {
+            print!("answer [a-{last}] ");
+            io::stdout().flush()?;
+            let mut answer = String::new();
+            if io::stdin().lock().read_line(&mut answer)? == 0 {
+                return Ok(None);
+            }
+            match answer.trim().to_lowercase().as_str() {
+                "" => return Ok(None),
+                letter => {
+                    if let Some(index) =
+                        (0..count).find(|index| letter == option_letter(*index).to_string())
+                    {
+                        return Ok(Some(index));
+                    }
+                    println!("Please answer with a letter from `a` to `{last}`.");
+                }
+            }
+        }
+    }
+}
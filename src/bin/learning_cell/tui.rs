@@ -0,0 +1,214 @@
+//! `learning_cell tui`: a full-screen course browser.
+//!
+//! The browser is drawn with `ratatui` on the alternate screen, with the terminal in raw mode so
+//! every key press is read by `crossterm` as it happens:
+//!
+//! ```text
+//! j / Down    select the next item          k / Up        select the previous item
+//! g / Home    select the first item         G / End       select the last item
+//! f / PgDn    scroll the viewer down        b / PgUp      scroll the viewer up
+//! t / Enter   run the selected exercise's tests
+//! q / Esc     quit
+//! ```
+
+use std::process::ExitCode;
+use std::time::SystemTime;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use learning_cell::curriculum::{self, Item};
+use learning_cell::error::Context;
+use learning_cell::exercises::Report;
+use learning_cell::progress::Progress;
+
+use super::{Args, Result};
+
+/// Width of the left column (progress and course tree).
+const SIDEBAR: u16 = 38;
+
+pub fn tui(args: &mut Args) -> Result<ExitCode> {
+    args.finish()?;
+    let mut browser = Browser {
+        items: curriculum::items(),
+        selected: 0,
+        list: ListState::default(),
+        scroll: 0,
+        page: 0,
+        report: None,
+        running: false,
+        progress: Progress::load_default().context("reading the progress file")?,
+    };
+    // `init` also installs a panic hook that gives the terminal back before the message prints.
+    let mut terminal = ratatui::init();
+    let result = browser.run(&mut terminal);
+    ratatui::restore();
+    result.map(|()| ExitCode::SUCCESS)
+}
+
+struct Browser {
+    items: Vec<Item>,
+    selected: usize,
+    /// Scroll position of the course tree, kept between frames so it only moves when it must.
+    list: ListState,
+    /// First line of the viewer that is shown.
+    scroll: usize,
+    /// Number of lines the viewer showed in the last frame.
+    page: usize,
+    /// Test output of the selected exercise, once it ran.
+    report: Option<Report>,
+    /// Whether the tests of the selected exercise are running.
+    running: bool,
+    progress: Progress,
+}
+
+impl Browser {
+    /// Draws the browser and handles key presses until the user quits.
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            // Resizes and other events only need the redraw.
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                // Raw mode turns Ctrl-C into a key press.
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(())
+                }
+                KeyCode::Char('j') | KeyCode::Down => self.select(self.selected + 1),
+                KeyCode::Char('k') | KeyCode::Up => self.select(self.selected.saturating_sub(1)),
+                KeyCode::Char('g') | KeyCode::Home => self.select(0),
+                KeyCode::Char('G') | KeyCode::End => self.select(usize::MAX),
+                KeyCode::Char('f') | KeyCode::PageDown => self.scroll += self.page,
+                KeyCode::Char('b') | KeyCode::PageUp => {
+                    self.scroll = self.scroll.saturating_sub(self.page)
+                }
+                KeyCode::Char('t') | KeyCode::Enter => {
+                    self.running = true;
+                    terminal.draw(|frame| self.draw(frame))?;
+                    let ran = self.run_tests();
+                    self.running = false;
+                    ran?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        let index = index.min(self.items.len() - 1);
+        if index != self.selected {
+            self.selected = index;
+            self.scroll = 0;
+            self.report = None;
+        }
+    }
+
+    fn run_tests(&mut self) -> Result<()> {
+        if let Item::Exercise(exercise) = self.items[self.selected] {
+            let report = exercise.run();
+            self.progress.record_exercise(exercise.id(), report.passed(), SystemTime::now());
+            self.progress.save_default().context("saving the progress file")?;
+            self.report = Some(report);
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [sidebar, viewer] =
+            Layout::horizontal([Constraint::Length(SIDEBAR), Constraint::Min(0)]).areas(main);
+        self.draw_sidebar(frame, sidebar);
+        self.draw_viewer(frame, viewer);
+        frame.render_widget(
+            Line::from("j/k: select  t: run tests  f/b: scroll  q: quit").dim(),
+            help,
+        );
+    }
+
+    /// Progress summary above the course tree.
+    fn draw_sidebar(&mut self, frame: &mut Frame, area: Rect) {
+        let [summary, tree] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+        let score = &self.progress.score;
+        let done = self.items.iter().filter(|item| score.is_completed(item.id())).count();
+        let summary_lines = vec![
+            Line::from(format!("{} points, {done}/{} done", score.points(), self.items.len())),
+            Line::from(format!(
+                "{} badges, best streak {}",
+                score.badges().len(),
+                score.best_streak()
+            )),
+        ];
+        frame.render_widget(Paragraph::new(summary_lines), summary);
+
+        let mut rows = Vec::new();
+        let mut chapter = None;
+        let mut selected_row = 0;
+        for (index, item) in self.items.iter().enumerate() {
+            if chapter != Some(item.chapter()) {
+                chapter = Some(item.chapter());
+                rows.push(ListItem::new(item.chapter().title()).bold());
+            }
+            if index == self.selected {
+                selected_row = rows.len();
+            }
+            let mark = if score.is_completed(item.id()) { "x" } else { " " };
+            rows.push(ListItem::new(format!("{:>3} [{mark}] {}", index + 1, item.id())));
+        }
+        self.list.select(Some(selected_row));
+        let tree_list = List::new(rows).highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(tree_list, tree, &mut self.list);
+    }
+
+    /// The lesson text or the exercise description and test output of the selected item.
+    fn draw_viewer(&mut self, frame: &mut Frame, area: Rect) {
+        let item = self.items[self.selected];
+        let block = Block::bordered().title(format!(" {} ", item.id()));
+        let inner = block.inner(area);
+        let text = match item {
+            Item::Lesson(lesson) => lesson.page().text(),
+            Item::Exercise(exercise) => {
+                let mut text = format!("{}\n\n{}\n\n", exercise.title(), exercise.description());
+                match &self.report {
+                    _ if self.running => text.push_str("Running the tests..."),
+                    Some(report) => text.push_str(&report.to_string()),
+                    None => text.push_str("Press `t` to run the tests."),
+                }
+                text
+            }
+        };
+        let lines = wrap(&text, usize::from(inner.width).max(1));
+        self.page = usize::from(inner.height).max(1);
+        self.scroll = self.scroll.min(lines.len().saturating_sub(self.page));
+        let shown: Vec<_> =
+            lines.into_iter().skip(self.scroll).take(self.page).map(Line::from).collect();
+        frame.render_widget(Paragraph::new(shown).block(block), area);
+    }
+}
+
+/// Splits `text` into lines of at most `width` characters, breaking at spaces where possible.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let mut rest = line;
+        while rest.chars().count() > width {
+            let cut = rest.char_indices().nth(width).map_or(rest.len(), |(index, _)| index);
+            let cut = rest[..cut].rfind(' ').filter(|&space| space > 0).unwrap_or(cut);
+            lines.push(rest[..cut].to_string());
+            rest = rest[cut..].trim_start();
+        }
+        lines.push(rest.to_string());
+    }
+    lines
+}
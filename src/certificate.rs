@@ -0,0 +1,156 @@
+//! Completion certificates.
+//!
+//! Once every [core chapter](CORE_CHAPTERS) is complete, [`Certificate::issue`] summarizes the
+//! learner's progress for an instructor: what was completed, the score and badges, and a
+//! [content hash](content_hash) of the progress data it was issued from.
+//! ```
+//! use std::time::UNIX_EPOCH;
+//! use learning_cell::certificate::{Certificate, CORE_CHAPTERS};
+//! use learning_cell::progress::Progress;
+//!
+//! let mut progress = Progress::default();
+//! let missing = Certificate::issue(&progress, None, UNIX_EPOCH).unwrap_err();
+//! assert!(missing.contains(&"cell_intro".to_string()));
+//!
+//! for chapter in CORE_CHAPTERS {
+//!     for item in chapter.items() {
+//!         progress.record_exercise(item.id(), true, UNIX_EPOCH);
+//!     }
+//! }
+//! let certificate = Certificate::issue(&progress, Some("ada"), UNIX_EPOCH).unwrap();
+//! assert!(certificate.to_text().contains("ada"));
+//! assert_eq!(certificate.hash, learning_cell::certificate::content_hash(&progress.to_text()));
+//! ```
+//! The hash lets an instructor match a certificate to the
+//! [progress export](crate::progress::Export) it came from. It is not a signature: anyone can
+//! compute the hash of edited progress.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::curriculum::Chapter;
+use crate::json::Json;
+use crate::progress::Progress;
+
+/// The chapters a certificate requires: those whose lesson is at most
+/// [`Difficulty::Core`](crate::curriculum::Difficulty::Core).
+pub const CORE_CHAPTERS: [Chapter; 3] = [Chapter::Cell, Chapter::RefCell, Chapter::Rc];
+
+/// A summary of completed progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    /// Who the certificate is for, if known.
+    pub learner: Option<String>,
+    /// When the certificate was issued.
+    pub issued_at: SystemTime,
+    /// Every complete chapter, core or not, in course order.
+    pub chapters: Vec<Chapter>,
+    /// Ids of every completed lesson and exercise.
+    pub completed: Vec<String>,
+    /// Points earned.
+    pub points: u32,
+    /// Longest run of successes.
+    pub best_streak: u32,
+    /// Names of the badges earned.
+    pub badges: Vec<String>,
+    /// [`content_hash`] of the progress file.
+    pub hash: String,
+}
+
+impl Certificate {
+    /// Issues a certificate for `progress`, or returns the ids of the core items still to do.
+    pub fn issue(
+        progress: &Progress,
+        learner: Option<&str>,
+        at: SystemTime,
+    ) -> Result<Certificate, Vec<String>> {
+        let score = &progress.score;
+        let is_done = |id: &str| score.is_completed(id);
+        let missing: Vec<_> = CORE_CHAPTERS
+            .iter()
+            .flat_map(|chapter| chapter.items())
+            .filter(|item| !is_done(item.id()))
+            .map(|item| item.id().to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
+        Ok(Certificate {
+            learner: learner.map(str::to_string),
+            issued_at: at,
+            chapters: Chapter::ALL
+                .into_iter()
+                .filter(|chapter| chapter.is_complete(&is_done))
+                .collect(),
+            completed: score.completed().iter().cloned().collect(),
+            points: score.points(),
+            best_streak: score.best_streak(),
+            badges: score.badges().iter().map(|badge| badge.name().to_string()).collect(),
+            hash: content_hash(&progress.to_text()),
+        })
+    }
+
+    /// The certificate as a JSON document.
+    pub fn to_json(&self) -> Json {
+        Json::object([
+            ("learner", self.learner.clone().into()),
+            ("issued_at", seconds(self.issued_at).into()),
+            ("chapters", Json::array(self.chapters.iter().map(|chapter| chapter.id()))),
+            ("completed", Json::array(self.completed.clone())),
+            ("points", Json::from(u64::from(self.points))),
+            ("best_streak", Json::from(u64::from(self.best_streak))),
+            ("badges", Json::array(self.badges.clone())),
+            ("hash", self.hash.clone().into()),
+        ])
+    }
+
+    /// The certificate as text, for printing or attaching to an email.
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("Certificate of completion: learning_cell\n");
+        text.push_str(&format!("{}\n\n", "=".repeat(40)));
+        if let Some(learner) = &self.learner {
+            text.push_str(&format!("Awarded to {learner}\n"));
+        }
+        text.push_str(&format!("Issued on {}\n\n", date(self.issued_at)));
+        let chapters: Vec<_> = self.chapters.iter().map(|chapter| chapter.title()).collect();
+        text.push_str(&format!("Chapters completed: {}\n", chapters.join(", ")));
+        text.push_str(&format!("Points: {}, best streak: {}\n", self.points, self.best_streak));
+        if !self.badges.is_empty() {
+            text.push_str(&format!("Badges: {}\n", self.badges.join(", ")));
+        }
+        text.push_str(&format!("\nCompleted items ({}):\n", self.completed.len()));
+        for id in &self.completed {
+            text.push_str(&format!("  - {id}\n"));
+        }
+        text.push_str(&format!("\nProgress hash: {}\n", self.hash));
+        text
+    }
+}
+
+/// A 64-bit FNV-1a hash of `text`, as `fnv1a64:` and 16 hex digits.
+pub fn content_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("fnv1a64:{hash:016x}")
+}
+
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
+/// `time` as a `YYYY-MM-DD` date in UTC.
+fn date(time: SystemTime) -> String {
+    // Howard Hinnant's `civil_from_days`.
+    let days = (seconds(time) / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
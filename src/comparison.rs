@@ -0,0 +1,274 @@
+//! The cell and smart pointer types of the course, side by side.
+//!
+//! The lessons introduce the types one at a time; this table collects what sets them apart: the
+//! methods we reach for, whether they can cross threads, how they fail and the mistakes learners
+//! make with them. The `explain` command of the CLI renders one row as a cheat sheet.
+//! ```
+//! use learning_cell::comparison::{self, Bound};
+//!
+//! let refcell = comparison::lookup("refcell").unwrap();
+//! assert_eq!(refcell.sync, Bound::Never);
+//! assert!(refcell.methods.iter().any(|(method, _)| method.contains("try_borrow_mut")));
+//! ```
+
+use std::fmt;
+
+use crate::curriculum::Chapter;
+
+/// Whether a type implements an auto trait such as `Send` or `Sync`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    /// For every `T`.
+    Always,
+    /// For no `T`.
+    Never,
+    /// When `T` satisfies the given bound, e.g. `T: Send`.
+    When(&'static str),
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bound::Always => f.write_str("yes"),
+            Bound::Never => f.write_str("no"),
+            Bound::When(bound) => write!(f, "if {bound}"),
+        }
+    }
+}
+
+/// One row of the comparison table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeInfo {
+    /// The type's name, e.g. `RefCell`.
+    pub name: &'static str,
+    /// Its full path in the standard library.
+    pub path: &'static str,
+    /// What the type is for, in one sentence.
+    pub summary: &'static str,
+    /// The chapter that teaches it.
+    pub chapter: Chapter,
+    /// The exercise tag of the type.
+    pub tag: &'static str,
+    /// Whether the type is `Send`.
+    pub send: Bound,
+    /// Whether the type is `Sync`.
+    pub sync: Bound,
+    /// The key methods and what they do.
+    pub methods: &'static [(&'static str, &'static str)],
+    /// How using the type can go wrong at run time.
+    pub failure_modes: &'static [&'static str],
+    /// Common mistakes.
+    pub pitfalls: &'static [&'static str],
+}
+
+/// Every type of the course, in the order the course introduces them.
+pub const TYPES: &[TypeInfo] = &[
+    TypeInfo {
+        name: "Cell",
+        path: "std::cell::Cell",
+        summary: "Interior mutability by moving values in and out, never by reference.",
+        chapter: Chapter::Cell,
+        tag: "cell",
+        send: Bound::When("T: Send"),
+        sync: Bound::Never,
+        methods: &[
+            ("get", "copy the value out (T: Copy)"),
+            ("set", "overwrite the value"),
+            ("replace", "swap in a new value and return the old one"),
+            ("take", "move the value out, leaving T::default()"),
+            ("swap", "exchange the values of two cells"),
+            ("get_mut", "a plain &mut T, given &mut Cell<T>"),
+        ],
+        failure_modes: &["none: every operation always succeeds"],
+        pitfalls: &[
+            "`get` needs `T: Copy`; use `take` or `replace` for String, Vec, ...",
+            "there is no way to borrow the inner value through `&Cell<T>`",
+        ],
+    },
+    TypeInfo {
+        name: "RefCell",
+        path: "std::cell::RefCell",
+        summary: "Interior mutability with borrows checked at run time.",
+        chapter: Chapter::RefCell,
+        tag: "refcell",
+        send: Bound::When("T: Send"),
+        sync: Bound::Never,
+        methods: &[
+            ("borrow", "a shared Ref<T> guard"),
+            ("borrow_mut", "an exclusive RefMut<T> guard"),
+            ("try_borrow / try_borrow_mut", "the same, returning an error instead of panicking"),
+            ("replace / take / swap", "move values in and out, borrowing mutably"),
+            ("into_inner", "consume the cell and return the value"),
+        ],
+        failure_modes: &[
+            "panics with `already borrowed: BorrowMutError` on conflicting borrows",
+            "panics with `already mutably borrowed: BorrowError` while a RefMut is alive",
+        ],
+        pitfalls: &[
+            "holding a guard across a callback that borrows the same cell again",
+            "guards in a `match` scrutinee or `if let` live until the end of the block",
+            "`replace`, `take` and `swap` borrow mutably too, and panic the same way",
+        ],
+    },
+    TypeInfo {
+        name: "Rc",
+        path: "std::rc::Rc",
+        summary: "Shared ownership on one thread, counted at run time.",
+        chapter: Chapter::Rc,
+        tag: "rc",
+        send: Bound::Never,
+        sync: Bound::Never,
+        methods: &[
+            ("Rc::clone", "another owner; increments the strong count"),
+            ("Rc::downgrade", "a Weak pointer that does not keep the value alive"),
+            ("Rc::strong_count / weak_count", "the current counts"),
+            ("Rc::try_unwrap", "the value, if this is the only owner"),
+            ("Rc::get_mut / make_mut", "&mut T if unique, or after cloning the value"),
+        ],
+        failure_modes: &["reference cycles are never freed: the memory leaks"],
+        pitfalls: &[
+            "`Rc<T>` only gives `&T`; pair it with `RefCell` to mutate",
+            "parent and child both holding an Rc to each other",
+        ],
+    },
+    TypeInfo {
+        name: "Weak",
+        path: "std::rc::Weak",
+        summary: "A non-owning pointer into an Rc, for back links and caches.",
+        chapter: Chapter::Rc,
+        tag: "weak",
+        send: Bound::Never,
+        sync: Bound::Never,
+        methods: &[
+            ("upgrade", "an Rc, if the value is still alive"),
+            ("Weak::new", "a pointer that never upgrades"),
+            ("strong_count", "how many owners are left"),
+        ],
+        failure_modes: &["`upgrade` returns None once the last Rc is gone"],
+        pitfalls: &["unwrapping `upgrade()` without handling a dropped owner"],
+    },
+    TypeInfo {
+        name: "Arc",
+        path: "std::sync::Arc",
+        summary: "Shared ownership across threads, with atomic reference counts.",
+        chapter: Chapter::Threads,
+        tag: "arc",
+        send: Bound::When("T: Send + Sync"),
+        sync: Bound::When("T: Send + Sync"),
+        methods: &[
+            ("Arc::clone", "another owner, e.g. for a spawned thread"),
+            ("Arc::downgrade", "a sync::Weak pointer"),
+            ("Arc::try_unwrap / into_inner", "the value, once this is the only owner"),
+        ],
+        failure_modes: &["reference cycles leak, exactly as with Rc"],
+        pitfalls: &[
+            "`Arc<RefCell<T>>` is not Send: use `Arc<Mutex<T>>` or `Arc<RwLock<T>>`",
+            "counting atomically costs more than Rc on a single thread",
+        ],
+    },
+    TypeInfo {
+        name: "Mutex",
+        path: "std::sync::Mutex",
+        summary: "Exclusive access across threads; other threads block until it is free.",
+        chapter: Chapter::Threads,
+        tag: "mutex",
+        send: Bound::When("T: Send"),
+        sync: Bound::When("T: Send"),
+        methods: &[
+            ("lock", "block until a MutexGuard is available"),
+            ("try_lock", "a guard only if no one holds the lock"),
+            ("get_mut / into_inner", "the value without locking, given ownership"),
+            ("is_poisoned / clear_poison", "check or reset the poison flag"),
+        ],
+        failure_modes: &[
+            "deadlocks when a thread locks twice or two threads lock in opposite orders",
+            "`lock` returns Err (poisoned) after a thread panicked holding the guard",
+        ],
+        pitfalls: &[
+            "keeping the guard alive longer than needed, e.g. across slow I/O",
+            "`lock().unwrap()` turns one panic into a panic on every thread",
+        ],
+    },
+    TypeInfo {
+        name: "RwLock",
+        path: "std::sync::RwLock",
+        summary: "Many readers or one writer across threads.",
+        chapter: Chapter::Threads,
+        tag: "rwlock",
+        send: Bound::When("T: Send"),
+        sync: Bound::When("T: Send + Sync"),
+        methods: &[
+            ("read", "a shared read guard"),
+            ("write", "an exclusive write guard"),
+            ("try_read / try_write", "guards only if available right away"),
+        ],
+        failure_modes: &[
+            "deadlocks (or panics) when a thread holding a read guard asks for a write guard",
+            "poisoning, like Mutex, after a panic during a write",
+        ],
+        pitfalls: &[
+            "upgrading a read guard to a write guard is not possible: drop it first",
+            "writers can starve under a constant stream of readers on some platforms",
+        ],
+    },
+    TypeInfo {
+        name: "OnceLock",
+        path: "std::sync::OnceLock",
+        summary: "A value written at most once, then shared freely across threads.",
+        chapter: Chapter::Threads,
+        tag: "oncelock",
+        send: Bound::When("T: Send"),
+        sync: Bound::When("T: Send + Sync"),
+        methods: &[
+            ("get", "the value, if it was set"),
+            ("set", "store the value, or get it back if already set"),
+            ("get_or_init", "initialize on first use, exactly once"),
+        ],
+        failure_modes: &["`get_or_init` deadlocks if the initializer calls it again"],
+        pitfalls: &["initializers that depend on each other in a cycle"],
+    },
+    TypeInfo {
+        name: "AtomicUsize",
+        path: "std::sync::atomic::AtomicUsize",
+        summary: "A lock-free integer shared across threads (also AtomicBool, AtomicI32, ...).",
+        chapter: Chapter::Threads,
+        tag: "atomics",
+        send: Bound::Always,
+        sync: Bound::Always,
+        methods: &[
+            ("load / store", "read or write with a memory Ordering"),
+            ("fetch_add / fetch_sub", "update and return the previous value"),
+            ("compare_exchange", "write only if the value is still the expected one"),
+        ],
+        failure_modes: &["none, but wrong Orderings give subtly wrong results"],
+        pitfalls: &[
+            "a load followed by a store is not atomic: use fetch_* or compare_exchange",
+            "Relaxed does not order other memory accesses",
+        ],
+    },
+    TypeInfo {
+        name: "UnsafeCell",
+        path: "std::cell::UnsafeCell",
+        summary: "The primitive every other cell is built on: a raw pointer to the inside.",
+        chapter: Chapter::Unsafe,
+        tag: "unsafecell",
+        send: Bound::When("T: Send"),
+        sync: Bound::Never,
+        methods: &[
+            ("get", "a *mut T to the value"),
+            ("get_mut", "a &mut T, given &mut UnsafeCell<T>"),
+            ("into_inner", "consume the cell and return the value"),
+        ],
+        failure_modes: &["undefined behaviour if references from `get` alias mutably"],
+        pitfalls: &[
+            "implementing Sync for a wrapper without real synchronization",
+            "handing out a &mut T while a & T to the same value is alive",
+        ],
+    },
+];
+
+/// Looks up a type by name or exercise tag, ignoring case.
+pub fn lookup(name: &str) -> Option<&'static TypeInfo> {
+    let name = name.to_lowercase();
+    TYPES.iter().find(|info| info.name.to_lowercase() == name || info.tag == name)
+}
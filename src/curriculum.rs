@@ -0,0 +1,260 @@
+//! The order in which lessons and exercises should be tackled.
+//!
+//! The course is split into [`Chapter`]s. A chapter is unlocked once every chapter it depends on
+//! is complete, and a chapter is complete once its lessons and all of its exercises are done.
+//! Given the ids of the items a learner has finished, [`next`] picks the first unlocked item that
+//! is still to do.
+//! ```
+//! use std::collections::HashSet;
+//! use learning_cell::curriculum;
+//!
+//! let mut done = HashSet::new();
+//! assert_eq!(curriculum::next(|id| done.contains(id)).unwrap().id(), "cell");
+//!
+//! done.insert("cell");
+//! assert_eq!(curriculum::next(|id| done.contains(id)).unwrap().id(), "cell_intro");
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::exercises::{self, Exercise};
+use crate::lessons::{self, Lesson};
+
+/// A group of lessons and exercises about one topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Chapter {
+    /// `Cell` and interior mutability for `Copy` types.
+    Cell,
+    /// `RefCell` and runtime borrow checking.
+    RefCell,
+    /// Shared ownership with `Rc`, `Weak` and `Rc<RefCell<T>>`.
+    Rc,
+    /// `Mutex`, `RwLock`, atomics and the other thread-safe cells.
+    Threads,
+    /// `UnsafeCell` and building the cells ourselves.
+    Unsafe,
+}
+
+impl Chapter {
+    /// Every chapter, in course order.
+    pub const ALL: [Chapter; 5] =
+        [Chapter::Cell, Chapter::RefCell, Chapter::Rc, Chapter::Threads, Chapter::Unsafe];
+
+    /// Stable identifier, also used as the id of the chapter's lesson.
+    pub fn id(self) -> &'static str {
+        match self {
+            Chapter::Cell => "cell",
+            Chapter::RefCell => "refcell",
+            Chapter::Rc => "rc",
+            Chapter::Threads => "threads",
+            Chapter::Unsafe => "unsafe",
+        }
+    }
+
+    /// Human readable title.
+    pub fn title(self) -> &'static str {
+        match self {
+            Chapter::Cell => "Cell",
+            Chapter::RefCell => "RefCell",
+            Chapter::Rc => "Rc and shared ownership",
+            Chapter::Threads => "Sharing across threads",
+            Chapter::Unsafe => "UnsafeCell and building our own cells",
+        }
+    }
+
+    /// Looks up a chapter by [`id`](Chapter::id).
+    pub fn from_id(id: &str) -> Option<Chapter> {
+        Chapter::ALL.into_iter().find(|chapter| chapter.id() == id)
+    }
+
+    /// The chapters that must be complete before this one is unlocked.
+    pub fn prerequisites(self) -> &'static [Chapter] {
+        match self {
+            Chapter::Cell => &[],
+            Chapter::RefCell => &[Chapter::Cell],
+            Chapter::Rc => &[Chapter::RefCell],
+            Chapter::Threads => &[Chapter::RefCell],
+            Chapter::Unsafe => &[Chapter::Rc, Chapter::Threads],
+        }
+    }
+
+    /// Difficulty of the chapter's main lesson.
+    pub fn difficulty(self) -> Difficulty {
+        match self {
+            Chapter::Cell | Chapter::RefCell => Difficulty::Intro,
+            Chapter::Rc => Difficulty::Core,
+            Chapter::Threads => Difficulty::Advanced,
+            Chapter::Unsafe => Difficulty::Unsafe,
+        }
+    }
+
+    /// Topic tags of the chapter's main lesson.
+    pub fn tags(self) -> &'static [&'static str] {
+        match self {
+            Chapter::Cell => &["cell", "copy"],
+            Chapter::RefCell => &["refcell", "borrowing"],
+            Chapter::Rc => &["rc", "weak", "leaks"],
+            Chapter::Threads => &["arc", "mutex", "rwlock", "atomics"],
+            Chapter::Unsafe => &["unsafecell", "soundness"],
+        }
+    }
+
+    /// Whether the chapter has a main lesson write-up (not every chapter does yet).
+    pub fn has_lesson(self) -> bool {
+        lessons::find(self.id()).is_some()
+    }
+
+    /// The chapter's [lessons](lessons::all), built-in and from packs, followed by its exercises.
+    pub fn items(self) -> Vec<Item> {
+        let lessons = lessons::all()
+            .into_iter()
+            .filter(move |lesson| lesson.chapter() == self)
+            .map(Item::Lesson);
+        let exercises = exercises::all()
+            .into_iter()
+            .filter(move |exercise| exercise.chapter() == self)
+            .map(Item::Exercise);
+        lessons.chain(exercises).collect()
+    }
+
+    /// Whether every item of the chapter is done.
+    pub fn is_complete(self, is_done: &impl Fn(&str) -> bool) -> bool {
+        self.items().iter().all(|item| is_done(item.id()))
+    }
+
+    /// Whether every prerequisite of the chapter is complete.
+    pub fn is_unlocked(self, is_done: &impl Fn(&str) -> bool) -> bool {
+        self.prerequisites().iter().all(|chapter| chapter.is_complete(is_done))
+    }
+}
+
+impl fmt::Display for Chapter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.title())
+    }
+}
+
+/// How demanding a lesson or exercise is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    /// First contact with a type: a few lines, one idea.
+    Intro,
+    /// The everyday patterns, including the usual borrow pitfalls.
+    Core,
+    /// Several moving parts: threads, callbacks, designs with trade-offs.
+    Advanced,
+    /// Requires writing `unsafe` code.
+    Unsafe,
+}
+
+impl Difficulty {
+    /// Every difficulty, from easiest to hardest.
+    pub const ALL: [Difficulty; 4] =
+        [Difficulty::Intro, Difficulty::Core, Difficulty::Advanced, Difficulty::Unsafe];
+
+    /// Stable identifier.
+    pub fn id(self) -> &'static str {
+        match self {
+            Difficulty::Intro => "intro",
+            Difficulty::Core => "core",
+            Difficulty::Advanced => "advanced",
+            Difficulty::Unsafe => "unsafe",
+        }
+    }
+
+    /// Looks up a difficulty by [`id`](Difficulty::id).
+    pub fn from_id(id: &str) -> Option<Difficulty> {
+        Difficulty::ALL.into_iter().find(|difficulty| difficulty.id() == id)
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// Something a learner can complete.
+#[derive(Debug, Clone, Copy)]
+pub enum Item {
+    /// Reading a lesson, built-in or from a [lesson pack](crate::packs).
+    Lesson(&'static dyn Lesson),
+    /// Solving an exercise.
+    Exercise(&'static dyn Exercise),
+}
+
+impl Item {
+    /// The id recorded when the item is completed.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Item::Lesson(lesson) => lesson.id(),
+            Item::Exercise(exercise) => exercise.id(),
+        }
+    }
+
+    /// Human readable title.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Item::Lesson(lesson) => lesson.title(),
+            Item::Exercise(exercise) => exercise.title(),
+        }
+    }
+
+    /// The chapter the item belongs to.
+    pub fn chapter(&self) -> Chapter {
+        match self {
+            Item::Lesson(lesson) => lesson.chapter(),
+            Item::Exercise(exercise) => exercise.chapter(),
+        }
+    }
+
+    /// How demanding the item is.
+    pub fn difficulty(&self) -> Difficulty {
+        match self {
+            Item::Lesson(lesson) => lesson.difficulty(),
+            Item::Exercise(exercise) => exercise.difficulty(),
+        }
+    }
+
+    /// The item's topic tags.
+    pub fn tags(&self) -> &'static [&'static str] {
+        match self {
+            Item::Lesson(lesson) => lesson.tags(),
+            Item::Exercise(exercise) => exercise.tags(),
+        }
+    }
+}
+
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for Item {}
+
+/// Every item of the course, chapter by chapter.
+pub fn items() -> Vec<Item> {
+    Chapter::ALL.into_iter().flat_map(Chapter::items).collect()
+}
+
+/// The first item that is unlocked but not yet done, or `None` when the course is finished.
+/// A lesson is skipped until the lessons in its
+/// [prerequisites](crate::lessons::LessonMeta::prerequisites) are done, and an exercise until
+/// its [prerequisites](Exercise::prerequisites) are.
+///
+/// `is_done` tells whether the item with the given id has been completed.
+pub fn next(is_done: impl Fn(&str) -> bool) -> Option<Item> {
+    let ready = |item: &Item| match item {
+        Item::Lesson(lesson) => lesson.meta().prerequisites.iter().all(|id| is_done(id)),
+        Item::Exercise(exercise) => exercise.prerequisites().iter().all(|id| is_done(id)),
+    };
+    Chapter::ALL
+        .into_iter()
+        .filter(|chapter| chapter.is_unlocked(&is_done))
+        .flat_map(Chapter::items)
+        .find(|item| !is_done(item.id()) && ready(item))
+}
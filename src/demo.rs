@@ -0,0 +1,80 @@
+//! The lessons' examples as functions.
+//!
+//! Doctests prove that the lessons' examples behave as described, but they only run under
+//! `cargo test`. The `demonstrate_*` functions of the lesson modules, such as
+//! [`Cell::demonstrate_get_set`](crate::Cell::demonstrate_get_set), perform the same operations
+//! at run time and return a [`DemoOutcome`]: every call made, what it returned and which fields
+//! it changed, as data for the command line to print and for tests to check. A lesson's demos
+//! are run together by [`Lesson::run`](crate::lessons::Lesson::run).
+//! ```
+//! let outcome = learning_cell::Cell::demonstrate_get_set();
+//! let set = &outcome.steps[1];
+//! assert_eq!(set.code, "a.special.set(2)");
+//! assert_eq!(set.changes[0].to_string(), "special: 42 -> 2");
+//! assert_eq!(outcome.steps[2].returned.as_deref(), Some("2"));
+//! ```
+//! Operations that panic, like a conflicting `RefCell` borrow, are run under
+//! [`catch_panic`](crate::util::catch_panic): the panic message is recorded in the step and the
+//! demo goes on.
+
+use std::fmt;
+
+use crate::snapshot::FieldChange;
+
+/// What a demo did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemoOutcome {
+    /// What the demo shows.
+    pub title: &'static str,
+    /// The operations, in the order they ran.
+    pub steps: Vec<DemoStep>,
+}
+
+/// One operation of a demo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemoStep {
+    /// The operation, as code.
+    pub code: &'static str,
+    /// `Debug` representation of the returned value, or `None` for `()` and panics.
+    pub returned: Option<String>,
+    /// The panic message, if the operation panicked.
+    pub panicked: Option<String>,
+    /// The fields the operation changed, with their values before and after.
+    pub changes: Vec<FieldChange>,
+}
+
+impl DemoStep {
+    /// Records that `code` returned `returned` and made `changes`.
+    pub fn new(code: &'static str, returned: impl fmt::Debug, changes: Vec<FieldChange>) -> Self {
+        let returned = Some(format!("{returned:?}")).filter(|returned| returned != "()");
+        DemoStep { code, returned, panicked: None, changes }
+    }
+
+    /// Records that `code` panicked with `message`, after making `changes`.
+    pub fn panicked(code: &'static str, message: String, changes: Vec<FieldChange>) -> Self {
+        DemoStep { code, returned: None, panicked: Some(message), changes }
+    }
+}
+
+impl fmt::Display for DemoStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "> {}", self.code)?;
+        if let Some(returned) = &self.returned {
+            writeln!(f, "  returned {returned}")?;
+        }
+        if let Some(message) = &self.panicked {
+            writeln!(f, "  panicked: {message}")?;
+        }
+        for change in &self.changes {
+            writeln!(f, "  changed {change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DemoOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.title)?;
+        self.steps.iter().try_for_each(|step| write!(f, "{step}"))
+    }
+}
@@ -0,0 +1,344 @@
+//! Borrow events emitted by the instrumented cells and locks.
+//!
+//! [`TracedRefCell`](crate::impls::TracedRefCell) and [`TracedMutex`](crate::impls::TracedMutex)
+//! report every borrow, lock and release they perform as a [`BorrowEvent`]. There are two ways to
+//! observe those events:
+//!
+//! - [`record`] collects the events emitted by the current thread while a closure runs. This is
+//!   what tests and the CLI use to show a borrow timeline.
+//! - [`set_hook`] installs a process-wide callback that sees every event from every thread. This
+//!   is the place to forward events to a logging framework.
+//!
+//! ```
+//! use learning_cell::diagnostics::{self, Action, BorrowKind};
+//! use learning_cell::impls::TracedRefCell;
+//!
+//! let cell = TracedRefCell::with_label(5, "counter");
+//!
+//! let (_, events) = diagnostics::record(|| {
+//!     *cell.borrow_mut() += 1;
+//! });
+//!
+//! assert_eq!(events.len(), 2);
+//! assert_eq!(events[0].label, Some("counter"));
+//! assert_eq!(events[0].kind, BorrowKind::Exclusive);
+//! assert_eq!(events[0].action, Action::Acquire);
+//! assert!(matches!(events[1].action, Action::Release { .. }));
+//! ```
+//!
+//! # The `tracing` feature
+//!
+//! With `--features tracing`, every event is also emitted to the [`tracing`] crate, with target
+//! `learning_cell::diagnostics` and the fields `cell`, `label`, `kind` (and `held`, for releases).
+//! Acquires and releases are `TRACE` events, conflicts `DEBUG` events. Each borrow or lock that is
+//! granted opens a `borrow` span, which closes when the guard is dropped, so a subscriber sees
+//! how long every borrow was held and which events happened while it was.
+//!
+//! [`tracing`]: https://docs.rs/tracing
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::util::{Clock, SystemClock};
+
+/// The kind of access that was requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BorrowKind {
+    /// A `borrow` (or a read lock): any number of these can coexist.
+    Shared,
+    /// A `borrow_mut` (or a lock): only one at a time.
+    Exclusive,
+}
+
+/// Who holds a `RefCell`'s value at a given moment, as its borrow flag records it.
+///
+/// The standard library keeps the flag private. The crate's own cells report it:
+/// [`TracedRefCell::borrow_state`](crate::impls::TracedRefCell::borrow_state),
+/// [`MyRefCell::borrow_state`](crate::exercises::my_refcell::MyRefCell::borrow_state) and
+/// [`BorrowSimulator::state`](crate::simulator::BorrowSimulator::state).
+/// ```
+/// use learning_cell::diagnostics::BorrowState;
+/// use learning_cell::impls::TracedRefCell;
+///
+/// let cell = TracedRefCell::new(1);
+/// let first = cell.borrow();
+/// let second = cell.borrow();
+/// assert_eq!(cell.borrow_state(), BorrowState::Shared(2));
+/// assert_eq!(cell.borrow_state().to_string(), "shared by 2 borrows");
+/// drop((first, second));
+///
+/// let writer = cell.borrow_mut();
+/// assert_eq!(cell.borrow_state(), BorrowState::Exclusive);
+/// drop(writer);
+/// assert_eq!(cell.borrow_state(), BorrowState::Unused);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BorrowState {
+    /// Not borrowed: any borrow would succeed.
+    Unused,
+    /// Borrowed by this many `Ref`s: more shared borrows would succeed, `borrow_mut` would not.
+    Shared(usize),
+    /// Borrowed by a `RefMut`: every other borrow would fail.
+    Exclusive,
+}
+
+impl BorrowState {
+    /// The state a borrow flag stands for: the number of shared borrows, or a negative number
+    /// for an exclusive one.
+    pub fn from_flag(flag: isize) -> BorrowState {
+        match flag {
+            0 => BorrowState::Unused,
+            flag if flag > 0 => BorrowState::Shared(flag as usize),
+            _ => BorrowState::Exclusive,
+        }
+    }
+
+    /// The borrow flag as the standard library keeps it: `0`, the number of shared borrows, or
+    /// `-1`.
+    pub fn flag(self) -> isize {
+        match self {
+            BorrowState::Unused => 0,
+            BorrowState::Shared(count) => count as isize,
+            BorrowState::Exclusive => -1,
+        }
+    }
+
+    /// Whether a borrow of `kind` would be granted now.
+    pub fn allows(self, kind: BorrowKind) -> bool {
+        matches!(
+            (self, kind),
+            (BorrowState::Unused, _) | (BorrowState::Shared(_), BorrowKind::Shared)
+        )
+    }
+}
+
+impl fmt::Display for BorrowState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BorrowState::Unused => f.write_str("unused"),
+            BorrowState::Shared(1) => f.write_str("shared by 1 borrow"),
+            BorrowState::Shared(count) => write!(f, "shared by {count} borrows"),
+            BorrowState::Exclusive => f.write_str("exclusively borrowed"),
+        }
+    }
+}
+
+/// What happened to the borrow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// The borrow or lock was granted.
+    Acquire,
+    /// The guard was dropped after being held for `held`.
+    Release {
+        /// Time between the matching `Acquire` and this release.
+        held: Duration,
+    },
+    /// The borrow was refused because of a conflicting borrow.
+    Conflict,
+}
+
+/// A single borrow event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorrowEvent {
+    /// Unique id of the cell or lock within this process.
+    pub cell: u64,
+    /// Human readable label given to the cell, if any.
+    pub label: Option<&'static str>,
+    /// Shared or exclusive access.
+    pub kind: BorrowKind,
+    /// Acquire, release or conflict.
+    pub action: Action,
+}
+
+impl fmt::Display for BorrowEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            BorrowKind::Shared => "shared",
+            BorrowKind::Exclusive => "exclusive",
+        };
+        match self.label {
+            Some(label) => write!(f, "#{} ({label}) ", self.cell)?,
+            None => write!(f, "#{} ", self.cell)?,
+        }
+        match self.action {
+            Action::Acquire => write!(f, "{kind} acquire"),
+            Action::Release { held } => write!(f, "{kind} release after {held:?}"),
+            Action::Conflict => write!(f, "{kind} conflict"),
+        }
+    }
+}
+
+type Hook = Arc<dyn Fn(&BorrowEvent) + Send + Sync>;
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+struct Recording {
+    clock: Rc<dyn Clock>,
+    events: Vec<BorrowEvent>,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// Installs `hook` as the process-wide event callback, replacing any previous one.
+///
+/// The hook may itself borrow traced cells, or replace or clear the hook: no lock is held while
+/// it runs.
+/// ```
+/// use learning_cell::diagnostics;
+/// use learning_cell::impls::TracedRefCell;
+///
+/// // A one-shot hook, which removes itself on the first event.
+/// diagnostics::set_hook(|event| {
+///     println!("{event}");
+///     diagnostics::clear_hook();
+/// });
+/// let cell = TracedRefCell::new(1);
+/// drop(cell.borrow());
+/// ```
+pub fn set_hook(hook: impl Fn(&BorrowEvent) + Send + Sync + 'static) {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Removes the process-wide event callback.
+pub fn clear_hook() {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Runs `f` and returns the events emitted on this thread while it ran.
+///
+/// Recordings nest: an inner one takes the events emitted while it runs, then the outer one
+/// resumes, even if `f` panicked.
+/// ```
+/// use std::panic;
+/// use learning_cell::diagnostics;
+/// use learning_cell::impls::TracedRefCell;
+///
+/// let cell = TracedRefCell::new(1);
+/// let ((), events) = diagnostics::record(|| {
+///     let inner = panic::catch_unwind(|| diagnostics::record(|| panic!("lost")));
+///     assert!(inner.is_err());
+///     drop(cell.borrow());
+/// });
+/// assert_eq!(events.len(), 2);
+/// ```
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, Vec<BorrowEvent>) {
+    record_with_clock(Rc::new(SystemClock), f)
+}
+
+/// Like [`record`], but hold times are measured with `clock`.
+///
+/// Pass a [`FakeClock`](crate::util::FakeClock) to get deterministic hold times:
+/// ```
+/// use std::rc::Rc;
+/// use std::time::Duration;
+/// use learning_cell::diagnostics::{self, Action};
+/// use learning_cell::impls::TracedRefCell;
+/// use learning_cell::util::FakeClock;
+///
+/// let clock = Rc::new(FakeClock::new());
+/// let cell = TracedRefCell::new(0);
+///
+/// let (_, events) = diagnostics::record_with_clock(clock.clone(), || {
+///     let _guard = cell.borrow();
+///     clock.advance(Duration::from_secs(3));
+/// });
+///
+/// assert_eq!(events[1].action, Action::Release { held: Duration::from_secs(3) });
+/// ```
+pub fn record_with_clock<R>(clock: Rc<dyn Clock>, f: impl FnOnce() -> R) -> (R, Vec<BorrowEvent>) {
+    let previous = RECORDING
+        .with(|recording| recording.borrow_mut().replace(Recording { clock, events: Vec::new() }));
+    let mut restore = Restore { previous: Some(previous) };
+    let result = f();
+    let recording = restore.restore();
+    (result, recording.map(|r| r.events).unwrap_or_default())
+}
+
+/// Puts back the recording that was active before [`record_with_clock`], even if its closure
+/// panics.
+struct Restore {
+    /// The recording to put back, until it is.
+    previous: Option<Option<Recording>>,
+}
+
+impl Restore {
+    /// Reinstates the previous recording and returns the one it replaces.
+    fn restore(&mut self) -> Option<Recording> {
+        let previous = self.previous.take()?;
+        RECORDING
+            .try_with(|recording| std::mem::replace(&mut *recording.borrow_mut(), previous))
+            .ok()
+            .flatten()
+    }
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+/// Hands out a fresh cell id.
+pub(crate) fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Current time according to the active recording's clock, or the system clock.
+///
+/// Guards call this when they are dropped, which may happen while the thread's locals are being
+/// destroyed; the system clock stands in for a recording that is already gone.
+pub(crate) fn now() -> Instant {
+    RECORDING
+        .try_with(|recording| recording.borrow().as_ref().map(|r| r.clock.now()))
+        .ok()
+        .flatten()
+        .unwrap_or_else(Instant::now)
+}
+
+/// Delivers an event to the active recording and to the process-wide hook.
+pub(crate) fn emit(cell: u64, label: Option<&'static str>, kind: BorrowKind, action: Action) {
+    let event = BorrowEvent { cell, label, kind, action };
+
+    // Like `now`, this may run while the thread's locals are destroyed, with no recording left.
+    let _ = RECORDING.try_with(|recording| {
+        if let Some(recording) = recording.borrow_mut().as_mut() {
+            recording.events.push(event.clone());
+        }
+    });
+
+    #[cfg(feature = "tracing")]
+    trace(&event);
+
+    // Clone the hook out, so it runs without the lock and may call `set_hook` itself.
+    let hook = HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(hook) = hook {
+        hook(&event);
+    }
+}
+
+/// Opens the `tracing` span of a borrow that was just granted, see the
+/// [`tracing` feature](self#the-tracing-feature).
+#[cfg(feature = "tracing")]
+pub(crate) fn span(cell: u64, label: Option<&'static str>, kind: BorrowKind) -> tracing::Span {
+    tracing::trace_span!("borrow", cell, label, kind = ?kind)
+}
+
+/// Emits `event` to `tracing`, in the span of its borrow if there is one.
+#[cfg(feature = "tracing")]
+fn trace(event: &BorrowEvent) {
+    let BorrowEvent { cell, label, kind, action } = *event;
+    match action {
+        Action::Acquire => tracing::trace!(cell, label, kind = ?kind, "acquire"),
+        Action::Release { held } => {
+            tracing::trace!(cell, label, kind = ?kind, held = ?held, "release")
+        }
+        Action::Conflict => tracing::debug!(cell, label, kind = ?kind, "conflict"),
+    }
+}
@@ -0,0 +1,155 @@
+//! Borrow diagrams of the lessons.
+//!
+//! Each lesson about borrowing comes with a small demo built on the
+//! [traced cells](crate::impls::TracedRefCell): it replays what the lesson's examples do while
+//! [`diagnostics`] records the borrow events. [`render`] turns such an event log into a sequence
+//! diagram, in [Mermaid](https://mermaid.js.org/) or Graphviz DOT, that shows which borrows
+//! overlapped and which were refused.
+//! ```
+//! use learning_cell::curriculum::Chapter;
+//! use learning_cell::diagram::{self, Format};
+//!
+//! let events = diagram::demo(Chapter::RefCell).unwrap();
+//! let mermaid = diagram::render(&events, Format::Mermaid);
+//! assert!(mermaid.starts_with("sequenceDiagram\n"));
+//! assert!(mermaid.contains("exclusive borrow refused"));
+//! ```
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::curriculum::Chapter;
+use crate::diagnostics::{self, Action, BorrowEvent, BorrowKind};
+use crate::impls::{TracedMutex, TracedRefCell};
+use crate::lessons::LessonId;
+use crate::util::FakeClock;
+
+/// The output language of [`render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A Mermaid `sequenceDiagram`, rendered by GitHub, mdBook plugins and the Mermaid CLI.
+    Mermaid,
+    /// A Graphviz `digraph`, rendered with `dot -Tsvg`.
+    Dot,
+}
+
+impl Format {
+    /// The usual file extension of the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Mermaid => "mmd",
+            Format::Dot => "dot",
+        }
+    }
+}
+
+/// Runs the instrumented demo of `lesson` and returns its borrow events.
+///
+/// Returns `None` for lessons without borrows to show: `Cell` never hands out references, and
+/// `UnsafeCell` bypasses any tracking. The demo runs on a fake clock, so hold times, and with
+/// them the events, are the same on every run.
+pub fn demo(lesson: LessonId) -> Option<Vec<BorrowEvent>> {
+    let clock = Rc::new(FakeClock::new());
+    let tick = || clock.advance(Duration::from_millis(1));
+    let demo = || match lesson {
+        Chapter::RefCell => {
+            let special = TracedRefCell::with_label(42, "special");
+            let special_nocopy = TracedRefCell::with_label("hi".to_string(), "special_nocopy");
+            {
+                let first = special.borrow();
+                let second = special.borrow();
+                tick();
+                assert_eq!(*first, *second);
+            }
+            *special_nocopy.borrow_mut() = "bye".to_string();
+            let refer = special.borrow();
+            tick();
+            assert!(special.try_borrow_mut().is_err());
+            drop(refer);
+        }
+        Chapter::Rc => {
+            let node = Rc::new(TracedRefCell::with_label(vec![1], "node"));
+            let owner = Rc::clone(&node);
+            owner.borrow_mut().push(2);
+            let reader = node.borrow();
+            tick();
+            assert!(owner.try_borrow_mut().is_err());
+            drop(reader);
+        }
+        Chapter::Threads => {
+            let counter = TracedMutex::with_label(0, "counter");
+            for _ in 0..2 {
+                let mut guard = counter.lock().unwrap();
+                tick();
+                *guard += 1;
+            }
+        }
+        Chapter::Cell | Chapter::Unsafe => {}
+    };
+    let ((), events) = diagnostics::record_with_clock(clock.clone(), demo);
+    (!events.is_empty()).then_some(events)
+}
+
+/// Draws `events` as a sequence diagram in `format`.
+pub fn render(events: &[BorrowEvent], format: Format) -> String {
+    match format {
+        Format::Mermaid => mermaid(events),
+        Format::Dot => dot(events),
+    }
+}
+
+fn mermaid(events: &[BorrowEvent]) -> String {
+    let mut text = String::from("sequenceDiagram\n    participant program\n");
+    let mut cells: Vec<u64> = Vec::new();
+    for event in events {
+        if !cells.contains(&event.cell) {
+            cells.push(event.cell);
+            text.push_str(&format!("    participant {}\n", name(event)));
+        }
+    }
+    for event in events {
+        let cell = name(event);
+        let line = match event.action {
+            Action::Acquire => format!("program->>+{cell}: {}", method(event.kind)),
+            Action::Release { held } => format!("{cell}-->>-program: release after {held:?}"),
+            Action::Conflict => format!("program-x{cell}: {} refused", method(event.kind)),
+        };
+        text.push_str(&format!("    {line}\n"));
+    }
+    text
+}
+
+fn dot(events: &[BorrowEvent]) -> String {
+    let mut text = String::from("digraph borrows {\n    rankdir=TB;\n    node [shape=box];\n");
+    for (index, event) in events.iter().enumerate() {
+        let (label, color) = match event.action {
+            Action::Acquire => (method(event.kind).to_string(), "black"),
+            Action::Release { held } => (format!("release after {held:?}"), "gray"),
+            Action::Conflict => (format!("{} refused", method(event.kind)), "red"),
+        };
+        text.push_str(&format!(
+            "    e{index} [label=\"{}: {label}\", color={color}];\n",
+            name(event)
+        ));
+        if index > 0 {
+            text.push_str(&format!("    e{} -> e{index};\n", index - 1));
+        }
+    }
+    text.push_str("}\n");
+    text
+}
+
+/// The participant name of the event's cell: its label, or its id.
+fn name(event: &BorrowEvent) -> String {
+    match event.label {
+        Some(label) => label.to_string(),
+        None => format!("cell{}", event.cell),
+    }
+}
+
+fn method(kind: BorrowKind) -> &'static str {
+    match kind {
+        BorrowKind::Shared => "shared borrow",
+        BorrowKind::Exclusive => "exclusive borrow",
+    }
+}
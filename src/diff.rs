@@ -0,0 +1,66 @@
+//! Line diffs of expected and actual output.
+//!
+//! When an exercise is graded on the lines it produces (log messages, dispatched events), dumping
+//! both lists leaves the learner to play spot-the-difference. [`render`] instead shows the first
+//! line where they diverge, a little context before it, and a caret under the first character
+//! that differs:
+//! ```
+//! use learning_cell::diff;
+//!
+//! let expected = ["processing order 7", "charged 100 for order 7"];
+//! let actual = ["processing order 7", "charged 100 for order 8"];
+//! assert_eq!(
+//!     diff::render(&expected, &actual).unwrap(),
+//!     "first difference at line 2 (- expected, + actual):\n    \
+//!      processing order 7\n  \
+//!      - charged 100 for order 7\n  \
+//!      + charged 100 for order 8\n                          \
+//!      ^\n"
+//! );
+//! assert_eq!(diff::render(&expected, &expected), None);
+//! ```
+//! Lines starting with `- ` and `+ ` are the expected and actual line, so a terminal can color
+//! them. The [`check_lines!`](crate::check_lines) macro uses this in hidden tests.
+
+/// Lines of agreeing output shown before the first difference.
+const CONTEXT: usize = 2;
+
+/// Index of the first line where `expected` and `actual` differ, counting a missing line as a
+/// difference, or `None` if they are equal.
+pub fn first_divergence<E: AsRef<str>, A: AsRef<str>>(
+    expected: &[E],
+    actual: &[A],
+) -> Option<usize> {
+    let length = expected.len().max(actual.len());
+    (0..length).find(|&index| {
+        expected.get(index).map(AsRef::as_ref) != actual.get(index).map(AsRef::as_ref)
+    })
+}
+
+/// Describes where `actual` first differs from `expected`, or returns `None` if they are equal.
+///
+/// A side that ran out of lines shows as `(no more lines)`; lines left over after the first
+/// difference are counted, not shown.
+pub fn render<E: AsRef<str>, A: AsRef<str>>(expected: &[E], actual: &[A]) -> Option<String> {
+    let index = first_divergence(expected, actual)?;
+    let mut text = format!("first difference at line {} (- expected, + actual):\n", index + 1);
+    for line in &expected[index.saturating_sub(CONTEXT)..index] {
+        text.push_str(&format!("    {}\n", line.as_ref()));
+    }
+    let expected_line = expected.get(index).map(AsRef::as_ref);
+    let actual_line = actual.get(index).map(AsRef::as_ref);
+    text.push_str(&format!("  - {}\n", expected_line.unwrap_or("(no more lines)")));
+    text.push_str(&format!("  + {}\n", actual_line.unwrap_or("(no more lines)")));
+    if let (Some(expected_line), Some(actual_line)) = (expected_line, actual_line) {
+        let column = expected_line.chars().zip(actual_line.chars()).take_while(|(a, b)| a == b);
+        text.push_str(&format!("    {}^\n", " ".repeat(column.count())));
+    }
+    let remaining = |lines: usize| lines.saturating_sub(index + 1);
+    match (remaining(expected.len()), remaining(actual.len())) {
+        (0, 0) => {}
+        (expected, actual) => text.push_str(&format!(
+            "  ({expected} more expected and {actual} more actual lines after that)\n"
+        )),
+    }
+    Some(text)
+}
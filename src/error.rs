@@ -0,0 +1,222 @@
+//! One error type for the whole crate.
+//!
+//! Each module reports errors with a type of its own ([`SimError`], [`ManifestError`], ...) that
+//! says exactly what can go wrong there. Code using several modules at once, like the command
+//! line tool, can turn all of them into a [`LearningCellError`] with `?`, adding [`Context`] on
+//! the way:
+//! ```
+//! use std::error::Error;
+//! use learning_cell::error::{Context, LearningCellError, Result};
+//! use learning_cell::simulator::{BorrowSimulator, SimError};
+//!
+//! fn borrow_twice() -> Result<()> {
+//!     let mut cell = BorrowSimulator::new();
+//!     cell.borrow_mut()?;
+//!     cell.borrow().context("second borrow")?;
+//!     Ok(())
+//! }
+//!
+//! let err = borrow_twice().unwrap_err();
+//! assert_eq!(err.to_string(), "second borrow");
+//! assert_eq!(err.source().unwrap().to_string(), "already mutably borrowed");
+//! assert!(matches!(err.root(), LearningCellError::Simulator(SimError::AlreadyMutablyBorrowed)));
+//! ```
+
+use std::cell::{BorrowError, BorrowMutError};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::sync::PoisonError;
+
+use crate::grading::GradeReport;
+use crate::impls::NonReentrantError;
+use crate::locale::UnknownLocale;
+use crate::manifest::ManifestError;
+use crate::simulator::SimError;
+
+/// `Result` with a [`LearningCellError`].
+pub type Result<T, E = LearningCellError> = std::result::Result<T, E>;
+
+/// Any error of the crate.
+///
+/// The variants wrapping another error display as that error and share its
+/// [`source`](Error::source); [`Context`](LearningCellError::Context) displays its own message
+/// and has the error it wraps as its source.
+#[derive(Debug)]
+pub enum LearningCellError {
+    /// A `RefCell::try_borrow` failed.
+    Borrow(BorrowError),
+    /// A `RefCell::try_borrow_mut` failed.
+    BorrowMut(BorrowMutError),
+    /// A [`NonReentrantRefCell`](crate::impls::NonReentrantRefCell) refused a borrow.
+    NonReentrant(NonReentrantError),
+    /// A thread panicked while holding a lock. Holds the message of the `PoisonError`, which
+    /// cannot be kept itself since it owns the guard.
+    Poisoned(String),
+    /// The [borrow simulator](crate::simulator) refused an operation.
+    Simulator(SimError),
+    /// A submission failed grading. Holds the whole report, passing checks included.
+    Grading(GradeReport),
+    /// An exercise manifest could not be read.
+    Manifest(ManifestError),
+    /// There is no translation for the requested locale.
+    Locale(UnknownLocale),
+    /// Reading or writing a file, or running a program, failed.
+    Io(io::Error),
+    /// An error described by its message alone, such as a mistyped command line.
+    Message(String),
+    /// What was being done when `source` happened.
+    Context {
+        /// What was being done, such as "loading the progress file".
+        context: String,
+        /// The error that happened.
+        source: Box<LearningCellError>,
+    },
+}
+
+impl LearningCellError {
+    /// Wraps the error in a [`Context`](LearningCellError::Context).
+    pub fn context(self, context: impl Into<String>) -> LearningCellError {
+        LearningCellError::Context { context: context.into(), source: Box::new(self) }
+    }
+
+    /// The error under all the context.
+    pub fn root(&self) -> &LearningCellError {
+        match self {
+            LearningCellError::Context { source, .. } => source.root(),
+            err => err,
+        }
+    }
+
+    /// The error and its sources, from the outermost context in, joined by `": "`.
+    /// ```
+    /// use learning_cell::error::LearningCellError;
+    ///
+    /// let err = LearningCellError::from("no such file").context("reading the manifest");
+    /// assert_eq!(err.report(), "reading the manifest: no such file");
+    /// ```
+    pub fn report(&self) -> String {
+        let chain = std::iter::successors(Some(self as &dyn Error), |err| (*err).source());
+        chain.map(ToString::to_string).collect::<Vec<_>>().join(": ")
+    }
+}
+
+impl fmt::Display for LearningCellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LearningCellError::Borrow(err) => err.fmt(f),
+            LearningCellError::BorrowMut(err) => err.fmt(f),
+            LearningCellError::NonReentrant(err) => err.fmt(f),
+            LearningCellError::Poisoned(message) => f.write_str(message),
+            LearningCellError::Simulator(err) => err.fmt(f),
+            LearningCellError::Grading(report) => {
+                let failures: Vec<_> = report
+                    .failures()
+                    .map(|check| format!("{}: {}", check.name, check.outcome.as_ref().unwrap_err()))
+                    .collect();
+                write!(f, "grading failed ({})", failures.join("; "))
+            }
+            LearningCellError::Manifest(err) => err.fmt(f),
+            LearningCellError::Locale(err) => err.fmt(f),
+            LearningCellError::Io(err) => err.fmt(f),
+            LearningCellError::Message(message) => f.write_str(message),
+            LearningCellError::Context { context, .. } => f.write_str(context),
+        }
+    }
+}
+
+impl Error for LearningCellError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LearningCellError::Borrow(err) => err.source(),
+            LearningCellError::BorrowMut(err) => err.source(),
+            LearningCellError::NonReentrant(err) => err.source(),
+            LearningCellError::Simulator(err) => err.source(),
+            LearningCellError::Manifest(err) => err.source(),
+            LearningCellError::Locale(err) => err.source(),
+            LearningCellError::Io(err) => err.source(),
+            LearningCellError::Context { source, .. } => Some(source.as_ref()),
+            LearningCellError::Poisoned(_)
+            | LearningCellError::Grading(_)
+            | LearningCellError::Message(_) => None,
+        }
+    }
+}
+
+impl From<BorrowError> for LearningCellError {
+    fn from(err: BorrowError) -> Self {
+        LearningCellError::Borrow(err)
+    }
+}
+
+impl From<BorrowMutError> for LearningCellError {
+    fn from(err: BorrowMutError) -> Self {
+        LearningCellError::BorrowMut(err)
+    }
+}
+
+impl From<NonReentrantError> for LearningCellError {
+    fn from(err: NonReentrantError) -> Self {
+        LearningCellError::NonReentrant(err)
+    }
+}
+
+impl<T> From<PoisonError<T>> for LearningCellError {
+    fn from(err: PoisonError<T>) -> Self {
+        LearningCellError::Poisoned(err.to_string())
+    }
+}
+
+impl From<SimError> for LearningCellError {
+    fn from(err: SimError) -> Self {
+        LearningCellError::Simulator(err)
+    }
+}
+
+impl From<GradeReport> for LearningCellError {
+    fn from(report: GradeReport) -> Self {
+        LearningCellError::Grading(report)
+    }
+}
+
+impl From<ManifestError> for LearningCellError {
+    fn from(err: ManifestError) -> Self {
+        LearningCellError::Manifest(err)
+    }
+}
+
+impl From<UnknownLocale> for LearningCellError {
+    fn from(err: UnknownLocale) -> Self {
+        LearningCellError::Locale(err)
+    }
+}
+
+impl From<io::Error> for LearningCellError {
+    fn from(err: io::Error) -> Self {
+        LearningCellError::Io(err)
+    }
+}
+
+impl From<String> for LearningCellError {
+    fn from(message: String) -> Self {
+        LearningCellError::Message(message)
+    }
+}
+
+impl From<&str> for LearningCellError {
+    fn from(message: &str) -> Self {
+        LearningCellError::Message(message.to_string())
+    }
+}
+
+/// Adds context to the error of a `Result`, converting it into a [`LearningCellError`].
+pub trait Context<T> {
+    /// Wraps the error, if any, in a [`LearningCellError::Context`] saying what was being done.
+    fn context(self, context: impl Into<String>) -> Result<T>;
+}
+
+impl<T, E: Into<LearningCellError>> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|err| err.into().context(context))
+    }
+}
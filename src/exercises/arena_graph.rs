@@ -0,0 +1,344 @@
+//! Replacing `Rc<RefCell<Node>>` with an arena.
+//!
+//! The `shared_graph` exercise built a graph out of `Rc<RefCell<Node>>`s. It works, but every
+//! node is a separate allocation, every access goes through a borrow check at run time, and
+//! `double_weights` had to tiptoe around borrowing a node twice.
+//!
+//! Here the graph owns all of its nodes in a `Vec`, and edges refer to nodes by index. The
+//! graph is mutated through `&mut self` like any ordinary data structure: no `Rc`, no `RefCell`.
+//! Indices have one weakness: after a node is removed and its slot reused, an old index would
+//! silently point at the new node. A generational index fixes that. Each slot counts how many
+//! times it has been reused, and a `NodeId` only matches a slot of the same generation.
+//!
+//! The last test benchmarks your arena against the `Rc<RefCell<Node>>` version and prints both
+//! timings in the report.
+
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use super::shared_graph::{Node, NodeRef};
+use super::{note, Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::bench;
+
+/// Refers to a node of a [`Graph`]. Stays invalid once the node is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    index: u32,
+    generation: u32,
+}
+
+/// A node stored in the arena.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArenaNode {
+    /// Name of the node.
+    pub name: String,
+    /// Outgoing edges and their weights.
+    pub edges: Vec<(NodeId, u32)>,
+}
+
+/// One entry of the arena.
+// Only the methods you write read the slots.
+#[cfg_attr(not(feature = "solutions"), allow(dead_code))]
+#[derive(Debug, Default)]
+struct Slot {
+    /// Bumped every time the slot's node is removed.
+    generation: u32,
+    node: Option<ArenaNode>,
+}
+
+/// A directed graph owning its nodes.
+#[derive(Debug, Default)]
+pub struct Graph {
+    slots: Vec<Slot>,
+    /// Indices of the empty slots, reused before the arena grows.
+    free: Vec<u32>,
+}
+
+impl Graph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Whether the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+impl Graph {
+    /// Adds a node without edges, reusing a free slot if there is one.
+    pub fn add_node(&mut self, name: &str) -> NodeId {
+        let _ = name;
+        todo!()
+    }
+
+    /// Removes the node `id` and returns it, or `None` if `id` is stale.
+    pub fn remove_node(&mut self, id: NodeId) -> Option<ArenaNode> {
+        let _ = id;
+        todo!()
+    }
+
+    /// The node `id`, or `None` if `id` is stale.
+    pub fn get(&self, id: NodeId) -> Option<&ArenaNode> {
+        let _ = id;
+        todo!()
+    }
+
+    /// Adds an edge from `from` to `to`. Returns `false`, changing nothing, if either is stale.
+    pub fn connect(&mut self, from: NodeId, to: NodeId, weight: u32) -> bool {
+        let _ = (from, to, weight);
+        todo!()
+    }
+
+    /// The names of the live nodes `id` has an edge to, in insertion order.
+    pub fn neighbors(&self, id: NodeId) -> Vec<String> {
+        let _ = id;
+        todo!()
+    }
+
+    /// Doubles the weight of every edge reachable from `start`, visiting each node once.
+    pub fn double_weights(&mut self, start: NodeId) {
+        let _ = start;
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{ArenaNode, Graph, NodeId, Slot};
+
+    impl Graph {
+        fn slot(&self, id: NodeId) -> Option<&Slot> {
+            self.slots.get(id.index as usize).filter(|slot| slot.generation == id.generation)
+        }
+
+        fn get_mut(&mut self, id: NodeId) -> Option<&mut ArenaNode> {
+            let slot = self.slots.get_mut(id.index as usize)?;
+            if slot.generation != id.generation {
+                return None;
+            }
+            slot.node.as_mut()
+        }
+
+        pub fn add_node(&mut self, name: &str) -> NodeId {
+            let node = ArenaNode { name: name.to_string(), edges: Vec::new() };
+            let index = match self.free.pop() {
+                Some(index) => index,
+                None => {
+                    self.slots.push(Slot::default());
+                    (self.slots.len() - 1) as u32
+                }
+            };
+            let slot = &mut self.slots[index as usize];
+            slot.node = Some(node);
+            NodeId { index, generation: slot.generation }
+        }
+
+        pub fn remove_node(&mut self, id: NodeId) -> Option<ArenaNode> {
+            let slot = self.slots.get_mut(id.index as usize)?;
+            if slot.generation != id.generation {
+                return None;
+            }
+            let node = slot.node.take()?;
+            slot.generation += 1;
+            self.free.push(id.index);
+            Some(node)
+        }
+
+        pub fn get(&self, id: NodeId) -> Option<&ArenaNode> {
+            self.slot(id)?.node.as_ref()
+        }
+
+        pub fn connect(&mut self, from: NodeId, to: NodeId, weight: u32) -> bool {
+            if self.get(to).is_none() {
+                return false;
+            }
+            match self.get_mut(from) {
+                Some(node) => {
+                    node.edges.push((to, weight));
+                    true
+                }
+                None => false,
+            }
+        }
+
+        pub fn neighbors(&self, id: NodeId) -> Vec<String> {
+            let Some(node) = self.get(id) else { return Vec::new() };
+            node.edges.iter().filter_map(|(to, _)| self.get(*to)).map(|n| n.name.clone()).collect()
+        }
+
+        pub fn double_weights(&mut self, start: NodeId) {
+            let mut visited = vec![false; self.slots.len()];
+            let mut stack = vec![start];
+            while let Some(id) = stack.pop() {
+                let Some(node) = self.get_mut(id) else { continue };
+                if std::mem::replace(&mut visited[id.index as usize], true) {
+                    continue;
+                }
+                for (to, weight) in &mut node.edges {
+                    *weight *= 2;
+                    stack.push(*to);
+                }
+            }
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "arena_graph",
+    title: "Replace `Rc<RefCell>` with an arena and indices",
+    chapter: Chapter::Rc,
+    difficulty: Difficulty::Advanced,
+    tags: &["arenas", "graphs", "performance"],
+    description: "Re-implement the shared graph as a `Vec`-backed arena addressed by \
+                  generational `NodeId`s, then compare its speed with the `Rc<RefCell>` version.",
+    tests: &[
+        Test { name: "build", run: build },
+        Test { name: "stale_ids", run: stale_ids },
+        Test { name: "slots_are_reused", run: slots_are_reused },
+        Test { name: "double_weights_with_cycles", run: double_weights_with_cycles },
+        Test { name: "benchmark", run: benchmark },
+    ],
+    hints: &[
+        Hint(
+            "A `NodeId` is valid when `slots[id.index]` exists, has the same `generation` and \
+              still holds a node. Write one private lookup helper and use it everywhere.",
+        ),
+        Hint(
+            "`remove_node` takes the node out of its slot, bumps the slot's generation so every \
+              outstanding `NodeId` goes stale, and pushes the index onto `free`. `add_node` pops \
+              from `free` before growing `slots`.",
+        ),
+        Hint(
+            "`double_weights` can use an explicit stack of `NodeId`s and a `Vec<bool>` indexed by \
+              slot. With `&mut self` there is no borrow to trip over: take `&mut` the node, update \
+              its edges, push their targets.",
+        ),
+    ],
+};
+
+fn build() {
+    let mut graph = Graph::new();
+    let (a, b, c) = (graph.add_node("a"), graph.add_node("b"), graph.add_node("c"));
+    check!(graph.connect(a, b, 1); see "arena_graph#hints");
+    check!(graph.connect(a, c, 2); see "arena_graph#hints");
+    check!(graph.connect(c, c, 3); see "arena_graph#hints");
+    check!(graph.len(), 3; see "arena_graph#hints");
+    check!(graph.neighbors(a), ["b", "c"]; see "arena_graph#hints");
+    check!(graph.neighbors(c), ["c"]; see "arena_graph#hints");
+    check!(graph.get(b).map(|node| node.name.as_str()), Some("b"); see "arena_graph#hints");
+}
+
+fn stale_ids() {
+    let mut graph = Graph::new();
+    let (a, b) = (graph.add_node("a"), graph.add_node("b"));
+    graph.connect(a, b, 1);
+    check!(
+        graph.remove_node(b).map(|node| node.name),
+        Some("b".to_string());
+        see "arena_graph#hints"
+    );
+    check!(graph.remove_node(b), None; see "arena_graph#hints");
+    check!(graph.get(b), None; see "arena_graph#hints");
+    check!(!graph.connect(a, b, 1); see "arena_graph#hints");
+    check!(!graph.connect(b, a, 1); see "arena_graph#hints");
+    check!(
+        graph.neighbors(a).is_empty();
+        see "arena_graph#hints", "edges to removed nodes must be skipped"
+    );
+    check!(graph.len(), 1; see "arena_graph#hints");
+}
+
+fn slots_are_reused() {
+    let mut graph = Graph::new();
+    let old = graph.add_node("old");
+    graph.remove_node(old);
+    let new = graph.add_node("new");
+    check!(old != new; see "arena_graph#hints");
+    check!(
+        graph.get(old),
+        None;
+        see "arena_graph#hints", "a stale id must not reach the node in its reused slot"
+    );
+    check!(graph.get(new).map(|node| node.name.as_str()), Some("new"); see "arena_graph#hints");
+    check!(graph.slots.len(), 1; see "arena_graph#hints", "the free slot should have been reused");
+}
+
+fn double_weights_with_cycles() {
+    let mut graph = Graph::new();
+    let (a, b, c, lone) =
+        (graph.add_node("a"), graph.add_node("b"), graph.add_node("c"), graph.add_node("lone"));
+    graph.connect(a, b, 1);
+    graph.connect(b, c, 2);
+    graph.connect(c, a, 3);
+    graph.connect(b, b, 4);
+    graph.connect(lone, a, 5);
+    graph.double_weights(a);
+
+    let weights = |id| graph.get(id).unwrap().edges.iter().map(|(_, w)| *w).collect::<Vec<_>>();
+    check!(weights(a), [2]; see "arena_graph#hints");
+    check!(weights(b), [4, 8]; see "arena_graph#hints");
+    check!(weights(c), [6]; see "arena_graph#hints");
+    check!(weights(lone), [5]; see "arena_graph#hints", "`lone` is not reachable from `a`");
+}
+
+/// Number of nodes in the benchmark graphs. Every node has an edge to the next three.
+const BENCH_NODES: usize = 2_000;
+
+/// `double_weights` for the `Rc<RefCell<Node>>` graph of the `shared_graph` exercise.
+fn rc_double_weights(start: &NodeRef) {
+    let mut visited = HashSet::new();
+    let mut stack = vec![Rc::clone(start)];
+    while let Some(node) = stack.pop() {
+        if !visited.insert(Rc::as_ptr(&node)) {
+            continue;
+        }
+        for (to, weight) in &mut node.borrow_mut().edges {
+            *weight *= 2;
+            stack.push(Rc::clone(to));
+        }
+    }
+}
+
+fn benchmark() {
+    let mut arena = Graph::new();
+    let ids: Vec<_> = (0..BENCH_NODES).map(|i| arena.add_node(&i.to_string())).collect();
+    for (i, id) in ids.iter().enumerate() {
+        for step in 1..=3 {
+            arena.connect(*id, ids[(i + step) % BENCH_NODES], 1);
+        }
+    }
+
+    let shared: Vec<NodeRef> = (0..BENCH_NODES).map(|i| Node::new(&i.to_string())).collect();
+    for (i, node) in shared.iter().enumerate() {
+        for step in 1..=3 {
+            let to = Rc::clone(&shared[(i + step) % BENCH_NODES]);
+            node.borrow_mut().edges.push((to, 1));
+        }
+    }
+
+    let arena_time = bench(10, || arena.double_weights(ids[0]));
+    let rc_time = bench(10, || rc_double_weights(&shared[0]));
+    note(format!("arena:          {arena_time}"));
+    note(format!("Rc<RefCell<_>>: {rc_time}"));
+    note(format!("the arena is {:.1}x as fast", arena_time.speedup_over(&rc_time)));
+
+    check!(arena.get(ids[0]).unwrap().edges[0].1, 1 << 10; see "arena_graph#hints");
+    // The nodes form a ring of `Rc`s: clear the edges so they are freed.
+    for node in &shared {
+        node.borrow_mut().edges.clear();
+    }
+}
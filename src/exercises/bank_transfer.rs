@@ -0,0 +1,221 @@
+//! Bank transfer consistency.
+//!
+//! Every account keeps its balance in a `RefCell<i64>`, so `transfer` can move money through a
+//! shared `&Bank`. The provided `transfer` works for the happy path, but it debits the source
+//! account before it knows whether the credit can succeed. When something goes wrong halfway
+//! through, money disappears. It also panics when asked to transfer from an account to itself.
+//!
+//! Make `transfer` all-or-nothing: on error, no balance may have changed.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+/// Why a transfer was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferError {
+    /// There is no account with this index.
+    UnknownAccount(usize),
+    /// The amount is zero or negative.
+    InvalidAmount(i64),
+    /// The source account does not hold enough money.
+    InsufficientFunds {
+        /// Balance of the source account.
+        available: i64,
+        /// Amount that was requested.
+        requested: i64,
+    },
+    /// The destination balance would overflow.
+    Overflow,
+    /// Source and destination are the same account.
+    SameAccount,
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownAccount(index) => write!(f, "no account #{index}"),
+            Self::InvalidAmount(amount) => write!(f, "invalid amount {amount}"),
+            Self::InsufficientFunds { available, requested } => {
+                write!(f, "insufficient funds: {available} available, {requested} requested")
+            }
+            Self::Overflow => f.write_str("destination balance would overflow"),
+            Self::SameAccount => f.write_str("cannot transfer to the same account"),
+        }
+    }
+}
+
+impl std::error::Error for TransferError {}
+
+/// An account.
+#[derive(Debug)]
+pub struct Account {
+    /// Current balance.
+    pub balance: RefCell<i64>,
+}
+
+/// A set of accounts, addressed by index.
+#[derive(Debug)]
+pub struct Bank {
+    /// The accounts.
+    pub accounts: Vec<Account>,
+}
+
+impl Bank {
+    /// Opens one account per entry of `balances`.
+    pub fn new(balances: &[i64]) -> Self {
+        let accounts = balances.iter().map(|b| Account { balance: RefCell::new(*b) }).collect();
+        Self { accounts }
+    }
+
+    /// Balance of account `index`.
+    pub fn balance(&self, index: usize) -> Option<i64> {
+        self.accounts.get(index).map(|account| *account.balance.borrow())
+    }
+
+    /// Balances of every account.
+    pub fn balances(&self) -> Vec<i64> {
+        self.accounts.iter().map(|account| *account.balance.borrow()).collect()
+    }
+
+    fn account(&self, index: usize) -> Result<&Account, TransferError> {
+        self.accounts.get(index).ok_or(TransferError::UnknownAccount(index))
+    }
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+impl Bank {
+    /// Moves `amount` from account `from` to account `to`. On error, nothing changes.
+    pub fn transfer(&self, from: usize, to: usize, amount: i64) -> Result<(), TransferError> {
+        let mut source = self.account(from)?.balance.borrow_mut();
+        *source -= amount;
+
+        let mut destination = self.account(to)?.balance.borrow_mut();
+        *destination = destination.checked_add(amount).ok_or(TransferError::Overflow)?;
+
+        if amount <= 0 {
+            return Err(TransferError::InvalidAmount(amount));
+        }
+        if *source < 0 {
+            return Err(TransferError::InsufficientFunds {
+                available: *source + amount,
+                requested: amount,
+            });
+        }
+        Ok(())
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{Bank, TransferError};
+
+    impl Bank {
+        pub fn transfer(&self, from: usize, to: usize, amount: i64) -> Result<(), TransferError> {
+            let source = self.account(from)?;
+            let destination = self.account(to)?;
+            if from == to {
+                return Err(TransferError::SameAccount);
+            }
+            if amount <= 0 {
+                return Err(TransferError::InvalidAmount(amount));
+            }
+
+            let mut source = source.balance.borrow_mut();
+            let mut destination = destination.balance.borrow_mut();
+            if *source < amount {
+                return Err(TransferError::InsufficientFunds {
+                    available: *source,
+                    requested: amount,
+                });
+            }
+            let credited = destination.checked_add(amount).ok_or(TransferError::Overflow)?;
+
+            // Every check passed: only now touch the balances.
+            *source -= amount;
+            *destination = credited;
+            Ok(())
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "bank_transfer",
+    title: "Bank transfer consistency",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Core,
+    tags: &["refcell", "consistency", "errors"],
+    description: "Make `Bank::transfer` all-or-nothing: validate everything before touching a \
+                  balance, and report the right error.",
+    tests: &[
+        Test { name: "successful_transfer", run: successful_transfer },
+        Test { name: "insufficient_funds", run: insufficient_funds },
+        Test { name: "invalid_amount", run: invalid_amount },
+        Test { name: "unknown_destination", run: unknown_destination },
+        Test { name: "overflow", run: overflow },
+        Test { name: "same_account", run: same_account },
+    ],
+    hints: &[
+        Hint(
+            "Every early return after `*source -= amount` leaves the bank inconsistent. Move all \
+              the checks before the first write.",
+        ),
+        Hint(
+            "Transferring from an account to itself borrows the same `RefCell` mutably twice. \
+              Compare the indices before borrowing anything.",
+        ),
+        Hint(
+            "Order: look up both accounts, reject `from == to` and `amount <= 0`, borrow both \
+              balances, check funds, compute `checked_add`, then write both balances.",
+        ),
+    ],
+};
+
+fn successful_transfer() {
+    let bank = Bank::new(&[100, 50]);
+    check!(bank.transfer(0, 1, 30), Ok(()); see "refcell#example-7");
+    check!(bank.balances(), [70, 80]; see "refcell#example-7");
+}
+
+fn insufficient_funds() {
+    let bank = Bank::new(&[10, 0]);
+    let err = bank.transfer(0, 1, 25).unwrap_err();
+    check!(
+        err,
+        TransferError::InsufficientFunds { available: 10, requested: 25 };
+        see "refcell#example-7"
+    );
+    check!(bank.balances(), [10, 0]; see "refcell#example-7");
+}
+
+fn invalid_amount() {
+    let bank = Bank::new(&[10, 0]);
+    check!(bank.transfer(0, 1, -5), Err(TransferError::InvalidAmount(-5)); see "refcell#example-7");
+    check!(bank.transfer(0, 1, 0), Err(TransferError::InvalidAmount(0)); see "refcell#example-7");
+    check!(bank.balances(), [10, 0]; see "refcell#example-7");
+}
+
+fn unknown_destination() {
+    let bank = Bank::new(&[10]);
+    check!(bank.transfer(0, 3, 5), Err(TransferError::UnknownAccount(3)); see "refcell#example-7");
+    check!(bank.balances(), [10]; see "refcell#example-7");
+}
+
+fn overflow() {
+    let bank = Bank::new(&[10, i64::MAX]);
+    check!(bank.transfer(0, 1, 5), Err(TransferError::Overflow); see "refcell#example-7");
+    check!(bank.balances(), [10, i64::MAX]; see "refcell#example-7");
+}
+
+fn same_account() {
+    let bank = Bank::new(&[10]);
+    check!(bank.transfer(0, 0, 5), Err(TransferError::SameAccount); see "refcell#example-7");
+    check!(bank.balances(), [10]; see "refcell#example-7");
+}
@@ -0,0 +1,217 @@
+//! A buffered logger.
+//!
+//! Writing every message straight to its destination is slow, so `BufferedLogger` keeps them in
+//! interior storage and only hands them over when `flush` is called. Both `log` and `flush` take
+//! `&self`: the logger is shared by every part of the program that logs.
+//!
+//! The destination is a closure passed to `flush`, and that is where the hazards are. The sink
+//! may itself log ("wrote 3 lines"), or even call `flush` again. `for_each` has the same
+//! problem: its callback may log or flush while the buffer is being iterated. None of this may
+//! panic, and no message may be lost or delivered twice.
+//!
+//! It implements the [`Logger`] trait from the `mock_logger` exercise.
+
+use std::cell::RefCell;
+
+use super::mock_logger::Logger;
+use super::{Hint, StaticExercise, Test};
+use crate::curriculum::{Chapter, Difficulty};
+use crate::{check, check_lines};
+
+// BEGIN EXERCISE
+/// A logger that keeps messages until they are flushed.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Default)]
+pub struct BufferedLogger {
+    buffer: RefCell<Vec<String>>,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl BufferedLogger {
+    /// Creates a logger with an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of messages waiting to be flushed.
+    pub fn pending(&self) -> usize {
+        self.buffer.borrow().len()
+    }
+
+    /// Hands every buffered message to `sink`, oldest first, and empties the buffer.
+    ///
+    /// Messages logged by `sink` itself stay buffered for the next flush.
+    pub fn flush(&self, sink: impl FnMut(&str)) {
+        let _ = sink;
+        todo!()
+    }
+
+    /// Calls `f` with every buffered message, oldest first, without removing them.
+    ///
+    /// `f` may log or flush; it only sees the messages that were buffered when `for_each` was
+    /// called.
+    pub fn for_each(&self, f: impl FnMut(&str)) {
+        let _ = f;
+        todo!()
+    }
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Logger for BufferedLogger {
+    fn log(&self, message: &str) {
+        let _ = message;
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::BufferedLogger;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{Logger, RefCell};
+
+    #[derive(Debug, Default)]
+    pub struct BufferedLogger {
+        buffer: RefCell<Vec<String>>,
+    }
+
+    impl BufferedLogger {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn pending(&self) -> usize {
+            self.buffer.borrow().len()
+        }
+
+        pub fn flush(&self, mut sink: impl FnMut(&str)) {
+            // Take the messages out first: the buffer is empty and unborrowed while the sink
+            // runs, so whatever the sink logs lands in it for the next flush.
+            let messages = self.buffer.take();
+            for message in &messages {
+                sink(message);
+            }
+        }
+
+        pub fn for_each(&self, mut f: impl FnMut(&str)) {
+            let messages = self.buffer.borrow().clone();
+            for message in &messages {
+                f(message);
+            }
+        }
+    }
+
+    impl Logger for BufferedLogger {
+        fn log(&self, message: &str) {
+            self.buffer.borrow_mut().push(message.to_string());
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "buffered_logger",
+    title: "Logger with buffered interior state",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Core,
+    tags: &["refcell", "reentrancy"],
+    description: "Implement `BufferedLogger`: `log` appends to a `RefCell<Vec<String>>`, `flush` \
+                  drains it into a sink that may itself log or flush.",
+    tests: &[
+        Test { name: "log_then_flush", run: log_then_flush },
+        Test { name: "sink_logs", run: sink_logs },
+        Test { name: "sink_flushes", run: sink_flushes },
+        Test { name: "for_each_keeps_messages", run: for_each_keeps_messages },
+        Test {
+            name: "for_each_callback_logs_and_flushes",
+            run: for_each_callback_logs_and_flushes,
+        },
+    ],
+    hints: &[
+        Hint(
+            "A `for` loop over `self.buffer.borrow()` (or `borrow_mut().drain(..)`) keeps the \
+              buffer borrowed for the whole loop, including while the callback runs.",
+        ),
+        Hint(
+            "`flush` can move the messages out of the buffer before calling the sink: \
+              `RefCell::take` swaps in an empty `Vec` and returns the old one.",
+        ),
+        Hint(
+            "`for_each` must not remove anything, so iterate over a clone of the buffer instead \
+              of the buffer itself.",
+        ),
+    ],
+};
+
+fn collect(logger: &BufferedLogger) -> Vec<String> {
+    let mut lines = Vec::new();
+    logger.flush(|line| lines.push(line.to_string()));
+    lines
+}
+
+fn log_then_flush() {
+    let logger = BufferedLogger::new();
+    logger.log("one");
+    logger.log("two");
+    check!(logger.pending(), 2; see "refcell#example-6");
+    check_lines!(collect(&logger), ["one", "two"]; see "refcell#example-6");
+    check!(logger.pending(), 0; see "refcell#example-6");
+    check!(collect(&logger).is_empty(); see "refcell#example-6");
+}
+
+fn sink_logs() {
+    let logger = BufferedLogger::new();
+    logger.log("a");
+    logger.log("b");
+    let mut written = Vec::new();
+    logger.flush(|line| {
+        written.push(line.to_string());
+        logger.log(&format!("wrote {line}"));
+    });
+    check_lines!(written, ["a", "b"]; see "refcell#example-6");
+    check_lines!(collect(&logger), ["wrote a", "wrote b"]; see "refcell#example-6");
+}
+
+fn sink_flushes() {
+    let logger = BufferedLogger::new();
+    logger.log("outer");
+    let mut written = Vec::new();
+    logger.flush(|line| {
+        written.push(line.to_string());
+        logger.log("inner");
+        logger.flush(|line| written.push(format!("nested {line}")));
+    });
+    check_lines!(written, ["outer", "nested inner"]; see "refcell#example-6");
+    check!(logger.pending(), 0; see "refcell#example-6");
+}
+
+fn for_each_keeps_messages() {
+    let logger = BufferedLogger::new();
+    logger.log("x");
+    logger.log("y");
+    let mut seen = Vec::new();
+    logger.for_each(|line| seen.push(line.to_string()));
+    check_lines!(seen, ["x", "y"]; see "refcell#example-6");
+    check!(logger.pending(), 2; see "refcell#example-6");
+}
+
+fn for_each_callback_logs_and_flushes() {
+    let logger = BufferedLogger::new();
+    logger.log("first");
+    logger.log("second");
+    let mut seen = Vec::new();
+    let mut flushed = Vec::new();
+    logger.for_each(|line| {
+        seen.push(line.to_string());
+        logger.log(&format!("saw {line}"));
+        if line == "first" {
+            logger.flush(|line| flushed.push(line.to_string()));
+        }
+    });
+    check_lines!(seen, ["first", "second"]; see "refcell#example-6");
+    check_lines!(flushed, ["first", "second", "saw first"]; see "refcell#example-6");
+    check_lines!(collect(&logger), ["saw second"]; see "refcell#example-6");
+}
@@ -0,0 +1,90 @@
+//! Reading and writing a `Cell`.
+//!
+//! See the [`Cell`](crate::Cell) lesson.
+
+use std::cell::Cell;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+// BEGIN EXERCISE
+/// Increments the counter by one and returns the value it had *before* the increment.
+///
+/// Note that `counter` is a shared reference.
+#[cfg(not(feature = "solutions"))]
+pub fn bump(counter: &Cell<u32>) -> u32 {
+    let _ = counter;
+    todo!("read the value with `get`, then `set` it one higher")
+}
+
+/// Appends `suffix` to the string stored in `text`.
+///
+/// `String` is not `Copy`, so `get` is not available.
+#[cfg(not(feature = "solutions"))]
+pub fn append(text: &Cell<String>, suffix: &str) {
+    let _ = (text, suffix);
+    todo!("move the string out with `take`, push onto it and put it back with `set`")
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::{append, bump};
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::cell::Cell;
+
+    pub fn bump(counter: &Cell<u32>) -> u32 {
+        let previous = counter.get();
+        counter.set(previous + 1);
+        previous
+    }
+
+    pub fn append(text: &Cell<String>, suffix: &str) {
+        let mut string = text.take();
+        string.push_str(suffix);
+        text.set(string);
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "cell_intro",
+    title: "Reading and writing a Cell",
+    chapter: Chapter::Cell,
+    difficulty: Difficulty::Intro,
+    tags: &["cell", "copy"],
+    description: "Implement `bump` and `append` using only a shared reference to the Cell.",
+    tests: &[
+        Test { name: "bump_returns_previous_value", run: bump_returns_previous_value },
+        Test { name: "bump_twice", run: bump_twice },
+        Test { name: "append_keeps_existing_text", run: append_keeps_existing_text },
+    ],
+    hints: &[
+        Hint("`Cell<u32>` is `Copy`-friendly: look at `get` and `set`."),
+        Hint("`Cell<String>` has no `get`, but `take` leaves a `String::default()` behind."),
+        Hint("`let mut s = text.take(); s.push_str(suffix); text.set(s);`"),
+    ],
+};
+
+fn bump_returns_previous_value() {
+    let counter = Cell::new(41);
+    check!(bump(&counter), 41; see "cell#example-3");
+    check!(counter.get(), 42; see "cell#example-3");
+}
+
+fn bump_twice() {
+    let counter = Cell::new(0);
+    let shared = &counter;
+    bump(shared);
+    bump(shared);
+    check!(counter.get(), 2; see "cell#example-3");
+}
+
+fn append_keeps_existing_text() {
+    let text = Cell::new("hello".to_string());
+    append(&text, ", world");
+    check!(text.take(), "hello, world"; see "cell#example-5");
+}
@@ -0,0 +1,174 @@
+//! The split-borrow problem, solved with `Cell` slices.
+//!
+//! Looking at two neighboring elements of a `&mut [T]` at the same time is surprisingly awkward.
+//! `slice.windows(2)` only hands out shared references, and two `&mut` into the same slice need
+//! index juggling with `split_at_mut`.
+//!
+//! `Cell` has a neat way out. `Cell::from_mut` turns a `&mut [T]` into a `&Cell<[T]>`, and
+//! `as_slice_of_cells` turns that into a `&[Cell<T>]`. Now `windows(2)` yields pairs of shared
+//! references through which both elements can be read *and* written.
+//!
+//! Implement both functions that way. They must call the provided [`compare_and_swap`] and
+//! [`add_into`] helpers, which only accept `&Cell`s, for every adjacent pair. The tests count
+//! the calls and reject `split_at_mut`.
+
+use std::cell::Cell;
+
+use super::{active_code, Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+thread_local! {
+    /// How many times the helpers below were called on this thread.
+    static HELPER_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count_call() {
+    HELPER_CALLS.with(|calls| calls.set(calls.get() + 1));
+}
+
+/// Swaps `a` and `b` if `a` is greater. Returns whether they were swapped.
+pub fn compare_and_swap(a: &Cell<i32>, b: &Cell<i32>) -> bool {
+    count_call();
+    if a.get() > b.get() {
+        a.swap(b);
+        true
+    } else {
+        false
+    }
+}
+
+/// Adds the value of `from` to `to`.
+pub fn add_into(from: &Cell<i64>, to: &Cell<i64>) {
+    count_call();
+    to.set(to.get() + from.get());
+}
+
+// BEGIN EXERCISE
+/// Runs one bubble sort pass over `values`: calls [`compare_and_swap`] on every adjacent pair,
+/// left to right. Returns the number of swaps.
+#[cfg(not(feature = "solutions"))]
+pub fn bubble_pass(values: &mut [i32]) -> usize {
+    let _ = values;
+    todo!()
+}
+
+/// Replaces every element with the sum of itself and all the elements before it, using
+/// [`add_into`] on every adjacent pair, left to right.
+#[cfg(not(feature = "solutions"))]
+pub fn prefix_sums(values: &mut [i64]) {
+    let _ = values;
+    todo!()
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::{bubble_pass, prefix_sums};
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{add_into, compare_and_swap, Cell};
+
+    pub fn bubble_pass(values: &mut [i32]) -> usize {
+        let cells = Cell::from_mut(values).as_slice_of_cells();
+        cells.windows(2).filter(|pair| compare_and_swap(&pair[0], &pair[1])).count()
+    }
+
+    pub fn prefix_sums(values: &mut [i64]) {
+        let cells = Cell::from_mut(values).as_slice_of_cells();
+        for pair in cells.windows(2) {
+            add_into(&pair[0], &pair[1]);
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "cell_slices",
+    title: "The split-borrow problem solved with `Cell` slices",
+    chapter: Chapter::Cell,
+    difficulty: Difficulty::Advanced,
+    tags: &["cell", "slices", "borrowing"],
+    description: "Implement `bubble_pass` and `prefix_sums` over adjacent pairs using \
+                  `Cell::from_mut` and `as_slice_of_cells` instead of `split_at_mut`.",
+    tests: &[
+        Test { name: "bubble_pass_swaps", run: bubble_pass_swaps },
+        Test { name: "bubble_pass_sorts", run: bubble_pass_sorts },
+        Test { name: "prefix_sums_values", run: prefix_sums_values },
+        Test { name: "every_pair_uses_a_helper", run: every_pair_uses_a_helper },
+        Test { name: "no_split_at_mut", run: no_split_at_mut },
+    ],
+    hints: &[
+        Hint(
+            "`Cell::from_mut(values)` gives a `&Cell<[i32]>`; call `.as_slice_of_cells()` on it \
+              to get a `&[Cell<i32>]`.",
+        ),
+        Hint(
+            "`windows(2)` on the slice of cells yields `&[Cell<i32>]` of length two: pass \
+              `&pair[0]` and `&pair[1]` to the helper.",
+        ),
+        Hint(
+            "`cells.windows(2).filter(|pair| compare_and_swap(&pair[0], &pair[1])).count()` is \
+              the whole bubble pass.",
+        ),
+    ],
+};
+
+fn helper_calls() -> usize {
+    HELPER_CALLS.with(Cell::get)
+}
+
+fn bubble_pass_swaps() {
+    let mut values = [3, 1, 2];
+    check!(bubble_pass(&mut values), 2; see "cell_slices#hints");
+    check!(values, [1, 2, 3]; see "cell_slices#hints");
+    check!(bubble_pass(&mut values), 0; see "cell_slices#hints");
+}
+
+fn bubble_pass_sorts() {
+    let mut values = [5, -1, 4, 4, 0, 9, 2];
+    while bubble_pass(&mut values) > 0 {}
+    check!(values, [-1, 0, 2, 4, 4, 5, 9]; see "cell_slices#hints");
+    check!(bubble_pass(&mut []), 0; see "cell_slices#hints");
+}
+
+fn prefix_sums_values() {
+    let mut values = [1, 2, 3, 4];
+    prefix_sums(&mut values);
+    check!(values, [1, 3, 6, 10]; see "cell_slices#hints");
+    let mut single = [7];
+    prefix_sums(&mut single);
+    check!(single, [7]; see "cell_slices#hints");
+}
+
+fn every_pair_uses_a_helper() {
+    let before = helper_calls();
+    bubble_pass(&mut [4, 3, 2, 1, 0]);
+    check!(
+        helper_calls() - before,
+        4;
+        see "cell_slices#hints", "`bubble_pass` must call `compare_and_swap` per pair"
+    );
+
+    let before = helper_calls();
+    prefix_sums(&mut [1, 1, 1]);
+    check!(
+        helper_calls() - before,
+        2;
+        see "cell_slices#hints", "`prefix_sums` must call `add_into` per pair"
+    );
+}
+
+fn no_split_at_mut() {
+    let code = active_code(include_str!("cell_slices.rs"));
+    check!(
+        !code.contains("split_at_mut");
+        see "cell_slices#hints", "use `Cell` slices, not `split_at_mut`"
+    );
+    check!(!code.contains("unsafe"); see "cell_slices#hints", "the solution must not use `unsafe`");
+    check!(
+        code.contains("as_slice_of_cells");
+        see "cell_slices#hints", "the solution must use `as_slice_of_cells`"
+    );
+}
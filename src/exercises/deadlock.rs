@@ -0,0 +1,168 @@
+//! Fixing a deadlock.
+//!
+//! Each account guards its balance with its own `Mutex`, so that transfers between unrelated
+//! accounts can run in parallel. `transfer` locks the source account, audits it, then locks the
+//! destination.
+//!
+//! Now run a transfer from `a` to `b` on one thread and from `b` to `a` on another. The first
+//! thread holds `a` and waits for `b`; the second holds `b` and waits for `a`. Neither will ever
+//! let go. The audit makes the window wide enough that this happens on nearly every run.
+//!
+//! Fix `transfer` so that it can no longer deadlock, either by always taking the two locks in
+//! the same order or by never holding both at once. The tests give up after a few seconds and
+//! report a deadlock.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::{join, with_timeout};
+
+/// An account.
+#[derive(Debug)]
+pub struct Account {
+    /// Unique number of the account.
+    pub id: u32,
+    /// Current balance.
+    pub balance: Mutex<i64>,
+}
+
+impl Account {
+    /// Opens an account.
+    pub fn new(id: u32, balance: i64) -> Arc<Self> {
+        Arc::new(Self { id, balance: Mutex::new(balance) })
+    }
+
+    /// Current balance.
+    pub fn balance(&self) -> i64 {
+        *self.balance.lock().unwrap()
+    }
+}
+
+/// Checks the books of a locked account. Slow.
+pub fn audit(_balance: &i64) {
+    thread::sleep(Duration::from_millis(1));
+}
+
+// BEGIN EXERCISE
+/// Moves `amount` from `from` to `to`, auditing the source account first.
+#[cfg(not(feature = "solutions"))]
+pub fn transfer(from: &Account, to: &Account, amount: i64) {
+    let mut source = from.balance.lock().unwrap();
+    audit(&source);
+    let mut destination = to.balance.lock().unwrap();
+    *source -= amount;
+    *destination += amount;
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::transfer;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{audit, Account};
+
+    pub fn transfer(from: &Account, to: &Account, amount: i64) {
+        if from.id == to.id {
+            return;
+        }
+        // Every transfer locks the account with the lower id first, so no two threads can each
+        // hold the lock the other one is waiting for.
+        let (first, second) = if from.id < to.id { (from, to) } else { (to, from) };
+        let mut first = first.balance.lock().unwrap();
+        let mut second = second.balance.lock().unwrap();
+        let (source, destination) =
+            if from.id < to.id { (&mut *first, &mut *second) } else { (&mut *second, &mut *first) };
+        audit(source);
+        *source -= amount;
+        *destination += amount;
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "deadlock",
+    title: "Fix the deadlock",
+    chapter: Chapter::Threads,
+    difficulty: Difficulty::Advanced,
+    tags: &["mutex", "deadlocks", "debugging"],
+    description: "Make `transfer` deadlock-free with a global lock order or a smaller critical \
+                  section. Tests time out if two transfers wait for each other.",
+    tests: &[
+        Test { name: "single_transfer", run: single_transfer },
+        Test { name: "opposite_directions", run: opposite_directions },
+        Test { name: "ring", run: ring },
+        Test { name: "to_itself", run: to_itself },
+    ],
+    hints: &[
+        Hint(
+            "A deadlock needs a cycle: thread 1 holds `a` and wants `b` while thread 2 holds `b` \
+              and wants `a`. Break the cycle and the deadlock is gone.",
+        ),
+        Hint(
+            "If every thread locks the account with the smaller `id` first, the thread that wins \
+              that lock can always get the other one too.",
+        ),
+        Hint(
+            "Sort the two accounts by `id`, lock them in that order, then figure out which guard \
+              is the source. Transferring to the same account should do nothing: locking it \
+              twice would deadlock too.",
+        ),
+    ],
+};
+
+/// How long a test may take before we call it a deadlock.
+const LIMIT: Duration = Duration::from_secs(5);
+
+/// Runs `rounds` transfers of one unit along each `(from, to)` route, one thread per route.
+fn run_routes(accounts: &[Arc<Account>], routes: &[(usize, usize)], rounds: usize) {
+    let accounts = accounts.to_vec();
+    let routes = routes.to_vec();
+    let outcome = with_timeout(LIMIT, move || {
+        let threads: Vec<_> = routes
+            .into_iter()
+            .map(|(from, to)| {
+                let (from, to) = (Arc::clone(&accounts[from]), Arc::clone(&accounts[to]));
+                thread::spawn(move || {
+                    for _ in 0..rounds {
+                        transfer(&from, &to, 1);
+                    }
+                })
+            })
+            .collect();
+        threads.into_iter().for_each(join);
+    });
+    if let Err(err) = outcome {
+        panic!("deadlock: the transfers {err}");
+    }
+}
+
+fn single_transfer() {
+    let (a, b) = (Account::new(1, 100), Account::new(2, 0));
+    run_routes(&[Arc::clone(&a), Arc::clone(&b)], &[(0, 1)], 1);
+    check!((a.balance(), b.balance()), (99, 1); see "deadlock#hints");
+}
+
+fn opposite_directions() {
+    let (a, b) = (Account::new(1, 100), Account::new(2, 100));
+    run_routes(&[Arc::clone(&a), Arc::clone(&b)], &[(0, 1), (1, 0)], 50);
+    check!((a.balance(), b.balance()), (100, 100); see "deadlock#hints");
+}
+
+fn ring() {
+    let accounts = [Account::new(3, 10), Account::new(1, 10), Account::new(2, 10)];
+    run_routes(&accounts, &[(0, 1), (1, 2), (2, 0)], 30);
+    let balances: Vec<_> = accounts.iter().map(|account| account.balance()).collect();
+    check!(balances, [10, 10, 10]; see "deadlock#hints");
+}
+
+fn to_itself() {
+    let a = Account::new(1, 10);
+    run_routes(&[Arc::clone(&a)], &[(0, 0)], 1);
+    check!(a.balance(), 10; see "deadlock#hints");
+}
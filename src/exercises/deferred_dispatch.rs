@@ -0,0 +1,248 @@
+//! An event dispatcher with deferred mutation.
+//!
+//! Handlers receive the dispatcher itself, so they can subscribe new handlers or unsubscribe
+//! existing ones (including themselves) while an event is being dispatched. Changing the handler
+//! list while iterating over it would need a `borrow_mut` while `dispatch` holds a `borrow`, which
+//! panics. Instead, changes requested during a dispatch are recorded as [`Command`]s in a queue
+//! and applied once the dispatch is over.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+/// Identifies a subscribed handler.
+pub type HandlerId = usize;
+
+/// A handler, called with the dispatcher and the message.
+pub type Handler = Box<dyn Fn(&Dispatcher, &str)>;
+
+/// A change to the handler list, postponed until the current dispatch is over.
+pub enum Command {
+    /// Add the handler with the given id.
+    Subscribe(HandlerId, Handler),
+    /// Remove the handler with the given id.
+    Unsubscribe(HandlerId),
+}
+
+// BEGIN EXERCISE
+/// Dispatches messages to handlers.
+#[cfg(not(feature = "solutions"))]
+#[derive(Default)]
+pub struct Dispatcher {
+    handlers: RefCell<Vec<(HandlerId, Handler)>>,
+    queue: RefCell<Vec<Command>>,
+    next_id: Cell<HandlerId>,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Dispatcher {
+    /// Creates a dispatcher without handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `handler` and returns its id. During a dispatch, the handler is added once the
+    /// dispatch is over.
+    pub fn subscribe(&self, handler: impl Fn(&Dispatcher, &str) + 'static) -> HandlerId {
+        let _ = (handler, &self.next_id);
+        todo!()
+    }
+
+    /// Removes the handler `id`. During a dispatch, the handler is removed once the dispatch is
+    /// over (so it still receives the current message).
+    pub fn unsubscribe(&self, id: HandlerId) {
+        let _ = id;
+        todo!()
+    }
+
+    /// Calls every handler with `message`, then applies the queued commands.
+    pub fn dispatch(&self, message: &str) {
+        let _ = (message, &self.handlers, &self.queue);
+        todo!()
+    }
+
+    /// Number of subscribed handlers, not counting queued changes.
+    pub fn len(&self) -> usize {
+        todo!()
+    }
+
+    /// Whether there are no subscribed handlers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::Dispatcher;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{Cell, Command, Handler, HandlerId, RefCell};
+
+    #[derive(Default)]
+    pub struct Dispatcher {
+        handlers: RefCell<Vec<(HandlerId, Handler)>>,
+        queue: RefCell<Vec<Command>>,
+        next_id: Cell<HandlerId>,
+    }
+
+    impl Dispatcher {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn subscribe(&self, handler: impl Fn(&Dispatcher, &str) + 'static) -> HandlerId {
+            let id = self.next_id.get();
+            self.next_id.set(id + 1);
+            self.submit(Command::Subscribe(id, Box::new(handler)));
+            id
+        }
+
+        pub fn unsubscribe(&self, id: HandlerId) {
+            self.submit(Command::Unsubscribe(id));
+        }
+
+        pub fn dispatch(&self, message: &str) {
+            for (_, handler) in self.handlers.borrow().iter() {
+                handler(self, message);
+            }
+            self.apply_queue();
+        }
+
+        pub fn len(&self) -> usize {
+            self.handlers.borrow().len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// Queues `command`, and applies it right away unless a dispatch is running.
+        fn submit(&self, command: Command) {
+            self.queue.borrow_mut().push(command);
+            if self.handlers.try_borrow_mut().is_ok() {
+                self.apply_queue();
+            }
+        }
+
+        fn apply_queue(&self) {
+            let commands = std::mem::take(&mut *self.queue.borrow_mut());
+            let mut handlers = self.handlers.borrow_mut();
+            for command in commands {
+                match command {
+                    Command::Subscribe(id, handler) => handlers.push((id, handler)),
+                    Command::Unsubscribe(id) => handlers.retain(|(other, _)| *other != id),
+                }
+            }
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "deferred_dispatch",
+    title: "Event dispatcher with deferred mutation",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Advanced,
+    tags: &["refcell", "callbacks", "reentrancy"],
+    description: "Let handlers subscribe and unsubscribe during dispatch by queueing the changes \
+                  in a `RefCell<Vec<Command>>` and applying them afterwards.",
+    tests: &[
+        Test { name: "dispatches_to_handlers", run: dispatches_to_handlers },
+        Test { name: "unsubscribe_outside_dispatch", run: unsubscribe_outside_dispatch },
+        Test { name: "handler_unsubscribes_itself", run: handler_unsubscribes_itself },
+        Test { name: "handler_subscribes_another", run: handler_subscribes_another },
+    ],
+    hints: &[
+        Hint(
+            "While `dispatch` iterates over `handlers.borrow()`, nobody may `borrow_mut` the \
+              handler list. Push a `Command` onto the queue instead.",
+        ),
+        Hint(
+            "`handlers.try_borrow_mut().is_ok()` tells you whether a dispatch is running. \
+              Outside of a dispatch, apply the queue immediately.",
+        ),
+        Hint(
+            "Write one `apply_queue` that `mem::take`s the queue and applies every command to \
+              `handlers.borrow_mut()`; call it at the end of `dispatch` and from \
+              `subscribe`/`unsubscribe` when no dispatch is running.",
+        ),
+    ],
+};
+
+fn recorder() -> (Rc<RefCell<Vec<String>>>, impl Fn(&'static str) -> Handler) {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let make = {
+        let log = Rc::clone(&log);
+        move |name: &'static str| -> Handler {
+            let log = Rc::clone(&log);
+            Box::new(move |_: &Dispatcher, message: &str| {
+                log.borrow_mut().push(format!("{name}:{message}"))
+            })
+        }
+    };
+    (log, make)
+}
+
+fn dispatches_to_handlers() {
+    let (log, handler) = recorder();
+    let dispatcher = Dispatcher::new();
+    dispatcher.subscribe(handler("a"));
+    dispatcher.subscribe(handler("b"));
+    dispatcher.dispatch("hi");
+    check!(*log.borrow(), ["a:hi", "b:hi"]; see "refcell#example-6");
+}
+
+fn unsubscribe_outside_dispatch() {
+    let (log, handler) = recorder();
+    let dispatcher = Dispatcher::new();
+    let a = dispatcher.subscribe(handler("a"));
+    dispatcher.subscribe(handler("b"));
+    dispatcher.unsubscribe(a);
+    check!(dispatcher.len(), 1; see "refcell#example-6");
+    dispatcher.dispatch("hi");
+    check!(*log.borrow(), ["b:hi"]; see "refcell#example-6");
+}
+
+fn handler_unsubscribes_itself() {
+    let (log, handler) = recorder();
+    let dispatcher = Dispatcher::new();
+    let once = handler("once");
+    let id = Rc::new(Cell::new(0));
+    let own_id = Rc::clone(&id);
+    id.set(dispatcher.subscribe(move |dispatcher, message| {
+        once(dispatcher, message);
+        dispatcher.unsubscribe(own_id.get());
+    }));
+
+    dispatcher.dispatch("first");
+    dispatcher.dispatch("second");
+    check!(*log.borrow(), ["once:first"]; see "refcell#example-6");
+    check!(dispatcher.is_empty(); see "refcell#example-6");
+}
+
+fn handler_subscribes_another() {
+    let (log, handler) = recorder();
+    let dispatcher = Dispatcher::new();
+    let late = RefCell::new(Some(handler("late")));
+    dispatcher.subscribe(move |dispatcher, _| {
+        if let Some(late) = late.borrow_mut().take() {
+            dispatcher.subscribe(late);
+        }
+    });
+
+    dispatcher.dispatch("first");
+    check!(dispatcher.len(), 2; see "refcell#example-6");
+    check!(
+        log.borrow().is_empty();
+        see "refcell#example-6",
+        "handlers added during dispatch start with the next one"
+    );
+    dispatcher.dispatch("second");
+    check!(*log.borrow(), ["late:second"]; see "refcell#example-6");
+}
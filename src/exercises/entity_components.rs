@@ -0,0 +1,321 @@
+//! An entity system with shared mutable components.
+//!
+//! In a game, many systems run over the same entities every frame: movement updates positions,
+//! poison eats away health, the status system walks each entity's effects. The systems below
+//! only receive a shared `&World`, so every component an entity carries needs interior
+//! mutability, and each component gets the cell type that fits it:
+//!
+//! - `position`, `velocity` and `health` are small `Copy` values that are only ever read or
+//!   replaced whole. A `Cell` is enough, with no borrow flag to check or trip over.
+//! - `effects` is a `Vec<Effect>`, which cannot be copied out cheaply, and the status system
+//!   iterates over it in place. That needs a `RefCell`.
+//!
+//! Give each component its own cell. Putting them all in one `RefCell<Components>` would make
+//! the status system panic: it holds a borrow of the effects while it applies damage.
+
+use std::cell::Ref;
+
+use super::{active_code, Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+/// A position or a velocity on the map.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Vec2 {
+    /// Horizontal component.
+    pub x: i32,
+    /// Vertical component.
+    pub y: i32,
+}
+
+/// A lasting effect on an entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Effect {
+    /// Loses this much health every tick.
+    Poisoned(i32),
+    /// Gains this much health every tick.
+    Regenerating(i32),
+    /// Does not move.
+    Frozen,
+}
+
+// BEGIN EXERCISE
+/// Something that lives in the world.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug)]
+pub struct Entity {
+    /// Name of the entity.
+    pub name: String,
+    // TODO: the components: position, velocity, health, effects.
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Entity {
+    /// Creates an entity at `position` with `health`, not moving and without effects.
+    pub fn new(name: &str, position: Vec2, health: i32) -> Self {
+        let _ = (position, health);
+        Self { name: name.to_string() }
+    }
+
+    /// Current position.
+    pub fn position(&self) -> Vec2 {
+        todo!()
+    }
+
+    /// Moves the entity to `position`.
+    pub fn set_position(&self, position: Vec2) {
+        let _ = position;
+        todo!()
+    }
+
+    /// Current velocity.
+    pub fn velocity(&self) -> Vec2 {
+        todo!()
+    }
+
+    /// Changes the velocity.
+    pub fn set_velocity(&self, velocity: Vec2) {
+        let _ = velocity;
+        todo!()
+    }
+
+    /// Current health.
+    pub fn health(&self) -> i32 {
+        todo!()
+    }
+
+    /// Adds `delta` (possibly negative) to the health.
+    pub fn change_health(&self, delta: i32) {
+        let _ = delta;
+        todo!()
+    }
+
+    /// The active effects.
+    pub fn effects(&self) -> Ref<'_, Vec<Effect>> {
+        todo!()
+    }
+
+    /// Adds an effect.
+    pub fn add_effect(&self, effect: Effect) {
+        let _ = effect;
+        todo!()
+    }
+
+    /// Removes every effect for which `keep` returns `false`.
+    pub fn retain_effects(&self, keep: impl FnMut(&Effect) -> bool) {
+        let _ = keep;
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::Entity;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::cell::{Cell, RefCell};
+
+    use super::{Effect, Ref, Vec2};
+
+    #[derive(Debug)]
+    pub struct Entity {
+        pub name: String,
+        position: Cell<Vec2>,
+        velocity: Cell<Vec2>,
+        health: Cell<i32>,
+        effects: RefCell<Vec<Effect>>,
+    }
+
+    impl Entity {
+        pub fn new(name: &str, position: Vec2, health: i32) -> Self {
+            Self {
+                name: name.to_string(),
+                position: Cell::new(position),
+                velocity: Cell::new(Vec2::default()),
+                health: Cell::new(health),
+                effects: RefCell::new(Vec::new()),
+            }
+        }
+
+        pub fn position(&self) -> Vec2 {
+            self.position.get()
+        }
+
+        pub fn set_position(&self, position: Vec2) {
+            self.position.set(position);
+        }
+
+        pub fn velocity(&self) -> Vec2 {
+            self.velocity.get()
+        }
+
+        pub fn set_velocity(&self, velocity: Vec2) {
+            self.velocity.set(velocity);
+        }
+
+        pub fn health(&self) -> i32 {
+            self.health.get()
+        }
+
+        pub fn change_health(&self, delta: i32) {
+            self.health.set(self.health.get() + delta);
+        }
+
+        pub fn effects(&self) -> Ref<'_, Vec<Effect>> {
+            self.effects.borrow()
+        }
+
+        pub fn add_effect(&self, effect: Effect) {
+            self.effects.borrow_mut().push(effect);
+        }
+
+        pub fn retain_effects(&self, keep: impl FnMut(&Effect) -> bool) {
+            self.effects.borrow_mut().retain(keep);
+        }
+    }
+    // END SOLUTION
+}
+
+/// All the entities of a game.
+#[derive(Debug, Default)]
+pub struct World {
+    /// The entities, in spawning order.
+    pub entities: Vec<Entity>,
+}
+
+/// Moves every entity that is not frozen by its velocity.
+pub fn movement_system(world: &World) {
+    for entity in &world.entities {
+        if entity.effects().contains(&Effect::Frozen) {
+            continue;
+        }
+        let (position, velocity) = (entity.position(), entity.velocity());
+        entity.set_position(Vec2 { x: position.x + velocity.x, y: position.y + velocity.y });
+    }
+}
+
+/// Applies poison and regeneration, while looking at the effects.
+pub fn status_system(world: &World) {
+    for entity in &world.entities {
+        for effect in entity.effects().iter() {
+            match effect {
+                Effect::Poisoned(damage) => entity.change_health(-damage),
+                Effect::Regenerating(amount) => entity.change_health(*amount),
+                Effect::Frozen => {}
+            }
+        }
+    }
+}
+
+/// Lifts every effect from entities that have run out of health.
+pub fn cleanup_system(world: &World) {
+    for entity in world.entities.iter().filter(|entity| entity.health() <= 0) {
+        entity.retain_effects(|_| false);
+    }
+}
+
+/// Runs one frame: every system once, in order.
+pub fn tick(world: &World) {
+    movement_system(world);
+    status_system(world);
+    cleanup_system(world);
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "entity_components",
+    title: "Entity system with shared mutable components",
+    chapter: Chapter::Cell,
+    difficulty: Difficulty::Core,
+    tags: &["cell", "refcell", "design"],
+    description: "Store each component of an `Entity` in the right cell type (`Cell` for the \
+                  `Copy` ones, `RefCell` for the effect list) so that every system can update \
+                  them through `&World`.",
+    tests: &[
+        Test { name: "components", run: components },
+        Test { name: "systems", run: systems },
+        Test { name: "borrowed_effects_do_not_block", run: borrowed_effects_do_not_block },
+        Test { name: "cell_types", run: cell_types },
+    ],
+    hints: &[
+        Hint(
+            "One cell per component. `Vec2` and `i32` are `Copy`: `Cell::get` and `Cell::set` \
+              are all you need for them.",
+        ),
+        Hint(
+            "`effects` returns a `Ref<'_, Vec<Effect>>`, which only a `RefCell` can hand out. \
+              `add_effect` and `retain_effects` take a short `borrow_mut()`.",
+        ),
+        Hint(
+            "`position: Cell<Vec2>, velocity: Cell<Vec2>, health: Cell<i32>, effects: \
+              RefCell<Vec<Effect>>`.",
+        ),
+    ],
+};
+
+fn components() {
+    let entity = Entity::new("crab", Vec2 { x: 1, y: 2 }, 10);
+    check!(entity.position(), Vec2 { x: 1, y: 2 }; see "nested#example-1");
+    check!(entity.velocity(), Vec2::default(); see "nested#example-1");
+    entity.set_velocity(Vec2 { x: 0, y: -1 });
+    entity.set_position(Vec2 { x: 5, y: 5 });
+    entity.change_health(-3);
+    entity.add_effect(Effect::Frozen);
+    entity.add_effect(Effect::Poisoned(1));
+    entity.retain_effects(|effect| *effect != Effect::Frozen);
+    check!(entity.velocity(), Vec2 { x: 0, y: -1 }; see "nested#example-1");
+    check!(entity.position(), Vec2 { x: 5, y: 5 }; see "nested#example-1");
+    check!(entity.health(), 7; see "nested#example-1");
+    check!(*entity.effects(), [Effect::Poisoned(1)]; see "nested#example-1");
+}
+
+fn systems() {
+    let world = World {
+        entities: vec![
+            Entity::new("runner", Vec2::default(), 3),
+            Entity::new("statue", Vec2::default(), 5),
+        ],
+    };
+    let (runner, statue) = (&world.entities[0], &world.entities[1]);
+    runner.set_velocity(Vec2 { x: 1, y: 0 });
+    runner.add_effect(Effect::Poisoned(2));
+    statue.set_velocity(Vec2 { x: 1, y: 1 });
+    statue.add_effect(Effect::Frozen);
+    statue.add_effect(Effect::Regenerating(1));
+
+    tick(&world);
+    tick(&world);
+    check!(runner.position(), Vec2 { x: 2, y: 0 }; see "nested#example-1");
+    check!(runner.health(), -1; see "nested#example-1");
+    check!(
+        runner.effects().is_empty();
+        see "nested#example-1", "cleanup should have lifted the poison"
+    );
+    check!(statue.position(), Vec2::default(); see "nested#example-1");
+    check!(statue.health(), 7; see "nested#example-1");
+}
+
+fn borrowed_effects_do_not_block() {
+    let entity = Entity::new("crab", Vec2::default(), 10);
+    entity.add_effect(Effect::Poisoned(1));
+    let effects = entity.effects();
+    entity.change_health(-1);
+    entity.set_position(Vec2 { x: 1, y: 1 });
+    check!(effects.len(), 1; see "nested#example-1");
+    check!(entity.health(), 9; see "nested#example-1");
+}
+
+fn cell_types() {
+    let code = active_code(include_str!("entity_components.rs"));
+    for field in ["position: Cell<Vec2>", "velocity: Cell<Vec2>", "health: Cell<i32>"] {
+        check!(
+            code.contains(field);
+            see "nested#example-1", "expected `{field}`: `Copy` components need no borrow flag"
+        );
+    }
+    check!(
+        code.contains("effects: RefCell<Vec<Effect>>");
+        see "nested#example-1", "expected a `RefCell` for `effects`"
+    );
+}
@@ -0,0 +1,218 @@
+//! An observer pattern with `RefCell`.
+//!
+//! `EventBus` keeps a list of subscribers and calls each of them when an event is published.
+//! Subscribing and publishing both take `&self`, so the list has to live in a `RefCell`.
+//!
+//! The tricky part: a subscriber may itself subscribe a new callback *while* the bus is
+//! dispatching an event. A naive `publish` that holds `subscribers.borrow()` while calling every
+//! callback will panic as soon as the callback calls `subscribe`. A subscriber added during
+//! dispatch should start receiving events from the *next* `publish`.
+//!
+//! See the [`RefCell`](crate::RefCell) lesson.
+
+use std::cell::RefCell;
+
+use super::{Hint, StaticExercise, Test};
+use crate::curriculum::{Chapter, Difficulty};
+use crate::{check, check_lines};
+
+/// Something that happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// What happened.
+    pub name: String,
+}
+
+impl Event {
+    /// Creates an event called `name`.
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string() }
+    }
+}
+
+/// A callback registered on an `EventBus`.
+pub type Subscriber = Box<dyn Fn(&Event)>;
+
+// BEGIN EXERCISE
+/// Calls every subscriber for each published event.
+#[cfg(not(feature = "solutions"))]
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: RefCell<Vec<Subscriber>>,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl EventBus {
+    /// Creates a bus without subscribers.
+    pub fn new() -> Self {
+        Self { subscribers: RefCell::new(Vec::new()) }
+    }
+
+    /// Registers `subscriber`. May be called from within a subscriber during `publish`.
+    pub fn subscribe(&self, subscriber: impl Fn(&Event) + 'static) {
+        let _ = (&self.subscribers, subscriber);
+        todo!()
+    }
+
+    /// Calls every subscriber with `event`, in subscription order.
+    pub fn publish(&self, event: &Event) {
+        let _ = event;
+        todo!()
+    }
+
+    /// Number of registered subscribers, including ones added during the current dispatch.
+    pub fn len(&self) -> usize {
+        todo!()
+    }
+
+    /// Whether there are no subscribers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::EventBus;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::cell::RefCell;
+
+    use super::{Event, Subscriber};
+
+    #[derive(Default)]
+    pub struct EventBus {
+        subscribers: RefCell<Vec<Subscriber>>,
+        /// Subscribers added while `subscribers` is borrowed by `publish`.
+        pending: RefCell<Vec<Subscriber>>,
+    }
+
+    impl EventBus {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn subscribe(&self, subscriber: impl Fn(&Event) + 'static) {
+            match self.subscribers.try_borrow_mut() {
+                Ok(mut subscribers) => subscribers.push(Box::new(subscriber)),
+                Err(_) => self.pending.borrow_mut().push(Box::new(subscriber)),
+            }
+        }
+
+        pub fn publish(&self, event: &Event) {
+            for subscriber in self.subscribers.borrow().iter() {
+                subscriber(event);
+            }
+
+            let pending = std::mem::take(&mut *self.pending.borrow_mut());
+            self.subscribers.borrow_mut().extend(pending);
+        }
+
+        pub fn len(&self) -> usize {
+            let dispatching = self.subscribers.try_borrow().map_or(0, |s| s.len());
+            dispatching + self.pending.borrow().len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "event_bus",
+    title: "Build an observer pattern with RefCell",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Core,
+    tags: &["refcell", "callbacks", "reentrancy"],
+    description: "Implement `EventBus::subscribe` and `EventBus::publish`, including subscribers \
+                  that subscribe during dispatch.",
+    tests: &[
+        Test { name: "delivers_to_every_subscriber", run: delivers_to_every_subscriber },
+        Test { name: "delivers_in_order", run: delivers_in_order },
+        Test { name: "subscribe_during_dispatch", run: subscribe_during_dispatch },
+    ],
+    hints: &[
+        Hint(
+            "Calling a subscriber while `subscribers.borrow()` is alive is fine, until that \
+              subscriber calls `subscribe`.",
+        ),
+        Hint(
+            "`try_borrow_mut` tells you whether a dispatch is in progress. Park new subscribers \
+              somewhere else until it finishes.",
+        ),
+        Hint(
+            "Add a `pending: RefCell<Vec<Subscriber>>` field; `subscribe` pushes there \
+              when `subscribers` is busy and `publish` moves them over after the loop.",
+        ),
+    ],
+};
+
+fn delivers_to_every_subscriber() {
+    use std::rc::Rc;
+
+    let bus = EventBus::new();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    for id in 0..3 {
+        let seen = Rc::clone(&seen);
+        bus.subscribe(move |event| seen.borrow_mut().push((id, event.name.clone())));
+    }
+
+    bus.publish(&Event::new("click"));
+    check!(bus.len(), 3; see "refcell#example-6");
+    check!(seen.borrow().len(), 3; see "refcell#example-6");
+}
+
+fn delivers_in_order() {
+    use std::rc::Rc;
+
+    let bus = EventBus::new();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    for id in ['a', 'b', 'c'] {
+        let seen = Rc::clone(&seen);
+        bus.subscribe(move |event| seen.borrow_mut().push(format!("{id} got {}", event.name)));
+    }
+
+    bus.publish(&Event::new("first"));
+    bus.publish(&Event::new("second"));
+    let expected = [
+        "a got first",
+        "b got first",
+        "c got first",
+        "a got second",
+        "b got second",
+        "c got second",
+    ];
+    check_lines!(seen.borrow(), expected; see "refcell#example-6");
+}
+
+fn subscribe_during_dispatch() {
+    use std::rc::{Rc, Weak};
+
+    let bus = Rc::new(EventBus::new());
+    let seen = Rc::new(RefCell::new(Vec::new()));
+
+    let weak: Weak<EventBus> = Rc::downgrade(&bus);
+    let recorder = Rc::clone(&seen);
+    bus.subscribe(move |event| {
+        if event.name == "open" {
+            let recorder = Rc::clone(&recorder);
+            weak.upgrade()
+                .unwrap()
+                .subscribe(move |event| recorder.borrow_mut().push(event.name.clone()));
+        }
+    });
+
+    bus.publish(&Event::new("open"));
+    check!(bus.len(), 2; see "refcell#example-6");
+    check!(
+        seen.borrow().is_empty();
+        see "refcell#example-6", "subscribers added during dispatch start next time"
+    );
+
+    bus.publish(&Event::new("close"));
+    check_lines!(seen.borrow(), ["close"]; see "refcell#example-6");
+}
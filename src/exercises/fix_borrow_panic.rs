@@ -0,0 +1,101 @@
+//! Fix the borrow panic.
+//!
+//! `double_evens_and_total` compiles just fine, but calling it panics because the `RefCell` is
+//! already borrowed. Restructure the borrows so that no guard is alive when the next conflicting
+//! borrow is taken. Reaching for `unsafe` or cloning the whole vector does not count: the grader
+//! checks the source for both.
+//!
+//! See the [`RefCell`](crate::RefCell) lesson.
+
+use std::cell::RefCell;
+
+use super::{active_code, Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+// BEGIN EXERCISE
+/// Doubles every even number in `numbers`, then appends the total of the *original* numbers.
+///
+/// `[1, 2, 3]` becomes `[1, 4, 3, 6]`.
+#[cfg(not(feature = "solutions"))]
+pub fn double_evens_and_total(numbers: &RefCell<Vec<i32>>) {
+    let list = numbers.borrow();
+    let total: i32 = list.iter().sum();
+
+    for number in numbers.borrow_mut().iter_mut() {
+        if *number % 2 == 0 {
+            *number *= 2;
+        }
+    }
+
+    numbers.borrow_mut().push(total);
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::double_evens_and_total;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    use std::cell::RefCell;
+
+    // BEGIN SOLUTION
+    pub fn double_evens_and_total(numbers: &RefCell<Vec<i32>>) {
+        let total: i32 = numbers.borrow().iter().sum();
+
+        let mut list = numbers.borrow_mut();
+        for number in list.iter_mut() {
+            if *number % 2 == 0 {
+                *number *= 2;
+            }
+        }
+        list.push(total);
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "fix_borrow_panic",
+    title: "Fix the borrow panic",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Intro,
+    tags: &["refcell", "borrowing", "debugging"],
+    description: "Restructure the guard scopes in `double_evens_and_total` so it no longer \
+                  panics, without `unsafe` or `clone`.",
+    tests: &[
+        Test { name: "does_not_panic", run: does_not_panic },
+        Test { name: "computes_the_right_values", run: computes_the_right_values },
+        Test { name: "empty_list", run: empty_list },
+        Test { name: "no_cop_outs", run: no_cop_outs },
+    ],
+    hints: &[
+        Hint("The `Ref` returned by `borrow()` lives until the end of the scope it is bound in."),
+        Hint("Compute the total in a single expression so the shared guard is a temporary."),
+        Hint("`let total: i32 = numbers.borrow().iter().sum();` then take one `borrow_mut()`."),
+    ],
+};
+
+fn does_not_panic() {
+    double_evens_and_total(&RefCell::new(vec![1, 2, 3]));
+}
+
+fn computes_the_right_values() {
+    let numbers = RefCell::new(vec![1, 2, 3, 4]);
+    double_evens_and_total(&numbers);
+    check!(*numbers.borrow(), [1, 4, 3, 8, 10]; see "refcell#example-6");
+}
+
+fn empty_list() {
+    let numbers = RefCell::new(vec![]);
+    double_evens_and_total(&numbers);
+    check!(*numbers.borrow(), [0]; see "refcell#example-6");
+}
+
+fn no_cop_outs() {
+    let code = active_code(include_str!("fix_borrow_panic.rs"));
+    check!(!code.contains("unsafe"); see "refcell#example-6", "the solution must not use `unsafe`");
+    check!(
+        !code.contains("clone");
+        see "refcell#example-6", "the solution must not clone the data"
+    );
+}
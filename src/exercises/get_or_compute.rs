@@ -0,0 +1,162 @@
+//! A `get_or_compute` caching layer.
+//!
+//! `Cache::get_or_compute` returns the cached value for a key, or calls the given closure to
+//! compute it on a miss. Since the method takes `&self`, the entries live in a `RefCell`.
+//!
+//! The closure is user code, and user code does surprising things. Computing one entry often
+//! needs another (a page that includes a header, a price that depends on a rate), so the closure
+//! may call back into the very same cache. If `get_or_compute` still holds a borrow of the
+//! entries while the closure runs, that call panics. This is the re-entrancy problem from the
+//! [`RefCell`](crate::RefCell) lesson.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::catch_panic;
+
+/// A cache from `K` to `V`.
+#[derive(Debug)]
+pub struct Cache<K, V> {
+    entries: RefCell<HashMap<K, V>>,
+}
+
+impl<K: Hash + Eq, V: Clone> Cache<K, V> {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self { entries: RefCell::new(HashMap::new()) }
+    }
+
+    /// A copy of the cached value for `key`, without computing anything.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    /// Number of cached entries.
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Cache<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+impl<K: Hash + Eq, V: Clone> Cache<K, V> {
+    /// Returns the cached value for `key`, or computes it with `compute`, caches it and returns
+    /// it. `compute` may use the cache.
+    pub fn get_or_compute(&self, key: K, compute: impl FnOnce() -> V) -> V {
+        self.entries.borrow_mut().entry(key).or_insert_with(compute).clone()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{Cache, Hash};
+
+    impl<K: Hash + Eq, V: Clone> Cache<K, V> {
+        pub fn get_or_compute(&self, key: K, compute: impl FnOnce() -> V) -> V {
+            if let Some(value) = self.get(&key) {
+                return value;
+            }
+            // No borrow is alive while `compute` runs.
+            let value = compute();
+            // `compute` may have filled this very key through the cache; keep the first value.
+            self.entries.borrow_mut().entry(key).or_insert(value).clone()
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "get_or_compute",
+    title: "`get_or_compute` caching layer",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Core,
+    tags: &["refcell", "caching", "reentrancy"],
+    description: "Fix `Cache::get_or_compute` so that it never holds a borrow of its entries \
+                  while the user's closure runs: the closure may call back into the cache.",
+    tests: &[
+        Test { name: "computes_once", run: computes_once },
+        Test { name: "closure_reads_the_cache", run: closure_reads_the_cache },
+        Test { name: "closure_fills_the_cache", run: closure_fills_the_cache },
+        Test { name: "closure_fills_the_same_key", run: closure_fills_the_same_key },
+        Test { name: "survives_a_panicking_closure", run: survives_a_panicking_closure },
+    ],
+    hints: &[
+        Hint(
+            "`entry(key).or_insert_with(compute)` calls `compute` while the `RefMut` returned by \
+              `borrow_mut()` is still alive.",
+        ),
+        Hint(
+            "Split the method in three steps, each with its own short-lived borrow: look the key \
+              up, compute the value with no borrow held, insert it.",
+        ),
+        Hint(
+            "If the closure already inserted the same key (through a nested call), keep the \
+              existing value: `entry(key).or_insert(value).clone()`.",
+        ),
+    ],
+};
+
+fn computes_once() {
+    let cache = Cache::new();
+    let calls = Cell::new(0);
+    let compute = || {
+        calls.set(calls.get() + 1);
+        "value".to_string()
+    };
+    check!(cache.get_or_compute(1, compute), "value"; see "refcell#example-6");
+    check!(cache.get_or_compute(1, compute), "value"; see "refcell#example-6");
+    check!(calls.get(), 1; see "refcell#example-6");
+    check!(cache.len(), 1; see "refcell#example-6");
+}
+
+fn closure_reads_the_cache() {
+    let cache = Cache::new();
+    cache.get_or_compute("rate", || 3);
+    let price = cache.get_or_compute("price", || 10 * cache.get(&"rate").unwrap());
+    check!(price, 30; see "refcell#example-6");
+}
+
+fn closure_fills_the_cache() {
+    let cache = Cache::new();
+    let page = cache.get_or_compute("page", || {
+        let header = cache.get_or_compute("header", || "<h1>Hi</h1>".to_string());
+        format!("{header}<p>body</p>")
+    });
+    check!(page, "<h1>Hi</h1><p>body</p>"; see "refcell#example-6");
+    check!(cache.get(&"header").as_deref(), Some("<h1>Hi</h1>"); see "refcell#example-6");
+    check!(cache.len(), 2; see "refcell#example-6");
+}
+
+fn closure_fills_the_same_key() {
+    let cache = Cache::new();
+    let value = cache.get_or_compute("key", || {
+        cache.get_or_compute("key", || 1);
+        2
+    });
+    check!(value, 1; see "refcell#example-6", "the value cached first must win");
+    check!(cache.get(&"key"), Some(1); see "refcell#example-6");
+}
+
+fn survives_a_panicking_closure() {
+    let cache = Cache::new();
+    let result = catch_panic(|| cache.get_or_compute(1, || panic!("no value for you")));
+    check!(result.is_err(); see "refcell#example-6");
+    check!(cache.get_or_compute(1, || 5), 5; see "refcell#example-6");
+}
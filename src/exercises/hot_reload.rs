@@ -0,0 +1,208 @@
+//! Hot-reloadable settings with `RwLock`.
+//!
+//! A server reads its settings on every request, from many threads, and an operator can ask it
+//! to reload them from disk at any time. Reads vastly outnumber reloads, which makes this a
+//! textbook case for `RwLock`: any number of readers at once, a writer only now and then.
+//!
+//! Two requirements make it interesting:
+//!
+//! - Readers must never observe a half-updated configuration. If the new settings change both
+//!   `workers` and `queue_size`, nobody may see the new `workers` with the old `queue_size`.
+//! - Loading the new settings is slow (it is I/O). Readers must keep working while a reload is
+//!   loading, so no lock may be held while `load` runs.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::{join, with_timeout};
+
+/// The settings of the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Increases with every reload.
+    pub version: u64,
+    /// Number of worker threads.
+    pub workers: u64,
+    /// Capacity of the request queue.
+    pub queue_size: u64,
+}
+
+// BEGIN EXERCISE
+/// Settings that can be read from any thread and reloaded at any time.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug)]
+pub struct Settings {
+    // TODO: where the current `Config` lives.
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Settings {
+    /// Creates settings holding `config`.
+    pub fn new(config: Config) -> Self {
+        let _ = config;
+        todo!()
+    }
+
+    /// The current configuration. Never a mix of two versions.
+    pub fn get(&self) -> Arc<Config> {
+        todo!()
+    }
+
+    /// Calls `load` and, if it succeeds, makes its result the current configuration. On error,
+    /// the current configuration stays in place and the error is returned.
+    ///
+    /// Readers are not blocked while `load` runs.
+    pub fn reload(&self, load: impl FnOnce() -> Result<Config, String>) -> Result<(), String> {
+        let _ = load;
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::Settings;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::sync::RwLock;
+
+    use super::{Arc, Config};
+
+    #[derive(Debug)]
+    pub struct Settings {
+        current: RwLock<Arc<Config>>,
+    }
+
+    impl Settings {
+        pub fn new(config: Config) -> Self {
+            Self { current: RwLock::new(Arc::new(config)) }
+        }
+
+        pub fn get(&self) -> Arc<Config> {
+            // Clone the `Arc`, not the config: the read lock is held for a few instructions.
+            Arc::clone(&self.current.read().unwrap())
+        }
+
+        pub fn reload(&self, load: impl FnOnce() -> Result<Config, String>) -> Result<(), String> {
+            // Load first, without any lock. Only swapping the pointer needs the write lock.
+            let config = Arc::new(load()?);
+            *self.current.write().unwrap() = config;
+            Ok(())
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "hot_reload",
+    title: "Hot-reloadable settings with `RwLock`",
+    chapter: Chapter::Threads,
+    difficulty: Difficulty::Advanced,
+    tags: &["rwlock", "arc"],
+    description: "Implement `Settings::get` and `Settings::reload` so that readers on many \
+                  threads never see a half-updated config and keep working during a slow reload.",
+    tests: &[
+        Test { name: "get_and_reload", run: get_and_reload },
+        Test { name: "failed_reload_keeps_config", run: failed_reload_keeps_config },
+        Test { name: "readers_work_during_load", run: readers_work_during_load },
+        Test { name: "no_half_updated_config", run: no_half_updated_config },
+    ],
+    hints: &[
+        Hint(
+            "Keep the whole `Config` behind one lock, so a reload replaces every field at once. \
+              Separate locks (or atomics) per field let readers see a mix.",
+        ),
+        Hint(
+            "Store an `Arc<Config>` in a `RwLock`. `get` clones the `Arc` under a read lock, so \
+              readers share the config without copying it and without holding the lock.",
+        ),
+        Hint(
+            "In `reload`, call `load()?` before taking the write lock; then `*self.current \
+              .write().unwrap() = Arc::new(config)`.",
+        ),
+    ],
+};
+
+fn config(version: u64) -> Config {
+    Config { version, workers: version, queue_size: version * 10 }
+}
+
+fn get_and_reload() {
+    let settings = Settings::new(config(1));
+    let old = settings.get();
+    check!(settings.reload(|| Ok(config(2))), Ok(()); see "hot_reload#hints");
+    check!(*settings.get(), config(2); see "hot_reload#hints");
+    check!(*old, config(1); see "hot_reload#hints", "a config that was handed out must not change");
+}
+
+fn failed_reload_keeps_config() {
+    let settings = Settings::new(config(1));
+    check!(
+        settings.reload(|| Err("missing file".to_string())),
+        Err("missing file".into());
+        see "hot_reload#hints"
+    );
+    check!(*settings.get(), config(1); see "hot_reload#hints");
+}
+
+fn readers_work_during_load() {
+    let settings = Arc::new(Settings::new(config(1)));
+    let reader = Arc::clone(&settings);
+    let result = settings.reload(move || {
+        // A request arrives while the new settings are still being read from disk.
+        let seen = with_timeout(Duration::from_secs(2), move || reader.get().version)
+            .map_err(|_| "`get` was blocked while `load` ran".to_string())?;
+        check!(seen, 1; see "hot_reload#hints");
+        Ok(config(2))
+    });
+    result.unwrap_or_else(|err| panic!("{err}"));
+    check!(settings.get().version, 2; see "hot_reload#hints");
+}
+
+fn no_half_updated_config() {
+    let outcome = with_timeout(Duration::from_secs(20), || {
+        let settings = Arc::new(Settings::new(config(0)));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let (settings, done) = (Arc::clone(&settings), Arc::clone(&done));
+                thread::spawn(move || {
+                    let mut last = 0;
+                    while !done.load(Ordering::Relaxed) {
+                        let seen = settings.get();
+                        check!(
+                            *seen,
+                            config(seen.version);
+                            see "hot_reload#hints", "saw a half-updated config"
+                        );
+                        check!(
+                            seen.version >= last;
+                            see "hot_reload#hints", "went back from {last} to {}", seen.version
+                        );
+                        last = seen.version;
+                    }
+                })
+            })
+            .collect();
+
+        for version in 1..=300 {
+            settings.reload(|| Ok(config(version))).unwrap();
+            thread::yield_now();
+        }
+        done.store(true, Ordering::Relaxed);
+        readers.into_iter().for_each(join);
+        settings.get().version
+    });
+    check!(
+        outcome.unwrap_or_else(|err| panic!("the stress test {err}")),
+        300;
+        see "hot_reload#hints"
+    );
+}
@@ -0,0 +1,155 @@
+//! A string interner.
+//!
+//! An interner hands out a small, cheap-to-compare [`Symbol`] for each distinct string. Interning
+//! the same string twice must return the same symbol, and must not allocate a second copy of the
+//! string: only a miss may call `to_string` and insert into the map.
+//!
+//! The map is a [`CountingCell`], so the tests can tell whether an
+//! `intern` call mutably borrowed it (which is the only way to insert, and so to allocate).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::impls::CountingCell;
+
+/// An interned string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(pub u32);
+
+// BEGIN EXERCISE
+/// Maps strings to symbols and back.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Default)]
+pub struct Interner {
+    /// Every interned string and its symbol.
+    pub map: CountingCell<HashMap<String, Symbol>>,
+    /// `strings[symbol.0]` is the string of `symbol`.
+    strings: RefCell<Vec<String>>,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Interner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the symbol of `text`, interning it if it is new.
+    pub fn intern(&self, text: &str) -> Symbol {
+        let _ = (text, &self.strings);
+        todo!()
+    }
+
+    /// Returns the string of `symbol`, if it was handed out by this interner.
+    pub fn resolve(&self, symbol: Symbol) -> Option<String> {
+        let _ = symbol;
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::Interner;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{CountingCell, HashMap, RefCell, Symbol};
+
+    #[derive(Debug, Default)]
+    pub struct Interner {
+        pub map: CountingCell<HashMap<String, Symbol>>,
+        strings: RefCell<Vec<String>>,
+    }
+
+    impl Interner {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn intern(&self, text: &str) -> Symbol {
+            if let Some(symbol) = self.map.borrow().get(text) {
+                return *symbol;
+            }
+
+            let mut strings = self.strings.borrow_mut();
+            let symbol = Symbol(strings.len() as u32);
+            strings.push(text.to_string());
+            self.map.borrow_mut().insert(text.to_string(), symbol);
+            symbol
+        }
+
+        pub fn resolve(&self, symbol: Symbol) -> Option<String> {
+            self.strings.borrow().get(symbol.0 as usize).cloned()
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "interner",
+    title: "String interner",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Core,
+    tags: &["refcell", "caching"],
+    description: "Implement `Interner::intern(&self, &str) -> Symbol` and `resolve`, without \
+                  allocating when the string is already interned.",
+    tests: &[
+        Test { name: "same_string_same_symbol", run: same_string_same_symbol },
+        Test {
+            name: "different_strings_different_symbols",
+            run: different_strings_different_symbols,
+        },
+        Test { name: "resolve_round_trips", run: resolve_round_trips },
+        Test { name: "hit_does_not_allocate", run: hit_does_not_allocate },
+    ],
+    hints: &[
+        Hint("`HashMap<String, _>::get` accepts a `&str`, no need to build a `String` to look up."),
+        Hint("Check the map with a shared `borrow()` first and return early on a hit."),
+        Hint(
+            "On a miss: the next symbol is `Symbol(strings.len() as u32)`; push the string and \
+              insert it into the map with `borrow_mut()`.",
+        ),
+    ],
+};
+
+fn same_string_same_symbol() {
+    let interner = Interner::new();
+    let a = interner.intern("hello");
+    let b = interner.intern(&String::from("hello"));
+    check!(a, b; see "refcell#example-5");
+}
+
+fn different_strings_different_symbols() {
+    let interner = Interner::new();
+    let symbols: Vec<_> = ["a", "b", "c", "a"].iter().map(|s| interner.intern(s)).collect();
+    check!(symbols[0] != symbols[1]; see "refcell#example-5");
+    check!(symbols[1] != symbols[2]; see "refcell#example-5");
+    check!(symbols[0], symbols[3]; see "refcell#example-5");
+}
+
+fn resolve_round_trips() {
+    let interner = Interner::new();
+    let symbol = interner.intern("cell");
+    interner.intern("refcell");
+    check!(interner.resolve(symbol).as_deref(), Some("cell"); see "refcell#example-5");
+    check!(interner.resolve(Symbol(99)), None; see "refcell#example-5");
+}
+
+fn hit_does_not_allocate() {
+    let interner = Interner::new();
+    interner.intern("stable");
+    interner.map.reset_counts();
+
+    for _ in 0..10 {
+        interner.intern("stable");
+    }
+    check!(
+        interner.map.counts().exclusive,
+        0;
+        see "refcell#example-5", "interning an existing string inserted again"
+    );
+}
@@ -0,0 +1,287 @@
+//! Migrating a job queue to threads.
+//!
+//! The `JobQueue` below works, and its single-threaded tests pass. Every clone of the queue is a
+//! handle to the same jobs, shared through an `Rc<RefCell<_>>`. Now the jobs should be processed
+//! by a pool of worker threads, each holding its own handle, and the compiler refuses to move an
+//! `Rc` into `thread::spawn`.
+//!
+//! Port the queue to `Arc<Mutex<_>>` (or another thread-safe design) without changing its
+//! methods. The original tests must keep passing; the new ones run workers on several threads.
+
+use std::marker::PhantomData;
+use std::thread;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::join;
+
+/// A unit of work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Job {
+    /// Identifies the job.
+    pub id: u32,
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Default)]
+struct State {
+    pending: VecDeque<Job>,
+    completed: Vec<u32>,
+}
+
+/// A queue of jobs. Clones are handles to the same queue.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Clone, Default)]
+pub struct JobQueue {
+    state: Rc<RefCell<State>>,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl JobQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a job at the back of the queue.
+    pub fn push(&self, job: Job) {
+        self.state.borrow_mut().pending.push_back(job);
+    }
+
+    /// Takes the job at the front of the queue.
+    pub fn pop(&self) -> Option<Job> {
+        self.state.borrow_mut().pending.pop_front()
+    }
+
+    /// Number of jobs waiting.
+    pub fn len(&self) -> usize {
+        self.state.borrow().pending.len()
+    }
+
+    /// Whether no job is waiting.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Records that `job` has been processed.
+    pub fn finish(&self, job: Job) {
+        self.state.borrow_mut().completed.push(job.id);
+    }
+
+    /// The ids of the processed jobs, in the order they finished.
+    pub fn completed(&self) -> Vec<u32> {
+        self.state.borrow().completed.clone()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::JobQueue;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex, MutexGuard};
+
+    use super::Job;
+
+    #[derive(Debug, Default)]
+    struct State {
+        pending: VecDeque<Job>,
+        completed: Vec<u32>,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct JobQueue {
+        state: Arc<Mutex<State>>,
+    }
+
+    impl JobQueue {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        fn lock(&self) -> MutexGuard<'_, State> {
+            self.state.lock().unwrap()
+        }
+
+        pub fn push(&self, job: Job) {
+            self.lock().pending.push_back(job);
+        }
+
+        pub fn pop(&self) -> Option<Job> {
+            self.lock().pending.pop_front()
+        }
+
+        pub fn len(&self) -> usize {
+            self.lock().pending.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        pub fn finish(&self, job: Job) {
+            self.lock().completed.push(job.id);
+        }
+
+        pub fn completed(&self) -> Vec<u32> {
+            self.lock().completed.clone()
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "job_queue",
+    title: "Migrate single-threaded code to multi-threaded",
+    chapter: Chapter::Threads,
+    difficulty: Difficulty::Core,
+    tags: &["arc", "mutex", "migration"],
+    description: "Port the working `Rc<RefCell<_>>` `JobQueue` to a thread-safe design so that \
+                  worker threads can share it, keeping its API and its old tests intact.",
+    tests: &[
+        Test { name: "fifo", run: fifo },
+        Test { name: "clones_share_jobs", run: clones_share_jobs },
+        Test { name: "is_send_and_sync", run: is_send_and_sync },
+        Test { name: "four_workers", run: four_workers },
+        Test { name: "producer_and_workers", run: producer_and_workers },
+    ],
+    hints: &[
+        Hint(
+            "`Rc` becomes `Arc`, `RefCell` becomes `Mutex`. The `State` struct can stay as it \
+              is.",
+        ),
+        Hint(
+            "`borrow()` and `borrow_mut()` both become `lock().unwrap()`. A small private \
+              `fn lock(&self) -> MutexGuard<'_, State>` keeps the methods as short as before.",
+        ),
+        Hint(
+            "`state: Arc<Mutex<State>>`; `#[derive(Clone, Default)]` still works, since both \
+              `Arc` and `Mutex` implement `Default` and `Arc` implements `Clone`.",
+        ),
+    ],
+};
+
+/// Whether `T` is `Send + Sync`, decided at compile time but reported at run time.
+///
+/// The inherent `holds` only exists when the bound is satisfied; otherwise method resolution
+/// falls back to the trait method. This only works on concrete types, not in generic code.
+macro_rules! is_send_and_sync {
+    ($ty:ty) => {{
+        struct Probe<T>(PhantomData<T>);
+        #[allow(dead_code)]
+        trait Fallback {
+            fn holds(&self) -> bool {
+                false
+            }
+        }
+        impl<T> Fallback for Probe<T> {}
+        impl<T: Send + Sync> Probe<T> {
+            #[allow(dead_code)]
+            fn holds(&self) -> bool {
+                true
+            }
+        }
+        Probe::<$ty>(PhantomData).holds()
+    }};
+}
+
+/// Lets a `JobQueue` cross into a thread once [`require_thread_safe`] has checked that it may.
+///
+/// The tests have to compile against the original `Rc` queue too, so they cannot name
+/// `JobQueue: Send` as a bound.
+struct Checked(JobQueue);
+
+// SAFETY: `Checked` is only built by `require_thread_safe`, after it has verified that
+// `JobQueue` is `Send + Sync`.
+unsafe impl Send for Checked {}
+
+fn require_thread_safe(queue: &JobQueue) -> Checked {
+    check!(
+        is_send_and_sync!(JobQueue);
+        see "job_queue#hints",
+        "`JobQueue` cannot be shared between threads yet: it must be `Send + Sync`"
+    );
+    Checked(queue.clone())
+}
+
+fn fifo() {
+    let queue = JobQueue::new();
+    check!(queue.is_empty(); see "job_queue#hints");
+    queue.push(Job { id: 1 });
+    queue.push(Job { id: 2 });
+    check!(queue.len(), 2; see "job_queue#hints");
+    check!(queue.pop(), Some(Job { id: 1 }); see "job_queue#hints");
+    check!(queue.pop(), Some(Job { id: 2 }); see "job_queue#hints");
+    check!(queue.pop(), None; see "job_queue#hints");
+}
+
+fn clones_share_jobs() {
+    let queue = JobQueue::new();
+    let worker = queue.clone();
+    queue.push(Job { id: 7 });
+    let job = worker.pop().unwrap();
+    worker.finish(job);
+    check!(queue.is_empty(); see "job_queue#hints");
+    check!(queue.completed(), [7]; see "job_queue#hints");
+}
+
+fn is_send_and_sync() {
+    require_thread_safe(&JobQueue::new());
+}
+
+fn work(queue: Checked) {
+    let queue = queue.0;
+    while let Some(job) = queue.pop() {
+        queue.finish(job);
+    }
+}
+
+fn produce(queue: Checked, count: u32) {
+    let queue = queue.0;
+    for id in 0..count {
+        queue.push(Job { id });
+    }
+}
+
+fn four_workers() {
+    let queue = JobQueue::new();
+    for id in 0..1000 {
+        queue.push(Job { id });
+    }
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let handle = require_thread_safe(&queue);
+            thread::spawn(move || work(handle))
+        })
+        .collect();
+    workers.into_iter().for_each(join);
+
+    let mut completed = queue.completed();
+    completed.sort_unstable();
+    check!(completed, (0..1000).collect::<Vec<_>>(); see "job_queue#hints");
+    check!(queue.is_empty(); see "job_queue#hints");
+}
+
+fn producer_and_workers() {
+    let queue = JobQueue::new();
+    let producer = require_thread_safe(&queue);
+    let producer = thread::spawn(move || produce(producer, 500));
+    join(producer);
+
+    let workers: Vec<_> = (0..3)
+        .map(|_| {
+            let handle = require_thread_safe(&queue);
+            thread::spawn(move || work(handle))
+        })
+        .collect();
+    workers.into_iter().for_each(join);
+    check!(queue.completed().len(), 500; see "job_queue#hints");
+}
@@ -0,0 +1,271 @@
+//! A doubly linked list with `Rc<RefCell<Node>>` and `Weak`.
+//!
+//! Each node owns the next node through an `Rc` and points back at the previous node. If the back
+//! pointer were an `Rc` as well, every pair of neighbors would form a reference cycle and no node
+//! would ever be freed. The tests check that with the crate's [leak checker](crate::leakcheck).
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::leakcheck::LeakTracker;
+
+/// A strong link to a node.
+pub type Link<T> = Option<Rc<RefCell<ListNode<T>>>>;
+
+/// A node of the list.
+#[derive(Debug)]
+pub struct ListNode<T> {
+    /// The element.
+    pub value: T,
+    /// The next node. Owns it.
+    pub next: Link<T>,
+    /// The previous node. Does not own it.
+    pub prev: Option<Weak<RefCell<ListNode<T>>>>,
+}
+
+// BEGIN EXERCISE
+/// A doubly linked list.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug)]
+pub struct List<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl<T> List<T> {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self { head: None, tail: None, len: 0 }
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds `value` at the front.
+    pub fn push_front(&mut self, value: T) {
+        let _ = (&self.head, &self.tail, value);
+        todo!()
+    }
+
+    /// Adds `value` at the back.
+    pub fn push_back(&mut self, value: T) {
+        let _ = value;
+        todo!()
+    }
+
+    /// Removes and returns the first element.
+    pub fn pop_front(&mut self) -> Option<T> {
+        todo!()
+    }
+
+    /// Removes and returns the last element.
+    pub fn pop_back(&mut self) -> Option<T> {
+        todo!()
+    }
+}
+
+#[cfg(not(feature = "solutions"))]
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::List;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{Link, ListNode, Rc, RefCell};
+
+    #[derive(Debug)]
+    pub struct List<T> {
+        head: Link<T>,
+        tail: Link<T>,
+        len: usize,
+    }
+
+    impl<T> List<T> {
+        pub fn new() -> Self {
+            Self { head: None, tail: None, len: 0 }
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        pub fn push_front(&mut self, value: T) {
+            let node = Rc::new(RefCell::new(ListNode { value, next: None, prev: None }));
+            match self.head.take() {
+                Some(old) => {
+                    old.borrow_mut().prev = Some(Rc::downgrade(&node));
+                    node.borrow_mut().next = Some(old);
+                }
+                None => self.tail = Some(Rc::clone(&node)),
+            }
+            self.head = Some(node);
+            self.len += 1;
+        }
+
+        pub fn push_back(&mut self, value: T) {
+            let node = Rc::new(RefCell::new(ListNode { value, next: None, prev: None }));
+            match self.tail.take() {
+                Some(old) => {
+                    node.borrow_mut().prev = Some(Rc::downgrade(&old));
+                    old.borrow_mut().next = Some(Rc::clone(&node));
+                }
+                None => self.head = Some(Rc::clone(&node)),
+            }
+            self.tail = Some(node);
+            self.len += 1;
+        }
+
+        pub fn pop_front(&mut self) -> Option<T> {
+            let old = self.head.take()?;
+            match old.borrow_mut().next.take() {
+                Some(next) => {
+                    next.borrow_mut().prev = None;
+                    self.head = Some(next);
+                }
+                None => self.tail = None,
+            }
+            self.len -= 1;
+            Some(Self::into_value(old))
+        }
+
+        pub fn pop_back(&mut self) -> Option<T> {
+            let old = self.tail.take()?;
+            match old.borrow_mut().prev.take().and_then(|prev| prev.upgrade()) {
+                Some(prev) => {
+                    prev.borrow_mut().next = None;
+                    self.tail = Some(prev);
+                }
+                None => self.head = None,
+            }
+            self.len -= 1;
+            Some(Self::into_value(old))
+        }
+
+        fn into_value(node: Rc<RefCell<ListNode<T>>>) -> T {
+            match Rc::try_unwrap(node) {
+                Ok(node) => node.into_inner().value,
+                Err(_) => unreachable!("a popped node has no other strong references"),
+            }
+        }
+    }
+
+    impl<T> Default for List<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Drop for List<T> {
+        fn drop(&mut self) {
+            // Unlink iteratively, so long lists do not overflow the stack with recursive drops.
+            while self.pop_front().is_some() {}
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "linked_list",
+    title: "Doubly linked list",
+    chapter: Chapter::Rc,
+    difficulty: Difficulty::Advanced,
+    tags: &["rc", "weak", "refcell", "leaks"],
+    description: "Implement push and pop at both ends of a doubly linked list built from \
+                  `Rc<RefCell<_>>` forward links and `Weak` back links, without leaking nodes.",
+    tests: &[
+        Test { name: "push_pop_front", run: push_pop_front },
+        Test { name: "push_pop_back", run: push_pop_back },
+        Test { name: "mixed_ends", run: mixed_ends },
+        Test { name: "no_leaks", run: no_leaks },
+    ],
+    hints: &[
+        Hint(
+            "The list owns `head` and `tail`; each node owns `next` and only points at `prev` \
+              through a `Weak`.",
+        ),
+        Hint(
+            "When popping, unlink the neighbor first (`take()` its link), then turn the popped \
+              `Rc` back into the value with `Rc::try_unwrap(node).ok().unwrap().into_inner()`.",
+        ),
+        Hint(
+            "`push_back`: create the node, set `node.prev = Rc::downgrade(&old_tail)`, set \
+              `old_tail.next = Some(node.clone())`, then replace `tail`. Empty list: head = tail.",
+        ),
+    ],
+};
+
+fn push_pop_front() {
+    let mut list = List::new();
+    list.push_front(1);
+    list.push_front(2);
+    list.push_front(3);
+    check!(list.len(), 3; see "linked_list#hints");
+    check!(list.pop_front(), Some(3); see "linked_list#hints");
+    check!(list.pop_front(), Some(2); see "linked_list#hints");
+    check!(list.pop_front(), Some(1); see "linked_list#hints");
+    check!(list.pop_front(), None; see "linked_list#hints");
+    check!(list.is_empty(); see "linked_list#hints");
+}
+
+fn push_pop_back() {
+    let mut list = List::new();
+    list.push_back("a");
+    list.push_back("b");
+    check!(list.pop_back(), Some("b"); see "linked_list#hints");
+    check!(list.pop_back(), Some("a"); see "linked_list#hints");
+    check!(list.pop_back(), None; see "linked_list#hints");
+}
+
+fn mixed_ends() {
+    let mut list = List::new();
+    list.push_back(2);
+    list.push_front(1);
+    list.push_back(3);
+    check!(list.pop_back(), Some(3); see "linked_list#hints");
+    check!(list.pop_front(), Some(1); see "linked_list#hints");
+    check!(list.pop_back(), Some(2); see "linked_list#hints");
+    check!(list.pop_front(), None; see "linked_list#hints");
+
+    list.push_front(4);
+    check!(list.pop_back(), Some(4); see "linked_list#hints");
+}
+
+fn no_leaks() {
+    let tracker = LeakTracker::new();
+    {
+        let mut list = List::new();
+        for _ in 0..100 {
+            list.push_back(tracker.track());
+            list.push_front(tracker.track());
+        }
+        for _ in 0..30 {
+            list.pop_front();
+            list.pop_back();
+        }
+        check!(tracker.live(), 140; see "linked_list#hints");
+    }
+    tracker.assert_no_leaks();
+}
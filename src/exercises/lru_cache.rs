@@ -0,0 +1,199 @@
+//! An LRU cache with interior mutability.
+//!
+//! Reading from a least-recently-used cache is a write in disguise: `get` has to move the entry
+//! to the front of the recency order. Since `get` takes `&self`, the entries live in a
+//! `RefCell`.
+//!
+//! The cache also offers `get_or_insert_with`, which calls a user-provided closure on a miss.
+//! That closure may well look up other keys in the same cache, so no borrow may be held while it
+//! runs.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+// BEGIN EXERCISE
+/// A cache holding at most `capacity` entries, evicting the least recently used one.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug)]
+pub struct LruCache<K, V> {
+    capacity: usize,
+    /// Most recently used first.
+    entries: RefCell<VecDeque<(K, V)>>,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl<K: PartialEq + Clone, V: Clone> LruCache<K, V> {
+    /// Creates an empty cache. `capacity` must be at least one.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be at least one");
+        Self { capacity, entries: RefCell::new(VecDeque::new()) }
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The keys, most recently used first.
+    pub fn keys(&self) -> Vec<K> {
+        self.entries.borrow().iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    /// Returns a copy of the value for `key` and marks it as most recently used.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let _ = (key, self.capacity);
+        todo!()
+    }
+
+    /// Inserts or replaces the value for `key`, marking it as most recently used and evicting
+    /// the least recently used entry if the cache is over capacity.
+    pub fn put(&self, key: K, value: V) {
+        let _ = (key, value);
+        todo!()
+    }
+
+    /// Returns the value for `key`, computing and inserting it with `compute` on a miss.
+    ///
+    /// `compute` may use the cache itself.
+    pub fn get_or_insert_with(&self, key: K, compute: impl FnOnce() -> V) -> V {
+        let _ = (key, compute);
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::LruCache;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{RefCell, VecDeque};
+
+    #[derive(Debug)]
+    pub struct LruCache<K, V> {
+        capacity: usize,
+        entries: RefCell<VecDeque<(K, V)>>,
+    }
+
+    impl<K: PartialEq + Clone, V: Clone> LruCache<K, V> {
+        pub fn new(capacity: usize) -> Self {
+            assert!(capacity > 0, "capacity must be at least one");
+            Self { capacity, entries: RefCell::new(VecDeque::new()) }
+        }
+
+        pub fn len(&self) -> usize {
+            self.entries.borrow().len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        pub fn keys(&self) -> Vec<K> {
+            self.entries.borrow().iter().map(|(key, _)| key.clone()).collect()
+        }
+
+        pub fn get(&self, key: &K) -> Option<V> {
+            let mut entries = self.entries.borrow_mut();
+            let index = entries.iter().position(|(k, _)| k == key)?;
+            let entry = entries.remove(index)?;
+            let value = entry.1.clone();
+            entries.push_front(entry);
+            Some(value)
+        }
+
+        pub fn put(&self, key: K, value: V) {
+            let mut entries = self.entries.borrow_mut();
+            entries.retain(|(k, _)| *k != key);
+            entries.push_front((key, value));
+            entries.truncate(self.capacity);
+        }
+
+        pub fn get_or_insert_with(&self, key: K, compute: impl FnOnce() -> V) -> V {
+            if let Some(value) = self.get(&key) {
+                return value;
+            }
+            let value = compute();
+            self.put(key, value.clone());
+            value
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "lru_cache",
+    title: "LRU cache with interior mutability",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Core,
+    tags: &["refcell", "caching", "reentrancy"],
+    description: "Implement `get`, `put` and `get_or_insert_with` for an LRU cache whose lookups \
+                  update the recency order through `&self`.",
+    tests: &[
+        Test { name: "get_and_put", run: get_and_put },
+        Test { name: "evicts_least_recently_used", run: evicts_least_recently_used },
+        Test { name: "get_refreshes_recency", run: get_refreshes_recency },
+        Test { name: "nested_lookups", run: nested_lookups },
+    ],
+    hints: &[
+        Hint("`get` needs `borrow_mut()`: it moves the entry to the front of the deque."),
+        Hint(
+            "In `get_or_insert_with`, finish the lookup (and drop its guard) before calling \
+              `compute`, and only borrow again to insert the result.",
+        ),
+        Hint(
+            "`put`: `retain` away the old entry for the key, `push_front` the new one and \
+              `truncate(self.capacity)`.",
+        ),
+    ],
+};
+
+fn get_and_put() {
+    let cache = LruCache::new(2);
+    check!(cache.get(&"a"), None; see "refcell#example-6");
+    cache.put("a", 1);
+    cache.put("a", 2);
+    check!(cache.get(&"a"), Some(2); see "refcell#example-6");
+    check!(cache.len(), 1; see "refcell#example-6");
+}
+
+fn evicts_least_recently_used() {
+    let cache = LruCache::new(2);
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    check!(cache.keys(), [3, 2]; see "refcell#example-6");
+    check!(cache.get(&1), None; see "refcell#example-6");
+}
+
+fn get_refreshes_recency() {
+    let cache = LruCache::new(2);
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.get(&1);
+    cache.put(3, "three");
+    check!(cache.keys(), [3, 1]; see "refcell#example-6");
+}
+
+fn nested_lookups() {
+    let cache = LruCache::new(4);
+    cache.put("base", 10);
+    let derived = cache.get_or_insert_with("derived", || cache.get(&"base").unwrap() * 2);
+    check!(derived, 20; see "refcell#example-6");
+
+    let deeper = cache.get_or_insert_with("deeper", || {
+        cache.get_or_insert_with("derived", || unreachable!("already cached")) + 1
+    });
+    check!(deeper, 21; see "refcell#example-6");
+    check!(cache.keys(), ["deeper", "derived", "base"]; see "refcell#example-6");
+}
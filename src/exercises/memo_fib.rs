@@ -0,0 +1,157 @@
+//! Memoized Fibonacci with `RefCell<HashMap>`.
+//!
+//! `Fibonacci::fib` takes `&self`, yet it should remember every value it has computed so that
+//! each Fibonacci number is computed at most once. The memo is a
+//! [`CountingCell`], a `RefCell` that counts its borrows: the tests
+//! use the count of mutable borrows to check that nothing is computed twice.
+//!
+//! Watch out: `fib` is recursive. Holding a borrow of the memo across the recursive calls will
+//! panic.
+//!
+//! See the [`RefCell`](crate::RefCell) lesson.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::impls::CountingCell;
+use crate::util::with_timeout;
+
+// BEGIN EXERCISE
+/// A Fibonacci calculator that remembers its results.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Default)]
+pub struct Fibonacci {
+    /// `n -> fib(n)` for every `n` computed so far.
+    pub memo: CountingCell<HashMap<u64, u64>>,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Fibonacci {
+    /// Creates a calculator with an empty memo.
+    pub fn new() -> Self {
+        Self { memo: CountingCell::new(HashMap::new()) }
+    }
+
+    /// Returns the `n`th Fibonacci number (`fib(0) == 0`, `fib(1) == 1`), computing and storing
+    /// it in the memo only if it is not there yet.
+    pub fn fib(&self, n: u64) -> u64 {
+        let _ = n;
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::Fibonacci;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{CountingCell, HashMap};
+
+    #[derive(Debug, Default)]
+    pub struct Fibonacci {
+        pub memo: CountingCell<HashMap<u64, u64>>,
+    }
+
+    impl Fibonacci {
+        pub fn new() -> Self {
+            Self { memo: CountingCell::new(HashMap::new()) }
+        }
+
+        pub fn fib(&self, n: u64) -> u64 {
+            if let Some(value) = self.memo.borrow().get(&n) {
+                return *value;
+            }
+
+            let value = if n < 2 { n } else { self.fib(n - 1) + self.fib(n - 2) };
+            self.memo.borrow_mut().insert(n, value);
+            value
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "memo_fib",
+    title: "Memoized Fibonacci with RefCell<HashMap>",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Core,
+    tags: &["refcell", "caching", "recursion"],
+    description: "Implement `Fibonacci::fib(&self, n)` so that every value is computed once and \
+                  remembered in a `RefCell<HashMap<u64, u64>>`.",
+    tests: &[
+        Test { name: "small_values", run: small_values },
+        Test { name: "large_value", run: large_value },
+        Test { name: "each_value_computed_once", run: each_value_computed_once },
+        Test { name: "repeated_calls_hit_the_memo", run: repeated_calls_hit_the_memo },
+    ],
+    hints: &[
+        Hint("Look the value up first and return early on a hit; only compute on a miss."),
+        Hint(
+            "The `Ref` from `memo.borrow()` must be gone before you recurse, and the recursion \
+              must be done before you `borrow_mut()` to insert.",
+        ),
+        Hint(
+            "`if let Some(v) = self.memo.borrow().get(&n) { return *v; }` then compute \
+              `self.fib(n - 1) + self.fib(n - 2)` and insert it with a fresh `borrow_mut()`.",
+        ),
+    ],
+};
+
+fn small_values() {
+    let fibonacci = Fibonacci::new();
+    let values: Vec<_> = (0..10).map(|n| fibonacci.fib(n)).collect();
+    check!(values, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]; see "refcell#example-5");
+}
+
+/// How long computing a large Fibonacci number may take. With a memo it takes microseconds;
+/// without one, `fib(50)` alone takes minutes.
+const LIMIT: Duration = Duration::from_secs(5);
+
+/// Computes `fib(n)` with a fresh calculator, failing instead of hanging if it is not memoized.
+/// Returns the calculator and the value.
+fn fib_within_limit(n: u64) -> (Fibonacci, u64) {
+    let outcome = with_timeout(LIMIT, move || {
+        let fibonacci = Fibonacci::new();
+        let value = fibonacci.fib(n);
+        (fibonacci, value)
+    });
+    match outcome {
+        Ok(computed) => computed,
+        Err(err) => panic!("fib({n}) did not finish within {:?}, is the memo used?", err.limit),
+    }
+}
+
+fn large_value() {
+    let (_, value) = fib_within_limit(90);
+    check!(value, 2_880_067_194_370_816_120; see "refcell#example-5");
+}
+
+fn each_value_computed_once() {
+    let (fibonacci, _) = fib_within_limit(50);
+    let inserts = fibonacci.memo.counts().exclusive;
+    check!(
+        inserts <= 51;
+        see "refcell#example-5",
+        "computed {inserts} values to get fib(50), expected at most 51"
+    );
+}
+
+fn repeated_calls_hit_the_memo() {
+    let fibonacci = Fibonacci::new();
+    fibonacci.fib(30);
+    fibonacci.memo.reset_counts();
+
+    for n in 0..=30 {
+        fibonacci.fib(n);
+    }
+    check!(
+        fibonacci.memo.counts().exclusive,
+        0;
+        see "refcell#example-5", "already computed values were recomputed"
+    );
+}
@@ -0,0 +1,150 @@
+//! A mock object that records calls.
+//!
+//! Code under test usually talks to its collaborators through `&self` methods: a `Logger` trait
+//! has `fn log(&self, message: &str)`, not `&mut self`. A test double that wants to remember what
+//! it was called with therefore needs interior mutability. This is probably the most common
+//! everyday use of `RefCell`.
+
+use super::{Hint, StaticExercise, Test};
+use crate::curriculum::{Chapter, Difficulty};
+use crate::{check, check_lines};
+
+/// Something that accepts log messages.
+pub trait Logger {
+    /// Logs `message`.
+    fn log(&self, message: &str);
+}
+
+/// The code under test: charges an order and logs what happened.
+pub fn process_order(logger: &dyn Logger, id: u32, amount: i64) -> bool {
+    logger.log(&format!("processing order {id}"));
+    if amount <= 0 {
+        logger.log(&format!("rejected order {id}: invalid amount {amount}"));
+        return false;
+    }
+    logger.log(&format!("charged {amount} for order {id}"));
+    true
+}
+
+// BEGIN EXERCISE
+/// A logger that remembers every message, for use in tests.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Default)]
+pub struct MockLogger {
+    // TODO: somewhere to store the messages.
+}
+
+#[cfg(not(feature = "solutions"))]
+impl MockLogger {
+    /// Creates a logger that has not recorded anything yet.
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Every message logged so far, in order.
+    pub fn messages(&self) -> Vec<String> {
+        todo!()
+    }
+
+    /// Number of logged messages containing `needle`.
+    pub fn count_containing(&self, needle: &str) -> usize {
+        let _ = needle;
+        todo!()
+    }
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Logger for MockLogger {
+    fn log(&self, message: &str) {
+        let _ = message;
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::MockLogger;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::cell::RefCell;
+
+    use super::Logger;
+
+    #[derive(Debug, Default)]
+    pub struct MockLogger {
+        messages: RefCell<Vec<String>>,
+    }
+
+    impl MockLogger {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn messages(&self) -> Vec<String> {
+            self.messages.borrow().clone()
+        }
+
+        pub fn count_containing(&self, needle: &str) -> usize {
+            self.messages.borrow().iter().filter(|message| message.contains(needle)).count()
+        }
+    }
+
+    impl Logger for MockLogger {
+        fn log(&self, message: &str) {
+            self.messages.borrow_mut().push(message.to_string());
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "mock_logger",
+    title: "Mock object that records calls",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Intro,
+    tags: &["refcell", "testing", "traits"],
+    description: "Implement a `MockLogger` whose `log(&self, ..)` records messages so tests can \
+                  assert on them afterwards.",
+    tests: &[
+        Test { name: "records_in_order", run: records_in_order },
+        Test { name: "successful_order", run: successful_order },
+        Test { name: "rejected_order", run: rejected_order },
+    ],
+    hints: &[
+        Hint("`log` only gets `&self`, so the list of messages needs interior mutability."),
+        Hint(
+            "A `RefCell<Vec<String>>` field works; `String` is not `Copy`, so `Cell` would be \
+              awkward here.",
+        ),
+        Hint(
+            "`self.messages.borrow_mut().push(message.to_string())` in `log`, and \
+              `self.messages.borrow().clone()` in `messages`.",
+        ),
+    ],
+};
+
+fn records_in_order() {
+    let logger = MockLogger::new();
+    logger.log("one");
+    logger.log("two");
+    check_lines!(logger.messages(), ["one", "two"]; see "refcell#example-5");
+}
+
+fn successful_order() {
+    let logger = MockLogger::new();
+    check!(process_order(&logger, 7, 100); see "refcell#example-5");
+    check_lines!(
+        logger.messages(),
+        ["processing order 7", "charged 100 for order 7"];
+        see "refcell#example-5"
+    );
+}
+
+fn rejected_order() {
+    let logger = MockLogger::new();
+    check!(!process_order(&logger, 8, -5); see "refcell#example-5");
+    check!(logger.count_containing("rejected"), 1; see "refcell#example-5");
+    check!(logger.count_containing("charged"), 0; see "refcell#example-5");
+}
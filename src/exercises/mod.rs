@@ -0,0 +1,694 @@
+//! Hands-on exercises.
+//!
+//! Each exercise lives in its own file under `src/exercises/`. The file contains one or more
+//! functions or types whose bodies are left as `todo!()`, together with the hidden tests that
+//! check the learner's work. To solve an exercise, replace the `todo!()`s until every test of
+//! the exercise passes.
+//!
+//! Every exercise also ships a reference solution, compiled only with `--features solutions`.
+//! With the feature enabled, the scaffolded items are replaced by the solution, so the same
+//! hidden tests run against the reference implementation:
+//! ```text
+//! cargo test --features solutions
+//! ```
+//!
+//! The hidden tests are ordinary functions that panic on failure. They are not `#[test]`s, since
+//! an unsolved exercise would make the crate's own test suite fail. Instead, every exercise is
+//! listed in a registry which tooling can enumerate and run:
+//! ```
+//! use learning_cell::exercises;
+//!
+//! let exercise = exercises::get("cell_intro").unwrap();
+//! assert_eq!(exercise.title(), "Reading and writing a Cell");
+//!
+//! let report = exercise.run();
+//! assert_eq!(report.results.len(), exercise.tests().len());
+//! ```
+//!
+//! Other crates can add exercises of their own by implementing the [`Exercise`] trait (or simply
+//! filling in a [`StaticExercise`]) and registering them with [`register_exercise!`](crate::register_exercise). Registered
+//! exercises show up in [`all`], [`get`] and every other query, right after the built-in ones.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::RwLock;
+
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::catch_panic;
+
+pub mod arena_graph;
+pub mod bank_transfer;
+pub mod buffered_logger;
+pub mod cell_intro;
+pub mod cell_slices;
+pub mod deadlock;
+pub mod deferred_dispatch;
+pub mod entity_components;
+pub mod event_bus;
+pub mod fix_borrow_panic;
+pub mod get_or_compute;
+pub mod hot_reload;
+pub mod interner;
+pub mod job_queue;
+pub mod linked_list;
+pub mod lru_cache;
+pub mod memo_fib;
+pub mod mock_logger;
+pub mod my_cell;
+pub mod my_refcell;
+pub mod once_config;
+pub mod producer_consumer;
+pub mod reference_cycle;
+pub mod remove_refcell;
+pub mod rwlock_cache;
+pub mod shared_counter;
+pub mod shared_graph;
+pub mod spy_iterator;
+pub mod thread_counter;
+pub mod thread_pool;
+pub mod toposort;
+pub mod undo_redo;
+pub mod weak_tree;
+
+thread_local! {
+    /// Notes left by the hidden test that is currently running, see [`note`].
+    static NOTES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Exercises added with [`register`], in registration order.
+static REGISTERED: RwLock<Vec<&'static dyn Exercise>> = RwLock::new(Vec::new());
+
+/// The course installed by [`Registry::install`](crate::manifest::Registry::install), if any.
+static COURSE: RwLock<Option<Vec<&'static dyn Exercise>>> = RwLock::new(None);
+
+static EXERCISES: &[StaticExercise] = &[
+    cell_intro::EXERCISE,
+    fix_borrow_panic::EXERCISE,
+    shared_counter::EXERCISE,
+    event_bus::EXERCISE,
+    memo_fib::EXERCISE,
+    shared_graph::EXERCISE,
+    linked_list::EXERCISE,
+    weak_tree::EXERCISE,
+    reference_cycle::EXERCISE,
+    thread_counter::EXERCISE,
+    once_config::EXERCISE,
+    lru_cache::EXERCISE,
+    interner::EXERCISE,
+    deferred_dispatch::EXERCISE,
+    mock_logger::EXERCISE,
+    spy_iterator::EXERCISE,
+    bank_transfer::EXERCISE,
+    undo_redo::EXERCISE,
+    toposort::EXERCISE,
+    job_queue::EXERCISE,
+    remove_refcell::EXERCISE,
+    deadlock::EXERCISE,
+    producer_consumer::EXERCISE,
+    get_or_compute::EXERCISE,
+    buffered_logger::EXERCISE,
+    cell_slices::EXERCISE,
+    arena_graph::EXERCISE,
+    my_cell::EXERCISE,
+    my_refcell::EXERCISE,
+    thread_pool::EXERCISE,
+    entity_components::EXERCISE,
+    hot_reload::EXERCISE,
+    rwlock_cache::EXERCISE,
+];
+
+/// An exercise: what to do, hints on how to do it, and the hidden tests that check it.
+///
+/// Only the metadata and the tests are required; [`reveal`](Exercise::reveal) and
+/// [`run`](Exercise::run) have default implementations that fit every exercise. The built-in
+/// exercises are [`StaticExercise`]s, which is also the easiest way to write a new one.
+/// ```
+/// use learning_cell::curriculum::Chapter;
+/// use learning_cell::exercises::{self, Exercise, Test};
+///
+/// #[derive(Debug)]
+/// struct Homework;
+///
+/// impl Exercise for Homework {
+///     fn id(&self) -> &'static str {
+///         "homework"
+///     }
+///     fn title(&self) -> &'static str {
+///         "Homework"
+///     }
+///     fn chapter(&self) -> Chapter {
+///         Chapter::Cell
+///     }
+///     fn description(&self) -> &'static str {
+///         "Nothing to do, the test always passes."
+///     }
+///     fn tests(&self) -> &'static [Test] {
+///         &[Test { name: "passes", run: || {} }]
+///     }
+/// }
+///
+/// learning_cell::register_exercise!(Homework);
+/// assert!(exercises::get("homework").unwrap().run().passed());
+/// ```
+pub trait Exercise: fmt::Debug + Sync {
+    /// Unique, stable identifier.
+    fn id(&self) -> &'static str;
+
+    /// Short human readable title.
+    fn title(&self) -> &'static str;
+
+    /// The chapter the exercise belongs to.
+    fn chapter(&self) -> Chapter;
+
+    /// How demanding the exercise is.
+    fn difficulty(&self) -> Difficulty {
+        Difficulty::Core
+    }
+
+    /// Lowercase topic tags, like `"refcell"` or `"reentrancy"`.
+    fn tags(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// What the learner has to do.
+    fn description(&self) -> &'static str;
+
+    /// The hidden tests. The exercise is solved when all of them pass.
+    fn tests(&self) -> &'static [Test];
+
+    /// Escalating hints, from a gentle nudge to a near-solution.
+    fn hints(&self) -> &'static [Hint] {
+        &[]
+    }
+
+    /// Ids of the exercises to solve first. Only a [manifest](crate::manifest) sets any.
+    fn prerequisites(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// The first `level` hints (all of them if `level` is larger than the number of hints).
+    fn reveal(&self, level: usize) -> &'static [Hint] {
+        let hints = self.hints();
+        &hints[..level.min(hints.len())]
+    }
+
+    /// Runs every hidden test of the exercise, catching panics (including `todo!()`s).
+    fn run(&self) -> Report {
+        let results = self
+            .tests()
+            .iter()
+            .map(|test| {
+                NOTES.with(|notes| notes.borrow_mut().clear());
+                let outcome = catch_panic(test.run);
+                let notes = NOTES.with(|notes| notes.take());
+                TestResult { name: test.name, outcome, notes }
+            })
+            .collect();
+
+        Report { exercise: self.id(), results }
+    }
+}
+
+/// An [`Exercise`] described entirely by static data. Every built-in exercise is one.
+#[derive(Debug)]
+pub struct StaticExercise {
+    /// Unique, stable identifier (also the file name under `src/exercises/`).
+    pub id: &'static str,
+    /// Short human readable title.
+    pub title: &'static str,
+    /// The chapter the exercise belongs to.
+    pub chapter: Chapter,
+    /// How demanding the exercise is.
+    pub difficulty: Difficulty,
+    /// Lowercase topic tags, like `"refcell"` or `"reentrancy"`.
+    pub tags: &'static [&'static str],
+    /// What the learner has to do.
+    pub description: &'static str,
+    /// The hidden tests. The exercise is solved when all of them pass.
+    pub tests: &'static [Test],
+    /// Escalating hints, from a gentle nudge to a near-solution.
+    pub hints: &'static [Hint],
+}
+
+/// A single hidden test. Fails by panicking, just like a `#[test]`.
+#[derive(Debug)]
+pub struct Test {
+    /// Name of the test, shown in reports.
+    pub name: &'static str,
+    /// The test body.
+    pub run: fn(),
+}
+
+/// A hint for an exercise.
+///
+/// Hints are ordered: the first one only points in the right direction, the last one is almost
+/// the solution. Tooling should only show deeper hints on request, see [`reveal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hint(pub &'static str);
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Exercise for StaticExercise {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn title(&self) -> &'static str {
+        self.title
+    }
+
+    fn chapter(&self) -> Chapter {
+        self.chapter
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        self.tags
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn tests(&self) -> &'static [Test] {
+        self.tests
+    }
+
+    fn hints(&self) -> &'static [Hint] {
+        self.hints
+    }
+}
+
+/// The outcome of running an exercise's hidden tests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// Id of the exercise that was run.
+    pub exercise: &'static str,
+    /// One entry per hidden test, in order.
+    pub results: Vec<TestResult>,
+}
+
+/// The outcome of a single hidden test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    /// Name of the test.
+    pub name: &'static str,
+    /// `Err` holds the panic message of a failed test.
+    pub outcome: Result<(), String>,
+    /// Extra output of the test, such as benchmark timings, see [`note`].
+    pub notes: Vec<String>,
+}
+
+impl Report {
+    /// Whether every hidden test passed.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| result.outcome.is_ok())
+    }
+
+    /// The tests that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &TestResult> {
+        self.results.iter().filter(|result| result.outcome.is_err())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let passed = self.results.iter().filter(|result| result.outcome.is_ok()).count();
+        writeln!(f, "{}: {passed}/{} tests passed", self.exercise, self.results.len())?;
+        for result in &self.results {
+            match &result.outcome {
+                Ok(()) => writeln!(f, "  ok     {}", result.name)?,
+                Err(message) => {
+                    // Multi-line messages, like those of `check!`, stay aligned under the name.
+                    let message = message.replace('\n', "\n         ");
+                    writeln!(f, "  FAILED {}: {message}", result.name)?
+                }
+            }
+            for note in &result.notes {
+                writeln!(f, "         {note}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Attaches `message` to the result of the hidden test that is currently running.
+///
+/// Tests use this for output that is worth showing even when they pass, like benchmark timings.
+/// Outside of [`Exercise::run`], notes are discarded.
+pub fn note(message: impl Into<String>) {
+    NOTES.with(|notes| notes.borrow_mut().push(message.into()));
+}
+
+/// Every exercise of the course, in the suggested order.
+///
+/// That is the course of the installed [manifest](crate::manifest), or else every
+/// [`compiled`] exercise.
+pub fn all() -> Vec<&'static dyn Exercise> {
+    match &*COURSE.read().unwrap() {
+        Some(course) => course.clone(),
+        None => compiled(),
+    }
+}
+
+/// Every exercise compiled into the program, whatever the course: the built-in ones followed by
+/// the registered ones.
+pub fn compiled() -> Vec<&'static dyn Exercise> {
+    let builtin = EXERCISES.iter().map(|exercise| exercise as &dyn Exercise);
+    builtin.chain(REGISTERED.read().unwrap().iter().copied()).collect()
+}
+
+/// Replaces the course [`all`] lists, see [`Registry::install`](crate::manifest::Registry::install).
+pub(crate) fn set_course(course: Vec<&'static dyn Exercise>) {
+    *COURSE.write().unwrap() = Some(course);
+}
+
+/// Adds `exercise` to the registry, see [`register_exercise!`](crate::register_exercise).
+///
+/// # Panics
+///
+/// Panics if an exercise with the same id is already registered.
+pub fn register(exercise: &'static dyn Exercise) {
+    let mut registered = REGISTERED.write().unwrap();
+    let id = exercise.id();
+    let taken = EXERCISES.iter().any(|builtin| builtin.id == id)
+        || registered.iter().any(|other| other.id() == id);
+    assert!(!taken, "an exercise with id `{id}` is already registered");
+    registered.push(exercise);
+}
+
+/// Registers one or more exercises, so they show up next to the built-in ones.
+///
+/// Each argument is an expression whose type implements [`Exercise`](crate::exercises::Exercise);
+/// the value is kept alive for the rest of the program. A lesson pack typically exposes a
+/// function that registers all of its exercises, to be called once at startup:
+/// ```
+/// use learning_cell::curriculum::{Chapter, Difficulty};
+/// use learning_cell::exercises::{self, Hint, StaticExercise, Test};
+///
+/// pub fn register_pack() {
+///     learning_cell::register_exercise!(
+///         StaticExercise {
+///             id: "pack_first",
+///             title: "First exercise of the pack",
+///             chapter: Chapter::RefCell,
+///             difficulty: Difficulty::Intro,
+///             tags: &["refcell"],
+///             description: "...",
+///             tests: &[Test { name: "todo", run: || todo!() }],
+///             hints: &[Hint("Start here.")],
+///         },
+///     );
+/// }
+///
+/// register_pack();
+/// let exercise = exercises::get("pack_first").unwrap();
+/// assert!(!exercise.run().passed());
+/// assert_eq!(exercises::by_tag("refcell").last().unwrap().id(), "pack_first");
+/// ```
+#[macro_export]
+macro_rules! register_exercise {
+    ($($exercise:expr),+ $(,)?) => {
+        $(
+            $crate::exercises::register(::std::boxed::Box::leak(::std::boxed::Box::new($exercise)));
+        )+
+    };
+}
+
+/// Asserts something in a hidden test, pointing the learner at the lesson section to re-read if
+/// it does not hold.
+///
+/// `check!(condition; see "section")` checks a boolean, `check!(actual, expected; see "section")`
+/// checks that two values are equal. On failure the test panics with the checked expression, the
+/// values involved and the section, which is far more useful to a learner than a bare
+/// `assert_eq!` message. Like `assert!`, either form takes an explanation after the section, as a
+/// format string and its arguments.
+///
+/// The section is the anchor of one of the [`search::sections`](crate::search::sections), such
+/// as `refcell#example-6` for an example of a lesson, or `interner#hints` for an exercise's hints.
+/// ```
+/// use std::cell::Cell;
+/// use learning_cell::check;
+/// use learning_cell::util::catch_panic;
+///
+/// let counter = Cell::new(1);
+/// check!(counter.get() > 0; see "cell#example-3");
+///
+/// let message = catch_panic(|| check!(counter.get(), 2; see "cell#example-3")).unwrap_err();
+/// assert_eq!(
+///     message,
+///     "check failed: `counter.get()` should equal `2`\n  \
+///      actual: 1\n  \
+///      expected: 2\n\
+///      see cell#example-3"
+/// );
+///
+/// let message = catch_panic(|| {
+///     check!(counter.get() == 2; see "cell#example-3", "counted {} clicks", counter.get())
+/// });
+/// assert_eq!(
+///     message.unwrap_err(),
+///     "check failed: `counter.get() == 2` is false\n  counted 1 clicks\nsee cell#example-3"
+/// );
+/// ```
+#[macro_export]
+macro_rules! check {
+    ($condition:expr; see $section:expr $(, $($message:tt)+)?) => {
+        if !$condition {
+            ::std::panic!(
+                "check failed: `{}` is false\n{}see {}",
+                ::std::stringify!($condition),
+                $crate::__check_note!($($($message)+)?),
+                $section
+            );
+        }
+    };
+    ($actual:expr, $expected:expr; see $section:expr $(, $($message:tt)+)?) => {
+        match (&$actual, &$expected) {
+            (actual, expected) => {
+                if !(*actual == *expected) {
+                    ::std::panic!(
+                        "check failed: `{}` should equal `{}`\n  actual: {:?}\n  expected: {:?}\n{}see {}",
+                        ::std::stringify!($actual),
+                        ::std::stringify!($expected),
+                        actual,
+                        expected,
+                        $crate::__check_note!($($($message)+)?),
+                        $section
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Checks that a hidden test produced the expected lines, like [`check!`], but on failure shows
+/// only where the output first diverges (see [`diff::render`](crate::diff::render)).
+///
+/// Both sides can be anything that slices into strings: a `Vec<String>`, an array of `&str`, ...
+/// ```
+/// use learning_cell::check_lines;
+/// use learning_cell::util::catch_panic;
+///
+/// let log = vec!["one".to_string(), "two".to_string()];
+/// check_lines!(log, ["one", "two"]; see "refcell#example-5");
+///
+/// let message = catch_panic(|| check_lines!(log, ["one", "too"]; see "refcell#example-5"));
+/// assert!(message.unwrap_err().contains("  - too\n  + two\n"));
+/// ```
+#[macro_export]
+macro_rules! check_lines {
+    ($actual:expr, $expected:expr; see $section:expr) => {
+        if let ::std::option::Option::Some(diff) =
+            $crate::diff::render(&$expected[..], &$actual[..])
+        {
+            ::std::panic!(
+                "check failed: `{}` has the wrong lines\n{}see {}",
+                ::std::stringify!($actual),
+                diff,
+                $section
+            );
+        }
+    };
+}
+
+/// The part of an exercise's source the learner is expected to edit.
+///
+/// Exercise files mark the scaffold with `// BEGIN EXERCISE` / `// END EXERCISE` comments and the
+/// reference solution with `// BEGIN SOLUTION` / `// END SOLUTION`. With the `solutions` feature
+/// enabled, the solution is the code that is actually compiled, so that is what gets returned.
+pub(crate) fn active_code(source: &'static str) -> &'static str {
+    let (begin, end) = if cfg!(feature = "solutions") {
+        ("// BEGIN SOLUTION", "// END SOLUTION")
+    } else {
+        ("// BEGIN EXERCISE", "// END EXERCISE")
+    };
+    let start = source.find(begin).map_or(0, |start| start + begin.len());
+    let end = source[start..].find(end).map_or(source.len(), |end| start + end);
+    &source[start..end]
+}
+
+/// The source file of every built-in exercise, as compiled, see [`source`].
+static SOURCES: &[(&str, &str)] = &[
+    ("cell_intro", include_str!("cell_intro.rs")),
+    ("fix_borrow_panic", include_str!("fix_borrow_panic.rs")),
+    ("shared_counter", include_str!("shared_counter.rs")),
+    ("event_bus", include_str!("event_bus.rs")),
+    ("memo_fib", include_str!("memo_fib.rs")),
+    ("shared_graph", include_str!("shared_graph.rs")),
+    ("linked_list", include_str!("linked_list.rs")),
+    ("weak_tree", include_str!("weak_tree.rs")),
+    ("reference_cycle", include_str!("reference_cycle.rs")),
+    ("thread_counter", include_str!("thread_counter.rs")),
+    ("once_config", include_str!("once_config.rs")),
+    ("lru_cache", include_str!("lru_cache.rs")),
+    ("interner", include_str!("interner.rs")),
+    ("deferred_dispatch", include_str!("deferred_dispatch.rs")),
+    ("mock_logger", include_str!("mock_logger.rs")),
+    ("spy_iterator", include_str!("spy_iterator.rs")),
+    ("bank_transfer", include_str!("bank_transfer.rs")),
+    ("undo_redo", include_str!("undo_redo.rs")),
+    ("toposort", include_str!("toposort.rs")),
+    ("job_queue", include_str!("job_queue.rs")),
+    ("remove_refcell", include_str!("remove_refcell.rs")),
+    ("deadlock", include_str!("deadlock.rs")),
+    ("producer_consumer", include_str!("producer_consumer.rs")),
+    ("get_or_compute", include_str!("get_or_compute.rs")),
+    ("buffered_logger", include_str!("buffered_logger.rs")),
+    ("cell_slices", include_str!("cell_slices.rs")),
+    ("arena_graph", include_str!("arena_graph.rs")),
+    ("my_cell", include_str!("my_cell.rs")),
+    ("my_refcell", include_str!("my_refcell.rs")),
+    ("thread_pool", include_str!("thread_pool.rs")),
+    ("entity_components", include_str!("entity_components.rs")),
+    ("hot_reload", include_str!("hot_reload.rs")),
+    ("rwlock_cache", include_str!("rwlock_cache.rs")),
+];
+
+/// The source file of the built-in exercise `id`, as it was when the crate was compiled
+/// (including any edits the learner had made by then).
+///
+/// Exporters use it to carry an exercise's hidden tests over to other formats.
+/// ```
+/// let source = learning_cell::exercises::source("cell_intro").unwrap();
+/// assert!(source.contains("// BEGIN EXERCISE"));
+/// ```
+pub fn source(id: &str) -> Option<&'static str> {
+    SOURCES.iter().find(|(name, _)| *name == id).map(|(_, source)| *source)
+}
+
+/// The shipped scaffolds, see [`scaffold`].
+static SCAFFOLDS: &str = include_str!("scaffolds.txt");
+
+/// The scaffold of the built-in exercise `id` as shipped: the code between its
+/// `// BEGIN EXERCISE` and `// END EXERCISE` markers before any learner edited it.
+///
+/// Together with [`restore_scaffold`], this lets tooling undo a learner's changes.
+/// ```
+/// use learning_cell::exercises;
+///
+/// let scaffold = exercises::scaffold("cell_intro").unwrap();
+/// assert!(scaffold.contains("todo!"));
+///
+/// let edited = "// BEGIN EXERCISE\nfn bump() {}\n// END EXERCISE\n";
+/// let restored = exercises::restore_scaffold(edited, scaffold).unwrap();
+/// assert_eq!(restored, format!("// BEGIN EXERCISE\n{scaffold}// END EXERCISE\n"));
+///
+/// for exercise in exercises::all() {
+///     assert!(exercises::scaffold(exercise.id()).is_some(), "{}", exercise.id());
+/// }
+/// ```
+pub fn scaffold(id: &str) -> Option<&'static str> {
+    let header = format!("=== {id} ===\n");
+    let start = SCAFFOLDS.find(&header)? + header.len();
+    let end = SCAFFOLDS[start..].find("\n=== ").map_or(SCAFFOLDS.len(), |end| start + end + 1);
+    Some(&SCAFFOLDS[start..end])
+}
+
+/// Replaces the code between the exercise markers of `source` with `scaffold`.
+///
+/// Returns `None` if `source` has no (or broken) markers.
+pub fn restore_scaffold(source: &str, scaffold: &str) -> Option<String> {
+    const BEGIN: &str = "// BEGIN EXERCISE\n";
+    let start = source.find(BEGIN)? + BEGIN.len();
+    let end = start + source[start..].find("// END EXERCISE")?;
+    Some(format!("{}{scaffold}{}", &source[..start], &source[end..]))
+}
+
+/// All hints of the exercise `id`, or none if there is no such exercise.
+pub fn hints(id: &str) -> &'static [Hint] {
+    get(id).map_or(&[], |exercise| exercise.hints())
+}
+
+/// The hints of the exercise `id` up to and including reveal `level`.
+///
+/// Level `0` reveals nothing, level `1` the first hint, and so on.
+/// ```
+/// use learning_cell::exercises;
+///
+/// assert!(exercises::reveal("cell_intro", 0).is_empty());
+/// assert_eq!(exercises::reveal("cell_intro", 1).len(), 1);
+/// assert_eq!(exercises::reveal("cell_intro", 99), exercises::hints("cell_intro"));
+/// ```
+pub fn reveal(id: &str, level: usize) -> &'static [Hint] {
+    get(id).map_or(&[], |exercise| exercise.reveal(level))
+}
+
+/// The exercises of the given difficulty, in the suggested order.
+pub fn by_difficulty(difficulty: Difficulty) -> Vec<&'static dyn Exercise> {
+    all().into_iter().filter(|exercise| exercise.difficulty() == difficulty).collect()
+}
+
+/// The exercises tagged with `tag` (case-insensitive), in the suggested order.
+pub fn by_tag(tag: &str) -> Vec<&'static dyn Exercise> {
+    all()
+        .into_iter()
+        .filter(|exercise| exercise.tags().iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        .collect()
+}
+
+/// The exercises whose id, title, description or tags contain every word of `query`
+/// (case-insensitive), in the suggested order.
+/// ```
+/// use learning_cell::curriculum::Difficulty;
+/// use learning_cell::exercises;
+///
+/// let hits = exercises::search("memo fibonacci");
+/// assert_eq!(hits[0].id(), "memo_fib");
+///
+/// // Queries combine with the other filters to build a custom track.
+/// let track: Vec<_> = exercises::by_tag("reentrancy")
+///     .into_iter()
+///     .filter(|exercise| exercise.difficulty() <= Difficulty::Core)
+///     .collect();
+/// assert!(!track.is_empty());
+/// ```
+pub fn search(query: &str) -> Vec<&'static dyn Exercise> {
+    let words: Vec<_> = query.split_whitespace().map(str::to_lowercase).collect();
+    all()
+        .into_iter()
+        .filter(|exercise| {
+            let text = format!(
+                "{} {} {} {}",
+                exercise.id(),
+                exercise.title(),
+                exercise.description(),
+                exercise.tags().join(" ")
+            )
+            .to_lowercase();
+            words.iter().all(|word| text.contains(word.as_str()))
+        })
+        .collect()
+}
+
+/// Looks up an exercise by id.
+pub fn get(id: &str) -> Option<&'static dyn Exercise> {
+    all().into_iter().find(|exercise| exercise.id() == id)
+}
@@ -0,0 +1,214 @@
+//! Implementing `Cell` yourself.
+//!
+//! `Cell` is not magic. It is a thin wrapper around `UnsafeCell`, the one type through which Rust
+//! allows mutation behind a shared reference, plus a handful of rules that make that mutation
+//! sound:
+//!
+//! - `Cell` never hands out a reference to its contents, only copies (`get`) or whole values
+//!   moved in and out (`set`, `replace`, `take`). Nobody can hold a `&T` into the cell while it is
+//!   being overwritten.
+//! - `Cell` is not `Sync`. `UnsafeCell` already takes care of that, as long as you do not add an
+//!   `unsafe impl Sync`.
+//! - `set` must drop the old value exactly once: not zero times (a leak), not twice (a double
+//!   free).
+//!
+//! Fill in the methods of `MyCell` using `UnsafeCell::get` and a few lines of `unsafe` each.
+//! Write a `// SAFETY:` comment above every `unsafe` block explaining why it is fine.
+//!
+//! The hidden tests cannot see undefined behavior, only its symptoms. To let Miri check your
+//! `unsafe` code, run the exercise under it from a scratch example, e.g. `examples/my_cell.rs`:
+//! ```text
+//! fn main() {
+//!     print!("{}", learning_cell::exercises::get("my_cell").unwrap().run());
+//! }
+//! ```
+//! ```text
+//! cargo +nightly miri run --example my_cell
+//! ```
+
+use std::cell::UnsafeCell;
+
+use super::{active_code, Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::leakcheck::LeakTracker;
+
+/// A shareable mutable container, like [`std::cell::Cell`].
+// Only the methods you write read the value.
+#[cfg_attr(not(feature = "solutions"), allow(dead_code))]
+#[derive(Debug)]
+pub struct MyCell<T> {
+    value: UnsafeCell<T>,
+}
+
+impl<T> MyCell<T> {
+    /// Creates a new `MyCell` containing `value`.
+    pub const fn new(value: T) -> Self {
+        Self { value: UnsafeCell::new(value) }
+    }
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+impl<T> MyCell<T> {
+    /// Sets the contained value, dropping the old one.
+    pub fn set(&self, value: T) {
+        let _ = value;
+        todo!()
+    }
+
+    /// Replaces the contained value with `value` and returns the old one.
+    pub fn replace(&self, value: T) -> T {
+        let _ = value;
+        todo!()
+    }
+
+    /// Unwraps the value, consuming the cell.
+    pub fn into_inner(self) -> T {
+        todo!()
+    }
+}
+
+#[cfg(not(feature = "solutions"))]
+impl<T: Copy> MyCell<T> {
+    /// Returns a copy of the contained value.
+    pub fn get(&self) -> T {
+        todo!()
+    }
+}
+
+#[cfg(not(feature = "solutions"))]
+impl<T: Default> MyCell<T> {
+    /// Takes the value, leaving `Default::default()` in its place.
+    pub fn take(&self) -> T {
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::MyCell;
+
+    impl<T> MyCell<T> {
+        pub fn set(&self, value: T) {
+            // `replace` moves the old value out; dropping it here drops it exactly once.
+            drop(self.replace(value));
+        }
+
+        pub fn replace(&self, value: T) -> T {
+            // SAFETY: `MyCell` is not `Sync`, so no other thread can access the value, and no
+            // method hands out a reference into it, so no reference to it is alive on this
+            // thread either. The `&mut` only lives for the duration of `mem::replace`.
+            std::mem::replace(unsafe { &mut *self.value.get() }, value)
+        }
+
+        pub fn into_inner(self) -> T {
+            self.value.into_inner()
+        }
+    }
+
+    impl<T: Copy> MyCell<T> {
+        pub fn get(&self) -> T {
+            // SAFETY: as in `replace`, nothing else can be writing the value right now.
+            unsafe { *self.value.get() }
+        }
+    }
+
+    impl<T: Default> MyCell<T> {
+        pub fn take(&self) -> T {
+            self.replace(T::default())
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "my_cell",
+    title: "Implement `Cell` yourself",
+    chapter: Chapter::Unsafe,
+    difficulty: Difficulty::Unsafe,
+    tags: &["unsafecell", "cell", "soundness"],
+    description: "Fill in `get`, `set`, `replace`, `take` and `into_inner` of `MyCell`, a `Cell` \
+                  built on `UnsafeCell`, without leaking or double-dropping values.",
+    tests: &[
+        Test { name: "get_and_set", run: get_and_set },
+        Test { name: "replace_and_take", run: replace_and_take },
+        Test { name: "set_drops_the_old_value_once", run: set_drops_the_old_value_once },
+        Test { name: "replace_hands_the_old_value_back", run: replace_hands_the_old_value_back },
+        Test { name: "into_inner", run: into_inner },
+        Test { name: "built_on_unsafe_cell", run: built_on_unsafe_cell },
+    ],
+    hints: &[
+        Hint(
+            "`self.value.get()` returns a `*mut T`. Reading through it needs `unsafe`; so does \
+              turning it into a `&mut T`.",
+        ),
+        Hint(
+            "Write `replace` first with `std::mem::replace(unsafe { &mut *self.value.get() }, \
+              value)`. `set` and `take` are then one-liners on top of it.",
+        ),
+        Hint(
+            "Do not write the new value with `ptr::write` or `*ptr = value` carelessly: the \
+              first leaks the old value, and a `ptr::read` followed by an assignment drops it \
+              twice. `mem::replace` moves it out exactly once.",
+        ),
+    ],
+};
+
+fn get_and_set() {
+    let cell = MyCell::new(5);
+    check!(cell.get(), 5; see "my_cell#hints");
+    cell.set(6);
+    check!(cell.get(), 6; see "my_cell#hints");
+}
+
+fn replace_and_take() {
+    let cell = MyCell::new(String::from("old"));
+    check!(cell.replace(String::from("new")), "old"; see "my_cell#hints");
+    check!(cell.take(), "new"; see "my_cell#hints");
+    check!(cell.take(), ""; see "my_cell#hints");
+}
+
+fn set_drops_the_old_value_once() {
+    let tracker = LeakTracker::new();
+    let cell = MyCell::new(tracker.track());
+    cell.set(tracker.track());
+    cell.set(tracker.track());
+    check!(tracker.created(), 3; see "my_cell#hints");
+    check!(tracker.live(), 1; see "my_cell#hints", "`set` must drop the value it replaces");
+    drop(cell);
+    check!(tracker.live(), 0; see "my_cell#hints");
+}
+
+fn replace_hands_the_old_value_back() {
+    let tracker = LeakTracker::new();
+    let cell = MyCell::new(tracker.track());
+    let old = cell.replace(tracker.track());
+    check!(tracker.live(), 2; see "my_cell#hints", "`replace` must not drop the value it returns");
+    drop(old);
+    check!(tracker.live(), 1; see "my_cell#hints");
+}
+
+fn into_inner() {
+    let tracker = LeakTracker::new();
+    let cell = MyCell::new(vec![tracker.track()]);
+    let inner = cell.into_inner();
+    check!(inner.len(), 1; see "my_cell#hints");
+    check!(tracker.live(), 1; see "my_cell#hints");
+    drop(inner);
+    check!(tracker.live(), 0; see "my_cell#hints");
+}
+
+fn built_on_unsafe_cell() {
+    let code = active_code(include_str!("my_cell.rs"));
+    check!(!code.contains("unsafe impl"); see "my_cell#hints", "`MyCell` must stay `!Sync`");
+    let rest = code.replace("UnsafeCell", "").replace("MyCell", "");
+    for name in ["Cell", "Mutex", "RwLock", "Atomic"] {
+        check!(
+            !rest.contains(name);
+            see "my_cell#hints", "build `MyCell` on `UnsafeCell`, not on `{name}`"
+        );
+    }
+}
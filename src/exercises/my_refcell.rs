@@ -0,0 +1,343 @@
+//! Implementing `RefCell` yourself.
+//!
+//! `RefCell` is `Cell`'s big sibling: it does hand out references to its contents, and checks
+//! at run time that the borrowing rules hold. The bookkeeping is a single borrow flag:
+//!
+//! - `0`: not borrowed,
+//! - `n > 0`: `n` shared borrows (`MyRef`s) are alive,
+//! - `-1`: one exclusive borrow (a `MyRefMut`) is alive.
+//!
+//! A borrow that would break the rules fails instead of changing the flag, and dropping a guard
+//! undoes what creating it did. Everything else (the `UnsafeCell`, the guards dereferencing to
+//! the value, [`borrow_state`](MyRefCell::borrow_state) reading the flag) is provided.
+//!
+//! The hidden tests are differential: they run long random sequences of borrows and releases
+//! against both `MyRefCell` and `std::cell::RefCell`, and expect exactly the same successes and
+//! failures. Miri can check the `unsafe` parts; see the [`my_cell`](super::my_cell) exercise for
+//! how to run an exercise under it.
+
+use std::cell::{self, Cell, RefCell, UnsafeCell};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use super::{active_code, Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::diagnostics::BorrowState;
+use crate::util::{catch_panic, Rng};
+
+/// Value of the borrow flag while the cell is not borrowed.
+const UNUSED: isize = 0;
+/// Value of the borrow flag while a `MyRefMut` is alive.
+const WRITING: isize = -1;
+
+/// A mutable memory location with dynamically checked borrow rules, like [`std::cell::RefCell`].
+pub struct MyRefCell<T> {
+    value: UnsafeCell<T>,
+    /// The borrow flag, see the module documentation.
+    flag: Cell<isize>,
+}
+
+/// The value was already borrowed in a conflicting way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BorrowError;
+
+impl fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("already borrowed")
+    }
+}
+
+impl std::error::Error for BorrowError {}
+
+/// A shared borrow of a [`MyRefCell`].
+pub struct MyRef<'b, T> {
+    cell: &'b MyRefCell<T>,
+}
+
+/// An exclusive borrow of a [`MyRefCell`].
+pub struct MyRefMut<'b, T> {
+    cell: &'b MyRefCell<T>,
+}
+
+impl<T> MyRefCell<T> {
+    /// Creates a new `MyRefCell` containing `value`.
+    pub const fn new(value: T) -> Self {
+        Self { value: UnsafeCell::new(value), flag: Cell::new(UNUSED) }
+    }
+
+    /// Immutably borrows the value, panicking if it is mutably borrowed.
+    #[track_caller]
+    pub fn borrow(&self) -> MyRef<'_, T> {
+        self.try_borrow().expect("already mutably borrowed")
+    }
+
+    /// Mutably borrows the value, panicking if it is borrowed at all.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> MyRefMut<'_, T> {
+        self.try_borrow_mut().expect("already borrowed")
+    }
+
+    /// Unwraps the value, consuming the cell. No borrow can be alive, since we own the cell.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// What the borrow flag says right now.
+    pub fn borrow_state(&self) -> BorrowState {
+        BorrowState::from_flag(self.flag.get())
+    }
+}
+
+impl<T> Deref for MyRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: while a `MyRef` is alive the flag is positive, so no `MyRefMut` exists.
+        unsafe { &*self.cell.value.get() }
+    }
+}
+
+impl<T> Deref for MyRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: while a `MyRefMut` is alive the flag is `WRITING`, so no other guard exists.
+        unsafe { &*self.cell.value.get() }
+    }
+}
+
+impl<T> DerefMut for MyRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: as in `deref`, and `&mut self` makes this the only reference through the guard.
+        unsafe { &mut *self.cell.value.get() }
+    }
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+impl<T> MyRefCell<T> {
+    /// Immutably borrows the value, failing if it is mutably borrowed.
+    pub fn try_borrow(&self) -> Result<MyRef<'_, T>, BorrowError> {
+        todo!()
+    }
+
+    /// Mutably borrows the value, failing if it is borrowed at all.
+    pub fn try_borrow_mut(&self) -> Result<MyRefMut<'_, T>, BorrowError> {
+        todo!()
+    }
+}
+
+#[cfg(not(feature = "solutions"))]
+impl<T> Drop for MyRef<'_, T> {
+    fn drop(&mut self) {
+        todo!("release a shared borrow")
+    }
+}
+
+#[cfg(not(feature = "solutions"))]
+impl<T> Drop for MyRefMut<'_, T> {
+    fn drop(&mut self) {
+        todo!("release the exclusive borrow")
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{BorrowError, MyRef, MyRefCell, MyRefMut, UNUSED, WRITING};
+
+    impl<T> MyRefCell<T> {
+        pub fn try_borrow(&self) -> Result<MyRef<'_, T>, BorrowError> {
+            match self.flag.get() {
+                WRITING => Err(BorrowError),
+                readers => {
+                    self.flag.set(readers + 1);
+                    Ok(MyRef { cell: self })
+                }
+            }
+        }
+
+        pub fn try_borrow_mut(&self) -> Result<MyRefMut<'_, T>, BorrowError> {
+            if self.flag.get() != UNUSED {
+                return Err(BorrowError);
+            }
+            self.flag.set(WRITING);
+            Ok(MyRefMut { cell: self })
+        }
+    }
+
+    impl<T> Drop for MyRef<'_, T> {
+        fn drop(&mut self) {
+            self.cell.flag.set(self.cell.flag.get() - 1);
+        }
+    }
+
+    impl<T> Drop for MyRefMut<'_, T> {
+        fn drop(&mut self) {
+            self.cell.flag.set(UNUSED);
+        }
+    }
+    // END SOLUTION
+}
+
+impl<T: fmt::Debug> fmt::Debug for MyRefCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.flag.get() {
+            WRITING => f.write_str("MyRefCell { <borrowed> }"),
+            // SAFETY: the flag is not `WRITING`, so no `&mut` to the value exists. Only this
+            // thread can touch the cell, and it is busy formatting.
+            _ => f.debug_struct("MyRefCell").field("value", unsafe { &*self.value.get() }).finish(),
+        }
+    }
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "my_refcell",
+    title: "Implement `RefCell` yourself",
+    chapter: Chapter::Unsafe,
+    difficulty: Difficulty::Unsafe,
+    tags: &["unsafecell", "refcell", "soundness"],
+    description: "Implement the borrow-flag logic of `MyRefCell`: `try_borrow`, \
+                  `try_borrow_mut` and the guards' `Drop`. Graded against `std::cell::RefCell` \
+                  on random operation sequences.",
+    tests: &[
+        Test { name: "shared_then_exclusive", run: shared_then_exclusive },
+        Test { name: "guards_release", run: guards_release },
+        Test { name: "borrow_panics", run: borrow_panics },
+        Test { name: "matches_std", run: matches_std },
+        Test { name: "built_on_the_flag", run: built_on_the_flag },
+    ],
+    hints: &[
+        Hint(
+            "A shared borrow succeeds unless the flag is `WRITING`; an exclusive borrow only \
+              succeeds if the flag is `UNUSED`. A failed borrow must leave the flag alone.",
+        ),
+        Hint(
+            "On success, `try_borrow` adds one to the flag and `try_borrow_mut` sets it to \
+              `WRITING`. The guards undo that in `drop`.",
+        ),
+        Hint(
+            "`MyRef`'s drop is `self.cell.flag.set(self.cell.flag.get() - 1)`, `MyRefMut`'s is \
+              `self.cell.flag.set(UNUSED)`.",
+        ),
+    ],
+};
+
+fn shared_then_exclusive() {
+    let cell = MyRefCell::new(1);
+    {
+        let a = cell.try_borrow().unwrap();
+        let b = cell.try_borrow().unwrap();
+        check!(*a + *b, 2; see "my_refcell#hints");
+        check!(cell.try_borrow_mut().is_err(); see "my_refcell#hints");
+    }
+    let mut guard = cell.try_borrow_mut().unwrap();
+    *guard = 5;
+    check!(cell.try_borrow().is_err(); see "my_refcell#hints");
+    check!(cell.try_borrow_mut().is_err(); see "my_refcell#hints");
+    drop(guard);
+    check!(cell.into_inner(), 5; see "my_refcell#hints");
+}
+
+fn guards_release() {
+    let cell = MyRefCell::new(String::new());
+    for _ in 0..3 {
+        cell.borrow_mut().push('x');
+        let readers: Vec<_> = (0..3).map(|_| cell.borrow()).collect();
+        check!(readers.iter().all(|reader| reader.ends_with('x')); see "my_refcell#hints");
+    }
+    check!(*cell.borrow(), "xxx"; see "my_refcell#hints");
+}
+
+fn borrow_panics() {
+    let cell = MyRefCell::new(0);
+    let _reader = cell.borrow();
+    let message = catch_panic(|| cell.borrow_mut()).map(drop).unwrap_err();
+    check!(
+        message.contains("already borrowed");
+        see "my_refcell#hints", "unexpected panic: {message}"
+    );
+}
+
+/// The guards of one of the two implementations that are alive during [`matches_std`].
+enum Guard<R, W> {
+    Shared(R),
+    Exclusive(W),
+}
+
+type StdGuard<'a> = Guard<cell::Ref<'a, i64>, cell::RefMut<'a, i64>>;
+type MyGuard<'a> = Guard<MyRef<'a, i64>, MyRefMut<'a, i64>>;
+
+fn matches_std() {
+    for seed in 0..50 {
+        let mut rng = Rng::new(seed);
+        let (theirs, mine) = (RefCell::new(0), MyRefCell::new(0));
+        let (mut their_guards, mut my_guards): (Vec<StdGuard>, Vec<MyGuard>) = (vec![], vec![]);
+        let mut trace = Vec::new();
+
+        for step in 0..200 {
+            let op = rng.below(3);
+            match op {
+                0 => {
+                    trace.push("borrow");
+                    let (a, b) = (theirs.try_borrow(), mine.try_borrow());
+                    check!(
+                        b.is_ok(),
+                        a.is_ok();
+                        see "my_refcell#hints", "seed {seed}, step {step}: {trace:?}"
+                    );
+                    if let (Ok(a), Ok(b)) = (a, b) {
+                        check!(
+                            *b,
+                            *a;
+                            see "my_refcell#hints",
+                            "seed {seed}, step {step}: wrong value"
+                        );
+                        their_guards.push(Guard::Shared(a));
+                        my_guards.push(Guard::Shared(b));
+                    }
+                }
+                1 => {
+                    trace.push("borrow_mut");
+                    let (a, b) = (theirs.try_borrow_mut(), mine.try_borrow_mut());
+                    check!(
+                        b.is_ok(),
+                        a.is_ok();
+                        see "my_refcell#hints", "seed {seed}, step {step}: {trace:?}"
+                    );
+                    if let (Ok(mut a), Ok(mut b)) = (a, b) {
+                        let delta = rng.range(-9..10);
+                        *a += delta;
+                        *b += delta;
+                        their_guards.push(Guard::Exclusive(a));
+                        my_guards.push(Guard::Exclusive(b));
+                    }
+                }
+                _ if !my_guards.is_empty() => {
+                    trace.push("drop");
+                    let index = rng.below(my_guards.len());
+                    their_guards.swap_remove(index);
+                    my_guards.swap_remove(index);
+                }
+                _ => trace.push("nothing to drop"),
+            }
+        }
+        drop((their_guards, my_guards));
+        check!(
+            mine.into_inner(),
+            theirs.into_inner();
+            see "my_refcell#hints", "seed {seed}: final values differ"
+        );
+    }
+}
+
+fn built_on_the_flag() {
+    let code = active_code(include_str!("my_refcell.rs"));
+    let rest = code.replace("MyRefCell", "");
+    check!(
+        !rest.contains("RefCell");
+        see "my_refcell#hints", "build `MyRefCell` on its flag, not on `RefCell`"
+    );
+}
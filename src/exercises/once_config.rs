@@ -0,0 +1,108 @@
+//! A process-wide configuration singleton with `OnceLock`.
+//!
+//! Loading the configuration is expensive, so it must happen at most once per process, even when
+//! many threads ask for it at the same time. `OnceLock` is the thread-safe sibling of `OnceCell`:
+//! the first caller runs the initializer, everybody else waits for it and then gets a reference
+//! to the same value.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::join;
+
+/// How many times [`load`] has run.
+static LOADS: AtomicUsize = AtomicUsize::new(0);
+
+/// The application configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Name of the application.
+    pub name: String,
+    /// Size of the connection pool.
+    pub max_connections: u32,
+}
+
+/// Reads the configuration. Slow, call it as rarely as possible.
+pub fn load() -> Config {
+    LOADS.fetch_add(1, Ordering::SeqCst);
+    thread::sleep(Duration::from_millis(20));
+    Config { name: "learning_cell".to_string(), max_connections: 16 }
+}
+
+/// How many times [`load`] has run so far.
+pub fn load_count() -> usize {
+    LOADS.load(Ordering::SeqCst)
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+impl Config {
+    /// The configuration of this process, loaded on first use.
+    pub fn global() -> &'static Config {
+        todo!("keep the loaded config in a `static OnceLock<Config>`")
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::sync::OnceLock;
+
+    use super::{load, Config};
+
+    impl Config {
+        pub fn global() -> &'static Config {
+            static CONFIG: OnceLock<Config> = OnceLock::new();
+            CONFIG.get_or_init(load)
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "once_config",
+    title: "Configuration singleton with OnceLock",
+    chapter: Chapter::Threads,
+    difficulty: Difficulty::Core,
+    tags: &["oncelock", "singletons"],
+    description: "Implement `Config::global()` so that the expensive `load` runs exactly once, \
+                  even when called from many threads at once.",
+    tests: &[
+        Test { name: "returns_the_loaded_config", run: returns_the_loaded_config },
+        Test { name: "same_instance_every_time", run: same_instance_every_time },
+        Test { name: "loads_once_across_threads", run: loads_once_across_threads },
+    ],
+    hints: &[
+        Hint("A `static` can hold a `OnceLock<Config>`, since `OnceLock::new` is a `const fn`."),
+        Hint(
+            "`OnceLock::get_or_init` runs its closure only for the first caller; concurrent \
+              callers block until the value is ready.",
+        ),
+        Hint("`static CONFIG: OnceLock<Config> = OnceLock::new(); CONFIG.get_or_init(load)`"),
+    ],
+};
+
+fn returns_the_loaded_config() {
+    check!(Config::global().max_connections, 16; see "once_config#hints");
+}
+
+fn same_instance_every_time() {
+    check!(std::ptr::eq(Config::global(), Config::global()); see "once_config#hints");
+}
+
+fn loads_once_across_threads() {
+    let handles: Vec<_> =
+        (0..8).map(|_| thread::spawn(|| Config::global() as *const _ as usize)).collect();
+    let addresses: Vec<_> = handles.into_iter().map(join).collect();
+
+    check!(
+        addresses.windows(2).all(|pair| pair[0] == pair[1]);
+        see "once_config#hints", "threads saw different configs"
+    );
+    check!(load_count(), 1; see "once_config#hints", "the configuration was loaded more than once");
+}
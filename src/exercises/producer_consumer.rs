@@ -0,0 +1,273 @@
+//! Producer/consumer: shared state versus channels.
+//!
+//! A producer thread feeds numbers to a worker thread, which squares them and hands the results
+//! back. You will build this pipeline twice.
+//!
+//! 1. With shared state: a `SharedQueue` is an `Arc` around a `Mutex<VecDeque<T>>` and a
+//!    `Condvar`. `pop` blocks on the condition variable until there is an item, or until the
+//!    producer has called `close` and the queue is drained.
+//! 2. With `std::sync::mpsc` channels, which do all of the above for you.
+//!
+//! Compare the two when you are done. The quiz has a question about it.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::{join, with_timeout};
+
+// Only the methods you write read the state.
+#[cfg_attr(not(feature = "solutions"), allow(dead_code))]
+#[derive(Debug)]
+struct State<T> {
+    items: VecDeque<T>,
+    closed: bool,
+}
+
+/// A blocking FIFO queue. Clones are handles to the same queue.
+#[derive(Debug)]
+pub struct SharedQueue<T> {
+    inner: Arc<(Mutex<State<T>>, Condvar)>,
+}
+
+impl<T> Clone for SharedQueue<T> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<T> Default for SharedQueue<T> {
+    fn default() -> Self {
+        let state = State { items: VecDeque::new(), closed: false };
+        Self { inner: Arc::new((Mutex::new(state), Condvar::new())) }
+    }
+}
+
+impl<T> SharedQueue<T> {
+    /// Creates an empty, open queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+impl<T> SharedQueue<T> {
+    /// Adds `item` at the back and wakes up a waiting consumer.
+    pub fn push(&self, item: T) {
+        let _ = item;
+        todo!()
+    }
+
+    /// Marks the queue as finished: once the remaining items are popped, `pop` returns `None`.
+    pub fn close(&self) {
+        todo!()
+    }
+
+    /// Takes the item at the front, waiting for one if the queue is empty. Returns `None` once
+    /// the queue is closed and empty.
+    pub fn pop(&self) -> Option<T> {
+        todo!()
+    }
+}
+
+/// Squares every input on a worker thread, passing items through [`SharedQueue`]s.
+#[cfg(not(feature = "solutions"))]
+pub fn pipeline_shared(inputs: Vec<i64>) -> Vec<i64> {
+    let _ = inputs;
+    todo!()
+}
+
+/// Squares every input on a worker thread, passing items through `mpsc` channels.
+#[cfg(not(feature = "solutions"))]
+pub fn pipeline_channel(inputs: Vec<i64>) -> Vec<i64> {
+    let _ = inputs;
+    todo!()
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::{pipeline_channel, pipeline_shared};
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::sync::mpsc;
+
+    use super::{join, thread, SharedQueue};
+
+    impl<T> SharedQueue<T> {
+        pub fn push(&self, item: T) {
+            let (state, ready) = &*self.inner;
+            state.lock().unwrap().items.push_back(item);
+            ready.notify_one();
+        }
+
+        pub fn close(&self) {
+            let (state, ready) = &*self.inner;
+            state.lock().unwrap().closed = true;
+            // Every waiting consumer has to find out.
+            ready.notify_all();
+        }
+
+        pub fn pop(&self) -> Option<T> {
+            let (state, ready) = &*self.inner;
+            let mut state = state.lock().unwrap();
+            // Wake-ups can be spurious: check the condition again every time.
+            while state.items.is_empty() && !state.closed {
+                state = ready.wait(state).unwrap();
+            }
+            state.items.pop_front()
+        }
+    }
+
+    pub fn pipeline_shared(inputs: Vec<i64>) -> Vec<i64> {
+        let (requests, results) = (SharedQueue::new(), SharedQueue::new());
+
+        let worker = {
+            let (requests, results) = (requests.clone(), results.clone());
+            thread::spawn(move || {
+                while let Some(n) = requests.pop() {
+                    results.push(n * n);
+                }
+                results.close();
+            })
+        };
+        for n in inputs {
+            requests.push(n);
+        }
+        requests.close();
+
+        let mut squares = Vec::new();
+        while let Some(square) = results.pop() {
+            squares.push(square);
+        }
+        join(worker);
+        squares
+    }
+
+    pub fn pipeline_channel(inputs: Vec<i64>) -> Vec<i64> {
+        let (request_tx, request_rx) = mpsc::channel::<i64>();
+        let (result_tx, result_rx) = mpsc::channel();
+
+        let worker = thread::spawn(move || {
+            for n in request_rx {
+                result_tx.send(n * n).unwrap();
+            }
+            // Dropping `result_tx` here is what ends the loop below.
+        });
+        for n in inputs {
+            request_tx.send(n).unwrap();
+        }
+        drop(request_tx);
+
+        let squares = result_rx.iter().collect();
+        join(worker);
+        squares
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "producer_consumer",
+    title: "Producer/consumer: shared state vs channels",
+    chapter: Chapter::Threads,
+    difficulty: Difficulty::Advanced,
+    tags: &["mutex", "condvar", "channels"],
+    description: "Implement a blocking `SharedQueue` with `Mutex` + `Condvar`, then the same \
+                  squaring pipeline once on top of it and once with `mpsc` channels.",
+    tests: &[
+        Test { name: "queue_fifo", run: queue_fifo },
+        Test { name: "pop_waits_for_push", run: pop_waits_for_push },
+        Test { name: "close_wakes_consumers", run: close_wakes_consumers },
+        Test { name: "pipeline_shared_squares", run: pipeline_shared_squares },
+        Test { name: "pipeline_channel_squares", run: pipeline_channel_squares },
+        Test { name: "pipelines_agree", run: pipelines_agree },
+    ],
+    hints: &[
+        Hint(
+            "`pop` is the only method that waits. Every method that changes the state must \
+              notify the `Condvar`, or a waiting `pop` may sleep forever.",
+        ),
+        Hint(
+            "Wait in a loop: `while state.items.is_empty() && !state.closed { state = \
+              ready.wait(state).unwrap(); }`. `close` should use `notify_all`, since every \
+              consumer needs to see it.",
+        ),
+        Hint(
+            "With channels, dropping the last `Sender` plays the role of `close`: `for n in \
+              receiver` ends once every sender is gone. Make sure both senders are dropped, \
+              including the one moved into the worker.",
+        ),
+    ],
+};
+
+/// How long a test may wait before we assume a consumer will never wake up.
+const LIMIT: Duration = Duration::from_secs(5);
+
+fn finishes<R: Send + 'static>(f: impl FnOnce() -> R + Send + 'static) -> R {
+    with_timeout(LIMIT, f).unwrap_or_else(|err| panic!("the pipeline {err}"))
+}
+
+fn queue_fifo() {
+    let queue = SharedQueue::new();
+    queue.push(1);
+    queue.push(2);
+    queue.close();
+    check!(queue.pop(), Some(1); see "producer_consumer#hints");
+    check!(queue.pop(), Some(2); see "producer_consumer#hints");
+    check!(queue.pop(), None; see "producer_consumer#hints");
+}
+
+fn pop_waits_for_push() {
+    let popped = finishes(|| {
+        let queue = SharedQueue::new();
+        let producer = queue.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            producer.push("late");
+        });
+        let item = queue.pop();
+        join(handle);
+        item
+    });
+    check!(popped, Some("late"); see "producer_consumer#hints");
+}
+
+fn close_wakes_consumers() {
+    let popped = finishes(|| {
+        let queue = SharedQueue::<u8>::new();
+        let consumers: Vec<_> = (0..3)
+            .map(|_| {
+                let queue = queue.clone();
+                thread::spawn(move || queue.pop())
+            })
+            .collect();
+        thread::sleep(Duration::from_millis(20));
+        queue.close();
+        consumers.into_iter().map(join).collect::<Vec<_>>()
+    });
+    check!(popped, [None, None, None]; see "producer_consumer#hints");
+}
+
+fn pipeline_shared_squares() {
+    check!(finishes(|| pipeline_shared(vec![1, -2, 3])), [1, 4, 9]; see "producer_consumer#hints");
+    check!(finishes(|| pipeline_shared(Vec::new())), []; see "producer_consumer#hints");
+}
+
+fn pipeline_channel_squares() {
+    check!(finishes(|| pipeline_channel(vec![1, -2, 3])), [1, 4, 9]; see "producer_consumer#hints");
+    check!(finishes(|| pipeline_channel(Vec::new())), []; see "producer_consumer#hints");
+}
+
+fn pipelines_agree() {
+    let inputs: Vec<i64> = (-500..500).collect();
+    let expected: Vec<i64> = inputs.iter().map(|n| n * n).collect();
+    let copy = inputs.clone();
+    check!(finishes(move || pipeline_shared(copy)), expected; see "producer_consumer#hints");
+    check!(finishes(move || pipeline_channel(inputs)), expected; see "producer_consumer#hints");
+}
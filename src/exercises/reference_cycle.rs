@@ -0,0 +1,185 @@
+//! Find and break a reference cycle.
+//!
+//! A `Person` owns their `Pet` and the `Pet` knows its owner. The code below works, but every
+//! adopted pet and its owner keep each other alive forever: both links are `Rc`s. Run the tests,
+//! read the [leak report](crate::leakcheck) to see which values are stuck, and change one of the
+//! links to a `Weak` to break the cycle.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::leakcheck::{LeakTracker, Tracked};
+
+// BEGIN EXERCISE
+/// Someone who may own a pet.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug)]
+pub struct Person {
+    /// The person's name.
+    pub name: String,
+    pet: RefCell<Option<Rc<Pet>>>,
+    _token: Tracked,
+}
+
+/// A pet that may have an owner.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug)]
+pub struct Pet {
+    /// The pet's name.
+    pub name: String,
+    owner: RefCell<Option<Rc<Person>>>,
+    _token: Tracked,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Person {
+    /// Creates a person without a pet, tracked by `tracker`.
+    pub fn new(name: &str, tracker: &LeakTracker) -> Rc<Person> {
+        let _token = tracker.track_named(&format!("person {name}"));
+        Rc::new(Person { name: name.to_string(), pet: RefCell::new(None), _token })
+    }
+
+    /// The name of the person's pet.
+    pub fn pet_name(&self) -> Option<String> {
+        self.pet.borrow().as_ref().map(|pet| pet.name.clone())
+    }
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Pet {
+    /// Creates a pet without an owner, tracked by `tracker`.
+    pub fn new(name: &str, tracker: &LeakTracker) -> Rc<Pet> {
+        let _token = tracker.track_named(&format!("pet {name}"));
+        Rc::new(Pet { name: name.to_string(), owner: RefCell::new(None), _token })
+    }
+
+    /// The name of the pet's owner, if it has one that is still alive.
+    pub fn owner_name(&self) -> Option<String> {
+        self.owner.borrow().as_ref().map(|owner| owner.name.clone())
+    }
+}
+
+/// `person` adopts `pet`.
+#[cfg(not(feature = "solutions"))]
+pub fn adopt(person: &Rc<Person>, pet: &Rc<Pet>) {
+    *person.pet.borrow_mut() = Some(Rc::clone(pet));
+    *pet.owner.borrow_mut() = Some(Rc::clone(person));
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::{adopt, Person, Pet};
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::rc::Weak;
+
+    use super::{LeakTracker, Rc, RefCell, Tracked};
+
+    #[derive(Debug)]
+    pub struct Person {
+        pub name: String,
+        pet: RefCell<Option<Rc<Pet>>>,
+        _token: Tracked,
+    }
+
+    #[derive(Debug)]
+    pub struct Pet {
+        pub name: String,
+        owner: RefCell<Weak<Person>>,
+        _token: Tracked,
+    }
+
+    impl Person {
+        pub fn new(name: &str, tracker: &LeakTracker) -> Rc<Person> {
+            let _token = tracker.track_named(&format!("person {name}"));
+            Rc::new(Person { name: name.to_string(), pet: RefCell::new(None), _token })
+        }
+
+        pub fn pet_name(&self) -> Option<String> {
+            self.pet.borrow().as_ref().map(|pet| pet.name.clone())
+        }
+    }
+
+    impl Pet {
+        pub fn new(name: &str, tracker: &LeakTracker) -> Rc<Pet> {
+            let _token = tracker.track_named(&format!("pet {name}"));
+            Rc::new(Pet { name: name.to_string(), owner: RefCell::new(Weak::new()), _token })
+        }
+
+        pub fn owner_name(&self) -> Option<String> {
+            self.owner.borrow().upgrade().map(|owner| owner.name.clone())
+        }
+    }
+
+    pub fn adopt(person: &Rc<Person>, pet: &Rc<Pet>) {
+        *person.pet.borrow_mut() = Some(Rc::clone(pet));
+        *pet.owner.borrow_mut() = Rc::downgrade(person);
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "reference_cycle",
+    title: "Find and break a reference cycle",
+    chapter: Chapter::Rc,
+    difficulty: Difficulty::Core,
+    tags: &["rc", "weak", "leaks"],
+    description: "`Person` and `Pet` hold `Rc`s to each other and leak. Use the leak report to \
+                  find the cycle and break it with a `Weak`.",
+    tests: &[
+        Test { name: "adoption_links_both_ways", run: adoption_links_both_ways },
+        Test { name: "no_leak_after_adoption", run: no_leak_after_adoption },
+        Test { name: "pet_outlives_owner", run: pet_outlives_owner },
+    ],
+    hints: &[
+        Hint(
+            "The leak report names every value that is still alive after the test dropped its \
+              handles. Which two point at each other?",
+        ),
+        Hint("Decide who owns whom. A person owns their pet; the pet merely remembers its owner."),
+        Hint(
+            "Change `Pet::owner` to `RefCell<Weak<Person>>`, store `Rc::downgrade(person)` in \
+              `adopt` and `upgrade()` it in `owner_name`.",
+        ),
+    ],
+};
+
+fn adoption_links_both_ways() {
+    let tracker = LeakTracker::new();
+    let person = Person::new("Alice", &tracker);
+    let pet = Pet::new("Rex", &tracker);
+    adopt(&person, &pet);
+    check!(person.pet_name().as_deref(), Some("Rex"); see "reference_cycle#hints");
+    check!(pet.owner_name().as_deref(), Some("Alice"); see "reference_cycle#hints");
+}
+
+fn no_leak_after_adoption() {
+    let tracker = LeakTracker::new();
+    {
+        let person = Person::new("Alice", &tracker);
+        let pet = Pet::new("Rex", &tracker);
+        adopt(&person, &pet);
+    }
+    tracker.assert_no_leaks();
+}
+
+fn pet_outlives_owner() {
+    let tracker = LeakTracker::new();
+    let pet = Pet::new("Rex", &tracker);
+    {
+        let person = Person::new("Alice", &tracker);
+        adopt(&person, &pet);
+    }
+    check!(
+        pet.owner_name(),
+        None;
+        see "reference_cycle#hints", "the owner is gone, the pet should not keep them alive"
+    );
+    drop(pet);
+    tracker.assert_no_leaks();
+}
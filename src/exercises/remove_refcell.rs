@@ -0,0 +1,287 @@
+//! Removing `RefCell` by restructuring ownership.
+//!
+//! The code below was written by someone who had just discovered `RefCell`: every field of
+//! every struct is wrapped in one, and every method takes `&self`. None of it is needed. A
+//! `Party` owns its heroes and each hero owns its stats, so whoever changes them can simply hold
+//! a `&mut`.
+//!
+//! Rewrite `Hero` and `Party` without any interior mutability: no `RefCell`, no `Cell`, no
+//! `Mutex`. Methods that change something take `&mut self`. The tests check your code for
+//! those names, and exercise the same behavior as before.
+
+use super::{active_code, Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::grading::{Grader, SourcePatternGrader};
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+use std::cell::RefCell;
+
+/// A member of the party.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug)]
+pub struct Hero {
+    name: RefCell<String>,
+    hp: RefCell<u32>,
+    max_hp: RefCell<u32>,
+    inventory: RefCell<Vec<String>>,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Hero {
+    /// Creates a hero at full health with an empty inventory.
+    pub fn new(name: &str, max_hp: u32) -> Self {
+        Self {
+            name: RefCell::new(name.to_string()),
+            hp: RefCell::new(max_hp),
+            max_hp: RefCell::new(max_hp),
+            inventory: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The hero's name.
+    pub fn name(&self) -> String {
+        self.name.borrow().clone()
+    }
+
+    /// Current health.
+    pub fn hp(&self) -> u32 {
+        *self.hp.borrow()
+    }
+
+    /// Loses `amount` health, stopping at zero.
+    pub fn take_damage(&self, amount: u32) {
+        let mut hp = self.hp.borrow_mut();
+        *hp = hp.saturating_sub(amount);
+    }
+
+    /// Regains `amount` health, up to the maximum.
+    pub fn heal(&self, amount: u32) {
+        let mut hp = self.hp.borrow_mut();
+        *hp = (*hp + amount).min(*self.max_hp.borrow());
+    }
+
+    /// Adds `item` to the inventory.
+    pub fn pick_up(&self, item: &str) {
+        self.inventory.borrow_mut().push(item.to_string());
+    }
+
+    /// The items carried, in the order they were picked up.
+    pub fn inventory(&self) -> Vec<String> {
+        self.inventory.borrow().clone()
+    }
+}
+
+/// A group of heroes.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Default)]
+pub struct Party {
+    heroes: RefCell<Vec<Hero>>,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Party {
+    /// Creates an empty party.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `hero` to the party.
+    pub fn join(&self, hero: Hero) {
+        self.heroes.borrow_mut().push(hero);
+    }
+
+    /// Damages every hero by `amount`.
+    pub fn ambush(&self, amount: u32) {
+        for hero in self.heroes.borrow().iter() {
+            hero.take_damage(amount);
+        }
+    }
+
+    /// Heals the hero called `name`. Returns `false` if there is no such hero.
+    pub fn heal(&self, name: &str, amount: u32) -> bool {
+        let heroes = self.heroes.borrow();
+        match heroes.iter().find(|hero| hero.name() == name) {
+            Some(hero) => {
+                hero.heal(amount);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Health of the hero called `name`.
+    pub fn hp(&self, name: &str) -> Option<u32> {
+        self.heroes.borrow().iter().find(|hero| hero.name() == name).map(Hero::hp)
+    }
+
+    /// Health of the whole party.
+    pub fn total_hp(&self) -> u32 {
+        self.heroes.borrow().iter().map(Hero::hp).sum()
+    }
+
+    /// Names of the heroes still standing, in joining order.
+    pub fn standing(&self) -> Vec<String> {
+        self.heroes.borrow().iter().filter(|hero| hero.hp() > 0).map(Hero::name).collect()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::{Hero, Party};
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    #[derive(Debug)]
+    pub struct Hero {
+        name: String,
+        hp: u32,
+        max_hp: u32,
+        inventory: Vec<String>,
+    }
+
+    impl Hero {
+        pub fn new(name: &str, max_hp: u32) -> Self {
+            Self { name: name.to_string(), hp: max_hp, max_hp, inventory: Vec::new() }
+        }
+
+        pub fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        pub fn hp(&self) -> u32 {
+            self.hp
+        }
+
+        pub fn take_damage(&mut self, amount: u32) {
+            self.hp = self.hp.saturating_sub(amount);
+        }
+
+        pub fn heal(&mut self, amount: u32) {
+            self.hp = (self.hp + amount).min(self.max_hp);
+        }
+
+        pub fn pick_up(&mut self, item: &str) {
+            self.inventory.push(item.to_string());
+        }
+
+        pub fn inventory(&self) -> Vec<String> {
+            self.inventory.clone()
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Party {
+        heroes: Vec<Hero>,
+    }
+
+    impl Party {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn join(&mut self, hero: Hero) {
+            self.heroes.push(hero);
+        }
+
+        pub fn ambush(&mut self, amount: u32) {
+            for hero in &mut self.heroes {
+                hero.take_damage(amount);
+            }
+        }
+
+        pub fn heal(&mut self, name: &str, amount: u32) -> bool {
+            match self.heroes.iter_mut().find(|hero| hero.name == name) {
+                Some(hero) => {
+                    hero.heal(amount);
+                    true
+                }
+                None => false,
+            }
+        }
+
+        pub fn hp(&self, name: &str) -> Option<u32> {
+            self.heroes.iter().find(|hero| hero.name == name).map(Hero::hp)
+        }
+
+        pub fn total_hp(&self) -> u32 {
+            self.heroes.iter().map(Hero::hp).sum()
+        }
+
+        pub fn standing(&self) -> Vec<String> {
+            self.heroes.iter().filter(|hero| hero.hp > 0).map(Hero::name).collect()
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "remove_refcell",
+    title: "Remove `RefCell` entirely",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Core,
+    tags: &["refcell", "ownership", "refactoring"],
+    description: "Rewrite `Hero` and `Party` without any interior mutability, using plain \
+                  fields and `&mut self` methods.",
+    tests: &[
+        Test { name: "hero", run: hero },
+        Test { name: "party", run: party },
+        Test { name: "no_interior_mutability", run: no_interior_mutability },
+    ],
+    hints: &[
+        Hint(
+            "Nothing in this code is shared: the party owns its heroes and nobody else holds \
+              them. Ownership already gives you the right to mutate.",
+        ),
+        Hint(
+            "Unwrap every field (`hp: u32` instead of `hp: RefCell<u32>`), then let the compiler \
+              tell you which methods need `&mut self`.",
+        ),
+        Hint(
+            "In `Party`, iterate with `&mut self.heroes` or `self.heroes.iter_mut()` to get a \
+              `&mut Hero` for `take_damage` and `heal`.",
+        ),
+    ],
+};
+
+// The tests compile against both versions; `mut` is only needed by the solution.
+#[allow(unused_mut)]
+fn hero() {
+    let mut hero = Hero::new("Ferris", 10);
+    hero.take_damage(4);
+    check!(hero.hp(), 6; see "remove_refcell#hints");
+    hero.heal(100);
+    check!(hero.hp(), 10; see "remove_refcell#hints");
+    hero.take_damage(25);
+    check!(hero.hp(), 0; see "remove_refcell#hints");
+    hero.pick_up("rope");
+    hero.pick_up("lamp");
+    check!(hero.inventory(), ["rope", "lamp"]; see "remove_refcell#hints");
+    check!(hero.name(), "Ferris"; see "remove_refcell#hints");
+}
+
+#[allow(unused_mut)]
+fn party() {
+    let mut party = Party::new();
+    party.join(Hero::new("Ferris", 10));
+    party.join(Hero::new("Corro", 4));
+    party.ambush(5);
+    check!(party.total_hp(), 5; see "remove_refcell#hints");
+    check!(party.standing(), ["Ferris"]; see "remove_refcell#hints");
+    check!(party.heal("Corro", 2); see "remove_refcell#hints");
+    check!(!party.heal("Nobody", 2); see "remove_refcell#hints");
+    check!(party.hp("Corro"), Some(2); see "remove_refcell#hints");
+    check!(party.standing(), ["Ferris", "Corro"]; see "remove_refcell#hints");
+}
+
+fn no_interior_mutability() {
+    SourcePatternGrader::new("no interior mutability")
+        .forbid("Cell", "the code still uses `RefCell` or `Cell`")
+        .forbid("Mutex", "the code still uses `Mutex`")
+        .forbid("RwLock", "the code still uses `RwLock`")
+        .forbid("unsafe", "the code uses `unsafe`")
+        .grade(&active_code(include_str!("remove_refcell.rs")))
+        .assert_passed();
+}
@@ -0,0 +1,250 @@
+//! A read-mostly cache: `RwLock` versus `Mutex`.
+//!
+//! Implement the same cache twice, once behind a `Mutex` and once behind a `RwLock`. Both
+//! versions are correct; the difference is how they behave under load. Every lookup verifies
+//! the cached entry's checksum while holding the lock, which takes a little while. With a
+//! `Mutex`, readers wait for each other. With a `RwLock`, readers only wait for writers, and in
+//! a workload where 95% of the operations are reads, that is rarely.
+//!
+//! The last test is a benchmark. It runs the same 95%-read workload on several threads against
+//! both caches, prints the timings in the report, and requires the `RwLock` cache to be clearly
+//! faster.
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::grading::{BenchmarkGrader, Grader};
+use crate::util::{join, Rng};
+
+/// A cached value with its checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    value: String,
+    checksum: u64,
+}
+
+impl Entry {
+    /// Wraps `value`, computing its checksum.
+    pub fn new(value: &str) -> Self {
+        Self { value: value.to_string(), checksum: checksum(value) }
+    }
+
+    /// Verifies the checksum (slowly) and returns a copy of the value. Call it while holding the
+    /// cache's lock, so that no writer can replace the entry halfway through.
+    pub fn verified(&self) -> String {
+        thread::sleep(Duration::from_micros(200));
+        assert_eq!(checksum(&self.value), self.checksum, "corrupted cache entry");
+        self.value.clone()
+    }
+}
+
+fn checksum(value: &str) -> u64 {
+    value.bytes().fold(17, |sum, byte| sum.wrapping_mul(31).wrapping_add(byte.into()))
+}
+
+/// A cache that can be shared between threads.
+pub trait SharedCache: Send + Sync {
+    /// Creates an empty cache.
+    fn new() -> Self
+    where
+        Self: Sized;
+
+    /// The verified value for `key`.
+    fn get(&self, key: u32) -> Option<String>;
+
+    /// Inserts or replaces the value for `key`.
+    fn insert(&self, key: u32, value: &str);
+}
+
+// BEGIN EXERCISE
+/// The cache behind a `Mutex`.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Default)]
+pub struct MutexCache {
+    // TODO: a `Mutex<HashMap<u32, Entry>>`.
+}
+
+#[cfg(not(feature = "solutions"))]
+impl SharedCache for MutexCache {
+    fn new() -> Self {
+        todo!()
+    }
+
+    fn get(&self, key: u32) -> Option<String> {
+        let _ = key;
+        todo!()
+    }
+
+    fn insert(&self, key: u32, value: &str) {
+        let _ = (key, value);
+        todo!()
+    }
+}
+
+/// The cache behind a `RwLock`.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Default)]
+pub struct RwLockCache {
+    // TODO: a `RwLock<HashMap<u32, Entry>>`.
+}
+
+#[cfg(not(feature = "solutions"))]
+impl SharedCache for RwLockCache {
+    fn new() -> Self {
+        todo!()
+    }
+
+    fn get(&self, key: u32) -> Option<String> {
+        let _ = key;
+        todo!()
+    }
+
+    fn insert(&self, key: u32, value: &str) {
+        let _ = (key, value);
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::{MutexCache, RwLockCache};
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::collections::HashMap;
+    use std::sync::{Mutex, RwLock};
+
+    use super::{Entry, SharedCache};
+
+    #[derive(Debug, Default)]
+    pub struct MutexCache {
+        entries: Mutex<HashMap<u32, Entry>>,
+    }
+
+    impl SharedCache for MutexCache {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn get(&self, key: u32) -> Option<String> {
+            self.entries.lock().unwrap().get(&key).map(Entry::verified)
+        }
+
+        fn insert(&self, key: u32, value: &str) {
+            self.entries.lock().unwrap().insert(key, Entry::new(value));
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct RwLockCache {
+        entries: RwLock<HashMap<u32, Entry>>,
+    }
+
+    impl SharedCache for RwLockCache {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn get(&self, key: u32) -> Option<String> {
+            self.entries.read().unwrap().get(&key).map(Entry::verified)
+        }
+
+        fn insert(&self, key: u32, value: &str) {
+            self.entries.write().unwrap().insert(key, Entry::new(value));
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "rwlock_cache",
+    title: "Read-mostly cache: `RwLock` vs `Mutex`",
+    chapter: Chapter::Threads,
+    difficulty: Difficulty::Advanced,
+    tags: &["rwlock", "mutex", "performance"],
+    description: "Implement the same cache behind a `Mutex` and behind a `RwLock`. A benchmark \
+                  with 95% reads requires the `RwLock` version to be clearly faster.",
+    tests: &[
+        Test { name: "mutex_cache", run: mutex_cache },
+        Test { name: "rwlock_cache", run: rwlock_cache },
+        Test { name: "rwlock_beats_mutex", run: rwlock_beats_mutex },
+    ],
+    hints: &[
+        Hint(
+            "Both caches wrap a `HashMap<u32, Entry>`. `get` looks the key up and calls \
+              `Entry::verified` on the entry while the lock is held.",
+        ),
+        Hint(
+            "The `RwLock` cache takes `read()` in `get` and `write()` in `insert`. Taking \
+              `write()` everywhere would make it just as slow as the `Mutex`.",
+        ),
+        Hint("`self.entries.read().unwrap().get(&key).map(Entry::verified)` is the whole `get`."),
+    ],
+};
+
+fn check_cache<C: SharedCache>() {
+    let cache = C::new();
+    check!(cache.get(1), None; see "rwlock_cache#hints");
+    cache.insert(1, "one");
+    cache.insert(2, "two");
+    cache.insert(1, "uno");
+    check!(cache.get(1).as_deref(), Some("uno"); see "rwlock_cache#hints");
+    check!(cache.get(2).as_deref(), Some("two"); see "rwlock_cache#hints");
+}
+
+fn mutex_cache() {
+    check_cache::<MutexCache>();
+}
+
+fn rwlock_cache() {
+    check_cache::<RwLockCache>();
+}
+
+/// Threads running the benchmark workload at the same time.
+const THREADS: u64 = 4;
+/// Operations per thread, 95% of them reads.
+const OPERATIONS: usize = 60;
+/// How much faster the `RwLock` cache has to be.
+const REQUIRED_SPEEDUP: f64 = 1.5;
+
+/// Runs the read-mostly workload against a fresh cache on [`THREADS`] threads.
+fn workload<C: SharedCache + 'static>() {
+    let cache = Arc::new(C::new());
+    for key in 0..16 {
+        cache.insert(key, &format!("value {key}"));
+    }
+    let threads: Vec<_> = (0..THREADS)
+        .map(|seed| {
+            let cache = Arc::clone(&cache);
+            thread::spawn(move || {
+                let mut rng = Rng::new(seed);
+                for _ in 0..OPERATIONS {
+                    let key = rng.below(16) as u32;
+                    if rng.below(100) < 95 {
+                        check!(cache.get(key).is_some(); see "rwlock_cache#hints");
+                    } else {
+                        cache.insert(key, &format!("value {key}"));
+                    }
+                }
+            })
+        })
+        .collect();
+    threads.into_iter().for_each(join);
+}
+
+fn rwlock_beats_mutex() {
+    BenchmarkGrader::new(
+        "RwLock vs Mutex",
+        |_: &()| workload::<RwLockCache>(),
+        |_| workload::<MutexCache>(),
+    )
+    .iterations(3)
+    .min_speedup(REQUIRED_SPEEDUP)
+    .grade(&())
+    .assert_passed();
+}
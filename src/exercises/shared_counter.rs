@@ -0,0 +1,151 @@
+//! Convert a `&mut self` API to interior mutability.
+//!
+//! `Counter` used to look like this:
+//! ```ignore
+//! pub struct Counter {
+//!     count: u32,
+//! }
+//!
+//! impl Counter {
+//!     pub fn increment(&mut self) {
+//!         self.count += 1;
+//!     }
+//!
+//!     pub fn reset(&mut self) -> u32 {
+//!         std::mem::take(&mut self.count)
+//!     }
+//! }
+//! ```
+//! Every user of the counter now only has a `&Counter`. Change the field to a `Cell` so that
+//! `increment` and `reset` can take `&self`.
+//!
+//! See the [`Cell`](crate::Cell) lesson.
+
+use super::{active_code, Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+// BEGIN EXERCISE
+/// Counts events through a shared reference.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Default)]
+pub struct Counter {
+    count: u32,
+}
+
+#[cfg(not(feature = "solutions"))]
+impl Counter {
+    /// Creates a counter starting at zero.
+    pub fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    /// Adds one to the count.
+    pub fn increment(&self) {
+        todo!("`self.count += 1` needs `&mut self`; store the count in a `Cell` instead")
+    }
+
+    /// Returns the current count.
+    pub fn get(&self) -> u32 {
+        self.count
+    }
+
+    /// Sets the count back to zero and returns the count it had.
+    pub fn reset(&self) -> u32 {
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::Counter;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::cell::Cell;
+
+    #[derive(Debug, Default)]
+    pub struct Counter {
+        count: Cell<u32>,
+    }
+
+    impl Counter {
+        pub fn new() -> Self {
+            Self { count: Cell::new(0) }
+        }
+
+        pub fn increment(&self) {
+            self.count.set(self.count.get() + 1);
+        }
+
+        pub fn get(&self) -> u32 {
+            self.count.get()
+        }
+
+        pub fn reset(&self) -> u32 {
+            self.count.take()
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "shared_counter",
+    title: "Convert a &mut self API to interior mutability",
+    chapter: Chapter::Cell,
+    difficulty: Difficulty::Intro,
+    tags: &["cell", "rc"],
+    description: "Make `Counter::increment` and `Counter::reset` work through `&Counter` by \
+                  storing the count in a `Cell`.",
+    tests: &[
+        Test { name: "starts_at_zero", run: starts_at_zero },
+        Test { name: "increments_through_shared_reference", run: increments_through_shared_reference },
+        Test { name: "shared_by_two_owners", run: shared_by_two_owners },
+        Test { name: "reset_returns_previous_count", run: reset_returns_previous_count },
+        Test { name: "uses_cell", run: uses_cell },
+    ],
+    hints: &[
+        Hint("A `Cell<u32>` can be updated through `&self`."),
+        Hint("`increment` is a `get` followed by a `set`; `reset` is what `Cell::take` does."),
+        Hint("Change the field to `count: Cell<u32>` and use `self.count.set(self.count.get() + 1)`."),
+    ],
+};
+
+fn starts_at_zero() {
+    check!(Counter::new().get(), 0; see "cell#example-3");
+}
+
+fn increments_through_shared_reference() {
+    let counter = Counter::new();
+    let shared: &Counter = &counter;
+    shared.increment();
+    shared.increment();
+    check!(shared.get(), 2; see "cell#example-3");
+}
+
+fn shared_by_two_owners() {
+    struct Button<'a> {
+        clicks: &'a Counter,
+    }
+
+    let counter = Counter::new();
+    let ok = Button { clicks: &counter };
+    let cancel = Button { clicks: &counter };
+    ok.clicks.increment();
+    cancel.clicks.increment();
+    ok.clicks.increment();
+    check!(counter.get(), 3; see "cell#example-3");
+}
+
+fn reset_returns_previous_count() {
+    let counter = Counter::new();
+    (0..5).for_each(|_| counter.increment());
+    check!(counter.reset(), 5; see "cell#example-3");
+    check!(counter.get(), 0; see "cell#example-3");
+}
+
+fn uses_cell() {
+    let code = active_code(include_str!("shared_counter.rs"));
+    check!(code.contains("Cell<"); see "cell#example-3", "store the count in a `Cell`");
+}
@@ -0,0 +1,191 @@
+//! A directed graph with `Rc<RefCell<Node>>`.
+//!
+//! Nodes are shared: several nodes can have an edge to the same node, so each node is an
+//! `Rc<RefCell<Node>>`. Edges are added after the nodes are created, which is why the node needs
+//! the `RefCell`.
+//!
+//! The trap is in `double_weights`: while a node is mutably borrowed to update its edges, the
+//! traversal must not borrow a neighbor that might be the very same node (think self-loops and
+//! cycles).
+//!
+//! Graphs with cycles made of `Rc`s leak; the tests break their cycles by hand. The
+//! `reference_cycle` exercise shows how to avoid that with `Weak`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+/// A shared handle to a node.
+pub type NodeRef = Rc<RefCell<Node>>;
+
+/// A node of the graph.
+#[derive(Debug)]
+pub struct Node {
+    /// Name of the node, unique within a graph.
+    pub name: String,
+    /// Outgoing edges and their weights.
+    pub edges: Vec<(NodeRef, u32)>,
+}
+
+impl Node {
+    /// Creates a node without edges.
+    pub fn new(name: &str) -> NodeRef {
+        Rc::new(RefCell::new(Node { name: name.to_string(), edges: Vec::new() }))
+    }
+}
+
+// BEGIN EXERCISE
+/// Adds an edge from `from` to `to`. `from` and `to` may be the same node.
+#[cfg(not(feature = "solutions"))]
+pub fn connect(from: &NodeRef, to: &NodeRef, weight: u32) {
+    let _ = (from, to, weight);
+    todo!()
+}
+
+/// The names of the nodes `node` has an edge to, in insertion order.
+#[cfg(not(feature = "solutions"))]
+pub fn neighbors(node: &NodeRef) -> Vec<String> {
+    let _ = node;
+    todo!()
+}
+
+/// Doubles the weight of every edge reachable from `start`, visiting each node once.
+#[cfg(not(feature = "solutions"))]
+pub fn double_weights(start: &NodeRef) {
+    let _ = start;
+    todo!()
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::{connect, double_weights, neighbors};
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::rc::Rc;
+
+    use super::NodeRef;
+
+    pub fn connect(from: &NodeRef, to: &NodeRef, weight: u32) {
+        from.borrow_mut().edges.push((Rc::clone(to), weight));
+    }
+
+    pub fn neighbors(node: &NodeRef) -> Vec<String> {
+        node.borrow().edges.iter().map(|(to, _)| to.borrow().name.clone()).collect()
+    }
+
+    pub fn double_weights(start: &NodeRef) {
+        let mut visited = Vec::new();
+        let mut stack = vec![Rc::clone(start)];
+
+        while let Some(node) = stack.pop() {
+            if visited.iter().any(|seen| Rc::ptr_eq(seen, &node)) {
+                continue;
+            }
+
+            // The guard only lives for this block, so no neighbor is borrowed while it is alive.
+            let next: Vec<NodeRef> = {
+                let mut node = node.borrow_mut();
+                for (_, weight) in node.edges.iter_mut() {
+                    *weight *= 2;
+                }
+                node.edges.iter().map(|(to, _)| Rc::clone(to)).collect()
+            };
+
+            visited.push(node);
+            stack.extend(next);
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "shared_graph",
+    title: "Build a graph with Rc<RefCell<Node>>",
+    chapter: Chapter::Rc,
+    difficulty: Difficulty::Core,
+    tags: &["rc", "refcell", "graphs"],
+    description: "Implement `connect`, `neighbors` and `double_weights` for a graph of shared \
+                  nodes, without panicking on self-loops and cycles.",
+    tests: &[
+        Test { name: "neighbors_in_insertion_order", run: neighbors_in_insertion_order },
+        Test { name: "shared_node", run: shared_node },
+        Test {
+            name: "double_weights_visits_each_node_once",
+            run: double_weights_visits_each_node_once,
+        },
+        Test { name: "double_weights_with_self_loop", run: double_weights_with_self_loop },
+    ],
+    hints: &[
+        Hint("`connect` and `neighbors` only need one `borrow_mut()` / `borrow()` each."),
+        Hint(
+            "In `double_weights`, collect the neighbors into a `Vec<NodeRef>` and drop the node's \
+              guard before visiting any of them. Track visited nodes with `Rc::ptr_eq`.",
+        ),
+        Hint(
+            "Use an explicit stack: pop a node, skip it if visited, update its weights and clone \
+              its neighbors inside a block, then push them.",
+        ),
+    ],
+};
+
+fn weights(node: &NodeRef) -> Vec<u32> {
+    node.borrow().edges.iter().map(|(_, weight)| *weight).collect()
+}
+
+fn neighbors_in_insertion_order() {
+    let a = Node::new("a");
+    let b = Node::new("b");
+    let c = Node::new("c");
+    connect(&a, &c, 1);
+    connect(&a, &b, 2);
+    check!(neighbors(&a), ["c", "b"]; see "shared_graph#hints");
+    check!(neighbors(&b).is_empty(); see "shared_graph#hints");
+}
+
+fn shared_node() {
+    let a = Node::new("a");
+    let b = Node::new("b");
+    let shared = Node::new("shared");
+    connect(&a, &shared, 1);
+    connect(&b, &shared, 1);
+
+    shared.borrow_mut().name = "renamed".to_string();
+    check!(neighbors(&a), ["renamed"]; see "shared_graph#hints");
+    check!(neighbors(&b), ["renamed"]; see "shared_graph#hints");
+}
+
+fn double_weights_visits_each_node_once() {
+    // a -> b -> d and a -> c -> d: `d` is reachable twice but must only be doubled once.
+    let (a, b, c, d, e) =
+        (Node::new("a"), Node::new("b"), Node::new("c"), Node::new("d"), Node::new("e"));
+    connect(&a, &b, 1);
+    connect(&a, &c, 2);
+    connect(&b, &d, 3);
+    connect(&c, &d, 4);
+    connect(&d, &e, 5);
+
+    double_weights(&a);
+    check!(weights(&a), [2, 4]; see "shared_graph#hints");
+    check!(weights(&b), [6]; see "shared_graph#hints");
+    check!(weights(&c), [8]; see "shared_graph#hints");
+    check!(weights(&d), [10]; see "shared_graph#hints");
+}
+
+fn double_weights_with_self_loop() {
+    let a = Node::new("a");
+    let b = Node::new("b");
+    connect(&a, &a, 1);
+    connect(&a, &b, 2);
+    connect(&b, &a, 3);
+
+    double_weights(&a);
+    check!(weights(&a), [2, 4]; see "shared_graph#hints");
+    check!(weights(&b), [6]; see "shared_graph#hints");
+
+    a.borrow_mut().edges.clear();
+}
@@ -0,0 +1,118 @@
+//! An iterator adapter with interior state.
+//!
+//! `.spy(&counter)` wraps an iterator and counts every item that passes through it into a
+//! `Cell<usize>` owned by the caller. Because the adapter only holds a `&Cell<usize>`, several
+//! spies in the same chain can share one counter, and the caller can read it at any time. With a
+//! `&mut usize` neither would be allowed.
+
+use std::cell::Cell;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+/// Adds [`spy`](SpyExt::spy) to every iterator.
+pub trait SpyExt: Iterator + Sized {
+    /// Counts the items yielded by this iterator into `counter`.
+    fn spy(self, counter: &Cell<usize>) -> Spy<'_, Self> {
+        Spy { inner: self, counter }
+    }
+}
+
+impl<I: Iterator> SpyExt for I {}
+
+/// The adapter returned by [`SpyExt::spy`].
+#[derive(Debug)]
+pub struct Spy<'c, I> {
+    inner: I,
+    counter: &'c Cell<usize>,
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+impl<I: Iterator> Iterator for Spy<'_, I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let _ = (&mut self.inner, self.counter);
+        todo!("yield the inner iterator's next item, counting it if there is one")
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::Spy;
+
+    impl<I: Iterator> Iterator for Spy<'_, I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<I::Item> {
+            let item = self.inner.next()?;
+            self.counter.set(self.counter.get() + 1);
+            Some(item)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.inner.size_hint()
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "spy_iterator",
+    title: "Iterator adapter with interior state",
+    chapter: Chapter::Cell,
+    difficulty: Difficulty::Core,
+    tags: &["cell", "iterators"],
+    description: "Implement `Iterator::next` for the `.spy(&counter)` adapter, which counts the \
+                  yielded items into a caller-owned `Cell<usize>`.",
+    tests: &[
+        Test { name: "counts_items", run: counts_items },
+        Test { name: "counts_inside_a_chain", run: counts_inside_a_chain },
+        Test { name: "shared_counter", run: shared_counter },
+        Test { name: "lazy", run: lazy },
+    ],
+    hints: &[
+        Hint("`next` forwards to the inner iterator's `next`."),
+        Hint("Only count when the inner iterator actually yielded something. `?` helps."),
+        Hint(
+            "`let item = self.inner.next()?; self.counter.set(self.counter.get() + 1); \
+              Some(item)`",
+        ),
+    ],
+};
+
+fn counts_items() {
+    let counter = Cell::new(0);
+    let items: Vec<_> = "abc".chars().spy(&counter).collect();
+    check!(items, ['a', 'b', 'c']; see "cell#example-3");
+    check!(counter.get(), 3; see "cell#example-3");
+}
+
+fn counts_inside_a_chain() {
+    let before = Cell::new(0);
+    let after = Cell::new(0);
+    let total: u32 = (1..=10).spy(&before).filter(|n| n % 2 == 0).spy(&after).map(|n| n * 10).sum();
+    check!(total, 300; see "cell#example-3");
+    check!((before.get(), after.get()), (10, 5); see "cell#example-3");
+}
+
+fn shared_counter() {
+    let counter = Cell::new(0);
+    let a = [1, 2, 3];
+    let b = [4, 5];
+    let sum: i32 = a.iter().spy(&counter).chain(b.iter().spy(&counter)).sum();
+    check!(sum, 15; see "cell#example-3");
+    check!(counter.get(), 5; see "cell#example-3");
+}
+
+fn lazy() {
+    let counter = Cell::new(0);
+    let mut iter = (0..100).spy(&counter).skip(2);
+    check!(counter.get(), 0; see "cell#example-3", "nothing is pulled before the first `next`");
+    check!(iter.next(), Some(2); see "cell#example-3");
+    check!(counter.get(), 3; see "cell#example-3");
+}
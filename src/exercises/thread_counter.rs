@@ -0,0 +1,139 @@
+//! A shared counter across threads.
+//!
+//! This counter works great, as long as there is only one thread:
+//! ```ignore
+//! #[derive(Clone)]
+//! pub struct SharedCounter {
+//!     count: Rc<Cell<i32>>,
+//! }
+//!
+//! impl SharedCounter {
+//!     pub fn increment(&self) {
+//!         self.count.set(self.count.get() + 1);
+//!     }
+//! }
+//! ```
+//! Neither `Rc` nor `Cell` can be shared between threads, so the compiler will not let us move
+//! a clone of it into `thread::spawn`. Give `SharedCounter` a thread-safe design. Either
+//! `Arc<Mutex<i32>>` or `Arc<AtomicI32>` will do; the tests only care that no increment is lost.
+
+use std::thread;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::join;
+
+// BEGIN EXERCISE
+/// A counter whose clones all share the same count, from any thread.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug, Clone, Default)]
+pub struct SharedCounter {
+    // TODO: the count used to be an `Rc<Cell<i32>>`.
+}
+
+#[cfg(not(feature = "solutions"))]
+impl SharedCounter {
+    /// Creates a counter starting at zero.
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Adds one to the count.
+    pub fn increment(&self) {
+        todo!()
+    }
+
+    /// Returns the current count.
+    pub fn get(&self) -> i32 {
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::SharedCounter;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct SharedCounter {
+        count: Arc<AtomicI32>,
+    }
+
+    impl SharedCounter {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn increment(&self) {
+            self.count.fetch_add(1, Ordering::Relaxed);
+        }
+
+        pub fn get(&self) -> i32 {
+            self.count.load(Ordering::Relaxed)
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "thread_counter",
+    title: "Shared counter across threads",
+    chapter: Chapter::Threads,
+    difficulty: Difficulty::Intro,
+    tags: &["arc", "atomics", "mutex"],
+    description: "Turn the `Rc<Cell<i32>>` counter into a thread-safe `SharedCounter` using \
+                  `Arc<Mutex<_>>` or an atomic.",
+    tests: &[
+        Test { name: "single_thread", run: single_thread },
+        Test { name: "clones_share_the_count", run: clones_share_the_count },
+        Test { name: "eight_threads", run: eight_threads },
+    ],
+    hints: &[
+        Hint("`Rc` becomes `Arc`. `Cell` becomes either a `Mutex` or an atomic integer."),
+        Hint(
+            "With a `Mutex`, lock, add one and let the guard drop. With an `AtomicI32`, a single \
+              `fetch_add` does the whole read-modify-write.",
+        ),
+        Hint(
+            "`count: Arc<AtomicI32>`, `increment` is `self.count.fetch_add(1, Ordering::Relaxed)` \
+              and `get` is `self.count.load(Ordering::Relaxed)`.",
+        ),
+    ],
+};
+
+fn single_thread() {
+    let counter = SharedCounter::new();
+    counter.increment();
+    counter.increment();
+    check!(counter.get(), 2; see "thread_counter#hints");
+}
+
+fn clones_share_the_count() {
+    let counter = SharedCounter::new();
+    let clone = counter.clone();
+    clone.increment();
+    check!(counter.get(), 1; see "thread_counter#hints");
+}
+
+fn eight_threads() {
+    let counter = SharedCounter::new();
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let counter = counter.clone();
+            thread::spawn(move || {
+                for _ in 0..10_000 {
+                    counter.increment();
+                }
+            })
+        })
+        .collect();
+
+    handles.into_iter().for_each(join);
+    check!(counter.get(), 80_000; see "thread_counter#hints");
+}
@@ -0,0 +1,291 @@
+//! A thread pool with a shared work queue.
+//!
+//! A fixed number of worker threads wait for tasks. `execute` puts a task in a queue that all
+//! the workers share; whichever worker is free takes it. The queue is shared, mutable state,
+//! accessed from every worker and from every thread that submits tasks, so it lives in an
+//! `Arc<Mutex<_>>`, with a `Condvar` to let idle workers sleep until there is work.
+//!
+//! `shutdown` must be graceful: every task submitted before it was called still runs, then the
+//! workers exit and are joined. Dropping the pool shuts it down the same way.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::util::{join, with_timeout};
+
+/// A unit of work for the pool.
+pub type Task = Box<dyn FnOnce() + Send + 'static>;
+
+// BEGIN EXERCISE
+/// A fixed-size pool of worker threads.
+#[cfg(not(feature = "solutions"))]
+#[derive(Debug)]
+pub struct ThreadPool {
+    // TODO: the shared queue and the workers' `JoinHandle`s.
+}
+
+#[cfg(not(feature = "solutions"))]
+impl ThreadPool {
+    /// Starts `size` worker threads. `size` must be at least one.
+    pub fn new(size: usize) -> Self {
+        let _ = size;
+        todo!()
+    }
+
+    /// Number of worker threads.
+    pub fn size(&self) -> usize {
+        todo!()
+    }
+
+    /// Queues `task` to run on one of the workers.
+    pub fn execute(&self, task: impl FnOnce() + Send + 'static) {
+        let _ = task;
+        todo!()
+    }
+
+    /// Runs every queued task, then stops and joins the workers.
+    pub fn shutdown(self) {
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::ThreadPool;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use std::collections::VecDeque;
+    use std::sync::Condvar;
+    use std::thread::JoinHandle;
+
+    use super::{join, thread, Arc, Mutex, Task};
+
+    #[derive(Default)]
+    struct Queue {
+        tasks: VecDeque<Task>,
+        closed: bool,
+    }
+
+    #[derive(Default)]
+    struct Shared {
+        queue: Mutex<Queue>,
+        ready: Condvar,
+    }
+
+    pub struct ThreadPool {
+        shared: Arc<Shared>,
+        workers: Vec<JoinHandle<()>>,
+    }
+
+    impl std::fmt::Debug for ThreadPool {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ThreadPool").field("size", &self.workers.len()).finish()
+        }
+    }
+
+    impl ThreadPool {
+        pub fn new(size: usize) -> Self {
+            assert!(size > 0, "a pool needs at least one worker");
+            let shared = Arc::new(Shared::default());
+            let workers = (0..size)
+                .map(|_| {
+                    let shared = Arc::clone(&shared);
+                    thread::spawn(move || work(&shared))
+                })
+                .collect();
+            Self { shared, workers }
+        }
+
+        pub fn size(&self) -> usize {
+            self.workers.len()
+        }
+
+        pub fn execute(&self, task: impl FnOnce() + Send + 'static) {
+            self.shared.queue.lock().unwrap().tasks.push_back(Box::new(task));
+            self.shared.ready.notify_one();
+        }
+
+        pub fn shutdown(self) {
+            // The work happens in `Drop`.
+        }
+    }
+
+    /// The loop of every worker: run tasks until the queue is closed and empty.
+    fn work(shared: &Shared) {
+        loop {
+            let task = {
+                let mut queue = shared.queue.lock().unwrap();
+                loop {
+                    if let Some(task) = queue.tasks.pop_front() {
+                        break task;
+                    }
+                    if queue.closed {
+                        return;
+                    }
+                    queue = shared.ready.wait(queue).unwrap();
+                }
+            };
+            // The lock is released while the task runs, so other workers can take tasks.
+            task();
+        }
+    }
+
+    impl Drop for ThreadPool {
+        fn drop(&mut self) {
+            self.shared.queue.lock().unwrap().closed = true;
+            self.shared.ready.notify_all();
+            self.workers.drain(..).for_each(join);
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "thread_pool",
+    title: "Thread pool with a shared work queue",
+    chapter: Chapter::Threads,
+    difficulty: Difficulty::Advanced,
+    tags: &["arc", "mutex", "condvar"],
+    description: "Build a fixed-size `ThreadPool` whose workers share a task queue behind an \
+                  `Arc<Mutex<_>>` and a `Condvar`, and shut it down without losing tasks.",
+    tests: &[
+        Test { name: "runs_tasks", run: runs_tasks },
+        Test { name: "uses_at_most_size_threads", run: uses_at_most_size_threads },
+        Test { name: "shutdown_runs_queued_tasks", run: shutdown_runs_queued_tasks },
+        Test { name: "drop_shuts_down", run: drop_shuts_down },
+        Test { name: "no_lost_tasks_under_load", run: no_lost_tasks_under_load },
+    ],
+    hints: &[
+        Hint(
+            "Share one `Arc` holding a `Mutex<VecDeque<Task>>` (plus a `closed` flag) and a \
+              `Condvar` between the pool and the workers. Keep the `JoinHandle`s in the pool.",
+        ),
+        Hint(
+            "A worker loops: lock, pop a task or wait on the `Condvar`, unlock, run the task. Do \
+              not run the task while holding the lock, or the pool runs one task at a time.",
+        ),
+        Hint(
+            "Shut down in `Drop` (and make `shutdown` simply drop `self`): set `closed`, \
+              `notify_all`, join every worker. Workers only exit once the queue is closed *and* \
+              empty, so queued tasks still run.",
+        ),
+    ],
+};
+
+/// How long a test may take before we assume the pool is stuck.
+const LIMIT: Duration = Duration::from_secs(10);
+
+fn within_limit<R: Send + 'static>(f: impl FnOnce() -> R + Send + 'static) -> R {
+    with_timeout(LIMIT, f).unwrap_or_else(|err| panic!("the pool {err}"))
+}
+
+fn runs_tasks() {
+    let done = within_limit(|| {
+        let pool = ThreadPool::new(2);
+        check!(pool.size(), 2; see "thread_pool#hints");
+        let done = Arc::new(AtomicUsize::new(0));
+        for _ in 0..10 {
+            let done = Arc::clone(&done);
+            pool.execute(move || {
+                done.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        pool.shutdown();
+        done.load(Ordering::SeqCst)
+    });
+    check!(done, 10; see "thread_pool#hints");
+}
+
+fn uses_at_most_size_threads() {
+    let threads = within_limit(|| {
+        let pool = ThreadPool::new(3);
+        let threads = Arc::new(Mutex::new(HashSet::new()));
+        for _ in 0..50 {
+            let threads = Arc::clone(&threads);
+            pool.execute(move || {
+                threads.lock().unwrap().insert(thread::current().id());
+                thread::sleep(Duration::from_micros(200));
+            });
+        }
+        pool.shutdown();
+        let threads = threads.lock().unwrap().clone();
+        threads
+    });
+    check!(
+        !threads.is_empty() && threads.len() <= 3;
+        see "thread_pool#hints", "tasks ran on {} threads", threads.len()
+    );
+    check!(
+        !threads.contains(&thread::current().id());
+        see "thread_pool#hints", "tasks must run on the workers"
+    );
+}
+
+fn shutdown_runs_queued_tasks() {
+    let done = within_limit(|| {
+        let pool = ThreadPool::new(1);
+        let done = Arc::new(AtomicUsize::new(0));
+        for _ in 0..5 {
+            let done = Arc::clone(&done);
+            pool.execute(move || {
+                thread::sleep(Duration::from_millis(5));
+                done.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        // Most of the tasks are still queued at this point.
+        pool.shutdown();
+        done.load(Ordering::SeqCst)
+    });
+    check!(done, 5; see "thread_pool#hints", "shutdown must wait for the queued tasks");
+}
+
+fn drop_shuts_down() {
+    let done = within_limit(|| {
+        let done = Arc::new(AtomicUsize::new(0));
+        {
+            let pool = ThreadPool::new(2);
+            let task_done = Arc::clone(&done);
+            pool.execute(move || {
+                thread::sleep(Duration::from_millis(10));
+                task_done.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        done.load(Ordering::SeqCst)
+    });
+    check!(done, 1; see "thread_pool#hints", "dropping the pool must wait for its tasks");
+}
+
+fn no_lost_tasks_under_load() {
+    let (sum, count) = within_limit(|| {
+        let pool = Arc::new(ThreadPool::new(4));
+        let sum = Arc::new(AtomicUsize::new(0));
+        let count = Arc::new(AtomicUsize::new(0));
+        let submitters: Vec<_> = (0..4)
+            .map(|submitter| {
+                let (pool, sum, count) = (Arc::clone(&pool), Arc::clone(&sum), Arc::clone(&count));
+                thread::spawn(move || {
+                    for i in 0..2_500 {
+                        let (sum, count) = (Arc::clone(&sum), Arc::clone(&count));
+                        pool.execute(move || {
+                            sum.fetch_add(submitter * 2_500 + i, Ordering::Relaxed);
+                            count.fetch_add(1, Ordering::Relaxed);
+                        });
+                    }
+                })
+            })
+            .collect();
+        submitters.into_iter().for_each(join);
+        Arc::try_unwrap(pool).expect("the submitters are done").shutdown();
+        (sum.load(Ordering::SeqCst), count.load(Ordering::SeqCst))
+    });
+    check!(count, 10_000; see "thread_pool#hints", "some tasks never ran");
+    check!(sum, (0..10_000).sum(); see "thread_pool#hints");
+}
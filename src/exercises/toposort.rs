@@ -0,0 +1,238 @@
+//! Topological sort over shared nodes.
+//!
+//! Tasks depend on other tasks, and a task can be a dependency of many others, so each task is an
+//! `Rc<RefCell<Task>>`. To sort them, a depth-first search stores its visit mark in the task
+//! itself instead of in a side table.
+//!
+//! Two things can go wrong. A dependency cycle has no valid order and must be reported as an
+//! error naming a task on the cycle, not loop forever. And a task that depends on itself means
+//! the search reaches a node it is currently holding a borrow of.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+
+/// A shared handle to a task.
+pub type TaskRef = Rc<RefCell<Task>>;
+
+/// Where a task is in the depth-first search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mark {
+    /// Not reached yet.
+    #[default]
+    Unvisited,
+    /// On the current search path: reaching it again means there is a cycle.
+    InProgress,
+    /// Already placed in the output.
+    Done,
+}
+
+/// A task of the build.
+#[derive(Debug)]
+pub struct Task {
+    /// Name of the task, unique within a build.
+    pub name: String,
+    /// Tasks that must come before this one.
+    pub deps: Vec<TaskRef>,
+    /// Visit mark used by [`toposort`].
+    pub mark: Mark,
+}
+
+impl Task {
+    /// Creates a task without dependencies.
+    pub fn new(name: &str) -> TaskRef {
+        Rc::new(RefCell::new(Task {
+            name: name.to_string(),
+            deps: Vec::new(),
+            mark: Mark::Unvisited,
+        }))
+    }
+}
+
+/// Makes `task` depend on `dep`.
+pub fn depend(task: &TaskRef, dep: &TaskRef) {
+    task.borrow_mut().deps.push(Rc::clone(dep));
+}
+
+/// The tasks contain a dependency cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    /// Name of a task on the cycle.
+    pub task: String,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "dependency cycle through `{}`", self.task)
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+// BEGIN EXERCISE
+/// Returns the names of `tasks` and all their dependencies, every task after the ones it depends
+/// on. Roots are visited in the order given, dependencies in insertion order.
+#[cfg(not(feature = "solutions"))]
+pub fn toposort(tasks: &[TaskRef]) -> Result<Vec<String>, CycleError> {
+    let _ = tasks;
+    todo!()
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::toposort;
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{CycleError, Mark, Rc, TaskRef};
+
+    pub fn toposort(tasks: &[TaskRef]) -> Result<Vec<String>, CycleError> {
+        // Marks left over from an earlier (possibly failed) sort would skip tasks.
+        let mut all = Vec::new();
+        for task in tasks {
+            reset(task, &mut all);
+        }
+
+        let mut order = Vec::new();
+        for task in tasks {
+            visit(task, &mut order)?;
+        }
+        Ok(order)
+    }
+
+    fn reset(task: &TaskRef, seen: &mut Vec<TaskRef>) {
+        if seen.iter().any(|t| Rc::ptr_eq(t, task)) {
+            return;
+        }
+        seen.push(Rc::clone(task));
+        let deps = {
+            let mut task = task.borrow_mut();
+            task.mark = Mark::Unvisited;
+            task.deps.clone()
+        };
+        for dep in &deps {
+            reset(dep, seen);
+        }
+    }
+
+    fn visit(task: &TaskRef, order: &mut Vec<String>) -> Result<(), CycleError> {
+        let deps = {
+            let mut node = task.borrow_mut();
+            match node.mark {
+                Mark::Done => return Ok(()),
+                Mark::InProgress => return Err(CycleError { task: node.name.clone() }),
+                Mark::Unvisited => node.mark = Mark::InProgress,
+            }
+            // Clone the handles so no borrow is held while visiting them.
+            node.deps.clone()
+        };
+        for dep in &deps {
+            visit(dep, order)?;
+        }
+        let mut node = task.borrow_mut();
+        node.mark = Mark::Done;
+        order.push(node.name.clone());
+        Ok(())
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "toposort",
+    title: "Topological sort over a shared-node graph",
+    chapter: Chapter::Rc,
+    difficulty: Difficulty::Advanced,
+    tags: &["rc", "refcell", "graphs"],
+    description: "Implement `toposort` with a depth-first search that keeps its visit marks in \
+                  each `Rc<RefCell<Task>>`, reporting dependency cycles as a `CycleError`.",
+    tests: &[
+        Test { name: "chain", run: chain },
+        Test { name: "diamond", run: diamond },
+        Test { name: "cycle", run: cycle },
+        Test { name: "self_dependency", run: self_dependency },
+        Test { name: "sort_twice", run: sort_twice },
+    ],
+    hints: &[
+        Hint(
+            "A task marked `InProgress` is on the current path. Reaching it again means a \
+              cycle; reaching a `Done` task means it is already in the output.",
+        ),
+        Hint(
+            "Do not keep `task.borrow_mut()` alive while visiting the dependencies: a task that \
+              depends on itself would be borrowed twice. Clone the `deps` vector (it only clones \
+              the `Rc`s) and drop the guard first.",
+        ),
+        Hint(
+            "The marks stay in the tasks after the sort. Set them back to `Unvisited` at the \
+              start, or a second sort will skip everything.",
+        ),
+    ],
+};
+
+/// Clears every edge so the `Rc` cycles in the tests do not leak.
+fn unlink(tasks: &[&TaskRef]) {
+    for task in tasks {
+        task.borrow_mut().deps.clear();
+    }
+}
+
+fn chain() {
+    let (compile, link, test) = (Task::new("compile"), Task::new("link"), Task::new("test"));
+    depend(&test, &link);
+    depend(&link, &compile);
+    check!(
+        toposort(&[Rc::clone(&test)]).unwrap(),
+        ["compile", "link", "test"];
+        see "toposort#hints"
+    );
+}
+
+fn diamond() {
+    let (fetch, lib, bin, package) =
+        (Task::new("fetch"), Task::new("lib"), Task::new("bin"), Task::new("package"));
+    depend(&lib, &fetch);
+    depend(&bin, &fetch);
+    depend(&package, &lib);
+    depend(&package, &bin);
+    let order = toposort(&[Rc::clone(&package)]).unwrap();
+    check!(order, ["fetch", "lib", "bin", "package"]; see "toposort#hints");
+}
+
+fn cycle() {
+    let (a, b, c) = (Task::new("a"), Task::new("b"), Task::new("c"));
+    depend(&a, &b);
+    depend(&b, &c);
+    depend(&c, &a);
+    let result = crate::util::catch_panic(|| toposort(&[Rc::clone(&a)]));
+    unlink(&[&a, &b, &c]);
+    let err = result.expect("toposort panicked").unwrap_err();
+    check!(
+        ["a", "b", "c"].contains(&err.task.as_str());
+        see "toposort#hints", "{err} is not on the cycle"
+    );
+}
+
+fn self_dependency() {
+    let task = Task::new("loop");
+    depend(&task, &task);
+    let result = crate::util::catch_panic(|| toposort(&[Rc::clone(&task)]));
+    unlink(&[&task]);
+    check!(
+        result.expect("toposort panicked"),
+        Err(CycleError { task: "loop".to_string() });
+        see "toposort#hints"
+    );
+}
+
+fn sort_twice() {
+    let (a, b) = (Task::new("a"), Task::new("b"));
+    depend(&b, &a);
+    let roots = [Rc::clone(&b)];
+    check!(toposort(&roots).unwrap(), ["a", "b"]; see "toposort#hints");
+    check!(toposort(&roots).unwrap(), ["a", "b"]; see "toposort#hints");
+}
@@ -0,0 +1,196 @@
+//! An editor with undo and redo.
+//!
+//! `Editor` is shared by reference between the parts of an application (the key handler, the
+//! menu bar, the autosave timer), so all of its methods take `&self`. Its text lives in a
+//! [`HistoryCell`], which already knows how to undo and redo whole values.
+//!
+//! Implement the editing commands on top of it. An edit that changes nothing must not leave an
+//! empty step in the history: pressing undo should always visibly do something.
+
+use std::ops::Range;
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::impls::HistoryCell;
+
+/// A plain-text document with an undo history.
+#[derive(Debug, Default)]
+pub struct Editor {
+    /// The document text and its history.
+    pub text: HistoryCell<String>,
+}
+
+impl Editor {
+    /// Creates an editor holding `text`, with an empty history.
+    pub fn new(text: &str) -> Self {
+        Self { text: HistoryCell::new(text.to_string()) }
+    }
+
+    /// A copy of the current text.
+    pub fn text(&self) -> String {
+        self.text.get()
+    }
+}
+
+// BEGIN EXERCISE
+#[cfg(not(feature = "solutions"))]
+impl Editor {
+    /// Inserts `s` at byte offset `at`. Offsets past the end insert at the end.
+    pub fn insert(&self, at: usize, s: &str) {
+        let _ = (at, s);
+        todo!()
+    }
+
+    /// Deletes the bytes in `range`, clamped to the end of the text.
+    pub fn delete(&self, range: Range<usize>) {
+        let _ = range;
+        todo!()
+    }
+
+    /// Takes back the last edit. Returns `false` if there was nothing to undo.
+    pub fn undo(&self) -> bool {
+        todo!()
+    }
+
+    /// Re-applies the last undone edit. Returns `false` if there was nothing to redo.
+    pub fn redo(&self) -> bool {
+        todo!()
+    }
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{Editor, Range};
+
+    impl Editor {
+        pub fn insert(&self, at: usize, s: &str) {
+            if s.is_empty() {
+                return;
+            }
+            self.text.update(|text| text.insert_str(at.min(text.len()), s));
+        }
+
+        pub fn delete(&self, range: Range<usize>) {
+            let len = self.text.with(String::len);
+            let range = range.start.min(len)..range.end.min(len);
+            if range.is_empty() {
+                return;
+            }
+            self.text.update(|text| {
+                text.replace_range(range, "");
+            });
+        }
+
+        pub fn undo(&self) -> bool {
+            self.text.undo()
+        }
+
+        pub fn redo(&self) -> bool {
+            self.text.redo()
+        }
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "undo_redo",
+    title: "Undo/redo stack",
+    chapter: Chapter::RefCell,
+    difficulty: Difficulty::Core,
+    tags: &["refcell", "history"],
+    description: "Implement `insert`, `delete`, `undo` and `redo` for an `Editor` whose text \
+                  lives in a `HistoryCell`. Edits that change nothing must not be recorded.",
+    tests: &[
+        Test { name: "edits", run: edits },
+        Test { name: "undo_and_redo", run: undo_and_redo },
+        Test { name: "edit_clears_redo", run: edit_clears_redo },
+        Test { name: "interleaved", run: interleaved },
+        Test { name: "no_empty_steps", run: no_empty_steps },
+    ],
+    hints: &[
+        Hint(
+            "`HistoryCell::update` records a new step for you, and `HistoryCell::undo`/`redo` \
+              already do the stack juggling.",
+        ),
+        Hint(
+            "`String::insert_str` and `String::replace_range` do the actual editing. Clamp the \
+              offsets to `text.len()` first.",
+        ),
+        Hint(
+            "Return early, before calling `update`, when the inserted string is empty or the \
+              clamped range to delete is empty.",
+        ),
+    ],
+};
+
+fn edits() {
+    let editor = Editor::new("hello");
+    editor.insert(5, " world");
+    check!(editor.text(), "hello world"; see "refcell#example-5");
+    editor.delete(0..6);
+    check!(editor.text(), "world"; see "refcell#example-5");
+    editor.insert(100, "!");
+    editor.delete(3..100);
+    check!(editor.text(), "wor"; see "refcell#example-5");
+}
+
+fn undo_and_redo() {
+    let editor = Editor::new("a");
+    editor.insert(1, "b");
+    editor.insert(2, "c");
+    check!(editor.undo(); see "refcell#example-5");
+    check!(editor.text(), "ab"; see "refcell#example-5");
+    check!(editor.undo(); see "refcell#example-5");
+    check!(editor.text(), "a"; see "refcell#example-5");
+    check!(!editor.undo(); see "refcell#example-5");
+    check!(editor.redo(); see "refcell#example-5");
+    check!(editor.redo(); see "refcell#example-5");
+    check!(editor.text(), "abc"; see "refcell#example-5");
+    check!(!editor.redo(); see "refcell#example-5");
+}
+
+fn edit_clears_redo() {
+    let editor = Editor::new("abc");
+    editor.delete(2..3);
+    check!(editor.undo(); see "refcell#example-5");
+    editor.insert(0, ">");
+    check!(!editor.redo(); see "refcell#example-5");
+    check!(editor.text(), ">abc"; see "refcell#example-5");
+}
+
+fn interleaved() {
+    let editor = Editor::new("");
+    editor.insert(0, "one");
+    editor.insert(3, " two");
+    editor.undo();
+    editor.insert(3, " three");
+    editor.delete(0..4);
+    check!(editor.text(), "three"; see "refcell#example-5");
+    editor.undo();
+    editor.undo();
+    check!(editor.text(), "one"; see "refcell#example-5");
+    editor.redo();
+    check!(editor.text(), "one three"; see "refcell#example-5");
+    editor.insert(0, "> ");
+    editor.undo();
+    editor.undo();
+    editor.undo();
+    check!(editor.text(), ""; see "refcell#example-5");
+    check!(!editor.undo(); see "refcell#example-5");
+}
+
+fn no_empty_steps() {
+    let editor = Editor::new("text");
+    editor.insert(2, "");
+    editor.delete(10..12);
+    editor.delete(1..1);
+    check!(
+        editor.text.undo_depth(),
+        0;
+        see "refcell#example-5", "an edit that changed nothing was recorded"
+    );
+    check!(!editor.undo(); see "refcell#example-5");
+}
@@ -0,0 +1,158 @@
+//! A tree with `Weak` parent pointers.
+//!
+//! Parents own their children (`Rc`), children only point at their parent (`Weak`). That way
+//! there is no cycle between a parent and its children, and dropping the root frees the whole
+//! tree. Both links change after the nodes are created, so they live in `RefCell`s.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use super::{Hint, StaticExercise, Test};
+use crate::check;
+use crate::curriculum::{Chapter, Difficulty};
+use crate::leakcheck::{LeakTracker, Tracked};
+
+/// A node of the tree.
+#[derive(Debug)]
+pub struct TreeNode {
+    /// Name of the node.
+    pub name: String,
+    /// The node's parent, if it has one.
+    pub parent: RefCell<Weak<TreeNode>>,
+    /// The node's children, in insertion order.
+    pub children: RefCell<Vec<Rc<TreeNode>>>,
+    _token: Option<Tracked>,
+}
+
+impl TreeNode {
+    /// Creates a node without parent or children.
+    pub fn new(name: &str) -> Rc<TreeNode> {
+        Self::build(name, None)
+    }
+
+    /// Like [`TreeNode::new`], but the node is counted by `tracker` until it is dropped.
+    pub fn tracked(name: &str, tracker: &LeakTracker) -> Rc<TreeNode> {
+        Self::build(name, Some(tracker.track()))
+    }
+
+    fn build(name: &str, token: Option<Tracked>) -> Rc<TreeNode> {
+        Rc::new(TreeNode {
+            name: name.to_string(),
+            parent: RefCell::new(Weak::new()),
+            children: RefCell::new(Vec::new()),
+            _token: token,
+        })
+    }
+
+    /// Names of the children, in order.
+    pub fn child_names(&self) -> Vec<String> {
+        self.children.borrow().iter().map(|child| child.name.clone()).collect()
+    }
+}
+
+// BEGIN EXERCISE
+/// Returns the parent of `node`, if it has one that is still alive.
+#[cfg(not(feature = "solutions"))]
+pub fn parent(node: &TreeNode) -> Option<Rc<TreeNode>> {
+    let _ = node;
+    todo!()
+}
+
+/// Makes `child` the last child of `parent`.
+///
+/// If `child` already has a parent, it is removed from that parent's children first.
+#[cfg(not(feature = "solutions"))]
+pub fn add_child(parent: &Rc<TreeNode>, child: &Rc<TreeNode>) {
+    let _ = (parent, child);
+    todo!()
+}
+// END EXERCISE
+
+#[cfg(feature = "solutions")]
+pub use solution::{add_child, parent};
+
+#[cfg(feature = "solutions")]
+mod solution {
+    // BEGIN SOLUTION
+    use super::{Rc, TreeNode};
+
+    pub fn parent(node: &TreeNode) -> Option<Rc<TreeNode>> {
+        node.parent.borrow().upgrade()
+    }
+
+    pub fn add_child(parent: &Rc<TreeNode>, child: &Rc<TreeNode>) {
+        if let Some(old) = self::parent(child) {
+            old.children.borrow_mut().retain(|c| !Rc::ptr_eq(c, child));
+        }
+        *child.parent.borrow_mut() = Rc::downgrade(parent);
+        parent.children.borrow_mut().push(Rc::clone(child));
+    }
+    // END SOLUTION
+}
+
+pub(super) const EXERCISE: StaticExercise = StaticExercise {
+    id: "weak_tree",
+    title: "Tree with Weak parent pointers",
+    chapter: Chapter::Rc,
+    difficulty: Difficulty::Core,
+    tags: &["rc", "weak", "trees"],
+    description: "Implement `parent` and `add_child` (including re-parenting) for a tree whose \
+                  children point at their parent through a `Weak`.",
+    tests: &[
+        Test { name: "parent_of_child", run: parent_of_child },
+        Test { name: "root_has_no_parent", run: root_has_no_parent },
+        Test { name: "reparenting", run: reparenting },
+        Test { name: "dropping_root_frees_tree", run: dropping_root_frees_tree },
+    ],
+    hints: &[
+        Hint("`Weak::upgrade` turns the parent pointer back into an `Rc`, if the parent is alive."),
+        Hint(
+            "Re-parenting has three steps: remove the child from the old parent's children, \
+              point the child at the new parent, push it onto the new parent's children.",
+        ),
+        Hint(
+            "Remove with `old.children.borrow_mut().retain(|c| !Rc::ptr_eq(c, child))` and set \
+              the parent with `*child.parent.borrow_mut() = Rc::downgrade(parent)`.",
+        ),
+    ],
+};
+
+fn parent_of_child() {
+    let root = TreeNode::new("root");
+    let leaf = TreeNode::new("leaf");
+    add_child(&root, &leaf);
+    check!(parent(&leaf).map(|p| p.name.clone()), Some("root".to_string()); see "weak_tree#hints");
+    check!(root.child_names(), ["leaf"]; see "weak_tree#hints");
+}
+
+fn root_has_no_parent() {
+    let root = TreeNode::new("root");
+    check!(parent(&root).is_none(); see "weak_tree#hints");
+}
+
+fn reparenting() {
+    let a = TreeNode::new("a");
+    let b = TreeNode::new("b");
+    let child = TreeNode::new("child");
+    add_child(&a, &child);
+    add_child(&b, &child);
+
+    check!(a.child_names().is_empty(); see "weak_tree#hints");
+    check!(b.child_names(), ["child"]; see "weak_tree#hints");
+    check!(Rc::ptr_eq(&parent(&child).unwrap(), &b); see "weak_tree#hints");
+}
+
+fn dropping_root_frees_tree() {
+    let tracker = LeakTracker::new();
+    {
+        let root = TreeNode::tracked("root", &tracker);
+        for i in 0..3 {
+            let child = TreeNode::tracked(&format!("child {i}"), &tracker);
+            add_child(&root, &child);
+            let grandchild = TreeNode::tracked(&format!("grandchild {i}"), &tracker);
+            add_child(&child, &grandchild);
+        }
+        check!(tracker.live(), 7; see "weak_tree#hints");
+    }
+    tracker.assert_no_leaks();
+}
@@ -0,0 +1,686 @@
+//! Publishing the course in other formats.
+//!
+//! The lessons live in doc comments and the exercises in the registry; the functions here turn
+//! both into files for other tools:
+//!
+//! - [`mdbook`] writes the source tree of an [mdBook](https://rust-lang.github.io/mdBook/), so an
+//!   instructor can publish the course as a website with `mdbook build`.
+//! - [`html`] writes one self-contained HTML page per chapter, with the styles inlined and the
+//!   code highlighted, for offline handouts.
+//! - [`rustlings`] writes a third-party exercise set for
+//!   [rustlings](https://github.com/rust-lang/rustlings), so the exercises can run in its watcher.
+//!
+//! Other tools can pull a single lesson with [`lesson_to_markdown`].
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::curriculum::Chapter;
+use crate::exercises::{self, Exercise};
+use crate::lessons::{self, Block, ExampleKind, Lesson, LessonId, Page};
+
+/// Renders one lesson as a standalone Markdown document.
+///
+/// Code examples keep their hidden setup lines and are fenced with their doctest annotation
+/// (`rust,compile_fail`, `rust,should_panic`, ...), so the document can be tested with
+/// `mdbook test` or `rustdoc --test`. Links to other parts of the API docs are reduced to their
+/// text. A chapter without a lesson yields a heading and a short note.
+/// ```
+/// use learning_cell::curriculum::Chapter;
+///
+/// let text = learning_cell::export::lesson_to_markdown(Chapter::RefCell);
+/// assert!(text.starts_with("# RefCell\n"));
+/// assert!(text.contains("```rust,should_panic\n"));
+/// ```
+pub fn lesson_to_markdown(lesson: LessonId) -> String {
+    match lessons::page(lesson) {
+        Some(page) => page_to_markdown(lesson.title(), &page),
+        None => format!("# {}\n\nThis chapter has no lesson yet.\n\n", lesson.title()),
+    }
+}
+
+/// Writes an mdBook source tree into `dir` and returns the paths of the files written.
+///
+/// The book has an introduction, then one section per chapter: the lesson (or a short overview
+/// for chapters without one) followed by a page per exercise. Each lesson page opens with the
+/// lesson's [metadata](crate::lessons::LessonMeta) and ends with links to the exercises that
+/// practice it. Existing files are overwritten.
+/// ```no_run
+/// let files = learning_cell::export::mdbook("book").unwrap();
+/// assert!(files.iter().any(|path| path.ends_with("SUMMARY.md")));
+/// ```
+pub fn mdbook(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let mut files = Files { written: Vec::new() };
+    files.write(
+        &dir.join("book.toml"),
+        "[book]\ntitle = \"Learning Cell\"\nsrc = \"src\"\n\n[output.html]\n",
+    )?;
+
+    let src = dir.join("src");
+    let mut summary = String::from("# Summary\n\n[Introduction](README.md)\n\n");
+    files.write(&src.join("README.md"), &introduction())?;
+    for chapter in Chapter::ALL {
+        let page = format!("{}/README.md", chapter.id());
+        summary.push_str(&format!("- [{}]({page})\n", chapter.title()));
+        files.write(&src.join(&page), &chapter_to_markdown(chapter))?;
+        for lesson in other_lessons(chapter) {
+            let page = book_page(lesson);
+            summary.push_str(&format!("  - [{}]({page})\n", escape(lesson.title())));
+            files.write(&src.join(&page), &lesson_to_book_page(lesson))?;
+        }
+        for exercise in exercises::all().into_iter().filter(|e| e.chapter() == chapter) {
+            let page = format!("{}/{}.md", chapter.id(), exercise.id());
+            summary.push_str(&format!("  - [{}]({page})\n", escape(exercise.title())));
+            files.write(&src.join(&page), &exercise_to_markdown(exercise))?;
+        }
+    }
+    files.write(&src.join("SUMMARY.md"), &summary)?;
+    Ok(files.written)
+}
+
+/// Writes one self-contained HTML handout per chapter, plus an `index.html`, into `dir`, and
+/// returns the paths of the files written.
+///
+/// Each handout holds the chapter's lesson followed by its exercises and their hints. The pages
+/// need nothing but a browser: no stylesheet, script or font is loaded from elsewhere.
+/// ```no_run
+/// let files = learning_cell::export::html("handouts").unwrap();
+/// assert!(files.iter().any(|path| path.ends_with("refcell.html")));
+/// ```
+pub fn html(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let mut files = Files { written: Vec::new() };
+    let mut index = String::from("# Learning Cell\n\n");
+    for chapter in Chapter::ALL {
+        index.push_str(&format!("- [{}]({}.html)\n", chapter.title(), chapter.id()));
+
+        let mut text = lesson_to_markdown(chapter);
+        for lesson in other_lessons(chapter) {
+            text.push_str(&page_to_markdown(lesson.title(), &lesson.page()));
+        }
+        text.push_str("# Exercises\n\n");
+        for exercise in exercises::all().into_iter().filter(|e| e.chapter() == chapter) {
+            // One level deeper, under the "Exercises" heading. The title is not escaped: the HTML
+            // renderer escapes it, and would show a Markdown `&lt;` as it is.
+            text.push_str(&format!("## {}\n\n{}\n", exercise.title(), exercise_body(exercise)));
+        }
+        let page = html_page(chapter.title(), &markdown_to_html(&text));
+        files.write(&dir.join(format!("{}.html", chapter.id())), &page)?;
+    }
+    files.write(&dir.join("index.html"), &html_page("Learning Cell", &markdown_to_html(&index)))?;
+    Ok(files.written)
+}
+
+/// Writes the built-in exercises as a rustlings exercise set into `dir` and returns the paths of
+/// the files written.
+///
+/// The layout is the one rustlings expects from third-party exercises: an `info.toml` listing
+/// the exercises with their hints, a `Cargo.toml` with one binary per exercise, and the
+/// exercises and their solutions under `exercises/<NN>_<chapter>/` and `solutions/`. Each file
+/// holds the scaffold followed by the exercise's hidden tests as `#[test]`s, so `rustlings`
+/// (or `cargo test --bin <exercise>`) checks it. The tests keep using this crate's helpers, which
+/// is why the generated crate depends on `learning_cell`.
+/// ```no_run
+/// let files = learning_cell::export::rustlings("interior-mutability").unwrap();
+/// assert!(files.iter().any(|path| path.ends_with("info.toml")));
+/// ```
+pub fn rustlings(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let mut files = Files { written: Vec::new() };
+    let mut info = format!(
+        "format_version = 1\n\nwelcome_message = {}\n\nfinal_message = {}\n",
+        toml_string(&format!("{}.", env!("CARGO_PKG_DESCRIPTION"))),
+        toml_string("You made it through the interior mutability track!"),
+    );
+    let mut bins = String::new();
+    for (number, chapter) in Chapter::ALL.into_iter().enumerate() {
+        let chapter_dir = format!("{:02}_{}", number + 1, chapter.id());
+        for exercise in exercises::all().into_iter().filter(|e| e.chapter() == chapter) {
+            let id = exercise.id();
+            // Exercises registered at run time have no source file to carry over.
+            let (Some(source), Some(scaffold)) = (exercises::source(id), exercises::scaffold(id))
+            else {
+                continue;
+            };
+            let hints: Vec<_> = exercise.hints().iter().map(|hint| hint.to_string()).collect();
+            info.push_str(&format!(
+                "\n[[exercises]]\nname = \"{id}\"\ndir = \"{chapter_dir}\"\nhint = {}\n",
+                toml_string(&hints.join("\n"))
+            ));
+            for (kind, solution) in [("exercises", false), ("solutions", true)] {
+                let path = format!("{kind}/{chapter_dir}/{id}.rs");
+                let file = rustlings_exercise(exercise, source, scaffold, solution);
+                files.write(&dir.join(&path), &file)?;
+                if !solution {
+                    bins.push_str(&format!("  {{ name = \"{id}\", path = \"{path}\" }},\n"));
+                    bins.push_str(&format!(
+                        "  {{ name = \"{id}_sol\", path = \"solutions/{chapter_dir}/{id}.rs\" }},\n"
+                    ));
+                }
+            }
+        }
+    }
+    files.write(&dir.join("info.toml"), &info)?;
+    files.write(
+        &dir.join("Cargo.toml"),
+        &format!(
+            "bin = [\n{bins}]\n\n[package]\nname = \"learning_cell_exercises\"\nedition = \"2021\"\n\
+             publish = false\n\n[dependencies]\nlearning_cell = {{ git = \"{}\" }}\n",
+            env!("CARGO_PKG_REPOSITORY")
+        ),
+    )?;
+    Ok(files.written)
+}
+
+/// Turns an exercise's source file into a standalone rustlings exercise (or, with `solution`,
+/// its solution): the code the learner works on, a `main`, and the hidden tests.
+fn rustlings_exercise(
+    exercise: &dyn Exercise,
+    source: &str,
+    scaffold: &str,
+    solution: bool,
+) -> String {
+    // The compiled source may hold the learner's edits; start over from the shipped scaffold.
+    let source = exercises::restore_scaffold(source, scaffold).unwrap_or_else(|| source.into());
+    let mut text = format!("// {}\n//\n", exercise.title());
+    for line in wrap(exercise.description(), 96) {
+        text.push_str(&format!("// {line}\n"));
+    }
+    text.push_str(&format!("//\n// Run `rustlings hint {}` if you get stuck.\n\n", exercise.id()));
+    // Some imports are only used by the tests at the bottom.
+    text.push_str("#![allow(unused_imports)]\n");
+
+    let mut lines = source.lines().skip_while(|line| line.starts_with("//!")).peekable();
+    let mut in_scaffold = false;
+    let mut tests = String::new();
+    while let Some(line) = lines.next() {
+        match line {
+            "// BEGIN EXERCISE" => in_scaffold = true,
+            "// END EXERCISE" => in_scaffold = false,
+            // The solution leaves out the scaffold but keeps its markers, removed at the end.
+            _ if solution && in_scaffold => continue,
+            _ => {}
+        }
+        if line.starts_with("pub(super) const EXERCISE") {
+            // Everything after the metadata is hidden tests and their helpers.
+            lines.by_ref().find(|line| *line == "};");
+            tests = lines.by_ref().map(|line| format!("{line}\n")).collect();
+            break;
+        }
+        if line == "#[cfg(feature = \"solutions\")]" {
+            if !solution {
+                // Skip the item, up to the end of a `mod solution { ... }` block.
+                if lines.next().is_some_and(|item| item.ends_with('{')) {
+                    lines.by_ref().find(|line| *line == "}");
+                }
+            }
+            continue;
+        }
+        if line == "#[cfg(not(feature = \"solutions\"))]" {
+            continue;
+        }
+        if line == "#[cfg_attr(not(feature = \"solutions\"), allow(dead_code))]" {
+            if !solution {
+                text.push_str("#[allow(dead_code)]\n");
+            }
+            continue;
+        }
+        if let Some(import) = line.strip_prefix("use ") {
+            text.push_str(&rewrite_import(import));
+            continue;
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+    if solution {
+        // The solution replaces the scaffold, so its markers now surround nothing.
+        text = text.replace("// BEGIN EXERCISE\n// END EXERCISE\n", "");
+    }
+    while text.contains("\n\n\n") {
+        text = text.replace("\n\n\n", "\n\n");
+    }
+
+    text.push_str(
+        "fn main() {\n    // You can experiment here; `rustlings` runs the tests below.\n}\n\n",
+    );
+    text.push_str("#[cfg(test)]\nmod tests {\n    #[allow(unused_imports)]\n    use super::*;\n");
+    let mut tests = tests.replace("crate::", "learning_cell::");
+    for test in exercise.tests() {
+        tests = tests
+            .replace(&format!("\nfn {}()", test.name), &format!("\n#[test]\nfn {}()", test.name));
+    }
+    if tests.contains("active_code(") {
+        tests.push_str(&active_code_helper(if solution { "SOLUTION" } else { "EXERCISE" }));
+    }
+    for line in tests.lines() {
+        text.push_str(if line.is_empty() { "\n" } else { "    " });
+        if !line.is_empty() {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    text.push_str("}\n");
+    text
+}
+
+/// A standalone copy of the exercises' `active_code` helper for the rustlings tests, looking for
+/// `marker` (`EXERCISE` or `SOLUTION`).
+fn active_code_helper(marker: &str) -> String {
+    format!(
+        "
+/// The code between the {marker} markers of this file.
+fn active_code(source: &'static str) -> &'static str {{
+    let start = source.find(\"// BEGIN {marker}\").map_or(0, |start| start + {});
+    let end = source[start..].find(\"// END {marker}\").map_or(source.len(), |end| start + end);
+    &source[start..end]
+}}
+",
+        "// BEGIN ".len() + marker.len()
+    )
+}
+
+/// Rewrites `use <import>` from an exercise module for a standalone file: the exercise metadata
+/// types disappear and crate paths point at `learning_cell`.
+fn rewrite_import(import: &str) -> String {
+    if import.starts_with("crate::curriculum::") {
+        return String::new();
+    }
+    if let Some(names) = import.strip_prefix("super::{").and_then(|rest| rest.strip_suffix("};")) {
+        let names: Vec<_> = names
+            .split(", ")
+            .filter(|name| !["Hint", "StaticExercise", "Test", "active_code"].contains(name))
+            .collect();
+        return match names.as_slice() {
+            [] => String::new(),
+            [name] => format!("use learning_cell::exercises::{name};\n"),
+            names => format!("use learning_cell::exercises::{{{}}};\n", names.join(", ")),
+        };
+    }
+    let import = import.replace("crate::", "learning_cell::");
+    format!("use {}\n", import.replace("super::", "learning_cell::exercises::"))
+}
+
+/// Splits `text` into lines of at most `width` characters at spaces.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+/// A TOML basic string.
+fn toml_string(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+/// Collects the paths of the files it writes.
+struct Files {
+    written: Vec<PathBuf>,
+}
+
+impl Files {
+    fn write(&mut self, path: &Path, contents: &str) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+        self.written.push(path.to_path_buf());
+        Ok(())
+    }
+}
+
+fn introduction() -> String {
+    format!(
+        "# Learning Cell\n\n{}.\n\nThe course is organized in chapters. Each starts with a \
+         lesson and continues with hands-on exercises: clone the repository, edit the exercise \
+         file and check your work with `cargo run -- verify <exercise>`.\n",
+        env!("CARGO_PKG_DESCRIPTION")
+    )
+}
+
+/// The chapter's lesson, or an overview if it has none.
+fn chapter_to_markdown(chapter: Chapter) -> String {
+    match lessons::find(chapter.id()) {
+        Some(lesson) => lesson_to_book_page(lesson),
+        None => {
+            let mut text = format!("# {}\n\nThis chapter has no lesson yet; ", chapter.title());
+            text.push_str("learn by doing with its exercises:\n\n");
+            for exercise in exercises::all().into_iter().filter(|e| e.chapter() == chapter) {
+                text.push_str(&format!("- [{}]({}.md)\n", escape(exercise.title()), exercise.id()));
+            }
+            text
+        }
+    }
+}
+
+/// The lessons of `chapter` other than its main lesson.
+fn other_lessons(chapter: Chapter) -> impl Iterator<Item = &'static dyn Lesson> {
+    let lessons = lessons::all().into_iter();
+    lessons.filter(move |lesson| lesson.chapter() == chapter && lesson.id() != chapter.id())
+}
+
+/// Where `lesson` goes in the book, relative to its `src` directory.
+fn book_page(lesson: &dyn Lesson) -> String {
+    if lesson.id() == lesson.chapter().id() {
+        format!("{}/README.md", lesson.chapter().id())
+    } else {
+        format!("{}/{}.md", lesson.chapter().id(), lesson.id())
+    }
+}
+
+/// The lesson under its metadata, followed by links to the exercises practicing it.
+fn lesson_to_book_page(lesson: &dyn Lesson) -> String {
+    let meta = lesson.meta();
+    let mut text = format!("# {}\n\n> {}\n\n", meta.title, strip_doc_links(meta.summary));
+    text.push_str(&format!("_Difficulty: {} · About {} minutes", meta.difficulty, meta.minutes));
+    let prerequisites: Vec<_> = meta
+        .prerequisites
+        .iter()
+        .filter_map(|id| lessons::find(id))
+        .map(|before| format!("[{}](../{})", escape(before.title()), book_page(before)))
+        .collect();
+    if !prerequisites.is_empty() {
+        text.push_str(&format!(" · Read first: {}", prerequisites.join(", ")));
+    }
+    text.push_str("_\n\n");
+    text.push_str(&blocks_to_markdown(&lesson.page()));
+    let practice = meta.exercises.iter().filter_map(|id| exercises::get(id));
+    for (number, exercise) in practice.enumerate() {
+        if number == 0 {
+            text.push_str("## Practice\n\n");
+        }
+        let page = format!("../{}/{}.md", exercise.chapter().id(), exercise.id());
+        text.push_str(&format!("- [{}]({page})\n", escape(exercise.title())));
+    }
+    text
+}
+
+fn page_to_markdown(title: &str, page: &Page) -> String {
+    format!("# {title}\n\n{}", blocks_to_markdown(page))
+}
+
+fn blocks_to_markdown(page: &Page) -> String {
+    let mut text = String::new();
+    for block in &page.blocks {
+        match block {
+            Block::Text(prose) => text.push_str(&strip_doc_links(prose)),
+            Block::Example(example) => {
+                let fence = match example.kind {
+                    ExampleKind::Runs => "rust",
+                    ExampleKind::CompileFail => "rust,compile_fail",
+                    ExampleKind::ShouldPanic => "rust,should_panic",
+                    ExampleKind::Ignored => "rust,ignore",
+                };
+                text.push_str(&format!("```{fence}\n{}```", example.source));
+            }
+        }
+        text.push_str("\n\n");
+    }
+    text
+}
+
+fn exercise_to_markdown(exercise: &dyn Exercise) -> String {
+    format!("# {}\n\n{}", escape(exercise.title()), exercise_body(exercise))
+}
+
+/// The exercise page below its title: metadata, description and hints.
+fn exercise_body(exercise: &dyn Exercise) -> String {
+    let mut text = format!("_Difficulty: {}", exercise.difficulty());
+    if !exercise.tags().is_empty() {
+        text.push_str(&format!(" · Topics: {}", exercise.tags().join(", ")));
+    }
+    text.push_str(&format!("_\n\n{}\n\n", strip_doc_links(exercise.description())));
+    text.push_str(&format!(
+        "Open `src/exercises/{id}.rs`, replace the `todo!()`s, then run \
+         `cargo run -- verify {id}`.\n",
+        id = exercise.id()
+    ));
+    for (number, hint) in exercise.hints().iter().enumerate() {
+        text.push_str(&format!(
+            "\n<details>\n<summary>Hint {}</summary>\n\n{hint}\n\n</details>\n",
+            number + 1
+        ));
+    }
+    text
+}
+
+/// Escapes `<` outside of code spans, so titles like `Rc<RefCell<Node>>` are not read as HTML.
+fn escape(text: &str) -> String {
+    let mut in_code = false;
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '`' => {
+                in_code = !in_code;
+                out.push(c);
+            }
+            '<' if !in_code => out.push_str("&lt;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Turns rustdoc's intra-doc links, which mean nothing outside of rustdoc, into plain code spans:
+/// ``[`Cell`](crate::Cell)`` and ``[`RefCell`]`` both become `` `Cell` `` and `` `RefCell` ``.
+fn strip_doc_links(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("[`") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find("`]") else {
+            rest = &rest[start..];
+            break;
+        };
+        let label = &after[..end + 1];
+        let mut tail = &after[end + 2..];
+        // Drop a link target pointing into the crate; keep real URLs.
+        if let Some(target) = tail.strip_prefix('(') {
+            match target.find(')') {
+                Some(close) if !target[..close].contains("://") => tail = &target[close + 1..],
+                _ => {
+                    out.push('[');
+                    out.push_str(label);
+                    out.push(']');
+                    rest = tail;
+                    continue;
+                }
+            }
+        }
+        out.push_str(label);
+        rest = tail;
+    }
+    out.push_str(rest);
+    out
+}
+
+const STYLE: &str = "\
+body { max-width: 46em; margin: 2em auto; padding: 0 1em; font: 16px/1.5 sans-serif; color: #222; }
+pre { background: #f6f8fa; padding: .8em; overflow-x: auto; border-radius: 4px; }
+code { font: 14px/1.4 monospace; background: #f6f8fa; }
+.note { font-size: 13px; color: #666; margin-bottom: -.6em; }
+.kw { color: #a626a4; } .str { color: #50a14f; } .com { color: #a0a1a7; font-style: italic; }
+.num { color: #986801; } .mac { color: #4078f2; } .ty { color: #c18401; }
+";
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape_html(title)
+    )
+}
+
+/// Renders the markdown this module produces: headings, paragraphs, lists, fenced code blocks,
+/// inline code, emphasis, links and raw HTML lines.
+fn markdown_to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_list = false;
+    let mut lines = text.lines();
+
+    let flush = |html: &mut String, paragraph: &mut Vec<&str>, in_list: &mut bool| {
+        if !paragraph.is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", inline(&paragraph.join("\n"))));
+            paragraph.clear();
+        }
+        if *in_list {
+            html.push_str("</ul>\n");
+            *in_list = false;
+        }
+    };
+
+    while let Some(line) = lines.next() {
+        if let Some(info) = line.strip_prefix("```") {
+            flush(&mut html, &mut paragraph, &mut in_list);
+            let mut code = String::new();
+            for line in lines.by_ref().take_while(|line| !line.starts_with("```")) {
+                // Hidden setup lines are for the compiler, not for readers.
+                let trimmed = line.trim_start();
+                if trimmed != "#" && !trimmed.starts_with("# ") {
+                    code.push_str(line);
+                    code.push('\n');
+                }
+            }
+            let note = match info.split(',').nth(1) {
+                Some("compile_fail") => Some("does not compile"),
+                Some("should_panic") => Some("panics"),
+                _ => None,
+            };
+            if let Some(note) = note {
+                html.push_str(&format!("<div class=\"note\">{note}</div>\n"));
+            }
+            html.push_str(&format!("<pre><code>{}</code></pre>\n", highlight(&code)));
+        } else if let Some(heading) = line.strip_prefix('#') {
+            flush(&mut html, &mut paragraph, &mut in_list);
+            let level = 1 + heading.chars().take_while(|&c| c == '#').count();
+            let heading = heading.trim_start_matches('#').trim();
+            html.push_str(&format!("<h{level}>{}</h{level}>\n", inline(heading)));
+        } else if let Some(item) = line.strip_prefix("- ") {
+            if !in_list {
+                flush(&mut html, &mut paragraph, &mut in_list);
+                html.push_str("<ul>\n");
+                in_list = true;
+            }
+            html.push_str(&format!("<li>{}</li>\n", inline(item)));
+        } else if line.trim().is_empty() {
+            flush(&mut html, &mut paragraph, &mut in_list);
+        } else if line.starts_with('<') {
+            flush(&mut html, &mut paragraph, &mut in_list);
+            html.push_str(line);
+            html.push('\n');
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush(&mut html, &mut paragraph, &mut in_list);
+    html
+}
+
+/// Renders inline markdown: `code`, **strong**, _emphasis_ and [links](url).
+fn inline(text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let closing = |delimiter: &str| rest[delimiter.len()..].find(delimiter);
+        match c {
+            '`' if closing("`").is_some() => {
+                let end = closing("`").unwrap() + 1;
+                html.push_str(&format!("<code>{}</code>", escape_html(&rest[1..end])));
+                rest = &rest[end + 1..];
+            }
+            '*' if rest.starts_with("**") && closing("**").is_some() => {
+                let end = closing("**").unwrap() + 2;
+                html.push_str(&format!("<strong>{}</strong>", inline(&rest[2..end])));
+                rest = &rest[end + 2..];
+            }
+            // Only at the start of a word, so `snake_case` stays as it is.
+            '_' if !html.ends_with(|c: char| c.is_alphanumeric()) && closing("_").is_some() => {
+                let end = closing("_").unwrap() + 1;
+                html.push_str(&format!("<em>{}</em>", inline(&rest[1..end])));
+                rest = &rest[end + 1..];
+            }
+            '[' => match rest.find("](").zip(rest.find(')')) {
+                Some((middle, end)) if middle < end => {
+                    let (label, url) = (&rest[1..middle], &rest[middle + 2..end]);
+                    html.push_str(&format!(
+                        "<a href=\"{}\">{}</a>",
+                        escape_html(url),
+                        inline(label)
+                    ));
+                    rest = &rest[end + 1..];
+                }
+                _ => {
+                    html.push('[');
+                    rest = &rest[1..];
+                }
+            },
+            _ => {
+                html.push_str(&escape_html(&c.to_string()));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    html
+}
+
+/// Wraps the tokens of Rust `code` in `<span>`s for the styles of [`STYLE`].
+fn highlight(code: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "break", "const", "continue", "crate", "dyn", "else", "enum", "fn", "for", "if",
+        "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self",
+        "Self", "static", "struct", "super", "trait", "type", "unsafe", "use", "where", "while",
+    ];
+    let span =
+        |class: &str, text: &str| format!("<span class=\"{class}\">{}</span>", escape_html(text));
+    let mut html = String::new();
+    let mut rest = code;
+    while let Some(c) = rest.chars().next() {
+        let (token, class) = if rest.starts_with("//") {
+            (&rest[..rest.find('\n').unwrap_or(rest.len())], Some("com"))
+        } else if c == '"' {
+            let end = rest[1..].find('"').map_or(rest.len(), |end| end + 2);
+            (&rest[..end], Some("str"))
+        } else if c.is_ascii_digit() {
+            let end =
+                rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len());
+            (&rest[..end], Some("num"))
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest.find(|c: char| !c.is_alphanumeric() && c != '_').unwrap_or(rest.len());
+            let word = &rest[..end];
+            let class = if KEYWORDS.contains(&word) {
+                Some("kw")
+            } else if rest[end..].starts_with('!') {
+                Some("mac")
+            } else if word.starts_with(|c: char| c.is_uppercase()) {
+                Some("ty")
+            } else {
+                None
+            };
+            (word, class)
+        } else {
+            (&rest[..c.len_utf8()], None)
+        };
+        match class {
+            Some(class) => html.push_str(&span(class, token)),
+            None => html.push_str(&escape_html(token)),
+        }
+        rest = &rest[token.len()..];
+    }
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,336 @@
+//! Reusable graders for exercises.
+//!
+//! Most exercises are graded by their hidden tests alone, but many checks come back again and
+//! again: run some tests against the learner's code, compare two implementations with a
+//! benchmark, make sure nothing leaked, look for (or rule out) a pattern in the source. Each of
+//! those is a [`Grader`], and graders compose with [`AllOf`]:
+//! ```
+//! use learning_cell::grading::{AllOf, Grader, SourcePatternGrader, UnitTestGrader};
+//!
+//! fn double(x: i32) -> i32 {
+//!     x * 2
+//! }
+//!
+//! let tests = UnitTestGrader::new()
+//!     .test("doubles", |f: &fn(i32) -> i32| assert_eq!(f(21), 42))
+//!     .test("negative", |f: &fn(i32) -> i32| assert_eq!(f(-1), -2));
+//! let report = tests.grade(&(double as fn(i32) -> i32));
+//! assert!(report.passed());
+//!
+//! let source = SourcePatternGrader::new("style").forbid("unsafe", "no `unsafe` needed here");
+//! let grader = AllOf(vec![Box::new(source)]);
+//! assert!(grader.grade(&"fn double(x: i32) -> i32 { x * 2 }").passed());
+//! assert!(!grader.grade(&"unsafe { x * 2 }").passed());
+//! ```
+//!
+//! The submission is passed as `&dyn Any`, so every grader decides what it can grade. A grader
+//! handed a submission of the wrong type fails with a check saying so, instead of panicking.
+
+use std::any::{type_name, Any};
+use std::fmt;
+
+use crate::leakcheck::LeakTracker;
+use crate::util::{bench, catch_panic};
+
+/// Something that can grade a submission.
+pub trait Grader {
+    /// Grades `submission`, which should be of the type the grader expects.
+    fn grade(&self, submission: &dyn Any) -> GradeReport;
+}
+
+/// The outcome of grading: one entry per check that was run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GradeReport {
+    /// The checks, in the order they ran.
+    pub checks: Vec<Check>,
+}
+
+/// A single check of a [`GradeReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// What was checked.
+    pub name: String,
+    /// `Err` explains why the check failed.
+    pub outcome: Result<(), String>,
+    /// Extra output worth showing even when the check passed, like benchmark timings.
+    pub notes: Vec<String>,
+}
+
+impl Check {
+    /// A check that passed.
+    pub fn pass(name: impl Into<String>) -> Self {
+        Self { name: name.into(), outcome: Ok(()), notes: Vec::new() }
+    }
+
+    /// A check that failed with `message`.
+    pub fn fail(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { name: name.into(), outcome: Err(message.into()), notes: Vec::new() }
+    }
+
+    /// Adds a note to the check.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+impl GradeReport {
+    /// A report with a single check.
+    pub fn single(check: Check) -> Self {
+        Self { checks: vec![check] }
+    }
+
+    /// Whether every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.outcome.is_ok())
+    }
+
+    /// The checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &Check> {
+        self.checks.iter().filter(|check| check.outcome.is_err())
+    }
+
+    /// Appends the checks of `other`.
+    pub fn merge(&mut self, other: GradeReport) {
+        self.checks.extend(other.checks);
+    }
+
+    /// For use inside a hidden test: forwards every note to the exercise report (see
+    /// [`exercises::note`](crate::exercises::note)) and panics if a check failed.
+    #[track_caller]
+    pub fn assert_passed(&self) {
+        for check in &self.checks {
+            check.notes.iter().for_each(|note| crate::exercises::note(note.as_str()));
+        }
+        let failures: Vec<_> = self
+            .failures()
+            .map(|check| format!("{}: {}", check.name, check.outcome.as_ref().unwrap_err()))
+            .collect();
+        if !failures.is_empty() {
+            panic!("{}", failures.join("; "));
+        }
+    }
+}
+
+impl fmt::Display for GradeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            match &check.outcome {
+                Ok(()) => writeln!(f, "ok     {}", check.name)?,
+                Err(message) => writeln!(f, "FAILED {}: {message}", check.name)?,
+            }
+            for note in &check.notes {
+                writeln!(f, "       {note}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Downcasts `submission`, or explains which type `grader` expected.
+fn expect<'s, T: 'static>(submission: &'s dyn Any, grader: &str) -> Result<&'s T, GradeReport> {
+    submission.downcast_ref::<T>().ok_or_else(|| {
+        GradeReport::single(Check::fail(grader, format!("expected a `{}`", type_name::<T>())))
+    })
+}
+
+/// The check function of a grader over submissions of type `T`.
+type CheckFn<T> = fn(&T);
+
+/// Runs named test functions against a submission of type `T`. A test fails by panicking.
+pub struct UnitTestGrader<T> {
+    tests: Vec<(&'static str, CheckFn<T>)>,
+}
+
+impl<T: 'static> UnitTestGrader<T> {
+    /// A grader without tests.
+    pub fn new() -> Self {
+        Self { tests: Vec::new() }
+    }
+
+    /// Adds a test.
+    pub fn test(mut self, name: &'static str, test: fn(&T)) -> Self {
+        self.tests.push((name, test));
+        self
+    }
+}
+
+impl<T: 'static> Default for UnitTestGrader<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> Grader for UnitTestGrader<T> {
+    fn grade(&self, submission: &dyn Any) -> GradeReport {
+        let submission = match expect::<T>(submission, "unit tests") {
+            Ok(submission) => submission,
+            Err(report) => return report,
+        };
+        let checks = self
+            .tests
+            .iter()
+            .map(|(name, test)| Check {
+                name: name.to_string(),
+                outcome: catch_panic(|| test(submission)),
+                notes: Vec::new(),
+            })
+            .collect();
+        GradeReport { checks }
+    }
+}
+
+/// Times a contender against a baseline, both run on the submission, and requires the contender
+/// to be a given factor faster. Uses [`util::bench`](crate::util::bench).
+pub struct BenchmarkGrader<T> {
+    name: &'static str,
+    contender: CheckFn<T>,
+    baseline: CheckFn<T>,
+    iterations: u32,
+    min_speedup: f64,
+}
+
+impl<T: 'static> BenchmarkGrader<T> {
+    /// Compares `contender` with `baseline`, by default requiring it to be at least as fast over
+    /// ten iterations.
+    pub fn new(name: &'static str, contender: fn(&T), baseline: fn(&T)) -> Self {
+        Self { name, contender, baseline, iterations: 10, min_speedup: 1.0 }
+    }
+
+    /// Runs each side `iterations` times.
+    pub fn iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Requires the contender to be at least `factor` times as fast as the baseline.
+    pub fn min_speedup(mut self, factor: f64) -> Self {
+        self.min_speedup = factor;
+        self
+    }
+}
+
+impl<T: 'static> Grader for BenchmarkGrader<T> {
+    fn grade(&self, submission: &dyn Any) -> GradeReport {
+        let submission = match expect::<T>(submission, self.name) {
+            Ok(submission) => submission,
+            Err(report) => return report,
+        };
+        let timings = catch_panic(|| {
+            let baseline = bench(self.iterations, || (self.baseline)(submission));
+            let contender = bench(self.iterations, || (self.contender)(submission));
+            (baseline, contender)
+        });
+        let (baseline, contender) = match timings {
+            Ok(timings) => timings,
+            Err(message) => return GradeReport::single(Check::fail(self.name, message)),
+        };
+
+        let speedup = contender.speedup_over(&baseline);
+        let check = if speedup >= self.min_speedup {
+            Check::pass(self.name)
+        } else {
+            Check::fail(
+                self.name,
+                format!("{speedup:.1}x as fast as the baseline, expected {}x", self.min_speedup),
+            )
+        };
+        GradeReport::single(
+            check
+                .with_note(format!("baseline:  {baseline}"))
+                .with_note(format!("contender: {contender}"))
+                .with_note(format!("speedup:   {speedup:.1}x")),
+        )
+    }
+}
+
+/// Runs a scenario with a fresh [`LeakTracker`] and fails if any tracked value is still alive
+/// afterwards.
+pub struct LeakCheckGrader<T> {
+    name: &'static str,
+    scenario: fn(&T, &LeakTracker),
+}
+
+impl<T: 'static> LeakCheckGrader<T> {
+    /// Checks that `scenario` drops everything it tracks.
+    pub fn new(name: &'static str, scenario: fn(&T, &LeakTracker)) -> Self {
+        Self { name, scenario }
+    }
+}
+
+impl<T: 'static> Grader for LeakCheckGrader<T> {
+    fn grade(&self, submission: &dyn Any) -> GradeReport {
+        let submission = match expect::<T>(submission, self.name) {
+            Ok(submission) => submission,
+            Err(report) => return report,
+        };
+        let tracker = LeakTracker::new();
+        let outcome = catch_panic(|| (self.scenario)(submission, &tracker))
+            .and_then(|()| tracker.check().map_err(|leak| leak.to_string()));
+        GradeReport::single(Check { name: self.name.to_string(), outcome, notes: Vec::new() })
+    }
+}
+
+/// Checks source code (a `&'static str` or a `String`) for required and forbidden patterns.
+#[derive(Debug, Clone)]
+pub struct SourcePatternGrader {
+    name: &'static str,
+    required: Vec<(&'static str, &'static str)>,
+    forbidden: Vec<(&'static str, &'static str)>,
+}
+
+impl SourcePatternGrader {
+    /// A grader without patterns.
+    pub fn new(name: &'static str) -> Self {
+        Self { name, required: Vec::new(), forbidden: Vec::new() }
+    }
+
+    /// Requires `pattern` to appear; `reason` is the failure message.
+    pub fn require(mut self, pattern: &'static str, reason: &'static str) -> Self {
+        self.required.push((pattern, reason));
+        self
+    }
+
+    /// Requires `pattern` not to appear; `reason` is the failure message.
+    pub fn forbid(mut self, pattern: &'static str, reason: &'static str) -> Self {
+        self.forbidden.push((pattern, reason));
+        self
+    }
+}
+
+impl Grader for SourcePatternGrader {
+    fn grade(&self, submission: &dyn Any) -> GradeReport {
+        let source = match submission.downcast_ref::<&'static str>() {
+            Some(source) => *source,
+            None => match expect::<String>(submission, self.name) {
+                Ok(source) => source.as_str(),
+                Err(report) => return report,
+            },
+        };
+        let problems: Vec<_> = self
+            .required
+            .iter()
+            .filter(|(pattern, _)| !source.contains(pattern))
+            .chain(self.forbidden.iter().filter(|(pattern, _)| source.contains(pattern)))
+            .map(|(_, reason)| *reason)
+            .collect();
+        if problems.is_empty() {
+            GradeReport::single(Check::pass(self.name))
+        } else {
+            GradeReport::single(Check::fail(self.name, problems.join("; ")))
+        }
+    }
+}
+
+/// Runs several graders on the same submission and merges their reports.
+pub struct AllOf(pub Vec<Box<dyn Grader>>);
+
+impl Grader for AllOf {
+    fn grade(&self, submission: &dyn Any) -> GradeReport {
+        let mut report = GradeReport::default();
+        for grader in &self.0 {
+            report.merge(grader.grade(submission));
+        }
+        report
+    }
+}
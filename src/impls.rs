@@ -0,0 +1,646 @@
+//! Cell types built on top of the standard library ones.
+//!
+//! These wrappers do not add any new capability to `Cell` or `RefCell`. Instead, they make the
+//! rules we discussed in the lessons visible: they catch mistakes earlier, report them with
+//! better messages, or let us count what is happening inside.
+
+use std::cell::{self, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+use std::sync::{LockResult, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use crate::diagnostics::{self, Action, BorrowKind, BorrowState};
+
+thread_local! {
+    /// Live shared guards of every [`TracedRefCell`] on this thread, keyed by the id of the cell.
+    /// `Ref`s cannot leave their thread, so this counts all of them.
+    static SHARED_BORROWS: RefCell<HashMap<u64, usize>> = RefCell::new(HashMap::new());
+
+    /// Outstanding mutable borrows of every [`NonReentrantRefCell`] on this thread, keyed by the
+    /// address of the cell, with where each was taken. A cell has at most one.
+    static MUT_BORROWS: RefCell<HashMap<usize, &'static Location<'static>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// A `RefCell` that recognizes re-entrant mutable borrows.
+///
+/// The most common way to hit `already borrowed: BorrowMutError` is not two borrows sitting next
+/// to each other, but a callback. We hold a `borrow_mut` guard, call some user-provided closure
+/// and that closure (directly or through a few layers) calls `borrow_mut` on the same cell again.
+/// Both borrows come from the same thread, one stack frame inside the other.
+///
+/// `NonReentrantRefCell` keeps a thread-local map of the mutable borrows it has handed out. When
+/// `borrow_mut` is called on a cell that already appears in the map, it knows this is a
+/// re-entrant borrow and reports a [`ReentrantBorrow`] pointing at the original borrow instead of
+/// the generic std panic.
+///
+/// A guard leaked with [`mem::forget`](std::mem::forget) keeps the cell mutably borrowed for
+/// good, as a leaked `RefMut` would, and every later `borrow_mut` reports it as re-entrant.
+/// ```
+/// use learning_cell::impls::{NonReentrantError, NonReentrantRefCell};
+///
+/// let listeners = NonReentrantRefCell::new(vec![1, 2, 3]);
+///
+/// let notify = |callback: &dyn Fn()| {
+///     let _guard = listeners.borrow_mut();
+///     callback();
+/// };
+///
+/// notify(&|| {
+///     // The callback tries to mutate the list that is being notified.
+///     let err = listeners.try_borrow_mut().unwrap_err();
+///     assert!(matches!(err, NonReentrantError::Reentrant(_)));
+/// });
+///
+/// // Once the outer guard is gone, the cell can be borrowed again.
+/// listeners.borrow_mut().push(4);
+/// ```
+pub struct NonReentrantRefCell<T> {
+    inner: RefCell<T>,
+}
+
+impl<T> NonReentrantRefCell<T> {
+    /// Creates a new `NonReentrantRefCell` containing `value`.
+    pub fn new(value: T) -> Self {
+        Self { inner: RefCell::new(value) }
+    }
+
+    /// Consumes the cell, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    /// Immutably borrows the wrapped value, exactly like [`RefCell::borrow`].
+    #[track_caller]
+    pub fn borrow(&self) -> cell::Ref<'_, T> {
+        self.inner.borrow()
+    }
+
+    /// Immutably borrows the wrapped value, exactly like [`RefCell::try_borrow`].
+    pub fn try_borrow(&self) -> Result<cell::Ref<'_, T>, cell::BorrowError> {
+        self.inner.try_borrow()
+    }
+
+    /// Mutably borrows the wrapped value.
+    ///
+    /// Panics with a [`ReentrantBorrow`] explanation if this thread already holds a mutable
+    /// borrow of the cell, or with the usual std message if there are shared borrows.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> NonReentrantRefMut<'_, T> {
+        match self.try_borrow_mut() {
+            Ok(guard) => guard,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Mutably borrows the wrapped value, returning an error if it is already borrowed.
+    #[track_caller]
+    pub fn try_borrow_mut(&self) -> Result<NonReentrantRefMut<'_, T>, NonReentrantError> {
+        let key = self.key();
+        let location = Location::caller();
+
+        // While the thread is being torn down the map may be gone already; then the cell falls
+        // back to the plain `RefCell` check. An entry while the cell is not mutably borrowed was
+        // left by a forgotten guard of a cell that lived at this address before.
+        let first = MUT_BORROWS.try_with(|borrows| borrows.borrow().get(&key).copied());
+        let first = first.ok().flatten().filter(|_| self.inner.try_borrow().is_err());
+        if let Some(first) = first {
+            return Err(NonReentrantError::Reentrant(ReentrantBorrow { first, location }));
+        }
+
+        let guard = self.inner.try_borrow_mut().map_err(|_| NonReentrantError::AlreadyBorrowed)?;
+        let _ = MUT_BORROWS.try_with(|borrows| borrows.borrow_mut().insert(key, location));
+
+        Ok(NonReentrantRefMut { guard, key })
+    }
+
+    fn key(&self) -> usize {
+        self as *const Self as usize
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for NonReentrantRefCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NonReentrantRefCell").field("value", &self.inner).finish()
+    }
+}
+
+/// A mutable borrow handed out by [`NonReentrantRefCell::borrow_mut`].
+pub struct NonReentrantRefMut<'b, T> {
+    guard: cell::RefMut<'b, T>,
+    key: usize,
+}
+
+impl<T> Deref for NonReentrantRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for NonReentrantRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for NonReentrantRefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.guard.fmt(f)
+    }
+}
+
+impl<T> Drop for NonReentrantRefMut<'_, T> {
+    fn drop(&mut self) {
+        // The guard may be dropped by another thread-local's destructor, after the map is gone.
+        let _ = MUT_BORROWS.try_with(|borrows| borrows.borrow_mut().remove(&self.key));
+    }
+}
+
+/// Why [`NonReentrantRefCell::try_borrow_mut`] failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonReentrantError {
+    /// This thread already holds a mutable borrow of the cell further up the stack.
+    Reentrant(ReentrantBorrow),
+    /// The cell is currently borrowed immutably.
+    AlreadyBorrowed,
+}
+
+impl fmt::Display for NonReentrantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reentrant(err) => err.fmt(f),
+            Self::AlreadyBorrowed => f.write_str("already borrowed: BorrowMutError"),
+        }
+    }
+}
+
+impl std::error::Error for NonReentrantError {}
+
+/// A mutable borrow was requested while an outer stack frame was still holding one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReentrantBorrow {
+    /// Where the outstanding mutable borrow was taken.
+    pub first: &'static Location<'static>,
+    /// Where the re-entrant borrow was attempted.
+    pub location: &'static Location<'static>,
+}
+
+impl fmt::Display for ReentrantBorrow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "re-entrant borrow_mut at {} while the mutable borrow taken at {} is still alive; \
+             this usually means a callback is calling back into the cell that is invoking it, \
+             drop the outer guard before running the callback",
+            self.location, self.first
+        )
+    }
+}
+
+impl std::error::Error for ReentrantBorrow {}
+
+/// A `RefCell` that reports every borrow to the [`diagnostics`] module.
+///
+/// Behaves exactly like [`RefCell`], except that each `borrow`, `borrow_mut` and guard drop is
+/// emitted as a [`BorrowEvent`](crate::diagnostics::BorrowEvent) carrying the cell's id and
+/// optional label.
+pub struct TracedRefCell<T> {
+    inner: RefCell<T>,
+    id: u64,
+    label: Option<&'static str>,
+}
+
+impl<T> TracedRefCell<T> {
+    /// Creates a new unlabeled `TracedRefCell` containing `value`.
+    pub fn new(value: T) -> Self {
+        Self { inner: RefCell::new(value), id: diagnostics::next_id(), label: None }
+    }
+
+    /// Creates a new `TracedRefCell` whose events carry `label`.
+    pub fn with_label(value: T, label: &'static str) -> Self {
+        Self { label: Some(label), ..Self::new(value) }
+    }
+
+    /// The id this cell reports in its events.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The label this cell reports in its events.
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
+    }
+
+    /// Consumes the cell, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    /// Who holds the value right now. Looking does not borrow the cell or emit any event.
+    pub fn borrow_state(&self) -> BorrowState {
+        let shared = SHARED_BORROWS
+            .try_with(|borrows| borrows.borrow().get(&self.id).copied())
+            .ok()
+            .flatten();
+        match shared {
+            Some(count) => BorrowState::Shared(count),
+            None if self.inner.try_borrow_mut().is_err() => BorrowState::Exclusive,
+            None => BorrowState::Unused,
+        }
+    }
+
+    /// Immutably borrows the wrapped value, panicking if it is mutably borrowed.
+    #[track_caller]
+    pub fn borrow(&self) -> Traced<cell::Ref<'_, T>> {
+        match self.try_borrow() {
+            Ok(guard) => guard,
+            Err(err) => panic!("already mutably borrowed: {err:?}"),
+        }
+    }
+
+    /// Immutably borrows the wrapped value, returning an error if it is mutably borrowed.
+    pub fn try_borrow(&self) -> Result<Traced<cell::Ref<'_, T>>, cell::BorrowError> {
+        let guard = self.inner.try_borrow();
+        let guard = self.traced(guard, BorrowKind::Shared)?;
+        let _ = SHARED_BORROWS
+            .try_with(|borrows| *borrows.borrow_mut().entry(self.id).or_default() += 1);
+        Ok(guard)
+    }
+
+    /// Mutably borrows the wrapped value, panicking if it is already borrowed.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> Traced<cell::RefMut<'_, T>> {
+        match self.try_borrow_mut() {
+            Ok(guard) => guard,
+            Err(err) => panic!("already borrowed: {err:?}"),
+        }
+    }
+
+    /// Mutably borrows the wrapped value, returning an error if it is already borrowed.
+    pub fn try_borrow_mut(&self) -> Result<Traced<cell::RefMut<'_, T>>, cell::BorrowMutError> {
+        let guard = self.inner.try_borrow_mut();
+        self.traced(guard, BorrowKind::Exclusive)
+    }
+
+    fn traced<G, E>(&self, guard: Result<G, E>, kind: BorrowKind) -> Result<Traced<G>, E> {
+        match guard {
+            Ok(guard) => Ok(Traced::acquire(guard, self.id, self.label, kind)),
+            Err(err) => {
+                diagnostics::emit(self.id, self.label, kind, Action::Conflict);
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TracedRefCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TracedRefCell")
+            .field("id", &self.id)
+            .field("label", &self.label)
+            .field("value", &self.inner)
+            .finish()
+    }
+}
+
+/// A `Mutex` that reports every lock to the [`diagnostics`] module.
+#[derive(Debug)]
+pub struct TracedMutex<T> {
+    inner: Mutex<T>,
+    id: u64,
+    label: Option<&'static str>,
+}
+
+impl<T> TracedMutex<T> {
+    /// Creates a new unlabeled `TracedMutex` containing `value`.
+    pub fn new(value: T) -> Self {
+        Self { inner: Mutex::new(value), id: diagnostics::next_id(), label: None }
+    }
+
+    /// Creates a new `TracedMutex` whose events carry `label`.
+    pub fn with_label(value: T, label: &'static str) -> Self {
+        Self { label: Some(label), ..Self::new(value) }
+    }
+
+    /// The id this lock reports in its events.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Acquires the lock, blocking until it is available. See [`Mutex::lock`].
+    pub fn lock(&self) -> LockResult<Traced<MutexGuard<'_, T>>> {
+        let (id, label) = (self.id, self.label);
+        match self.inner.lock() {
+            Ok(guard) => Ok(Traced::acquire(guard, id, label, BorrowKind::Exclusive)),
+            Err(err) => Err(PoisonError::new(Traced::acquire(
+                err.into_inner(),
+                id,
+                label,
+                BorrowKind::Exclusive,
+            ))),
+        }
+    }
+
+    /// Consumes the lock, returning the wrapped value.
+    pub fn into_inner(self) -> LockResult<T> {
+        self.inner.into_inner()
+    }
+}
+
+/// A guard returned by the traced types. Emits a release event when dropped.
+pub struct Traced<G> {
+    guard: G,
+    id: u64,
+    label: Option<&'static str>,
+    kind: BorrowKind,
+    acquired: Instant,
+    /// Open for as long as the guard lives.
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl<G> Traced<G> {
+    fn acquire(guard: G, id: u64, label: Option<&'static str>, kind: BorrowKind) -> Self {
+        let mut traced = Self {
+            guard,
+            id,
+            label,
+            kind,
+            acquired: diagnostics::now(),
+            #[cfg(feature = "tracing")]
+            span: diagnostics::span(id, label, kind),
+        };
+        traced.in_span(|| diagnostics::emit(id, label, kind, Action::Acquire));
+        traced.acquired = diagnostics::now();
+        traced
+    }
+
+    /// Runs `f` in the borrow's `tracing` span, when the feature is enabled.
+    fn in_span(&self, f: impl FnOnce()) {
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.enter();
+        f();
+    }
+}
+
+impl<G: Deref> Deref for Traced<G> {
+    type Target = G::Target;
+
+    fn deref(&self) -> &G::Target {
+        &self.guard
+    }
+}
+
+impl<G: DerefMut> DerefMut for Traced<G> {
+    fn deref_mut(&mut self) -> &mut G::Target {
+        &mut self.guard
+    }
+}
+
+impl<G: fmt::Debug> fmt::Debug for Traced<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.guard.fmt(f)
+    }
+}
+
+impl<G> Drop for Traced<G> {
+    fn drop(&mut self) {
+        // Only `TracedRefCell` hands out shared guards.
+        if self.kind == BorrowKind::Shared {
+            let _ = SHARED_BORROWS.try_with(|borrows| {
+                let mut borrows = borrows.borrow_mut();
+                if let Some(count) = borrows.get_mut(&self.id) {
+                    *count -= 1;
+                    if *count == 0 {
+                        borrows.remove(&self.id);
+                    }
+                }
+            });
+        }
+        let held = diagnostics::now().saturating_duration_since(self.acquired);
+        self.in_span(|| {
+            diagnostics::emit(self.id, self.label, self.kind, Action::Release { held })
+        });
+    }
+}
+
+/// A `RefCell` that counts how often it is borrowed.
+///
+/// Handy for asserting *how* some code used a cell, not just what it computed. For example, a
+/// cache that is hit should only need a shared borrow, while a miss needs a mutable one.
+/// ```
+/// use learning_cell::impls::CountingCell;
+///
+/// let cell = CountingCell::new(vec![1, 2]);
+/// cell.borrow_mut().push(3);
+/// assert_eq!(cell.borrow().len(), 3);
+/// assert_eq!(cell.borrow()[0], 1);
+///
+/// let counts = cell.counts();
+/// assert_eq!((counts.shared, counts.exclusive), (2, 1));
+/// ```
+///
+/// The counters themselves are `Cell<usize>`s, so counting works through `&self`.
+#[derive(Debug, Default)]
+pub struct CountingCell<T> {
+    inner: RefCell<T>,
+    shared: std::cell::Cell<usize>,
+    exclusive: std::cell::Cell<usize>,
+}
+
+/// How many borrows a [`CountingCell`] has handed out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BorrowCounts {
+    /// Successful `borrow`s.
+    pub shared: usize,
+    /// Successful `borrow_mut`s.
+    pub exclusive: usize,
+}
+
+impl<T> CountingCell<T> {
+    /// Creates a new `CountingCell` containing `value`, with both counters at zero.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: RefCell::new(value),
+            shared: std::cell::Cell::new(0),
+            exclusive: std::cell::Cell::new(0),
+        }
+    }
+
+    /// Consumes the cell, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    /// The number of borrows handed out so far.
+    pub fn counts(&self) -> BorrowCounts {
+        BorrowCounts { shared: self.shared.get(), exclusive: self.exclusive.get() }
+    }
+
+    /// Sets both counters back to zero.
+    pub fn reset_counts(&self) {
+        self.shared.set(0);
+        self.exclusive.set(0);
+    }
+
+    /// Immutably borrows the wrapped value, like [`RefCell::borrow`].
+    #[track_caller]
+    pub fn borrow(&self) -> cell::Ref<'_, T> {
+        let guard = self.inner.borrow();
+        self.shared.set(self.shared.get() + 1);
+        guard
+    }
+
+    /// Immutably borrows the wrapped value, like [`RefCell::try_borrow`].
+    pub fn try_borrow(&self) -> Result<cell::Ref<'_, T>, cell::BorrowError> {
+        let guard = self.inner.try_borrow()?;
+        self.shared.set(self.shared.get() + 1);
+        Ok(guard)
+    }
+
+    /// Mutably borrows the wrapped value, like [`RefCell::borrow_mut`].
+    #[track_caller]
+    pub fn borrow_mut(&self) -> cell::RefMut<'_, T> {
+        let guard = self.inner.borrow_mut();
+        self.exclusive.set(self.exclusive.get() + 1);
+        guard
+    }
+
+    /// Mutably borrows the wrapped value, like [`RefCell::try_borrow_mut`].
+    pub fn try_borrow_mut(&self) -> Result<cell::RefMut<'_, T>, cell::BorrowMutError> {
+        let guard = self.inner.try_borrow_mut()?;
+        self.exclusive.set(self.exclusive.get() + 1);
+        Ok(guard)
+    }
+}
+
+/// A cell that remembers its previous values.
+///
+/// Every [`set`](HistoryCell::set) pushes the old value onto an undo stack, so the change can be
+/// taken back with [`undo`](HistoryCell::undo) and re-applied with [`redo`](HistoryCell::redo).
+/// Like any other write, a new `set` after an `undo` discards the redo stack.
+/// ```
+/// use learning_cell::impls::HistoryCell;
+///
+/// let title = HistoryCell::new(String::from("draft"));
+/// title.set(String::from("final"));
+/// title.update(|t| t.push('!'));
+/// assert_eq!(title.get(), "final!");
+///
+/// assert!(title.undo());
+/// assert!(title.undo());
+/// assert_eq!(title.get(), "draft");
+/// assert!(!title.undo());
+///
+/// assert!(title.redo());
+/// assert_eq!(title.get(), "final");
+/// ```
+///
+/// The value and both stacks live in a single `RefCell`, but no borrow of it ever escapes a
+/// method. In particular, [`update`](HistoryCell::update) runs its closure on a copy, so the
+/// closure may freely read the cell it is updating.
+pub struct HistoryCell<T> {
+    state: RefCell<History<T>>,
+}
+
+struct History<T> {
+    current: T,
+    undo: Vec<T>,
+    redo: Vec<T>,
+}
+
+impl<T> HistoryCell<T> {
+    /// Creates a new `HistoryCell` containing `value`, with an empty history.
+    pub fn new(value: T) -> Self {
+        Self { state: RefCell::new(History { current: value, undo: Vec::new(), redo: Vec::new() }) }
+    }
+
+    /// Replaces the value, recording the old one so it can be undone.
+    pub fn set(&self, value: T) {
+        let mut state = self.state.borrow_mut();
+        let old = std::mem::replace(&mut state.current, value);
+        state.undo.push(old);
+        state.redo.clear();
+    }
+
+    /// Calls `f` with a reference to the current value.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.state.borrow().current)
+    }
+
+    /// Restores the previous value. Returns `false` if there was nothing to undo.
+    pub fn undo(&self) -> bool {
+        let mut state = self.state.borrow_mut();
+        let Some(previous) = state.undo.pop() else { return false };
+        let current = std::mem::replace(&mut state.current, previous);
+        state.redo.push(current);
+        true
+    }
+
+    /// Re-applies the last undone value. Returns `false` if there was nothing to redo.
+    pub fn redo(&self) -> bool {
+        let mut state = self.state.borrow_mut();
+        let Some(next) = state.redo.pop() else { return false };
+        let current = std::mem::replace(&mut state.current, next);
+        state.undo.push(current);
+        true
+    }
+
+    /// How many changes [`undo`](HistoryCell::undo) can take back.
+    pub fn undo_depth(&self) -> usize {
+        self.state.borrow().undo.len()
+    }
+
+    /// How many changes [`redo`](HistoryCell::redo) can re-apply.
+    pub fn redo_depth(&self) -> usize {
+        self.state.borrow().redo.len()
+    }
+
+    /// Forgets the history, keeping only the current value.
+    pub fn clear_history(&self) {
+        let mut state = self.state.borrow_mut();
+        state.undo.clear();
+        state.redo.clear();
+    }
+
+    /// Consumes the cell, returning the current value.
+    pub fn into_inner(self) -> T {
+        self.state.into_inner().current
+    }
+}
+
+impl<T: Clone> HistoryCell<T> {
+    /// Returns a copy of the current value.
+    pub fn get(&self) -> T {
+        self.state.borrow().current.clone()
+    }
+
+    /// Modifies a copy of the current value with `f` and stores the result as a new change.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut value = self.get();
+        f(&mut value);
+        self.set(value);
+    }
+}
+
+impl<T: Default> Default for HistoryCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for HistoryCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.borrow();
+        f.debug_struct("HistoryCell")
+            .field("value", &state.current)
+            .field("undo_depth", &state.undo.len())
+            .field("redo_depth", &state.redo.len())
+            .finish()
+    }
+}
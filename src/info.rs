@@ -0,0 +1,147 @@
+//! What the cell types cost in memory.
+//!
+//! Interior mutability is not free: a `RefCell` stores its borrow flag next to the value, a
+//! `Mutex` its lock, and `Rc` and `Arc` put the value on the heap behind a pointer. The
+//! [`size_report`] measures all of them with `size_of` and `align_of` on the machine the crate
+//! was built for, so the numbers are real rather than quoted from a book.
+//! ```
+//! use learning_cell::info::{self, Wrapper};
+//!
+//! let report = info::size_report();
+//! let row = report.iter().find(|row| row.inner == "u8").unwrap();
+//! assert_eq!(row.layout(Wrapper::Cell).size, 1);
+//! assert!(row.overhead(Wrapper::RefCell) >= std::mem::size_of::<isize>());
+//! assert_eq!(row.layout(Wrapper::Rc).size, std::mem::size_of::<usize>());
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::mem::{align_of, size_of};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A type wrapped around the measured value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Wrapper {
+    /// The value itself, `T`.
+    Plain,
+    /// `Cell<T>`.
+    Cell,
+    /// `RefCell<T>`.
+    RefCell,
+    /// `Mutex<T>`.
+    Mutex,
+    /// `RwLock<T>`.
+    RwLock,
+    /// `Rc<T>`. Only the pointer: the counts and the value live in the allocation.
+    Rc,
+    /// `Arc<T>`. Only the pointer, as for `Rc`.
+    Arc,
+}
+
+impl Wrapper {
+    /// Every wrapper, in the order of the report's columns.
+    pub const ALL: [Wrapper; 7] = [
+        Wrapper::Plain,
+        Wrapper::Cell,
+        Wrapper::RefCell,
+        Wrapper::Mutex,
+        Wrapper::RwLock,
+        Wrapper::Rc,
+        Wrapper::Arc,
+    ];
+
+    /// Looks up a wrapper by the name of its type, ignoring case, e.g. `refcell`.
+    pub fn lookup(name: &str) -> Option<Wrapper> {
+        let name = name.to_lowercase();
+        Wrapper::ALL.into_iter().find(|wrapper| {
+            let display = wrapper.to_string().to_lowercase();
+            display.strip_suffix("<t>").is_some_and(|stripped| stripped == name)
+        })
+    }
+}
+
+impl fmt::Display for Wrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Wrapper::Plain => "T",
+            Wrapper::Cell => "Cell<T>",
+            Wrapper::RefCell => "RefCell<T>",
+            Wrapper::Mutex => "Mutex<T>",
+            Wrapper::RwLock => "RwLock<T>",
+            Wrapper::Rc => "Rc<T>",
+            Wrapper::Arc => "Arc<T>",
+        })
+    }
+}
+
+/// The size and alignment of a type, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    /// `size_of` the type.
+    pub size: usize,
+    /// `align_of` the type.
+    pub align: usize,
+}
+
+impl Layout {
+    /// The layout of `T`.
+    pub fn of<T>() -> Layout {
+        Layout { size: size_of::<T>(), align: align_of::<T>() }
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} / {}", self.size, self.align)
+    }
+}
+
+/// The layouts of one `T` inside every [`Wrapper`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeRow {
+    /// The name of `T`, e.g. `String`.
+    pub inner: &'static str,
+    /// The layout of each wrapper around `T`, in the order of [`Wrapper::ALL`].
+    pub layouts: [(Wrapper, Layout); 7],
+}
+
+impl SizeRow {
+    /// Measures `T`, calling it `inner`.
+    pub fn of<T>(inner: &'static str) -> SizeRow {
+        SizeRow {
+            inner,
+            layouts: [
+                (Wrapper::Plain, Layout::of::<T>()),
+                (Wrapper::Cell, Layout::of::<Cell<T>>()),
+                (Wrapper::RefCell, Layout::of::<RefCell<T>>()),
+                (Wrapper::Mutex, Layout::of::<Mutex<T>>()),
+                (Wrapper::RwLock, Layout::of::<RwLock<T>>()),
+                (Wrapper::Rc, Layout::of::<Rc<T>>()),
+                (Wrapper::Arc, Layout::of::<Arc<T>>()),
+            ],
+        }
+    }
+
+    /// The layout of `wrapper` around `T`.
+    pub fn layout(&self, wrapper: Wrapper) -> Layout {
+        self.layouts.iter().find(|(w, _)| *w == wrapper).map(|(_, layout)| *layout).unwrap()
+    }
+
+    /// How many bytes bigger `wrapper` around `T` is than `T` itself. Zero for `Rc` and `Arc`
+    /// whenever `T` is at least as big as a pointer, although their allocation holds more.
+    pub fn overhead(&self, wrapper: Wrapper) -> usize {
+        self.layout(wrapper).size.saturating_sub(self.layout(Wrapper::Plain).size)
+    }
+}
+
+/// The layouts of a few representative types in every [`Wrapper`]: a byte, an `i32` like the
+/// lessons' `special`, a `u64` and a `String` like their `special_nocopy`.
+pub fn size_report() -> Vec<SizeRow> {
+    vec![
+        SizeRow::of::<u8>("u8"),
+        SizeRow::of::<i32>("i32"),
+        SizeRow::of::<u64>("u64"),
+        SizeRow::of::<String>("String"),
+    ]
+}
@@ -0,0 +1,171 @@
+//! Detecting values that are never dropped.
+//!
+//! `Rc` cycles do not cause a crash or an error, the memory is just never freed. To make leaks
+//! visible, give every value you care about a [`Tracked`] token from a [`LeakTracker`]. The
+//! tracker counts the tokens that are still alive, so after everything should have been dropped,
+//! a non-zero count means something leaked.
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use learning_cell::leakcheck::{LeakTracker, Tracked};
+//!
+//! struct Node {
+//!     _token: Tracked,
+//!     next: RefCell<Option<Rc<Node>>>,
+//! }
+//!
+//! let tracker = LeakTracker::new();
+//! {
+//!     let a = Rc::new(Node { _token: tracker.track(), next: RefCell::new(None) });
+//!     let b = Rc::new(Node { _token: tracker.track(), next: RefCell::new(Some(a.clone())) });
+//!     // Close the loop: a -> b -> a
+//!     *a.next.borrow_mut() = Some(b.clone());
+//! }
+//!
+//! assert_eq!(tracker.created(), 2);
+//! assert_eq!(tracker.live(), 2); // Both nodes leaked!
+//! assert!(tracker.check().is_err());
+//! ```
+//!
+//! Tokens are `Send` and `Sync`, so the tracker works across threads as well.
+//!
+//! # Finding the cycle
+//!
+//! Knowing *that* something leaked is only half of the story. Tokens handed out with
+//! [`track_named`](LeakTracker::track_named) remember a name, and the [`Leak`] report lists the
+//! names of everything that is still alive. The values that should be gone but are not are the
+//! members of the cycle:
+//! ```
+//! # use std::cell::RefCell;
+//! # use std::rc::Rc;
+//! # use learning_cell::leakcheck::{LeakTracker, Tracked};
+//! # struct Node {
+//! #     _token: Tracked,
+//! #     next: RefCell<Option<Rc<Node>>>,
+//! # }
+//! let tracker = LeakTracker::new();
+//! {
+//!     let a = Rc::new(Node { _token: tracker.track_named("a"), next: RefCell::new(None) });
+//!     let b = Rc::new(Node { _token: tracker.track_named("b"), next: RefCell::new(None) });
+//!     let c = Rc::new(Node { _token: tracker.track_named("c"), next: RefCell::new(None) });
+//!     *a.next.borrow_mut() = Some(b.clone());
+//!     *b.next.borrow_mut() = Some(a.clone());
+//!     *c.next.borrow_mut() = Some(a.clone());
+//! }
+//!
+//! let leak = tracker.check().unwrap_err();
+//! assert_eq!(leak.names, ["a", "b"]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct Counters {
+    created: AtomicUsize,
+    live: AtomicUsize,
+    /// Names of the live named tokens, keyed by token id.
+    names: Mutex<BTreeMap<usize, String>>,
+}
+
+/// Hands out [`Tracked`] tokens and counts how many of them are still alive.
+#[derive(Debug, Clone, Default)]
+pub struct LeakTracker {
+    counters: Arc<Counters>,
+}
+
+impl LeakTracker {
+    /// Creates a tracker that has not handed out any tokens yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hands out a new token. The token is counted as live until it is dropped.
+    pub fn track(&self) -> Tracked {
+        let id = self.counters.created.fetch_add(1, Ordering::SeqCst);
+        self.counters.live.fetch_add(1, Ordering::SeqCst);
+        Tracked { counters: Arc::clone(&self.counters), id }
+    }
+
+    /// Like [`track`](LeakTracker::track), but the name shows up in the [`Leak`] report if the
+    /// token is never dropped.
+    pub fn track_named(&self, name: &str) -> Tracked {
+        let token = self.track();
+        self.names().insert(token.id, name.to_string());
+        token
+    }
+
+    /// Names of the named tokens that are still alive, in creation order.
+    pub fn live_names(&self) -> Vec<String> {
+        self.names().values().cloned().collect()
+    }
+
+    fn names(&self) -> std::sync::MutexGuard<'_, BTreeMap<usize, String>> {
+        self.counters.names.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Number of tokens handed out so far.
+    pub fn created(&self) -> usize {
+        self.counters.created.load(Ordering::SeqCst)
+    }
+
+    /// Number of tokens that have not been dropped yet.
+    pub fn live(&self) -> usize {
+        self.counters.live.load(Ordering::SeqCst)
+    }
+
+    /// Returns an error describing the leak if any token is still alive.
+    pub fn check(&self) -> Result<(), Leak> {
+        match self.live() {
+            0 => Ok(()),
+            live => Err(Leak { live, created: self.created(), names: self.live_names() }),
+        }
+    }
+
+    /// Panics if any token is still alive.
+    #[track_caller]
+    pub fn assert_no_leaks(&self) {
+        if let Err(leak) = self.check() {
+            panic!("{leak}");
+        }
+    }
+}
+
+/// A token that is counted by its [`LeakTracker`] until it is dropped.
+#[derive(Debug)]
+pub struct Tracked {
+    counters: Arc<Counters>,
+    id: usize,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.counters.live.fetch_sub(1, Ordering::SeqCst);
+        self.counters.names.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.id);
+    }
+}
+
+/// Some tracked values were never dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leak {
+    /// Tokens still alive.
+    pub live: usize,
+    /// Tokens handed out in total.
+    pub created: usize,
+    /// Names of the live tokens that were created with a name.
+    pub names: Vec<String>,
+}
+
+impl fmt::Display for Leak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} tracked values were never dropped", self.live, self.created)?;
+        if !self.names.is_empty() {
+            write!(f, " ({})", self.names.join(", "))?;
+        }
+        f.write_str("; look for an `Rc` cycle")
+    }
+}
+
+impl std::error::Error for Leak {}
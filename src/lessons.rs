@@ -0,0 +1,434 @@
+//! The lesson write-ups, as data.
+//!
+//! A built-in lesson is the doc comment of one of the crate's top-level modules
+//! ([`Cell`](crate::Cell), [`RefCell`](crate::RefCell), ...). The comment is the single source
+//! of truth: `cargo doc` renders it, `cargo test` checks its examples, and this module parses it
+//! into a [`Page`] of prose and code examples so tools can present the same lesson in a terminal.
+//! ```
+//! use learning_cell::curriculum::Chapter;
+//! use learning_cell::lessons::{self, ExampleKind};
+//!
+//! let page = lessons::page(Chapter::RefCell).unwrap();
+//! let panics = page.examples().filter(|example| example.kind == ExampleKind::ShouldPanic);
+//! assert!(panics.count() >= 1);
+//! ```
+//! The prose is in the current [`locale`](crate::locale::locale).
+//!
+//! Every lesson, built-in or from a [pack](crate::packs), implements [`Lesson`], and [`all`]
+//! lists them, so tools can go through the course without knowing where each lesson lives:
+//! ```
+//! use learning_cell::lessons;
+//!
+//! for lesson in lessons::all() {
+//!     let examples = lesson.page().examples().count();
+//!     println!("{}: {examples} examples, {} demos", lesson.title(), lesson.run().demos.len());
+//! }
+//! let nested = lessons::find("nested").unwrap();
+//! assert_eq!(nested.title(), "Nesting cells");
+//! assert!(nested.run().demos[0].steps.iter().any(|step| step.panicked.is_some()));
+//! ```
+
+use std::fmt;
+
+use crate::curriculum::{Chapter, Difficulty};
+use crate::demo::DemoOutcome;
+use crate::{locale, packs};
+
+/// The crate root, where the lesson modules and their doc comments live.
+const SOURCE: &str = include_str!("lib.rs");
+
+/// Identifies the main lesson of a chapter, which is named after it. Other lessons go by their
+/// [`Lesson::id`].
+pub type LessonId = Chapter;
+
+/// A lesson of the course: a write-up to read and demos replaying it.
+pub trait Lesson: fmt::Debug + Sync {
+    /// Unique, stable identifier, recorded in the progress file.
+    fn id(&self) -> &'static str;
+
+    /// What the lesson is about, for listings and recommendations.
+    fn meta(&self) -> LessonMeta;
+
+    /// Human readable title.
+    fn title(&self) -> &'static str {
+        self.meta().title
+    }
+
+    /// The chapter the lesson is listed under.
+    fn chapter(&self) -> Chapter;
+
+    /// How demanding the lesson is.
+    fn difficulty(&self) -> Difficulty {
+        self.meta().difficulty
+    }
+
+    /// Topic tags.
+    fn tags(&self) -> &'static [&'static str];
+
+    /// The lesson parsed into prose and examples.
+    fn page(&self) -> Page;
+
+    /// Runs the lesson's demos, if it has any.
+    fn run(&self) -> LessonReport {
+        LessonReport { lesson: self.id(), demos: Vec::new() }
+    }
+}
+
+/// Describes a [`Lesson`] without reading it.
+/// ```
+/// use learning_cell::curriculum::Difficulty;
+/// use learning_cell::{exercises, lessons};
+///
+/// let meta = lessons::find("nested").unwrap().meta();
+/// assert_eq!(meta.difficulty, Difficulty::Core);
+/// assert_eq!(meta.prerequisites, ["cell", "refcell"]);
+///
+/// for lesson in lessons::all() {
+///     let meta = lesson.meta();
+///     assert!(meta.prerequisites.iter().all(|id| lessons::find(id).is_some()));
+///     assert!(meta.exercises.iter().all(|id| exercises::get(id).is_some()));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LessonMeta {
+    /// Human readable title.
+    pub title: &'static str,
+    /// What the lesson teaches, in one sentence.
+    pub summary: &'static str,
+    /// How demanding the lesson is.
+    pub difficulty: Difficulty,
+    /// Ids of the lessons to read first.
+    pub prerequisites: &'static [&'static str],
+    /// About how long the lesson takes to read and try out, in minutes.
+    pub minutes: u32,
+    /// Ids of the exercises that practice what the lesson teaches.
+    pub exercises: &'static [&'static str],
+}
+
+/// What running a [`Lesson`] did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LessonReport {
+    /// Id of the lesson.
+    pub lesson: &'static str,
+    /// The outcome of each demo, in the order the lesson explains them.
+    pub demos: Vec<DemoOutcome>,
+}
+
+impl fmt::Display for LessonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (number, demo) in self.demos.iter().enumerate() {
+            if number > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{demo}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A lesson written as the doc comment of one of the crate's modules.
+#[derive(Debug)]
+struct ModuleLesson {
+    id: &'static str,
+    meta: LessonMeta,
+    chapter: Chapter,
+    tags: &'static [&'static str],
+    /// Name of the module in `lib.rs`.
+    module: &'static str,
+    demos: fn() -> Vec<DemoOutcome>,
+}
+
+impl Lesson for ModuleLesson {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn meta(&self) -> LessonMeta {
+        self.meta
+    }
+
+    fn chapter(&self) -> Chapter {
+        self.chapter
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        self.tags
+    }
+
+    /// The lesson, with its prose in the current locale.
+    fn page(&self) -> Page {
+        let comment = doc_comment(self.module).expect("lesson modules have a doc comment");
+        let blocks = parse(&comment).into_iter().map(|block| match block {
+            Block::Text(prose) => Block::Text(locale::translate_paragraphs(&prose)),
+            example => example,
+        });
+        Page { chapter: self.chapter, blocks: blocks.collect() }
+    }
+
+    fn run(&self) -> LessonReport {
+        LessonReport { lesson: self.id, demos: (self.demos)() }
+    }
+}
+
+/// The built-in lessons, in course order. A chapter's main lesson has the chapter's id.
+static BUILT_IN: [ModuleLesson; 3] = [
+    ModuleLesson {
+        id: "cell",
+        meta: LessonMeta {
+            title: "Cell",
+            summary: "Mutating a `Copy` value behind a shared reference by moving it in and out.",
+            difficulty: Difficulty::Intro,
+            prerequisites: &[],
+            minutes: 15,
+            exercises: &["cell_intro", "shared_counter"],
+        },
+        chapter: Chapter::Cell,
+        tags: &["cell", "copy"],
+        module: "Cell",
+        demos: || vec![crate::Cell::demonstrate_get_set(), crate::Cell::demonstrate_replace_swap()],
+    },
+    ModuleLesson {
+        id: "refcell",
+        meta: LessonMeta {
+            title: "RefCell",
+            summary:
+                "Borrowing the inside of a shared value, with the borrow rules checked at run \
+                      time.",
+            difficulty: Difficulty::Intro,
+            prerequisites: &["cell"],
+            minutes: 20,
+            exercises: &["fix_borrow_panic", "event_bus"],
+        },
+        chapter: Chapter::RefCell,
+        tags: &["refcell", "borrowing"],
+        module: "RefCell",
+        demos: || vec![crate::RefCell::demonstrate_conflicting_borrow()],
+    },
+    ModuleLesson {
+        id: "nested",
+        meta: LessonMeta {
+            title: "Nesting cells",
+            summary: "Cells inside cells: one borrow flag per level and what each level allows.",
+            difficulty: Difficulty::Core,
+            prerequisites: &["cell", "refcell"],
+            minutes: 15,
+            exercises: &["entity_components"],
+        },
+        chapter: Chapter::RefCell,
+        tags: &["cell", "refcell", "composition"],
+        module: "Nested",
+        demos: || {
+            vec![
+                crate::Nested::demonstrate_reach_through_refcell(),
+                crate::Nested::demonstrate_swap_out_of_cell(),
+            ]
+        },
+    },
+];
+
+/// A parsed lesson.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    /// The chapter the lesson introduces.
+    pub chapter: Chapter,
+    /// The lesson's content, in reading order.
+    pub blocks: Vec<Block>,
+}
+
+/// A piece of a lesson.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    /// Markdown prose.
+    Text(String),
+    /// A code example.
+    Example(Example),
+}
+
+/// A code example of a lesson.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// What the example does when it is built and run.
+    pub kind: ExampleKind,
+    /// The full source, including the setup lines hidden from readers (those starting with `#`).
+    pub source: String,
+}
+
+/// What happens to an example, as annotated on its code fence and checked by `cargo test --doc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExampleKind {
+    /// Compiles and runs to completion.
+    Runs,
+    /// Is rejected by the compiler (` ```compile_fail `).
+    CompileFail,
+    /// Compiles, then panics at run time (` ```should_panic `).
+    ShouldPanic,
+    /// Illustration only, never built (` ```ignore `, ` ```text `).
+    Ignored,
+}
+
+impl ExampleKind {
+    fn from_fence(info: &str) -> ExampleKind {
+        match info.trim() {
+            "" | "rust" => ExampleKind::Runs,
+            "compile_fail" => ExampleKind::CompileFail,
+            "should_panic" => ExampleKind::ShouldPanic,
+            _ => ExampleKind::Ignored,
+        }
+    }
+}
+
+impl fmt::Display for ExampleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExampleKind::Runs => "runs",
+            ExampleKind::CompileFail => "does not compile",
+            ExampleKind::ShouldPanic => "panics",
+            ExampleKind::Ignored => "illustration",
+        })
+    }
+}
+
+impl Example {
+    /// The source as shown to readers, without the hidden setup lines.
+    pub fn visible(&self) -> String {
+        let lines = self.source.lines().filter(|line| !is_hidden(line));
+        lines.map(|line| format!("{line}\n")).collect()
+    }
+
+    /// The source as compiled, with the `#` of hidden lines removed.
+    pub fn compiled(&self) -> String {
+        let lines = self.source.lines().map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed == "#" {
+                ""
+            } else {
+                trimmed.strip_prefix("# ").unwrap_or(line)
+            }
+        });
+        lines.map(|line| format!("{line}\n")).collect()
+    }
+}
+
+fn is_hidden(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed == "#" || trimmed.starts_with("# ")
+}
+
+impl Page {
+    /// The code examples, in order.
+    pub fn examples(&self) -> impl Iterator<Item = &Example> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Example(example) => Some(example),
+            Block::Text(_) => None,
+        })
+    }
+
+    /// The number, counting from 1 like `learning_cell run` does, of the example `demo` performs:
+    /// of the examples that run, the first containing the most of the demo's operations. `None` if
+    /// no example runs.
+    /// ```
+    /// use learning_cell::lessons::{self, Lesson};
+    ///
+    /// let lesson = lessons::find("cell").unwrap();
+    /// let (page, demos) = (lesson.page(), lesson.run().demos);
+    /// assert_eq!(page.example_of(&demos[0]), Some(3));
+    /// assert_eq!(page.example_of(&demos[1]), Some(5));
+    /// ```
+    pub fn example_of(&self, demo: &DemoOutcome) -> Option<usize> {
+        let performed = |example: &Example| {
+            let source = example.visible();
+            demo.steps.iter().filter(|step| source.contains(step.code)).count()
+        };
+        let runnable = self.examples().enumerate().filter(|(_, example)| {
+            matches!(example.kind, ExampleKind::Runs | ExampleKind::ShouldPanic)
+        });
+        // Among equal counts, the highest `Reverse(index)` is the earliest example.
+        runnable
+            .map(|(index, example)| (performed(example), std::cmp::Reverse(index)))
+            .max()
+            .map(|(_, std::cmp::Reverse(index))| index + 1)
+    }
+
+    /// The lesson as plain text, code examples included.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for block in &self.blocks {
+            match block {
+                Block::Text(prose) => text.push_str(prose),
+                Block::Example(example) => text.push_str(&example.visible()),
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Every lesson, built-in and from the registered packs, in course order: chapter by chapter,
+/// each chapter's main lesson first.
+pub fn all() -> Vec<&'static dyn Lesson> {
+    let built_in = BUILT_IN.iter().map(|lesson| lesson as &dyn Lesson);
+    let packs = packs::lessons().into_iter().map(|lesson| lesson as &dyn Lesson);
+    let mut lessons: Vec<_> = built_in.chain(packs).collect();
+    lessons.sort_by_key(|lesson| lesson.chapter());
+    lessons
+}
+
+/// The lesson with the given id.
+pub fn find(id: &str) -> Option<&'static dyn Lesson> {
+    all().into_iter().find(|lesson| lesson.id() == id)
+}
+
+/// The main lesson of `chapter`, if the chapter has one, with its prose in the current locale.
+pub fn page(chapter: Chapter) -> Option<Page> {
+    find(chapter.id()).map(|lesson| lesson.page())
+}
+
+/// The page of every lesson, in course order.
+pub fn pages() -> Vec<Page> {
+    all().into_iter().map(|lesson| lesson.page()).collect()
+}
+
+/// The `///` comment right above `pub mod <module>`, without the slashes.
+fn doc_comment(module: &str) -> Option<String> {
+    let lines: Vec<_> = SOURCE.lines().collect();
+    let header = format!("pub mod {module} {{");
+    let end = lines.iter().position(|line| *line == header)?;
+    let start = lines[..end].iter().rposition(|line| !line.starts_with("///")).map_or(0, |i| i + 1);
+    let comment = lines[start..end].iter().map(|line| {
+        let line = &line[3..];
+        line.strip_prefix(' ').unwrap_or(line)
+    });
+    Some(comment.map(|line| format!("{line}\n")).collect())
+}
+
+pub(crate) fn parse(comment: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut text = String::new();
+    let mut example: Option<Example> = None;
+    for line in comment.lines() {
+        let fence = line.trim_start().strip_prefix("```");
+        match (&mut example, fence) {
+            (None, Some(info)) => {
+                if !text.trim().is_empty() {
+                    blocks.push(Block::Text(text.trim().to_string()));
+                }
+                text.clear();
+                example =
+                    Some(Example { kind: ExampleKind::from_fence(info), source: String::new() });
+            }
+            (Some(_), Some(_)) => blocks.extend(example.take().map(Block::Example)),
+            (Some(example), None) => {
+                example.source.push_str(line);
+                example.source.push('\n');
+            }
+            (None, None) => {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+    }
+    if !text.trim().is_empty() {
+        blocks.push(Block::Text(text.trim().to_string()));
+    }
+    blocks
+}
@@ -93,19 +93,220 @@
 /// make a sense. Instead, we will look into the [`RefCell`] struct.
 pub mod Cell {
     use std::cell::Cell;
+    use std::fmt::Debug;
 
+    use crate::demo::{DemoOutcome, DemoStep};
+    use crate::snapshot::{compare, FieldChange};
+
+    /// The lesson's struct, for any `Copy` type `C` and any type `N`.
+    ///
+    /// Without type arguments it is the `Immutable<i32, String>` of the lesson, and `default()`
+    /// holds the lesson's values. Other types make the same points:
+    /// ```
+    /// use learning_cell::Cell::Immutable;
+    ///
+    /// let a = Immutable::new('a', 'b', vec![1]);
+    /// let before = a.snapshot();
+    /// a.special.set('c');
+    /// let _ = a.special_nocopy.replace(vec![2]);
+    /// assert_eq!(a.diff(&before).len(), 2);
+    /// ```
+    ///
+    /// # Debug, Clone and PartialEq
+    ///
+    /// `Cell<T>` only implements these for `T: Copy`, so `special_nocopy` cannot be derived.
+    /// Instead, its value is taken out for a moment and put back, as in `snapshot`. None of them
+    /// can panic on account of the cells:
+    /// ```
+    /// let a = learning_cell::Cell::Immutable::default();
+    /// let b = a.clone();
+    /// assert!(a == a);
+    /// assert_eq!(a, b);
+    /// b.special_nocopy.set("bye".to_string());
+    /// assert_ne!(a, b);
+    /// let debug = format!("{b:?}");
+    /// assert_eq!(debug, r#"Immutable { regular: 1, special: 42, special_nocopy: "bye" }"#);
+    /// ```
     #[doc(hidden)]
-    pub struct Immutable {
-        pub regular: i32,
-        pub special: Cell<i32>,
-        pub special_nocopy: Cell<String>,
+    pub struct Immutable<C: Copy = i32, N = String> {
+        pub regular: C,
+        pub special: Cell<C>,
+        pub special_nocopy: Cell<N>,
     }
 
     impl Default for Immutable {
         fn default() -> Self {
-            Self { regular: 1, special: Cell::new(42), special_nocopy: Cell::new("hi".to_string()) }
+            Self::new(1, 42, "hi".to_string())
+        }
+    }
+
+    // `Cell<N>` is only `Debug`, `Clone` and `PartialEq` for `N: Copy`, since those need to read
+    // the value. For the other fields, we lend the value out with `take` and put it back.
+    // Nothing can observe the cell in between: `Cell` is not `Sync`.
+
+    /// Prints the values, not the cells: `Immutable { regular: 1, special: 42, .. }`.
+    impl<C: Copy + Debug, N: Default + Debug> Debug for Immutable<C, N> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.with_nocopy(|special_nocopy| {
+                f.debug_struct("Immutable")
+                    .field("regular", &self.regular)
+                    .field("special", &self.special.get())
+                    .field("special_nocopy", special_nocopy)
+                    .finish()
+            })
+        }
+    }
+
+    impl<C: Copy, N: Default + Clone> Clone for Immutable<C, N> {
+        fn clone(&self) -> Self {
+            let special_nocopy = self.with_nocopy(N::clone);
+            Self::new(self.regular, self.special.get(), special_nocopy)
+        }
+    }
+
+    impl<C: Copy + PartialEq, N: Default + PartialEq> PartialEq for Immutable<C, N> {
+        fn eq(&self, other: &Self) -> bool {
+            // Taking `special_nocopy` out of `self` would also empty `other` if they are the
+            // same struct, so a struct is equal to itself without looking.
+            if std::ptr::eq(self, other) {
+                return true;
+            }
+            self.regular == other.regular
+                && self.special.get() == other.special.get()
+                && self.with_nocopy(|mine| other.with_nocopy(|theirs| mine == theirs))
+        }
+    }
+
+    /// Plain-data copy of an [`Immutable`] at some point in time.
+    #[doc(hidden)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Snapshot<C = i32, N = String> {
+        pub regular: C,
+        pub special: C,
+        pub special_nocopy: N,
+    }
+
+    impl<C: Copy, N: Default> Immutable<C, N> {
+        /// Calls `f` with the value of `special_nocopy`, which is taken out of the cell for the
+        /// duration of the call.
+        fn with_nocopy<R>(&self, f: impl FnOnce(&N) -> R) -> R {
+            let value = self.special_nocopy.take();
+            let result = f(&value);
+            self.special_nocopy.set(value);
+            result
+        }
+    }
+
+    impl<C: Copy, N> Immutable<C, N> {
+        /// Wraps `special` and `special_nocopy` in their cells.
+        pub fn new(regular: C, special: C, special_nocopy: N) -> Self {
+            Self { regular, special: Cell::new(special), special_nocopy: Cell::new(special_nocopy) }
+        }
+    }
+
+    crate::__immutable_builder! {
+        /// A builder with no field set; `build` reports any field that is still missing.
+        ///
+        /// Note what the builder can and cannot promise: it checks the values once, in `build`,
+        /// but `special` and `special_nocopy` are cells, so anyone holding a `&Immutable` can
+        /// change them afterwards. An API over interior-mutable fields can only guarantee what
+        /// every `set` would keep true.
+        /// ```
+        /// use learning_cell::Cell::{BuildError, Immutable};
+        ///
+        /// let builder = Immutable::builder().regular(1).special(7);
+        /// let missing = builder.clone().build().err();
+        /// assert_eq!(missing, Some(BuildError::Missing("special_nocopy")));
+        ///
+        /// let a = builder.special_nocopy("hi".to_string()).build().unwrap();
+        /// assert_eq!((a.regular, a.special.get()), (1, 7));
+        /// a.special.set(-1);
+        ///
+        /// let other = Immutable::builder().regular(1.5).special(2.5).special_nocopy(vec!['a']);
+        /// assert!(other.build().is_ok());
+        /// ```
+        Immutable
+    }
+
+    /// Why a builder could not build its struct.
+    #[doc(hidden)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum BuildError {
+        /// The field with this name was never set.
+        Missing(&'static str),
+    }
+
+    impl std::fmt::Display for BuildError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::Missing(field) => write!(f, "`{field}` was never set"),
+            }
         }
     }
+
+    impl std::error::Error for BuildError {}
+
+    impl<C: Copy + PartialEq + Debug, N: Clone + Default + PartialEq + Debug> Immutable<C, N> {
+        /// Copies the current values out of the struct.
+        ///
+        /// `special_nocopy` cannot be read with `get`, so we `take` the value out, clone it and
+        /// put the original back.
+        pub fn snapshot(&self) -> Snapshot<C, N> {
+            let copy = self.with_nocopy(N::clone);
+            Snapshot { regular: self.regular, special: self.special.get(), special_nocopy: copy }
+        }
+
+        /// Lists the fields that changed since `before` was taken.
+        pub fn diff(&self, before: &Snapshot<C, N>) -> Vec<FieldChange> {
+            let after = self.snapshot();
+            let mut changes = Vec::new();
+            compare(&mut changes, "regular", &before.regular, &after.regular);
+            compare(&mut changes, "special", &before.special, &after.special);
+            compare(&mut changes, "special_nocopy", &before.special_nocopy, &after.special_nocopy);
+            changes
+        }
+    }
+
+    /// Performs `operation` on `a` and records what it returned and changed.
+    fn step<R: Debug>(
+        a: &Immutable,
+        code: &'static str,
+        operation: impl FnOnce() -> R,
+    ) -> DemoStep {
+        let before = a.snapshot();
+        let returned = operation();
+        DemoStep::new(code, returned, a.diff(&before))
+    }
+
+    /// Reads and writes the fields of an immutable [`Immutable`] with `get` and `set`.
+    pub fn demonstrate_get_set() -> DemoOutcome {
+        let a = Immutable::default();
+        let steps = vec![
+            step(&a, "a.special.get()", || a.special.get()),
+            step(&a, "a.special.set(2)", || a.special.set(2)),
+            step(&a, "a.special.get()", || a.special.get()),
+            step(&a, r#"a.special_nocopy.set("bye".to_string())"#, || {
+                a.special_nocopy.set("bye".to_string())
+            }),
+        ];
+        DemoOutcome { title: "get and set through a shared reference", steps }
+    }
+
+    /// Moves values in and out of the fields of an immutable [`Immutable`] with `replace`,
+    /// `swap` and `take`, which work for types that are not `Copy`.
+    pub fn demonstrate_replace_swap() -> DemoOutcome {
+        let a = Immutable::default();
+        let other = Cell::new("other".to_string());
+        let steps = vec![
+            step(&a, r#"a.special_nocopy.replace("HI!".to_string())"#, || {
+                a.special_nocopy.replace("HI!".to_string())
+            }),
+            step(&a, "a.special_nocopy.swap(&other)", || a.special_nocopy.swap(&other)),
+            step(&a, "a.special_nocopy.take()", || a.special_nocopy.take()),
+            step(&a, "a.special.replace(7)", || a.special.replace(7)),
+        ];
+        DemoOutcome { title: "replace, swap and take", steps }
+    }
 }
 
 /// _If you haven't read the [`Cell`] section, I recommend you do so before reading this
@@ -126,9 +327,18 @@ pub mod Cell {
 /// }
 /// ```
 /// This is a simple way to count references and in this case, we can get away without any race or
-/// deadlock conditions. This is because `RefCell` is not marked as Sync (or Send). Without the
-/// Sync marker, the compiler guarentees that `RefCell` cannot be passed to a different thread
-/// which in turn guarentees that the `borrow` field can only be updated by one thread.
+/// deadlock conditions. This is because `RefCell` is not `Sync`: the compiler does not let two
+/// threads share a `RefCell`, which in turn guarantees that the `borrow` field can only be updated
+/// by one thread. A `RefCell` is still `Send` when its value is, since once it moves to another
+/// thread, the old one cannot reach it anymore. Both claims are checked by the compiler on every
+/// build:
+/// ```
+/// use std::cell::RefCell;
+/// use learning_cell::{assert_not_sync, assert_send};
+///
+/// assert_not_sync!(RefCell<i32>);
+/// assert_send!(RefCell<i32>);
+/// ```
 ///
 /// To demonstrate this, we will crate the same **immutable** struct as in the [`Cell`] section but
 /// this time we will use `RefCell` instead of `Cell`.
@@ -206,23 +416,428 @@ pub mod Cell {
 /// ```
 /// Funny enough, when you call `borrow` or `borrow_mut` on a `RefCell`, those methods actually
 /// call `try_borrow` and `try_borrow_mut` under the hood and panic if the result is `Err`.
+///
+/// To know whether a borrow is allowed, a `RefCell` keeps count of the borrows it handed out in
+/// a _borrow flag_: the number of live `Ref`s, or a special value while a `RefMut` is alive. The
+/// standard library keeps the flag private, but [`TracedRefCell`](crate::impls::TracedRefCell),
+/// a `RefCell` instrumented by this crate, lets us print it.
+/// ```
+/// use learning_cell::impls::TracedRefCell;
+///
+/// let special = TracedRefCell::new(42);
+/// let first = special.borrow();
+/// let second = special.borrow();
+/// println!("{}", special.borrow_state()); // shared by 2 borrows
+/// assert!(special.try_borrow_mut().is_err());
+///
+/// drop((first, second));
+/// println!("{}", special.borrow_state()); // unused
+/// assert!(special.try_borrow_mut().is_ok());
+/// ```
+/// The flag lives right next to the value, so a `RefCell` takes more memory than what it holds,
+/// while a `Cell`, which never lends references and needs no flag, takes exactly as much. The
+/// [`info`] module measures this on the machine running the code.
+/// ```
+/// use learning_cell::info::{self, Wrapper};
+///
+/// for row in info::size_report() {
+///     let (plain, refcell) = (row.layout(Wrapper::Plain), row.layout(Wrapper::RefCell));
+///     println!("{}: {} bytes, {} in a RefCell", row.inner, plain.size, refcell.size);
+///     assert_eq!(row.overhead(Wrapper::Cell), 0);
+///     assert!(row.overhead(Wrapper::RefCell) > 0);
+/// }
+/// ```
 pub mod RefCell {
     use std::cell::RefCell;
+    use std::fmt::Debug;
+
+    use crate::demo::{DemoOutcome, DemoStep};
+    use crate::snapshot::{compare, FieldChange};
+    use crate::util::catch_panic;
 
     #[doc(hidden)]
-    pub struct Immutable {
-        pub regular: i32,
-        pub special: RefCell<i32>,
-        pub special_nocopy: RefCell<String>,
+    pub use crate::Cell::BuildError;
+
+    /// The lesson's struct, for any `Copy` type `C` and any type `N`.
+    ///
+    /// Without type arguments it is the `Immutable<i32, String>` of the lesson, and `default()`
+    /// holds the lesson's values.
+    ///
+    /// # Debug, Clone and PartialEq
+    ///
+    /// All three are derived, and each ends up borrowing the fields through `RefCell`'s own
+    /// impls. They differ in what they do when a field is mutably borrowed. `Debug` is used to
+    /// investigate, often right where a borrow is alive, so it uses `try_borrow` and prints
+    /// `<borrowed>` instead of the value:
+    /// ```
+    /// let a = learning_cell::RefCell::Immutable::default();
+    /// let guard = a.special.borrow_mut();
+    /// let debug = format!("{a:?}");
+    /// assert!(debug.contains("special: RefCell { value: <borrowed> }"));
+    /// assert!(debug.contains(r#"special_nocopy: RefCell { value: "hi" }"#));
+    /// drop(guard);
+    /// assert!(format!("{a:?}").contains("special: RefCell { value: 42 }"));
+    /// ```
+    /// `Clone` and `PartialEq` have no such way out: they need the value, so they `borrow` it and
+    /// panic while a mutable borrow is alive, just like calling `borrow` yourself would.
+    /// ```should_panic
+    /// let a = learning_cell::RefCell::Immutable::default();
+    /// assert!(a == a.clone());
+    ///
+    /// let _guard = a.special_nocopy.borrow_mut();
+    /// // Panic: already mutably borrowed: BorrowError
+    /// let _ = a.clone();
+    /// ```
+    #[doc(hidden)]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Immutable<C: Copy = i32, N = String> {
+        pub regular: C,
+        pub special: RefCell<C>,
+        pub special_nocopy: RefCell<N>,
     }
 
     impl Default for Immutable {
         fn default() -> Self {
+            Self::new(1, 42, "hi".to_string())
+        }
+    }
+
+    /// Plain-data copy of an [`Immutable`] at some point in time.
+    #[doc(hidden)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Snapshot<C = i32, N = String> {
+        pub regular: C,
+        pub special: C,
+        pub special_nocopy: N,
+    }
+
+    impl<C: Copy, N> Immutable<C, N> {
+        /// Wraps `special` and `special_nocopy` in their cells.
+        pub fn new(regular: C, special: C, special_nocopy: N) -> Self {
             Self {
-                regular: 1,
-                special: RefCell::new(42),
-                special_nocopy: RefCell::new("hi".to_string())
+                regular,
+                special: RefCell::new(special),
+                special_nocopy: RefCell::new(special_nocopy)
+            }
+        }
+    }
+
+    crate::__immutable_builder! {
+        /// A builder with no field set, like
+        /// [`Cell::Immutable::builder`](crate::Cell::Immutable::builder).
+        /// ```
+        /// use learning_cell::RefCell::Immutable;
+        ///
+        /// let a = Immutable::builder()
+        ///     .regular(1)
+        ///     .special(42)
+        ///     .special_nocopy("hello".to_string())
+        ///     .build()
+        ///     .unwrap();
+        /// assert_eq!(*a.special_nocopy.borrow(), "hello");
+        /// ```
+        Immutable
+    }
+
+    impl<C: Copy + PartialEq + Debug, N: Clone + PartialEq + Debug> Immutable<C, N> {
+        /// Copies the current values out of the struct.
+        ///
+        /// Panics if any of the fields is currently mutably borrowed.
+        pub fn snapshot(&self) -> Snapshot<C, N> {
+            Snapshot {
+                regular: self.regular,
+                special: *self.special.borrow(),
+                special_nocopy: self.special_nocopy.borrow().clone(),
             }
         }
+
+        /// Lists the fields that changed since `before` was taken.
+        pub fn diff(&self, before: &Snapshot<C, N>) -> Vec<FieldChange> {
+            let after = self.snapshot();
+            let mut changes = Vec::new();
+            compare(&mut changes, "regular", &before.regular, &after.regular);
+            compare(&mut changes, "special", &before.special, &after.special);
+            compare(&mut changes, "special_nocopy", &before.special_nocopy, &after.special_nocopy);
+            changes
+        }
+    }
+
+    /// Performs `operation` on `a` and records what it returned and changed, or the message it
+    /// panicked with.
+    ///
+    /// Panics if a field of `a` is mutably borrowed, since `a` has to be read before and after.
+    fn step<R: Debug>(
+        a: &Immutable,
+        code: &'static str,
+        operation: impl FnOnce() -> R,
+    ) -> DemoStep {
+        let before = a.snapshot();
+        match catch_panic(operation) {
+            Ok(returned) => DemoStep::new(code, returned, a.diff(&before)),
+            Err(message) => DemoStep::panicked(code, message, a.diff(&before)),
+        }
+    }
+
+    /// Asks for a mutable borrow while a shared one is alive, and records the panic instead of
+    /// crashing.
+    /// ```
+    /// let outcome = learning_cell::RefCell::demonstrate_conflicting_borrow();
+    /// let conflict = &outcome.steps[1];
+    /// assert_eq!(conflict.code, "a.special.borrow_mut()");
+    /// assert!(conflict.panicked.as_ref().unwrap().contains("already borrowed"));
+    /// assert_eq!(outcome.steps.last().unwrap().changes[0].to_string(), "special: 42 -> 24");
+    /// ```
+    pub fn demonstrate_conflicting_borrow() -> DemoOutcome {
+        let a = Immutable::default();
+        let refer = a.special.borrow();
+        let steps = vec![
+            DemoStep::new("let refer = a.special.borrow()", *refer, Vec::new()),
+            step(&a, "a.special.borrow_mut()", || a.special.borrow_mut()),
+            step(&a, "a.special.try_borrow_mut()", || a.special.try_borrow_mut().map(|_| ())),
+            step(&a, "a.special.replace(24)", || a.special.replace(24)),
+            step(&a, "drop(refer)", || drop(refer)),
+            step(&a, "a.special.replace(24)", || a.special.replace(24)),
+        ];
+        DemoOutcome { title: "a mutable borrow while a shared one is alive", steps }
+    }
+}
+
+/// _This section builds on both the [`Cell`] and the [`RefCell`] sections._
+///
+/// So far our cells held plain values. But the value inside a cell can itself have cells inside
+/// it, and it is worth seeing how interior mutability composes across such nesting levels (and
+/// where it does not).
+///
+/// To explore this, we will wrap the `Immutable` structs of the previous sections once more:
+/// - `in_cell`: the `Immutable` of the [`Cell`] section, wrapped in a Cell
+/// - `in_refcell`: the `Immutable` of the [`RefCell`] section, wrapped in a RefCell
+/// ```
+/// use std::cell::{Cell, RefCell};
+///
+/// struct Outer {
+///     in_cell: Cell<learning_cell::Cell::Immutable>,
+///     in_refcell: RefCell<learning_cell::RefCell::Immutable>,
+/// }
+///
+/// let outer = Outer {
+///     in_cell: Cell::new(Default::default()),
+///     in_refcell: RefCell::new(Default::default()),
+/// };
+/// ```
+///
+/// Let's start with `in_refcell`. A shared borrow of the outer `RefCell` gives us a shared
+/// reference to the inner struct, and a shared reference is all the inner cells ever need. So we
+/// can change `special` while only _reading_ the outer cell.
+/// ```
+/// # let outer = learning_cell::Nested::Outer::default();
+/// let inner = outer.in_refcell.borrow();
+/// *inner.special.borrow_mut() = 7;
+///
+/// // The outer cell is still only borrowed for reading, so others can read along.
+/// let another = outer.in_refcell.borrow();
+/// assert_eq!(*another.special.borrow(), 7);
+/// ```
+/// Each `RefCell` keeps its own borrow flag, one per nesting level. Reading the outer cell does
+/// not count as a borrow of the inner ones, and mutably borrowing an inner cell does not lock
+/// the outer one. The flags do not share any information either: two mutable borrows of the same
+/// inner cell still panic, no matter how they were reached.
+/// ```should_panic
+/// # let outer = learning_cell::Nested::Outer::default();
+/// let first = outer.in_refcell.borrow();
+/// let second = outer.in_refcell.borrow();
+/// let a = first.special_nocopy.borrow_mut();
+/// // Panic: already borrowed: BorrowMutError
+/// let b = second.special_nocopy.borrow_mut();
+/// ```
+/// Interior mutability stops where the cells stop, though. `regular` is a plain field, so it can
+/// only change through a mutable reference to the inner struct, and the only way to get one is
+/// a mutable borrow of the outer cell.
+/// ```compile_fail
+/// # let outer = learning_cell::Nested::Outer::default();
+/// // Error: cannot assign to data in dereference of `Ref<'_, Immutable>`
+/// outer.in_refcell.borrow().regular += 1;
+/// ```
+/// ```
+/// # let outer = learning_cell::Nested::Outer::default();
+/// outer.in_refcell.borrow_mut().regular += 1;
+/// assert_eq!(outer.in_refcell.borrow().regular, 2);
+/// ```
+///
+/// Now `in_cell`. A `Cell` never hands out references to its value, so we cannot reach the
+/// inner cells at all, even though they would be perfectly happy with a shared reference. And
+/// since `Immutable` is not `Copy`, we cannot `get` a copy of it either.
+/// ```compile_fail
+/// # let outer = learning_cell::Nested::Outer::default();
+/// // Error: the method `get` exists for `Cell<Immutable>`, but its trait bounds were not satisfied
+/// let inner = outer.in_cell.get();
+/// ```
+/// What remains is moving the whole struct out, changing it and moving it back.
+/// ```
+/// # let outer = learning_cell::Nested::Outer::default();
+/// let inner = outer.in_cell.take();
+/// inner.special.set(7);
+/// outer.in_cell.set(inner);
+/// ```
+/// While the struct is out, `in_cell` holds a default one, so the change is not atomic in any
+/// sense: code running in between (say, a callback) would see the default values. In short,
+/// interior mutability composes through `RefCell` (and through plain fields), but a `Cell`
+/// around a non-`Copy` value cuts it off.
+pub mod Nested {
+    use std::cell::{Cell, RefCell};
+    use std::fmt::Debug;
+
+    use crate::demo::{DemoOutcome, DemoStep};
+    use crate::snapshot::{compare, FieldChange};
+    use crate::util::catch_panic;
+
+    #[doc(hidden)]
+    #[derive(Default)]
+    pub struct Outer {
+        pub in_cell: Cell<crate::Cell::Immutable>,
+        pub in_refcell: RefCell<crate::RefCell::Immutable>,
+    }
+
+    /// Plain-data copy of an [`Outer`] at some point in time.
+    #[doc(hidden)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Snapshot {
+        pub in_cell: crate::Cell::Snapshot,
+        pub in_refcell: crate::RefCell::Snapshot,
+    }
+
+    impl Outer {
+        /// Copies the current values out of both inner structs.
+        ///
+        /// Panics if `in_refcell`, or any field inside it, is currently mutably borrowed.
+        pub fn snapshot(&self) -> Snapshot {
+            let in_cell = self.in_cell.take();
+            let snapshot = in_cell.snapshot();
+            self.in_cell.set(in_cell);
+
+            Snapshot { in_cell: snapshot, in_refcell: self.in_refcell.borrow().snapshot() }
+        }
+
+        /// Lists the fields that changed since `before` was taken, as paths like
+        /// `in_cell.special`.
+        pub fn diff(&self, before: &Snapshot) -> Vec<FieldChange> {
+            let after = self.snapshot();
+            let (old, new) = (&before.in_cell, &after.in_cell);
+            let mut changes = Vec::new();
+            compare(&mut changes, "in_cell.regular", &old.regular, &new.regular);
+            compare(&mut changes, "in_cell.special", &old.special, &new.special);
+            compare(
+                &mut changes,
+                "in_cell.special_nocopy",
+                &old.special_nocopy,
+                &new.special_nocopy,
+            );
+            let (old, new) = (&before.in_refcell, &after.in_refcell);
+            compare(&mut changes, "in_refcell.regular", &old.regular, &new.regular);
+            compare(&mut changes, "in_refcell.special", &old.special, &new.special);
+            compare(
+                &mut changes,
+                "in_refcell.special_nocopy",
+                &old.special_nocopy,
+                &new.special_nocopy,
+            );
+            changes
+        }
+    }
+
+    /// Performs `operation` on `outer` and records what it returned and changed, or the message
+    /// it panicked with.
+    fn step<R: Debug>(
+        outer: &Outer,
+        code: &'static str,
+        operation: impl FnOnce() -> R,
+    ) -> DemoStep {
+        let before = outer.snapshot();
+        match catch_panic(operation) {
+            Ok(returned) => DemoStep::new(code, returned, outer.diff(&before)),
+            Err(message) => DemoStep::panicked(code, message, outer.diff(&before)),
+        }
+    }
+
+    /// Changes the inner cells of `in_refcell` while only reading the outer cell, then shows that
+    /// `regular` needs a mutable borrow of the outer cell, which conflicts with readers.
+    /// ```
+    /// let outcome = learning_cell::Nested::demonstrate_reach_through_refcell();
+    /// let changed = &outcome.steps[0].changes[0];
+    /// assert_eq!(changed.to_string(), "in_refcell.special: 42 -> 7");
+    /// assert!(outcome.steps[3].panicked.is_some());
+    /// ```
+    pub fn demonstrate_reach_through_refcell() -> DemoOutcome {
+        let outer = Outer::default();
+        let code = "*outer.in_refcell.borrow().special.borrow_mut() = 7";
+        let mut steps =
+            vec![step(&outer, code, || *outer.in_refcell.borrow().special.borrow_mut() = 7)];
+        let reader = outer.in_refcell.borrow();
+        steps.push(DemoStep::new("let reader = outer.in_refcell.borrow()", (), Vec::new()));
+        steps.push(step(&outer, "reader.special_nocopy.borrow_mut().push('!')", || {
+            reader.special_nocopy.borrow_mut().push('!')
+        }));
+        steps.push(step(&outer, "outer.in_refcell.borrow_mut().regular += 1", || {
+            outer.in_refcell.borrow_mut().regular += 1
+        }));
+        steps.push(step(&outer, "drop(reader)", || drop(reader)));
+        steps.push(step(&outer, "outer.in_refcell.borrow_mut().regular += 1", || {
+            outer.in_refcell.borrow_mut().regular += 1
+        }));
+        DemoOutcome { title: "reaching into a RefCell through a shared borrow", steps }
+    }
+
+    /// Changes a field of the struct in `in_cell`, which means moving the whole struct out and
+    /// back in.
+    /// ```
+    /// let outcome = learning_cell::Nested::demonstrate_swap_out_of_cell();
+    /// assert_eq!(outcome.steps[1].changes, []);
+    /// let put_back = outcome.steps[2].changes.iter().map(|change| change.field);
+    /// assert!(put_back.eq(["in_cell.regular", "in_cell.special", "in_cell.special_nocopy"]));
+    /// ```
+    pub fn demonstrate_swap_out_of_cell() -> DemoOutcome {
+        let outer = Outer::default();
+        let before = outer.snapshot();
+        let inner = outer.in_cell.replace(crate::Cell::Immutable::new(0, 0, String::new()));
+        let code = "let inner = outer.in_cell.replace(Immutable::new(0, 0, String::new()))";
+        let mut steps = vec![DemoStep::new(code, &inner, outer.diff(&before))];
+        steps.push(step(&outer, "inner.special.set(7)", || inner.special.set(7)));
+        steps.push(step(&outer, "outer.in_cell.set(inner)", || outer.in_cell.set(inner)));
+        DemoOutcome { title: "moving a struct out of a Cell and back", steps }
     }
 }
+
+pub mod benchmarks;
+pub mod certificate;
+pub mod comparison;
+pub mod curriculum;
+pub mod demo;
+pub mod diagnostics;
+pub mod diagram;
+pub mod diff;
+pub mod error;
+pub mod exercises;
+pub mod export;
+pub mod grading;
+pub mod impls;
+pub mod info;
+pub mod json;
+pub mod leakcheck;
+pub mod lessons;
+pub mod locale;
+mod macros;
+pub mod manifest;
+pub mod matrix;
+pub mod packs;
+pub mod progress;
+pub mod quiz;
+pub mod runner;
+pub mod search;
+pub mod shared;
+pub mod simulator;
+pub mod snapshot;
+pub mod snippets;
+pub mod static_checks;
+pub mod util;
+
+#[cfg(feature = "derive")]
+pub use learning_cell_derive::InteriorMutable;
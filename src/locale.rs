@@ -0,0 +1,102 @@
+//! Translations of the lesson text.
+//!
+//! The lessons are written in English, in the crate's doc comments. A translation is a message
+//! catalog under `src/locales/`, pairing each paragraph of prose with its translation in the
+//! spirit of gettext's `.po` files. [`set_locale`] picks the catalog that [`lessons::page`]
+//! translates through; code examples are left as they are.
+//! ```
+//! use learning_cell::curriculum::Chapter;
+//! use learning_cell::lessons::{self, Block};
+//! use learning_cell::locale;
+//!
+//! locale::set_locale("tr").unwrap();
+//! let page = lessons::page(Chapter::Cell).unwrap();
+//! let Block::Text(intro) = &page.blocks[0] else { unreachable!() };
+//! assert!(intro.contains("iç değişkenlik"));
+//!
+//! assert!(locale::set_locale("xx").is_err());
+//! assert_eq!(locale::locale(), "tr");
+//! ```
+//! A paragraph missing from the catalog, for instance because its English has changed since it
+//! was translated, is shown in English, so an outdated translation never hides a lesson.
+//!
+//! [`lessons::page`]: crate::lessons::page
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+/// The locale the lessons are written in.
+pub const SOURCE_LOCALE: &str = "en";
+
+/// Environment variable the command line reads the locale from when `--lang` is not given.
+pub const LOCALE_VAR: &str = "LEARNING_CELL_LANG";
+
+/// The catalogs shipped with the crate, by locale.
+const CATALOGS: &[(&str, &str)] = &[("tr", include_str!("locales/tr.txt"))];
+
+static LOCALE: RwLock<&str> = RwLock::new(SOURCE_LOCALE);
+
+/// English paragraphs and their translations.
+type Messages = HashMap<&'static str, &'static str>;
+
+/// [`set_locale`] was given a locale without a catalog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownLocale(pub String);
+
+impl fmt::Display for UnknownLocale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no translation for locale `{}` (available: {})", self.0, available().join(", "))
+    }
+}
+
+impl std::error::Error for UnknownLocale {}
+
+/// Every locale the lessons can be read in, the source locale first.
+pub fn available() -> Vec<&'static str> {
+    let translated = CATALOGS.iter().map(|(code, _)| *code);
+    std::iter::once(SOURCE_LOCALE).chain(translated).collect()
+}
+
+/// Switches the lessons to `code`, such as `"tr"`, for the whole process.
+pub fn set_locale(code: &str) -> Result<(), UnknownLocale> {
+    let code = available()
+        .into_iter()
+        .find(|available| *available == code)
+        .ok_or_else(|| UnknownLocale(code.to_string()))?;
+    *LOCALE.write().unwrap() = code;
+    Ok(())
+}
+
+/// The current locale.
+pub fn locale() -> &'static str {
+    *LOCALE.read().unwrap()
+}
+
+/// `paragraph` in the current locale, or `paragraph` itself if there is no translation.
+pub fn translate(paragraph: &str) -> &str {
+    let catalog = catalogs().get(locale());
+    catalog.and_then(|messages| messages.get(paragraph.trim())).copied().unwrap_or(paragraph)
+}
+
+/// `text` with each of its paragraphs (separated by blank lines) [translated](translate).
+pub fn translate_paragraphs(text: &str) -> String {
+    if locale() == SOURCE_LOCALE {
+        return text.to_string();
+    }
+    let paragraphs = text.split("\n\n").map(str::trim).filter(|paragraph| !paragraph.is_empty());
+    paragraphs.map(translate).collect::<Vec<_>>().join("\n\n")
+}
+
+fn catalogs() -> &'static HashMap<&'static str, Messages> {
+    static PARSED: OnceLock<HashMap<&str, Messages>> = OnceLock::new();
+    PARSED.get_or_init(|| CATALOGS.iter().map(|(code, text)| (*code, parse(text))).collect())
+}
+
+/// Parses a catalog: `#` comments, then entries of a `msgid` line, the English paragraph, a
+/// `msgstr` line and the translation.
+fn parse(text: &'static str) -> Messages {
+    let entries = text.split("\nmsgid\n").skip(1);
+    let entries = entries.filter_map(|entry| entry.split_once("\nmsgstr\n"));
+    entries.map(|(id, translation)| (id.trim(), translation.trim())).collect()
+}
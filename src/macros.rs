@@ -0,0 +1,421 @@
+//! Declarative macros that take the boilerplate out of interior mutability.
+//!
+//! They are exported at the root of the crate; this module only keeps them in one place.
+
+/// Defines a struct whose fields are wrapped in cells, with accessors that only need `&self`.
+///
+/// Each field starts with one attribute saying how it is stored, then any others (such as doc
+/// comments):
+/// - `#[cell]`: a `Cell<T>`. The getter copies the value out, so `T` must be `Copy`.
+/// - `#[refcell]`: a `RefCell<T>`. The getter returns a `Ref<'_, T>`.
+/// - `#[atomic]`: the atomic type of `T` (see [`AtomicValue`](crate::util::AtomicValue)), so
+///   the struct can be shared between threads.
+/// - `#[plain]`: `T` itself, with no accessors.
+///
+/// The getter is named after the field. Adding `(set = name)` to the attribute also generates a
+/// setter with that name, since a declarative macro cannot make up `set_field` on its own. The
+/// struct gets a `new` taking every field's value, in order.
+/// ```
+/// use learning_cell::interior_mutable;
+///
+/// interior_mutable! {
+///     /// A player, changed by game systems that only hold `&Player`.
+///     #[derive(Debug, Default)]
+///     pub struct Player {
+///         #[plain]
+///         pub name: String,
+///         #[cell(set = set_health)]
+///         health: i32,
+///         #[refcell(set = set_inventory)]
+///         inventory: Vec<String>,
+///         #[atomic(set = set_score)]
+///         score: u64,
+///     }
+/// }
+///
+/// let player = Player::new("ferris".to_string(), 10, Vec::new(), 0);
+/// player.set_health(player.health() - 3);
+/// player.set_inventory(vec!["sword".to_string()]);
+/// player.set_score(player.score() + 100);
+///
+/// assert_eq!(player.health(), 7);
+/// assert_eq!(player.inventory()[0], "sword");
+/// assert_eq!(player.score(), 100);
+/// ```
+/// Each accessor is the one or two lines we would otherwise write by hand, so the rules of the
+/// lessons still apply: holding on to `inventory()` while calling `set_inventory` panics, just
+/// as it would with a hand-written `RefCell` field.
+/// ```should_panic
+/// # learning_cell::interior_mutable! {
+/// #     struct Player {
+/// #         #[refcell(set = set_inventory)]
+/// #         inventory: Vec<String>,
+/// #     }
+/// # }
+/// let player = Player::new(Vec::new());
+/// let inventory = player.inventory();
+/// // Panic: already borrowed: BorrowMutError
+/// player.set_inventory(vec!["shield".to_string()]);
+/// ```
+#[macro_export]
+macro_rules! interior_mutable {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                #[$kind:ident $((set = $set:ident))?]
+                $(#[$field_attr:meta])*
+                $field_vis:vis $field:ident: $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $(
+                $(#[$field_attr])*
+                $field_vis $field: $crate::interior_mutable!(@type $kind $ty),
+            )*
+        }
+
+        impl $name {
+            /// Creates the struct from the value of each field.
+            #[allow(clippy::too_many_arguments)]
+            $vis fn new($($field: $ty),*) -> Self {
+                Self { $($field: $crate::interior_mutable!(@wrap $kind $ty, $field)),* }
+            }
+
+            $($crate::interior_mutable!(@accessors $vis $kind $field: $ty $(, $set)?);)*
+        }
+    };
+
+    (@type cell $ty:ty) => { ::std::cell::Cell<$ty> };
+    (@type refcell $ty:ty) => { ::std::cell::RefCell<$ty> };
+    (@type atomic $ty:ty) => { <$ty as $crate::util::AtomicValue>::Atomic };
+    (@type plain $ty:ty) => { $ty };
+
+    (@wrap cell $ty:ty, $value:ident) => { ::std::cell::Cell::new($value) };
+    (@wrap refcell $ty:ty, $value:ident) => { ::std::cell::RefCell::new($value) };
+    (@wrap atomic $ty:ty, $value:ident) => { $crate::util::AtomicValue::into_atomic($value) };
+    (@wrap plain $ty:ty, $value:ident) => { $value };
+
+    (@accessors $vis:vis cell $field:ident: $ty:ty $(, $set:ident)?) => {
+        #[doc = ::std::concat!("Copies `", ::std::stringify!($field), "` out of its cell.")]
+        $vis fn $field(&self) -> $ty {
+            self.$field.get()
+        }
+
+        $(
+            #[doc = ::std::concat!("Overwrites `", ::std::stringify!($field), "`.")]
+            $vis fn $set(&self, value: $ty) {
+                self.$field.set(value);
+            }
+        )?
+    };
+    (@accessors $vis:vis refcell $field:ident: $ty:ty $(, $set:ident)?) => {
+        #[doc = ::std::concat!("Borrows `", ::std::stringify!($field), "`.")]
+        ///
+        /// Panics if it is currently mutably borrowed.
+        $vis fn $field(&self) -> ::std::cell::Ref<'_, $ty> {
+            self.$field.borrow()
+        }
+
+        $(
+            #[doc = ::std::concat!("Overwrites `", ::std::stringify!($field), "`.")]
+            ///
+            /// Panics if it is currently borrowed.
+            $vis fn $set(&self, value: $ty) {
+                self.$field.replace(value);
+            }
+        )?
+    };
+    (@accessors $vis:vis atomic $field:ident: $ty:ty $(, $set:ident)?) => {
+        #[doc = ::std::concat!("Reads `", ::std::stringify!($field), "`.")]
+        $vis fn $field(&self) -> $ty {
+            <$ty as $crate::util::AtomicValue>::load(&self.$field)
+        }
+
+        $(
+            #[doc = ::std::concat!("Overwrites `", ::std::stringify!($field), "`.")]
+            $vis fn $set(&self, value: $ty) {
+                <$ty as $crate::util::AtomicValue>::store(&self.$field, value);
+            }
+        )?
+    };
+    (@accessors $vis:vis plain $field:ident: $ty:ty) => {};
+}
+
+/// Wraps a value in a [`Shared`](crate::shared::Shared), that is an `Rc<RefCell<T>>`.
+///
+/// `Rc::new(RefCell::new(value))` says everything twice: the reader has to find the value at
+/// the bottom of two constructors, and every call site repeats which cells we chose. A macro that
+/// expands to exactly that code saves the typing without hiding anything, since it cannot do
+/// more than the code it stands for.
+/// ```
+/// use learning_cell::shared;
+///
+/// let scores = shared!(vec![1, 2]);
+/// let also_scores = scores.clone();
+/// also_scores.borrow_mut().push(3);
+/// assert_eq!(*scores.borrow(), [1, 2, 3]);
+/// ```
+/// A function would do for this one. It is a macro for symmetry with
+/// [`clone_all!`](crate::clone_all!), which has to be one.
+#[macro_export]
+macro_rules! shared {
+    ($value:expr $(,)?) => {
+        ::std::rc::Rc::new(::std::cell::RefCell::new($value))
+    };
+}
+
+/// Wraps a value in a [`SyncShared`](crate::shared::SyncShared), that is an `Arc<Mutex<T>>`.
+///
+/// The thread-safe counterpart of [`shared!`]: the same shape, with `Arc` for `Rc` and `Mutex`
+/// for `RefCell`. Switching a value from one to the other is a one-word change at the
+/// construction site, while the compiler points out every `borrow_mut` that has to become a
+/// `lock`.
+/// ```
+/// use std::thread;
+/// use learning_cell::{clone_all, shared_sync};
+///
+/// let total = shared_sync!(0);
+/// let handles: Vec<_> = (1..=3)
+///     .map(|n| {
+///         clone_all!(total);
+///         thread::spawn(move || *total.lock().unwrap() += n)
+///     })
+///     .collect();
+/// handles.into_iter().for_each(|handle| handle.join().unwrap());
+/// assert_eq!(*total.lock().unwrap(), 6);
+/// ```
+#[macro_export]
+macro_rules! shared_sync {
+    ($value:expr $(,)?) => {
+        ::std::sync::Arc::new(::std::sync::Mutex::new($value))
+    };
+}
+
+/// Shadows each named handle with a clone of itself, ready to move into a closure.
+///
+/// A `move` closure takes ownership of every handle it mentions, so code that keeps using a
+/// handle afterwards has to clone it first, under a new name or in a block:
+/// ```
+/// # use learning_cell::shared;
+/// let log = shared!(Vec::new());
+/// let callback = {
+///     let log = log.clone();
+///     move |event: &str| log.borrow_mut().push(event.to_string())
+/// };
+/// callback("click");
+/// assert_eq!(log.borrow().len(), 1);
+/// ```
+/// `clone_all!(a, b)` writes the `let a = a.clone();` lines for us. Unlike a function, a macro
+/// can declare variables in the caller's scope, and the names it declares are the caller's own
+/// identifiers, so they shadow the originals just like the hand-written lines do.
+/// ```
+/// use learning_cell::{clone_all, shared};
+///
+/// let log = shared!(Vec::new());
+/// let clicks = shared!(0);
+/// let callback = {
+///     clone_all!(log, clicks);
+///     move |event: &str| {
+///         log.borrow_mut().push(event.to_string());
+///         *clicks.borrow_mut() += 1;
+///     }
+/// };
+/// callback("click");
+/// assert_eq!((log.borrow().len(), *clicks.borrow()), (1, 1));
+/// ```
+#[macro_export]
+macro_rules! clone_all {
+    ($($handle:ident),+ $(,)?) => {
+        $(let $handle = ::std::clone::Clone::clone(&$handle);)+
+    };
+}
+
+/// Fails to compile unless every listed type implements the trait. Backs [`assert_send!`] and
+/// friends, and the compile-time checks of the [`matrix`](crate::matrix).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_impl {
+    ($trait:path: $($ty:ty),+ $(,)?) => {
+        const _: fn() = || {
+            fn implements<T: ?::std::marker::Sized + $trait>() {}
+            $(implements::<$ty>();)+
+        };
+    };
+}
+
+/// Fails to compile if any listed type implements the trait.
+///
+/// Asking for `<T as AmbiguousIfImpl<_>>::item` is fine while one impl applies, and ambiguous,
+/// hence an error, once the second one, for types implementing the trait, applies as well.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_not_impl {
+    ($trait:path: $($ty:ty),+ $(,)?) => {
+        const _: fn() = || {
+            trait AmbiguousIfImpl<A> {
+                fn item() {}
+            }
+            impl<T: ?::std::marker::Sized> AmbiguousIfImpl<()> for T {}
+            #[allow(dead_code)]
+            struct Implemented;
+            impl<T: ?::std::marker::Sized + $trait> AmbiguousIfImpl<Implemented> for T {}
+            $(<$ty as AmbiguousIfImpl<_>>::item();)+
+        };
+    };
+}
+
+/// Fails to compile unless every listed type is `Send`, that is, can move to another thread.
+///
+/// A claim like "an `Arc<Mutex<T>>` can be handed to a thread" is easy to write in prose and
+/// easy to get wrong. Written as an assertion, the compiler checks it on every build, and it
+/// costs nothing at run time.
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// learning_cell::assert_send!(Arc<Mutex<Vec<i32>>>, String);
+/// ```
+/// ```compile_fail
+/// // Error: `Rc<i32>` cannot be sent between threads safely
+/// learning_cell::assert_send!(std::rc::Rc<i32>);
+/// ```
+#[macro_export]
+macro_rules! assert_send {
+    ($($ty:ty),+ $(,)?) => {
+        $crate::__assert_impl!(::std::marker::Send: $($ty),+);
+    };
+}
+
+/// Fails to compile unless every listed type is `Sync`, that is, can be shared between threads
+/// by reference.
+/// ```
+/// use std::sync::Mutex;
+/// learning_cell::assert_sync!(Mutex<i32>, &'static str);
+/// ```
+/// ```compile_fail
+/// // Error: `Cell<i32>` cannot be shared between threads safely
+/// learning_cell::assert_sync!(std::cell::Cell<i32>);
+/// ```
+#[macro_export]
+macro_rules! assert_sync {
+    ($($ty:ty),+ $(,)?) => {
+        $crate::__assert_impl!(::std::marker::Sync: $($ty),+);
+    };
+}
+
+/// Fails to compile if any listed type is `Send`.
+///
+/// The negative counterpart of [`assert_send!`](crate::assert_send!), for the claims lessons
+/// make about what the compiler _rejects_. If a type ever became `Send`, the build would fail
+/// here with "type annotations needed", pointing at the assertion that no longer holds.
+/// ```
+/// use std::rc::Rc;
+/// learning_cell::assert_not_send!(Rc<i32>, std::sync::MutexGuard<'static, i32>);
+/// ```
+/// ```compile_fail
+/// // Error: type annotations needed, since `i32` is `Send`
+/// learning_cell::assert_not_send!(i32);
+/// ```
+#[macro_export]
+macro_rules! assert_not_send {
+    ($($ty:ty),+ $(,)?) => {
+        $crate::__assert_not_impl!(::std::marker::Send: $($ty),+);
+    };
+}
+
+/// Fails to compile if any listed type is `Sync`. See
+/// [`assert_not_send!`](crate::assert_not_send!).
+/// ```
+/// use std::cell::{Cell, RefCell};
+/// learning_cell::assert_not_sync!(Cell<i32>, RefCell<i32>);
+/// ```
+/// ```compile_fail
+/// // Error: type annotations needed, since `Mutex<i32>` is `Sync`
+/// learning_cell::assert_not_sync!(std::sync::Mutex<i32>);
+/// ```
+#[macro_export]
+macro_rules! assert_not_sync {
+    ($($ty:ty),+ $(,)?) => {
+        $crate::__assert_not_impl!(::std::marker::Sync: $($ty),+);
+    };
+}
+
+/// Defines the `ImmutableBuilder` of a lesson module, building the module's `$target` through
+/// its `new(regular, special, special_nocopy)`, and `$target::builder` with the given docs. The
+/// [`Cell`](crate::Cell) and [`RefCell`](crate::RefCell) lessons share it.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __immutable_builder {
+    ($(#[$builder_doc:meta])* $target:ident) => {
+        /// Builds an [`Immutable`] one field at a time. See [`Immutable::builder`].
+        #[doc(hidden)]
+        #[derive(Debug, Clone)]
+        pub struct ImmutableBuilder<C = i32, N = String> {
+            regular: Option<C>,
+            special: Option<C>,
+            special_nocopy: Option<N>,
+        }
+
+        impl<C: Copy, N> ImmutableBuilder<C, N> {
+            /// A builder with no field set.
+            pub fn new() -> Self {
+                Self { regular: None, special: None, special_nocopy: None }
+            }
+
+            /// Sets `regular`.
+            pub fn regular(mut self, value: C) -> Self {
+                self.regular = Some(value);
+                self
+            }
+
+            /// Sets the initial value of `special`.
+            pub fn special(mut self, value: C) -> Self {
+                self.special = Some(value);
+                self
+            }
+
+            /// Sets the initial value of `special_nocopy`.
+            pub fn special_nocopy(mut self, value: N) -> Self {
+                self.special_nocopy = Some(value);
+                self
+            }
+
+            /// Builds the struct, or reports the first field that was never set.
+            pub fn build(self) -> Result<$target<C, N>, $crate::Cell::BuildError> {
+                use $crate::Cell::BuildError;
+                Ok($target::new(
+                    self.regular.ok_or(BuildError::Missing("regular"))?,
+                    self.special.ok_or(BuildError::Missing("special"))?,
+                    self.special_nocopy.ok_or(BuildError::Missing("special_nocopy"))?,
+                ))
+            }
+        }
+
+        impl<C: Copy, N> Default for ImmutableBuilder<C, N> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<C: Copy, N> $target<C, N> {
+            $(#[$builder_doc])*
+            pub fn builder() -> ImmutableBuilder<C, N> {
+                ImmutableBuilder::new()
+            }
+        }
+    };
+}
+
+/// The line [`check!`] adds to its failure message for the explanation given after the section,
+/// or nothing.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_note {
+    () => {
+        ::std::string::String::new()
+    };
+    ($($message:tt)+) => {
+        ::std::format!("  {}\n", ::std::format_args!($($message)+))
+    };
+}
@@ -0,0 +1,430 @@
+//! Exercise manifests.
+//!
+//! A manifest lists the exercises of a course in order, together with the metadata an
+//! instructor may want to change: titles, difficulty, tags, hints and prerequisites. It is read
+//! at run time, so a course can be reordered, trimmed or re-hinted without recompiling the
+//! crate. The exercises themselves (their scaffolds and hidden tests) are still compiled in; a
+//! manifest entry refers to one by id.
+//!
+//! Manifests are TOML documents holding an array of `[[exercise]]` tables.
+//! ```toml
+//! # My course: RefCell first, and only the easy parts.
+//! [[exercise]]
+//! id = "fix_borrow_panic"
+//! hints = ["Look at how long `list` lives."]
+//!
+//! [[exercise]]
+//! id = "cell_intro"
+//! title = "Cells, revisited"
+//! difficulty = "core"
+//! tags = ["cell", "review"]
+//! prerequisites = ["fix_borrow_panic"]
+//! ```
+//! Every key but `id` is optional and defaults to the compiled-in value (no prerequisites, for
+//! `prerequisites`). A [`Registry`] combines a manifest with the compiled exercises, and
+//! [`Registry::install`] makes it the course every query of [`exercises`] answers from:
+//! ```no_run
+//! use learning_cell::exercises;
+//! use learning_cell::manifest::Registry;
+//!
+//! Registry::load("exercises.toml").unwrap().install();
+//! for exercise in exercises::all() {
+//!     println!("{}: {}", exercise.id(), exercise.title());
+//! }
+//! ```
+//! The command line tool installs the manifest named by `--manifest <path>` or
+//! [`$LEARNING_CELL_MANIFEST`](MANIFEST_VAR). The `exercises.toml` at the root of the repository
+//! describes the built-in course; it is a good starting point for a custom one.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::curriculum::{Chapter, Difficulty};
+use crate::exercises::{self, Exercise, Hint, Report, Test};
+
+/// Environment variable naming the manifest the command line tool installs.
+pub const MANIFEST_VAR: &str = "LEARNING_CELL_MANIFEST";
+
+/// A parsed manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// The entries, in course order.
+    #[serde(rename = "exercise", default)]
+    pub exercises: Vec<ManifestEntry>,
+}
+
+/// One `[[exercise]]` table of a manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestEntry {
+    /// Id of a compiled-in exercise.
+    pub id: String,
+    /// Replaces the exercise's title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Replaces the exercise's difficulty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    /// Replaces the exercise's tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Replaces the exercise's hints.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hints: Option<Vec<String>>,
+    /// Ids of the exercises that must be solved first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prerequisites: Vec<String>,
+}
+
+/// A manifest could not be read.
+#[derive(Debug)]
+pub enum ManifestError {
+    /// Reading the file failed.
+    Io(io::Error),
+    /// The text is not a valid manifest.
+    Syntax(toml::de::Error),
+    /// An entry refers to an exercise that does not exist.
+    UnknownExercise(String),
+    /// An exercise is listed twice.
+    Duplicate(String),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "cannot read manifest: {err}"),
+            Self::Syntax(err) => write!(f, "invalid manifest: {}", err.message()),
+            Self::UnknownExercise(id) => write!(f, "unknown exercise `{id}`"),
+            Self::Duplicate(id) => write!(f, "exercise `{id}` is listed twice"),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+impl From<io::Error> for ManifestError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ManifestError {
+    fn from(err: toml::de::Error) -> Self {
+        Self::Syntax(err)
+    }
+}
+
+impl Manifest {
+    /// Reads and parses the manifest at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Manifest, ManifestError> {
+        Manifest::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses a manifest.
+    /// ```
+    /// use learning_cell::manifest::Manifest;
+    ///
+    /// let manifest = Manifest::parse(
+    ///     r#"
+    ///     [[exercise]]
+    ///     id = "memo_fib"
+    ///     tags = [
+    ///         "recursion",  # multi-line arrays are fine
+    ///         "caching",
+    ///     ]
+    ///     "#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(manifest.exercises[0].tags.as_ref().unwrap(), &["recursion", "caching"]);
+    ///
+    /// let err = Manifest::parse("[[exercise]]\ntitle = \"no id\"").unwrap_err();
+    /// assert_eq!(err.to_string(), "invalid manifest: missing field `id`");
+    /// ```
+    pub fn parse(text: &str) -> Result<Manifest, ManifestError> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Writes the manifest in the format [`parse`](Manifest::parse) reads.
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("a manifest only holds strings and arrays of strings")
+    }
+}
+
+/// The exercises of a course, in order, with their metadata as configured by a manifest.
+#[derive(Debug, Clone)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+/// A compiled exercise with the metadata of a [`Registry`].
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// The compiled exercise, which provides the description and the hidden tests.
+    pub exercise: &'static dyn Exercise,
+    /// Title to show.
+    pub title: String,
+    /// How demanding the exercise is.
+    pub difficulty: Difficulty,
+    /// Lowercase topic tags.
+    pub tags: Vec<String>,
+    /// Escalating hints.
+    pub hints: Vec<String>,
+    /// Ids of the exercises that must be solved first.
+    pub prerequisites: Vec<String>,
+}
+
+impl Entry {
+    fn builtin(exercise: &'static dyn Exercise) -> Entry {
+        Entry {
+            exercise,
+            title: exercise.title().to_string(),
+            difficulty: exercise.difficulty(),
+            tags: exercise.tags().iter().map(|tag| tag.to_string()).collect(),
+            hints: exercise.hints().iter().map(|hint| hint.0.to_string()).collect(),
+            prerequisites: Vec::new(),
+        }
+    }
+
+    /// The exercise's id.
+    pub fn id(&self) -> &'static str {
+        self.exercise.id()
+    }
+
+    /// The first `level` hints (all of them if `level` is larger than the number of hints).
+    pub fn reveal(&self, level: usize) -> &[String] {
+        &self.hints[..level.min(self.hints.len())]
+    }
+
+    /// Runs the hidden tests of the exercise.
+    pub fn run(&self) -> Report {
+        self.exercise.run()
+    }
+
+    /// Whether every prerequisite is solved, according to `is_solved`.
+    pub fn is_unlocked(&self, mut is_solved: impl FnMut(&str) -> bool) -> bool {
+        self.prerequisites.iter().all(|id| is_solved(id))
+    }
+}
+
+impl Registry {
+    /// Every exercise with its compiled-in metadata, in the suggested order.
+    pub fn builtin() -> Registry {
+        Registry { entries: exercises::compiled().into_iter().map(Entry::builtin).collect() }
+    }
+
+    /// The exercises listed by `manifest`, in the manifest's order.
+    ///
+    /// Exercises the manifest does not mention are left out of the course.
+    /// ```
+    /// use learning_cell::manifest::{Manifest, Registry};
+    ///
+    /// let manifest = Manifest::parse(
+    ///     r#"
+    ///     [[exercise]]
+    ///     id = "memo_fib"
+    ///
+    ///     [[exercise]]
+    ///     id = "cell_intro"
+    ///     hints = ["Try `Cell::get`."]
+    ///     prerequisites = ["memo_fib"]
+    ///     "#,
+    /// )
+    /// .unwrap();
+    /// let registry = Registry::from_manifest(&manifest).unwrap();
+    ///
+    /// let ids: Vec<_> = registry.entries().iter().map(|entry| entry.id()).collect();
+    /// assert_eq!(ids, ["memo_fib", "cell_intro"]);
+    ///
+    /// let cell_intro = registry.get("cell_intro").unwrap();
+    /// assert_eq!(cell_intro.hints, ["Try `Cell::get`."]);
+    /// assert_eq!(cell_intro.title, "Reading and writing a Cell");
+    /// assert!(!cell_intro.is_unlocked(|_| false));
+    /// ```
+    pub fn from_manifest(manifest: &Manifest) -> Result<Registry, ManifestError> {
+        let mut entries: Vec<Entry> = Vec::new();
+        for item in &manifest.exercises {
+            let exercise = compiled(&item.id)
+                .ok_or_else(|| ManifestError::UnknownExercise(item.id.clone()))?;
+            if entries.iter().any(|entry| entry.id() == exercise.id()) {
+                return Err(ManifestError::Duplicate(item.id.clone()));
+            }
+            let mut entry = Entry::builtin(exercise);
+            if let Some(title) = &item.title {
+                entry.title = title.clone();
+            }
+            if let Some(difficulty) = item.difficulty {
+                entry.difficulty = difficulty;
+            }
+            if let Some(tags) = &item.tags {
+                entry.tags = tags.clone();
+            }
+            if let Some(hints) = &item.hints {
+                entry.hints = hints.clone();
+            }
+            entry.prerequisites = item.prerequisites.clone();
+            entries.push(entry);
+        }
+        for entry in &entries {
+            if let Some(missing) =
+                entry.prerequisites.iter().find(|id| compiled(id).is_none())
+            {
+                return Err(ManifestError::UnknownExercise(missing.clone()));
+            }
+        }
+        Ok(Registry { entries })
+    }
+
+    /// Reads the manifest at `path` and builds a registry from it.
+    pub fn load(path: impl AsRef<Path>) -> Result<Registry, ManifestError> {
+        Registry::from_manifest(&Manifest::load(path)?)
+    }
+
+    /// The exercises, in course order.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Looks up an exercise by id.
+    pub fn get(&self, id: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.id() == id)
+    }
+
+    /// Makes this registry the course: from now on [`exercises::all`], [`exercises::get`] and
+    /// every query built on them list its entries, in its order and with its metadata.
+    ///
+    /// Exercises registered afterwards are not part of the course. The metadata is kept for the
+    /// rest of the program, so install a registry once, at startup.
+    /// ```
+    /// use learning_cell::exercises;
+    /// use learning_cell::manifest::{Manifest, Registry};
+    ///
+    /// let manifest = Manifest::parse(
+    ///     r#"
+    ///     [[exercise]]
+    ///     id = "memo_fib"
+    ///
+    ///     [[exercise]]
+    ///     id = "cell_intro"
+    ///     title = "Cells, revisited"
+    ///     prerequisites = ["memo_fib"]
+    ///     "#,
+    /// )
+    /// .unwrap();
+    /// Registry::from_manifest(&manifest).unwrap().install();
+    ///
+    /// let ids: Vec<_> = exercises::all().iter().map(|exercise| exercise.id()).collect();
+    /// assert_eq!(ids, ["memo_fib", "cell_intro"]);
+    /// let cell_intro = exercises::get("cell_intro").unwrap();
+    /// assert_eq!(cell_intro.title(), "Cells, revisited");
+    /// assert_eq!(cell_intro.prerequisites(), ["memo_fib"]);
+    /// assert!(exercises::get("deadlock").is_none());
+    /// ```
+    pub fn install(self) {
+        let course = self
+            .entries
+            .into_iter()
+            .map(|entry| &*Box::leak(Box::new(Configured::new(entry))) as &dyn Exercise)
+            .collect();
+        exercises::set_course(course);
+    }
+
+    /// The manifest describing this registry, with every value spelled out.
+    pub fn to_manifest(&self) -> Manifest {
+        let exercises = self
+            .entries
+            .iter()
+            .map(|entry| ManifestEntry {
+                id: entry.id().to_string(),
+                title: Some(entry.title.clone()),
+                difficulty: Some(entry.difficulty),
+                tags: Some(entry.tags.clone()),
+                hints: Some(entry.hints.clone()),
+                prerequisites: entry.prerequisites.clone(),
+            })
+            .collect();
+        Manifest { exercises }
+    }
+}
+
+/// Looks up a compiled exercise, whether or not a course is installed.
+fn compiled(id: &str) -> Option<&'static dyn Exercise> {
+    exercises::compiled().into_iter().find(|exercise| exercise.id() == id)
+}
+
+/// An [`Entry`] of an installed registry: the compiled exercise with the manifest's metadata.
+#[derive(Debug)]
+struct Configured {
+    exercise: &'static dyn Exercise,
+    title: &'static str,
+    difficulty: Difficulty,
+    tags: &'static [&'static str],
+    hints: &'static [Hint],
+    prerequisites: &'static [&'static str],
+}
+
+impl Configured {
+    fn new(entry: Entry) -> Configured {
+        fn leak(text: String) -> &'static str {
+            Box::leak(text.into_boxed_str())
+        }
+        let leak_all = |items: Vec<String>| -> &'static [&'static str] {
+            Box::leak(items.into_iter().map(leak).collect())
+        };
+        let hints = entry.hints.into_iter().map(|hint| Hint(leak(hint))).collect();
+        Configured {
+            exercise: entry.exercise,
+            title: leak(entry.title),
+            difficulty: entry.difficulty,
+            tags: leak_all(entry.tags),
+            hints: Box::leak(hints),
+            prerequisites: leak_all(entry.prerequisites),
+        }
+    }
+}
+
+impl Exercise for Configured {
+    fn id(&self) -> &'static str {
+        self.exercise.id()
+    }
+
+    fn title(&self) -> &'static str {
+        self.title
+    }
+
+    fn chapter(&self) -> Chapter {
+        self.exercise.chapter()
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        self.tags
+    }
+
+    fn description(&self) -> &'static str {
+        self.exercise.description()
+    }
+
+    fn tests(&self) -> &'static [Test] {
+        self.exercise.tests()
+    }
+
+    fn hints(&self) -> &'static [Hint] {
+        self.hints
+    }
+
+    fn prerequisites(&self) -> &'static [&'static str] {
+        self.prerequisites
+    }
+
+    fn run(&self) -> Report {
+        self.exercise.run()
+    }
+}
@@ -0,0 +1,253 @@
+//! Which operations each wrapper type offers, as data.
+//!
+//! Learners keep asking the same questions: can I `get` out of a `RefCell`? Can I `borrow` a
+//! `Mutex`? Is a `Cell` `Sync`? Every [`Row`] answers them for one type, one [`Operation`] at a
+//! time. The rows are written with a macro that also type-checks every claim they make, down to
+//! `RefCell<i32>: !Sync`, so the table cannot drift from the standard library without breaking
+//! the build.
+//! ```
+//! use learning_cell::comparison::Bound;
+//! use learning_cell::matrix::{self, Operation};
+//!
+//! assert_eq!(matrix::CELL.get(Operation::Get), Bound::When("T: Copy"));
+//! assert_eq!(matrix::REFCELL.get(Operation::Get), Bound::Never);
+//!
+//! let lockable: Vec<_> = matrix::supporting(Operation::TryLock).map(|row| row.name).collect();
+//! assert_eq!(lockable, ["Mutex"]);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::comparison::Bound;
+
+/// Something a wrapper type may let us do with the value it holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Copy the value out through a shared reference.
+    Get,
+    /// Overwrite the value through a shared reference.
+    Set,
+    /// Put a new value in through a shared reference, getting the old one back.
+    Replace,
+    /// Move the value out through a shared reference, leaving the default behind.
+    Take,
+    /// Reach the value through a mutable reference to the wrapper.
+    GetMut,
+    /// Borrow the value for reading, panicking on conflict.
+    Borrow,
+    /// Borrow the value for writing, panicking on conflict.
+    BorrowMut,
+    /// Borrow the value for reading, or fail on conflict.
+    TryBorrow,
+    /// Borrow the value for writing, or fail on conflict.
+    TryBorrowMut,
+    /// Wait for exclusive access.
+    Lock,
+    /// Take exclusive access, or fail if someone has it.
+    TryLock,
+    /// Wait for shared read access.
+    Read,
+    /// Wait for exclusive write access.
+    Write,
+    /// Take read access, or fail if a writer has it.
+    TryRead,
+    /// Take write access, or fail if anyone has access.
+    TryWrite,
+    /// Move the wrapper to another thread.
+    Send,
+    /// Share the wrapper with another thread.
+    Sync,
+}
+
+impl Operation {
+    /// Every operation, in the order of the matrix's columns.
+    pub const ALL: [Operation; 17] = [
+        Operation::Get,
+        Operation::Set,
+        Operation::Replace,
+        Operation::Take,
+        Operation::GetMut,
+        Operation::Borrow,
+        Operation::BorrowMut,
+        Operation::TryBorrow,
+        Operation::TryBorrowMut,
+        Operation::Lock,
+        Operation::TryLock,
+        Operation::Read,
+        Operation::Write,
+        Operation::TryRead,
+        Operation::TryWrite,
+        Operation::Send,
+        Operation::Sync,
+    ];
+
+    /// Looks up an operation by method or trait name, ignoring case, e.g. `try_borrow_mut`.
+    pub fn lookup(name: &str) -> Option<Operation> {
+        let name = name.to_lowercase();
+        Operation::ALL.into_iter().find(|operation| operation.to_string().to_lowercase() == name)
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operation::Get => "get",
+            Operation::Set => "set",
+            Operation::Replace => "replace",
+            Operation::Take => "take",
+            Operation::GetMut => "get_mut",
+            Operation::Borrow => "borrow",
+            Operation::BorrowMut => "borrow_mut",
+            Operation::TryBorrow => "try_borrow",
+            Operation::TryBorrowMut => "try_borrow_mut",
+            Operation::Lock => "lock",
+            Operation::TryLock => "try_lock",
+            Operation::Read => "read",
+            Operation::Write => "write",
+            Operation::TryRead => "try_read",
+            Operation::TryWrite => "try_write",
+            Operation::Send => "Send",
+            Operation::Sync => "Sync",
+        })
+    }
+}
+
+/// The operations one wrapper type offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row {
+    /// The type's name, as in the [comparison table](crate::comparison).
+    pub name: &'static str,
+    /// The operations the type offers and for which `T`. Those not listed, it does not offer.
+    pub operations: &'static [(Operation, Bound)],
+}
+
+impl Row {
+    /// Whether the type offers `operation`.
+    pub fn get(&self, operation: Operation) -> Bound {
+        let found = self.operations.iter().find(|(op, _)| *op == operation);
+        found.map_or(Bound::Never, |(_, bound)| *bound)
+    }
+
+    /// Whether the type offers `operation` for at least some `T`.
+    pub fn supports(&self, operation: Operation) -> bool {
+        self.get(operation) != Bound::Never
+    }
+}
+
+/// Defines the rows of the matrix and checks, at compile time, each claim they make about `i32`.
+///
+/// An operation with a closure claims the closure compiles for the row's type, so a method that
+/// does not exist or got a stricter bound breaks the build. `Send` and `Sync` must be listed on
+/// every row: `Never` asserts the type does not implement the trait, any other bound that it
+/// does. Whatever a row does not list, it claims the type does not offer.
+macro_rules! capability_matrix {
+    ($(
+        $(#[$attr:meta])*
+        $row:ident = $name:literal for $ty:ty {
+            $($op:ident: $bound:ident $(($condition:literal))? $(=> |$value:ident| $call:expr)?),+
+            $(,)?
+        }
+    )+) => {
+        $(
+            $(#[$attr])*
+            pub const $row: Row = Row {
+                name: $name,
+                operations: &[$((Operation::$op, Bound::$bound $(($condition))?)),+],
+            };
+
+            const _: () = {
+                $(capability_matrix!(@check $ty, $op, $bound $(=> |$value| $call)?);)+
+            };
+        )+
+    };
+    (@check $ty:ty, $op:ident, $bound:ident => |$value:ident| $call:expr) => {
+        #[allow(unused_mut, clippy::let_unit_value)]
+        const _: fn($ty) = |mut $value| {
+            let _ = $call;
+        };
+    };
+    (@check $ty:ty, $trait:ident, Never) => {
+        $crate::__assert_not_impl!($trait: $ty);
+    };
+    (@check $ty:ty, $trait:ident, $bound:ident) => {
+        $crate::__assert_impl!($trait: $ty);
+    };
+}
+
+capability_matrix! {
+    /// `std::cell::Cell`.
+    CELL = "Cell" for Cell<i32> {
+        Get: When("T: Copy") => |cell| cell.get(),
+        Set: Always => |cell| cell.set(2),
+        Replace: Always => |cell| cell.replace(2),
+        Take: When("T: Default") => |cell| cell.take(),
+        GetMut: Always => |cell| *cell.get_mut() = 2,
+        Send: When("T: Send"),
+        Sync: Never,
+    }
+
+    /// `std::cell::RefCell`.
+    REFCELL = "RefCell" for RefCell<i32> {
+        Replace: Always => |cell| cell.replace(2),
+        Take: When("T: Default") => |cell| cell.take(),
+        GetMut: Always => |cell| *cell.get_mut() = 2,
+        Borrow: Always => |cell| *cell.borrow(),
+        BorrowMut: Always => |cell| *cell.borrow_mut() = 2,
+        TryBorrow: Always => |cell| cell.try_borrow().is_ok(),
+        TryBorrowMut: Always => |cell| cell.try_borrow_mut().is_ok(),
+        Send: When("T: Send"),
+        Sync: Never,
+    }
+
+    /// `std::sync::Mutex`.
+    MUTEX = "Mutex" for Mutex<i32> {
+        GetMut: Always => |mutex| mutex.get_mut().is_ok(),
+        Lock: Always => |mutex| mutex.lock().is_ok(),
+        TryLock: Always => |mutex| mutex.try_lock().is_ok(),
+        Send: When("T: Send"),
+        Sync: When("T: Send"),
+    }
+
+    /// `std::sync::RwLock`.
+    RWLOCK = "RwLock" for RwLock<i32> {
+        GetMut: Always => |lock| lock.get_mut().is_ok(),
+        Read: Always => |lock| lock.read().is_ok(),
+        Write: Always => |lock| lock.write().is_ok(),
+        TryRead: Always => |lock| lock.try_read().is_ok(),
+        TryWrite: Always => |lock| lock.try_write().is_ok(),
+        Send: When("T: Send"),
+        Sync: When("T: Send + Sync"),
+    }
+
+    /// `std::rc::Rc`. Its `get_mut` is an associated function, `Rc::get_mut(&mut rc)`, and only
+    /// succeeds while no other `Rc` or `Weak` points to the value.
+    RC = "Rc" for Rc<i32> {
+        GetMut: Always => |rc| Rc::get_mut(&mut rc).is_some(),
+        Send: Never,
+        Sync: Never,
+    }
+
+    /// `std::sync::Arc`. Like `Rc`, it only offers `Arc::get_mut`.
+    ARC = "Arc" for Arc<i32> {
+        GetMut: Always => |arc| Arc::get_mut(&mut arc).is_some(),
+        Send: When("T: Send + Sync"),
+        Sync: When("T: Send + Sync"),
+    }
+}
+
+/// Every row, in the order the course introduces the types.
+pub const MATRIX: &[Row] = &[CELL, REFCELL, MUTEX, RWLOCK, RC, ARC];
+
+/// Looks up a row by type name, ignoring case.
+pub fn row(name: &str) -> Option<&'static Row> {
+    let name = name.to_lowercase();
+    MATRIX.iter().find(|row| row.name.to_lowercase() == name)
+}
+
+/// The rows of the types offering `operation` for at least some `T`.
+pub fn supporting(operation: Operation) -> impl Iterator<Item = &'static Row> {
+    MATRIX.iter().filter(move |row| row.supports(operation))
+}
@@ -0,0 +1,166 @@
+//! Lesson packs: lessons and exercises from other crates.
+//!
+//! A pack is a crate that depends on `learning_cell`, implements [`LessonPack`] and exposes a
+//! function registering it. Once [`register_pack`] has been called, its lessons are part of the
+//! [curriculum](crate::curriculum) next to the built-in lesson of their chapter, and its exercises
+//! are in the [exercise registry](crate::exercises::register), so the command line lists, runs and
+//! recommends them like any other.
+//!
+//! Lessons are written in the same Markdown as the built-in ones. To have `cargo test` check
+//! their examples too, a pack can also include the text as the doc comment of a module:
+//! `#[doc = include_str!("lessons/weak.md")] pub mod weak {}`.
+//! ```
+//! use learning_cell::curriculum::{self, Chapter, Difficulty, Item};
+//! use learning_cell::lessons::LessonMeta;
+//! use learning_cell::packs::{self, LessonPack, PackLesson};
+//!
+//! struct WeakPack;
+//!
+//! impl LessonPack for WeakPack {
+//!     fn name(&self) -> &'static str {
+//!         "weak"
+//!     }
+//!
+//!     fn lessons(&self) -> &'static [PackLesson] {
+//!         &[PackLesson {
+//!             id: "weak_cycles",
+//!             meta: LessonMeta {
+//!                 title: "Breaking cycles with Weak",
+//!                 summary: "Back links that do not keep their target alive.",
+//!                 difficulty: Difficulty::Core,
+//!                 prerequisites: &["refcell"],
+//!                 minutes: 10,
+//!                 exercises: &[],
+//!             },
+//!             chapter: Chapter::Rc,
+//!             tags: &["rc", "weak"],
+//!             text: "A `Weak` does not keep its value alive.\n\
+//!                    ```\n\
+//!                    let strong = std::rc::Rc::new(1);\n\
+//!                    let weak = std::rc::Rc::downgrade(&strong);\n\
+//!                    drop(strong);\n\
+//!                    assert!(weak.upgrade().is_none());\n\
+//!                    ```\n",
+//!         }]
+//!     }
+//! }
+//!
+//! pub fn register_pack() {
+//!     packs::register_pack(&WeakPack);
+//! }
+//!
+//! register_pack();
+//! let lesson = packs::lesson("weak_cycles").unwrap();
+//! assert_eq!(lesson.page().examples().count(), 1);
+//! assert!(Chapter::Rc.items().contains(&Item::Lesson(lesson)));
+//! ```
+//!
+//! Registration is explicit: without a dependency like `inventory`, there is no portable way
+//! for a crate to register itself just by being linked. A course binary calls the `register_pack`
+//! function of every pack it ships before using the rest of the crate.
+
+use std::fmt;
+use std::sync::RwLock;
+
+use crate::curriculum::Chapter;
+use crate::exercises::{self, Exercise};
+use crate::lessons::{self, Lesson, LessonMeta, Page};
+
+/// A set of lessons and exercises provided by another crate.
+pub trait LessonPack: Sync {
+    /// Short name of the pack, shown in error messages.
+    fn name(&self) -> &'static str;
+
+    /// The lessons, in reading order.
+    fn lessons(&self) -> &'static [PackLesson];
+
+    /// The exercises, registered together with the pack.
+    fn exercises(&self) -> Vec<&'static dyn Exercise> {
+        Vec::new()
+    }
+}
+
+/// A lesson of a [`LessonPack`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackLesson {
+    /// Stable identifier, recorded in the progress file. Must not clash with any other lesson.
+    pub id: &'static str,
+    /// Title, difficulty and the like.
+    pub meta: LessonMeta,
+    /// The chapter the lesson is listed under.
+    pub chapter: Chapter,
+    /// Topic tags.
+    pub tags: &'static [&'static str],
+    /// The lesson, in Markdown.
+    pub text: &'static str,
+}
+
+impl PackLesson {
+    /// The lesson parsed into prose and examples.
+    pub fn page(&self) -> Page {
+        Page { chapter: self.chapter, blocks: lessons::parse(self.text) }
+    }
+}
+
+impl Lesson for PackLesson {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn meta(&self) -> LessonMeta {
+        self.meta
+    }
+
+    fn chapter(&self) -> Chapter {
+        self.chapter
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        self.tags
+    }
+
+    fn page(&self) -> Page {
+        PackLesson::page(self)
+    }
+}
+
+impl fmt::Display for PackLesson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.meta.title)
+    }
+}
+
+static PACKS: RwLock<Vec<&'static dyn LessonPack>> = RwLock::new(Vec::new());
+
+/// Adds `pack` to the course, along with its exercises.
+///
+/// # Panics
+///
+/// Panics if one of its lessons has the id of another lesson, built-in or from another pack, or
+/// if one of its exercises is already registered.
+pub fn register_pack(pack: &'static dyn LessonPack) {
+    for lesson in pack.lessons() {
+        let id = lesson.id;
+        let taken = Chapter::from_id(id).is_some() || lessons::find(id).is_some();
+        assert!(!taken, "pack `{}`: a lesson with id `{id}` already exists", pack.name());
+    }
+    for exercise in pack.exercises() {
+        exercises::register(exercise);
+    }
+    PACKS.write().unwrap().push(pack);
+}
+
+/// Every registered pack, in registration order.
+pub fn all() -> Vec<&'static dyn LessonPack> {
+    PACKS.read().unwrap().clone()
+}
+
+/// Every lesson of the registered packs.
+pub fn lessons() -> Vec<&'static PackLesson> {
+    all().into_iter().flat_map(|pack| pack.lessons()).collect()
+}
+
+/// The pack lesson with the given id.
+pub fn lesson(id: &str) -> Option<&'static PackLesson> {
+    lessons().into_iter().find(|lesson| lesson.id == id)
+}
@@ -0,0 +1,683 @@
+//! What the learner has done so far.
+//!
+//! [`Score`] awards points for solved exercises and answered quizzes, keeps track of streaks of
+//! successes and unlocks [`Badge`]s along the way. [`Progress`] is the store that holds the score
+//! and reads and writes it as a small line-based text file.
+//!
+//! By default the file lives in the user's data directory, see [`default_path`]; the command line
+//! tool loads it on startup and saves it after every command that changes it. To move progress
+//! to another machine, or hand it in for grading, it is wrapped in a versioned [`Export`].
+//! ```
+//! use learning_cell::progress::{Badge, Score};
+//!
+//! let mut score = Score::default();
+//! let unlocked = score.record_exercise("cell_intro", true);
+//!
+//! assert_eq!(score.points(), 10);
+//! assert_eq!(unlocked, vec![Badge::FirstSteps]);
+//!
+//! // Solving the same exercise again is not worth any more points.
+//! score.record_exercise("cell_intro", true);
+//! assert_eq!(score.points(), 10);
+//! assert_eq!(score.streak(), 2);
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::curriculum::{Chapter, Item};
+use crate::quiz::Grade;
+
+/// Environment variable overriding where progress is stored, see [`default_path`].
+pub const PATH_VAR: &str = "LEARNING_CELL_PROGRESS";
+
+/// Version of the [`Export`] format written by this crate.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Points for solving an exercise for the first time.
+pub const EXERCISE_POINTS: u32 = 10;
+/// Points per correctly answered quiz question.
+pub const QUIZ_ANSWER_POINTS: u32 = 2;
+/// Bonus points for answering every question of a quiz correctly.
+pub const PERFECT_QUIZ_BONUS: u32 = 5;
+
+/// A named achievement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Badge {
+    /// Solved a first exercise.
+    FirstSteps,
+    /// Solved every exercise of the `RefCell` chapter.
+    BorrowTamer,
+    /// Fixed the reference cycle exercise.
+    CycleBreaker,
+    /// Answered every question of a quiz correctly.
+    QuizWhiz,
+    /// Five successes in a row.
+    OnFire,
+}
+
+impl Badge {
+    /// Every badge.
+    pub const ALL: [Badge; 5] = [
+        Badge::FirstSteps,
+        Badge::BorrowTamer,
+        Badge::CycleBreaker,
+        Badge::QuizWhiz,
+        Badge::OnFire,
+    ];
+
+    /// Stable identifier used in the progress file.
+    pub fn id(self) -> &'static str {
+        match self {
+            Badge::FirstSteps => "first_steps",
+            Badge::BorrowTamer => "borrow_tamer",
+            Badge::CycleBreaker => "cycle_breaker",
+            Badge::QuizWhiz => "quiz_whiz",
+            Badge::OnFire => "on_fire",
+        }
+    }
+
+    /// Display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Badge::FirstSteps => "First Steps",
+            Badge::BorrowTamer => "Borrow Tamer",
+            Badge::CycleBreaker => "Cycle Breaker",
+            Badge::QuizWhiz => "Quiz Whiz",
+            Badge::OnFire => "On Fire",
+        }
+    }
+
+    /// How the badge is earned.
+    pub fn description(self) -> &'static str {
+        match self {
+            Badge::FirstSteps => "Solve your first exercise.",
+            Badge::BorrowTamer => "Solve every exercise of the RefCell chapter.",
+            Badge::CycleBreaker => "Break the reference cycle.",
+            Badge::QuizWhiz => "Answer every question of a quiz correctly.",
+            Badge::OnFire => "Succeed five times in a row.",
+        }
+    }
+
+    /// Looks up a badge by [`id`](Badge::id).
+    pub fn from_id(id: &str) -> Option<Badge> {
+        Badge::ALL.into_iter().find(|badge| badge.id() == id)
+    }
+}
+
+impl fmt::Display for Badge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Points, streaks and badges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Score {
+    points: u32,
+    streak: u32,
+    best_streak: u32,
+    completed: BTreeSet<String>,
+    badges: BTreeSet<Badge>,
+}
+
+impl Score {
+    /// Total points.
+    pub fn points(&self) -> u32 {
+        self.points
+    }
+
+    /// Number of successes in a row, reset by any failure.
+    pub fn streak(&self) -> u32 {
+        self.streak
+    }
+
+    /// The longest streak so far.
+    pub fn best_streak(&self) -> u32 {
+        self.best_streak
+    }
+
+    /// Ids of the completed lessons and exercises.
+    pub fn completed(&self) -> &BTreeSet<String> {
+        &self.completed
+    }
+
+    /// Whether the item with the given id is completed.
+    pub fn is_completed(&self, id: &str) -> bool {
+        self.completed.contains(id)
+    }
+
+    /// The badges unlocked so far.
+    pub fn badges(&self) -> &BTreeSet<Badge> {
+        &self.badges
+    }
+
+    /// Marks a lesson as read. Lessons are worth no points, but count towards chapters.
+    pub fn record_lesson(&mut self, id: &str) {
+        self.completed.insert(id.to_string());
+    }
+
+    /// Records an attempt at the exercise `id` and returns the badges it unlocked.
+    pub fn record_exercise(&mut self, id: &str, passed: bool) -> Vec<Badge> {
+        let before = self.badges.clone();
+        self.record_attempt(passed);
+        if passed && self.completed.insert(id.to_string()) {
+            self.points += EXERCISE_POINTS;
+            self.badges.insert(Badge::FirstSteps);
+            if id == "reference_cycle" {
+                self.badges.insert(Badge::CycleBreaker);
+            }
+        }
+        self.newly_unlocked(&before)
+    }
+
+    /// Records a graded quiz and returns the badges it unlocked.
+    pub fn record_quiz(&mut self, grade: &Grade) -> Vec<Badge> {
+        let before = self.badges.clone();
+        let perfect = grade.total > 0 && grade.correct == grade.total;
+        self.record_attempt(perfect);
+        self.points += grade.correct as u32 * QUIZ_ANSWER_POINTS;
+        if perfect {
+            self.points += PERFECT_QUIZ_BONUS;
+            self.badges.insert(Badge::QuizWhiz);
+        }
+        self.newly_unlocked(&before)
+    }
+
+    fn record_attempt(&mut self, success: bool) {
+        self.streak = if success { self.streak + 1 } else { 0 };
+        self.best_streak = self.best_streak.max(self.streak);
+    }
+
+    /// Unlocks the badges that depend on the accumulated state, then returns the badges that are
+    /// not in `before`.
+    fn newly_unlocked(&mut self, before: &BTreeSet<Badge>) -> Vec<Badge> {
+        let refcell: Vec<_> = Chapter::RefCell
+            .items()
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::Exercise(exercise) => Some(exercise.id()),
+                Item::Lesson(_) => None,
+            })
+            .collect();
+        if !refcell.is_empty() && refcell.iter().all(|id| self.is_completed(id)) {
+            self.badges.insert(Badge::BorrowTamer);
+        }
+        if self.streak >= 5 {
+            self.badges.insert(Badge::OnFire);
+        }
+
+        self.badges.difference(before).copied().collect()
+    }
+}
+
+/// Attempts, failures, hint usage and timing of every exercise the learner has tried.
+///
+/// Where [`Score`] celebrates what went well, `Stats` remembers where the learner struggled, so
+/// that they (or a dashboard) can go back to those exercises.
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use learning_cell::progress::Stats;
+///
+/// let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// let mut stats = Stats::default();
+/// stats.record_attempt("memo_fib", false, start);
+/// stats.record_hint("memo_fib", 2);
+/// stats.record_attempt("memo_fib", true, start + Duration::from_secs(300));
+///
+/// let memo_fib = stats.exercise("memo_fib").unwrap();
+/// assert_eq!((memo_fib.attempts, memo_fib.failures, memo_fib.hints), (2, 1, 2));
+/// assert_eq!(memo_fib.time_to_completion(), Some(Duration::from_secs(300)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    exercises: BTreeMap<String, ExerciseStats>,
+    quizzes: BTreeMap<Chapter, QuizStats>,
+}
+
+/// Quiz answers about one chapter, summed over every quiz taken, see [`Stats::record_quiz`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuizStats {
+    /// Questions answered correctly.
+    pub correct: u32,
+    /// Questions asked, skipped ones included.
+    pub asked: u32,
+}
+
+/// Statistics of a single exercise, see [`Stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExerciseStats {
+    /// Number of times the exercise was run.
+    pub attempts: u32,
+    /// Number of runs with at least one failing test.
+    pub failures: u32,
+    /// Deepest hint level revealed.
+    pub hints: u32,
+    /// When the exercise was first run.
+    pub first_attempt: Option<SystemTime>,
+    /// When the exercise first passed.
+    pub solved_at: Option<SystemTime>,
+}
+
+impl ExerciseStats {
+    /// Whether the exercise has passed at least once.
+    pub fn is_solved(&self) -> bool {
+        self.solved_at.is_some()
+    }
+
+    /// Time from the first attempt to the first success, if the exercise was solved.
+    pub fn time_to_completion(&self) -> Option<Duration> {
+        self.solved_at?.duration_since(self.first_attempt?).ok()
+    }
+}
+
+impl Stats {
+    /// Records a run of the exercise `id` at time `at`.
+    pub fn record_attempt(&mut self, id: &str, passed: bool, at: SystemTime) {
+        let stats = self.exercises.entry(id.to_string()).or_default();
+        stats.attempts += 1;
+        stats.first_attempt.get_or_insert(at);
+        if passed {
+            stats.solved_at.get_or_insert(at);
+        } else {
+            stats.failures += 1;
+        }
+    }
+
+    /// Records that hints of the exercise `id` were revealed up to `level`.
+    pub fn record_hint(&mut self, id: &str, level: usize) {
+        let stats = self.exercises.entry(id.to_string()).or_default();
+        stats.hints = stats.hints.max(level as u32);
+    }
+
+    /// The statistics of the exercise `id`, if it was ever attempted or hinted.
+    pub fn exercise(&self, id: &str) -> Option<&ExerciseStats> {
+        self.exercises.get(id)
+    }
+
+    /// Every exercise with statistics, by id.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ExerciseStats)> {
+        self.exercises.iter().map(|(id, stats)| (id.as_str(), stats))
+    }
+
+    /// Adds the answers of a graded quiz to the totals of their chapters.
+    /// ```
+    /// use learning_cell::curriculum::Chapter;
+    /// use learning_cell::progress::{QuizStats, Stats};
+    /// use learning_cell::quiz::Quiz;
+    ///
+    /// let quiz = Quiz::from_topic(Chapter::RefCell);
+    /// let mut stats = Stats::default();
+    /// stats.record_quiz(&quiz.grade(&[]));
+    ///
+    /// let asked = quiz.questions().len() as u32;
+    /// assert_eq!(stats.weakest_topics(), [(Chapter::RefCell, QuizStats { correct: 0, asked })]);
+    /// ```
+    pub fn record_quiz(&mut self, grade: &Grade) {
+        for result in &grade.results {
+            let stats = self.quizzes.entry(result.question.topic).or_default();
+            stats.asked += 1;
+            stats.correct += u32::from(result.correct);
+        }
+    }
+
+    /// The chapters quizzed so far, the lowest share of correct answers first.
+    pub fn weakest_topics(&self) -> Vec<(Chapter, QuizStats)> {
+        let mut topics: Vec<_> =
+            self.quizzes.iter().map(|(topic, stats)| (*topic, *stats)).collect();
+        // Compare `correct / asked` without dividing: a/b < c/d iff a*d < c*b.
+        topics.sort_by(|(_, a), (_, b)| {
+            (u64::from(a.correct) * u64::from(b.asked))
+                .cmp(&(u64::from(b.correct) * u64::from(a.asked)))
+        });
+        topics
+    }
+
+    /// The exercises that needed more than one attempt or any hint, the hardest first (most
+    /// failures, then most hints).
+    pub fn struggles(&self) -> Vec<(&str, &ExerciseStats)> {
+        let mut struggles: Vec<_> =
+            self.iter().filter(|(_, stats)| stats.failures > 0 || stats.hints > 0).collect();
+        struggles.sort_by_key(|(_, stats)| std::cmp::Reverse((stats.failures, stats.hints)));
+        struggles
+    }
+}
+
+/// Seconds since the Unix epoch, as stored in the progress file.
+fn to_seconds(time: Option<SystemTime>) -> String {
+    time.and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or_else(|| "-".to_string(), |since| since.as_secs().to_string())
+}
+
+fn from_seconds(text: &str) -> Option<Option<SystemTime>> {
+    match text {
+        "-" => Some(None),
+        seconds => Some(Some(UNIX_EPOCH + Duration::from_secs(seconds.parse().ok()?))),
+    }
+}
+
+/// The learner's saved progress.
+///
+/// The file format is line based: each line is a key followed by a space and a value, and lines
+/// starting with `#` are comments. Unknown keys are ignored so that older versions of the crate
+/// can read files written by newer ones.
+/// ```text
+/// # learning_cell progress
+/// points 42
+/// streak 1
+/// best_streak 3
+/// completed cell_intro
+/// badge first_steps
+/// stats cell_intro 3 2 1 1700000000 1700000420
+/// quiz refcell 7 10
+/// ```
+///
+/// A `stats` line holds an exercise id, its attempts, failures and deepest hint level, then the
+/// times of the first attempt and of the first success in seconds since the Unix epoch (`-` if
+/// there is none). A `quiz` line holds a chapter id and how many of the quiz questions about it
+/// were answered correctly, out of how many.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Points, streaks, completed items and badges.
+    pub score: Score,
+    /// Per-exercise attempts, hints and timing.
+    pub stats: Stats,
+}
+
+impl Progress {
+    /// Records a run of the exercise `id` in both the score and the statistics, returning the
+    /// badges it unlocked.
+    pub fn record_exercise(&mut self, id: &str, passed: bool, at: SystemTime) -> Vec<Badge> {
+        self.stats.record_attempt(id, passed, at);
+        self.score.record_exercise(id, passed)
+    }
+
+    /// Records a graded quiz in both the score and the statistics, returning the badges it
+    /// unlocked.
+    pub fn record_quiz(&mut self, grade: &Grade) -> Vec<Badge> {
+        self.stats.record_quiz(grade);
+        self.score.record_quiz(grade)
+    }
+
+    /// Forgets everything about the lesson or exercise `id`, as if it was never attempted.
+    ///
+    /// Points earned by solving the exercise are taken back; badges are kept.
+    pub fn forget(&mut self, id: &str) {
+        if self.score.completed.remove(id) && crate::exercises::get(id).is_some() {
+            self.score.points = self.score.points.saturating_sub(EXERCISE_POINTS);
+        }
+        self.stats.exercises.remove(id);
+    }
+
+    /// Reads progress from `path`. A missing file is treated as empty progress.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Progress> {
+        match fs::read_to_string(path) {
+            Ok(text) => Progress::parse(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Progress::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Reads progress from [`default_path`].
+    pub fn load_default() -> io::Result<Progress> {
+        Progress::load(default_path()?)
+    }
+
+    /// Writes progress to [`default_path`].
+    pub fn save_default(&self) -> io::Result<()> {
+        self.save(default_path()?)
+    }
+
+    /// Writes progress to `path`, creating parent directories as needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_text())
+    }
+
+    /// Parses the text format described on [`Progress`].
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use learning_cell::progress::{Badge, Progress};
+    ///
+    /// let mut progress = Progress::default();
+    /// progress.record_exercise("cell_intro", true, UNIX_EPOCH + Duration::from_secs(60));
+    /// progress.stats.record_hint("memo_fib", 1);
+    ///
+    /// let parsed = Progress::parse(&progress.to_text()).unwrap();
+    /// assert_eq!(parsed, progress);
+    /// assert!(parsed.score.badges().contains(&Badge::FirstSteps));
+    /// ```
+    pub fn parse(text: &str) -> io::Result<Progress> {
+        let mut score = Score::default();
+        let mut stats = Stats::default();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let invalid = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: invalid value for `{key}`: {value:?}", number + 1),
+                )
+            };
+            match key {
+                "points" => score.points = value.parse().map_err(|_| invalid())?,
+                "streak" => score.streak = value.parse().map_err(|_| invalid())?,
+                "best_streak" => score.best_streak = value.parse().map_err(|_| invalid())?,
+                "completed" => {
+                    score.completed.insert(value.to_string());
+                }
+                "badge" => {
+                    score.badges.insert(Badge::from_id(value).ok_or_else(invalid)?);
+                }
+                "stats" => {
+                    let (id, exercise) = parse_stats(value).ok_or_else(invalid)?;
+                    stats.exercises.insert(id, exercise);
+                }
+                "quiz" => {
+                    let (topic, quiz) = parse_quiz(value).ok_or_else(invalid)?;
+                    stats.quizzes.insert(topic, quiz);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Progress { score, stats })
+    }
+
+    /// Serializes to the text format described on [`Progress`].
+    pub fn to_text(&self) -> String {
+        let score = &self.score;
+        let mut text = String::from("# learning_cell progress\n");
+        text.push_str(&format!("points {}\n", score.points));
+        text.push_str(&format!("streak {}\n", score.streak));
+        text.push_str(&format!("best_streak {}\n", score.best_streak));
+        for id in &score.completed {
+            text.push_str(&format!("completed {id}\n"));
+        }
+        for badge in &score.badges {
+            text.push_str(&format!("badge {}\n", badge.id()));
+        }
+        for (id, stats) in self.stats.iter() {
+            text.push_str(&format!(
+                "stats {id} {} {} {} {} {}\n",
+                stats.attempts,
+                stats.failures,
+                stats.hints,
+                to_seconds(stats.first_attempt),
+                to_seconds(stats.solved_at),
+            ));
+        }
+        for (topic, quiz) in &self.stats.quizzes {
+            text.push_str(&format!("quiz {} {} {}\n", topic.id(), quiz.correct, quiz.asked));
+        }
+        text
+    }
+}
+
+/// Progress packed up for another machine or an instructor.
+///
+/// An export is the [`Progress`] file format with a header: a `format` line that must come
+/// first, followed by who exported it and when. A reader refuses exports with a `format` newer
+/// than [`FORMAT_VERSION`], rather than silently dropping what it does not understand.
+/// ```text
+/// # learning_cell progress export
+/// format 1
+/// learner ada
+/// exported 1700000000
+/// points 10
+/// completed cell_intro
+/// badge first_steps
+/// ```
+/// Version 1 is the only version so far.
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use learning_cell::progress::{Export, Progress};
+///
+/// let mut progress = Progress::default();
+/// progress.record_exercise("cell_intro", true, UNIX_EPOCH + Duration::from_secs(60));
+///
+/// let at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// let export = Export { learner: Some("ada".to_string()), exported_at: at, progress };
+/// assert_eq!(Export::parse(&export.to_text()).unwrap(), export);
+///
+/// assert!(Export::parse("format 2\npoints 10\n").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    /// Who the progress belongs to, if known.
+    pub learner: Option<String>,
+    /// When the export was written, to the second.
+    pub exported_at: SystemTime,
+    /// The exported progress.
+    pub progress: Progress,
+}
+
+impl Export {
+    /// Serializes to the format described on [`Export`].
+    pub fn to_text(&self) -> String {
+        let mut text = format!("# learning_cell progress export\nformat {FORMAT_VERSION}\n");
+        if let Some(learner) = &self.learner {
+            text.push_str(&format!("learner {learner}\n"));
+        }
+        text.push_str(&format!("exported {}\n", to_seconds(Some(self.exported_at))));
+        // Skip the comment line of the plain progress file.
+        for line in self.progress.to_text().lines().filter(|line| !line.starts_with('#')) {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Parses the format described on [`Export`].
+    pub fn parse(text: &str) -> io::Result<Export> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut lines =
+            text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#'));
+        let version = lines
+            .next()
+            .and_then(|line| line.strip_prefix("format "))
+            .ok_or_else(|| invalid("not a progress export: missing `format` line".to_string()))?;
+        let version: u32 =
+            version.parse().map_err(|_| invalid(format!("invalid format version {version:?}")))?;
+        if version > FORMAT_VERSION {
+            return Err(invalid(format!(
+                "the export has format {version}, but this version of learning_cell only \
+                 reads up to {FORMAT_VERSION}; please update"
+            )));
+        }
+
+        let (mut learner, mut exported_at) = (None, None);
+        for line in lines {
+            match line.split_once(' ') {
+                Some(("learner", name)) => learner = Some(name.to_string()),
+                Some(("exported", seconds)) => {
+                    let time = from_seconds(seconds).flatten();
+                    exported_at = Some(
+                        time.ok_or_else(|| invalid(format!("invalid export time {seconds:?}")))?,
+                    );
+                }
+                _ => {}
+            }
+        }
+        // The progress parser skips the header keys it does not know.
+        let progress = Progress::parse(text)?;
+        let exported_at =
+            exported_at.ok_or_else(|| invalid("missing `exported` line".to_string()))?;
+        Ok(Export { learner, exported_at, progress })
+    }
+}
+
+/// Where progress is stored when no path is given.
+///
+/// That is the file named by the [`PATH_VAR`] environment variable if it is set, and otherwise
+/// `learning_cell/progress.txt` in the platform's data directory:
+///
+/// - Linux and other Unixes: `$XDG_DATA_HOME`, falling back to `~/.local/share`
+/// - macOS: `~/Library/Application Support`
+/// - Windows: `%APPDATA%`
+/// ```
+/// use learning_cell::progress::{self, PATH_VAR};
+///
+/// std::env::set_var(PATH_VAR, "/tmp/my-progress.txt");
+/// assert_eq!(progress::default_path().unwrap().to_str(), Some("/tmp/my-progress.txt"));
+/// ```
+pub fn default_path() -> io::Result<PathBuf> {
+    if let Some(path) = env::var_os(PATH_VAR) {
+        return Ok(PathBuf::from(path));
+    }
+    let var = |name| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    let data_dir = if cfg!(windows) {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+    };
+    let data_dir = data_dir.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "cannot find a data directory, set {PATH_VAR} to choose where to save progress"
+            ),
+        )
+    })?;
+    Ok(data_dir.join("learning_cell").join("progress.txt"))
+}
+
+/// Parses the value of a `stats` line.
+fn parse_stats(value: &str) -> Option<(String, ExerciseStats)> {
+    let fields: Vec<_> = value.split(' ').collect();
+    let [id, attempts, failures, hints, first_attempt, solved_at] = fields[..] else {
+        return None;
+    };
+    let stats = ExerciseStats {
+        attempts: attempts.parse().ok()?,
+        failures: failures.parse().ok()?,
+        hints: hints.parse().ok()?,
+        first_attempt: from_seconds(first_attempt)?,
+        solved_at: from_seconds(solved_at)?,
+    };
+    Some((id.to_string(), stats))
+}
+
+/// Parses the value of a `quiz` line.
+fn parse_quiz(value: &str) -> Option<(Chapter, QuizStats)> {
+    let fields: Vec<_> = value.split(' ').collect();
+    let [topic, correct, asked] = fields[..] else {
+        return None;
+    };
+    let quiz = QuizStats { correct: correct.parse().ok()?, asked: asked.parse().ok()? };
+    Some((Chapter::from_id(topic)?, quiz))
+}
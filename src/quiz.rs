@@ -0,0 +1,565 @@
+//! Multiple-choice questions about the lessons.
+//!
+//! Every question in the [`bank`] knows how to [`observe`](Question::observe) its own answer by
+//! actually running the code it asks about, so the answer key can never drift away from what the
+//! standard library really does:
+//! ```
+//! for question in learning_cell::quiz::bank() {
+//!     assert_eq!(question.observe(), question.answer, "{}", question.id);
+//! }
+//! ```
+//!
+//! For classroom use, [`generate`] builds a quiz from randomized variants of the questions
+//! (different values, shuffled options). The same seed always produces the same quiz, so every
+//! student can get a different but reproducible one:
+//! ```
+//! use learning_cell::quiz;
+//!
+//! let a = quiz::generate(42, 5, None);
+//! let b = quiz::generate(42, 5, None);
+//! assert_eq!(a.questions().len(), 5);
+//! for (a, b) in a.questions().iter().zip(b.questions()) {
+//!     assert_eq!(a.prompt, b.prompt);
+//!     assert_eq!(a.options, b.options);
+//!     assert_eq!(a.observe(), a.answer);
+//! }
+//! ```
+//!
+//! A [`Quiz`] is a list of questions that can be graded:
+//! ```
+//! use learning_cell::curriculum::Chapter;
+//! use learning_cell::quiz::Quiz;
+//!
+//! let quiz = Quiz::from_topic(Chapter::RefCell);
+//! let answers: Vec<_> = quiz.questions().iter().map(|q| Some(q.answer)).collect();
+//!
+//! let grade = quiz.grade(&answers);
+//! assert_eq!(grade.correct, grade.total);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::curriculum::Chapter;
+use crate::util::{catch_panic, Rng};
+
+/// A multiple-choice question.
+#[derive(Clone)]
+pub struct Question {
+    /// Stable identifier.
+    pub id: String,
+    /// The chapter the question is about.
+    pub topic: Chapter,
+    /// The question itself.
+    pub prompt: String,
+    /// The possible answers.
+    pub options: Vec<String>,
+    /// Index of the correct option.
+    pub answer: usize,
+    /// Why the correct option is correct, shown after grading.
+    pub explanation: String,
+    /// The section to re-read: the anchor of one of the
+    /// [`search::sections`](crate::search::sections), e.g. `"refcell#example-7"`.
+    pub section: &'static str,
+    observe: Rc<dyn Fn() -> usize>,
+}
+
+impl Question {
+    /// Runs the code the question is about and returns the index of the option that matches the
+    /// observed behavior.
+    pub fn observe(&self) -> usize {
+        (self.observe)()
+    }
+}
+
+impl Question {
+    /// Returns the same question with its options in a random order.
+    pub fn shuffled(mut self, rng: &mut Rng) -> Question {
+        let mut order: Vec<usize> = (0..self.options.len()).collect();
+        rng.shuffle(&mut order);
+
+        // `position[old]` is where the option that used to be at `old` ended up.
+        let mut position = vec![0; order.len()];
+        for (new, old) in order.iter().enumerate() {
+            position[*old] = new;
+        }
+
+        self.options = order.iter().map(|old| self.options[*old].clone()).collect();
+        self.answer = position[self.answer];
+        let observe = self.observe;
+        self.observe = Rc::new(move || position[observe()]);
+        self
+    }
+}
+
+impl fmt::Debug for Question {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Question")
+            .field("id", &self.id)
+            .field("topic", &self.topic)
+            .field("prompt", &self.prompt)
+            .field("options", &self.options)
+            .field("answer", &self.answer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for Question {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.prompt)?;
+        for (index, option) in self.options.iter().enumerate() {
+            writeln!(f, "  {}) {option}", option_letter(index))?;
+        }
+        Ok(())
+    }
+}
+
+/// The letter used to show the option at `index` (`a`, `b`, ...).
+pub fn option_letter(index: usize) -> char {
+    (b'a' + index as u8) as char
+}
+
+#[allow(clippy::too_many_arguments)]
+fn question(
+    id: &str,
+    topic: Chapter,
+    prompt: &str,
+    options: &[&str],
+    answer: usize,
+    explanation: &str,
+    section: &'static str,
+    observe: impl Fn() -> usize + 'static,
+) -> Question {
+    Question {
+        id: id.to_string(),
+        topic,
+        prompt: prompt.to_string(),
+        options: options.iter().map(|option| option.to_string()).collect(),
+        answer,
+        explanation: explanation.to_string(),
+        section,
+        observe: Rc::new(observe),
+    }
+}
+
+/// Index of the only snippet in `snippets` that panics.
+fn panicking(snippets: &[fn()]) -> usize {
+    let panicked: Vec<_> = snippets.iter().map(|snippet| catch_panic(snippet).is_err()).collect();
+    assert_eq!(panicked.iter().filter(|p| **p).count(), 1, "exactly one snippet should panic");
+    panicked.iter().position(|p| *p).unwrap()
+}
+
+/// Every built-in question.
+pub fn bank() -> Vec<Question> {
+    vec![
+        cell_replace_returns(),
+        cell_take_leaves_default(),
+        refcell_which_panics(),
+        refcell_try_borrow_mut_while_shared(),
+        refcell_replace_while_borrowed(),
+        refcell_many_shared_borrows(),
+        threads_channel_closes_itself(),
+    ]
+}
+
+fn cell_replace_returns() -> Question {
+    question(
+        "cell_replace_returns",
+        Chapter::Cell,
+        "let c = Cell::new(3); What does `c.replace(7)` return?",
+        &["3", "7", "()", "10"],
+        0,
+        "`replace` puts the new value in and hands back the old one.",
+        "cell#example-5",
+        || {
+            let c = Cell::new(3);
+            match c.replace(7) {
+                3 => 0,
+                7 => 1,
+                _ => 3,
+            }
+        },
+    )
+}
+
+fn cell_take_leaves_default() -> Question {
+    question(
+        "cell_take_leaves_default",
+        Chapter::Cell,
+        "let c = Cell::new(\"hi\".to_string()); What is left inside `c` after `c.take()`?",
+        &["\"hi\"", "An empty String", "Nothing, `take` consumes the Cell", "It panics"],
+        1,
+        "`take` swaps in `Default::default()`, which for `String` is the empty string.",
+        "cell#example-5",
+        || {
+            let c = Cell::new("hi".to_string());
+            match catch_panic(|| c.take()) {
+                Err(_) => 3,
+                Ok(_) if c.take().is_empty() => 1,
+                Ok(_) => 0,
+            }
+        },
+    )
+}
+
+fn refcell_which_panics() -> Question {
+    question(
+        "refcell_which_panics",
+        Chapter::RefCell,
+        "Given `let c = RefCell::new(1);`, which of these panics?",
+        &[
+            "let _a = c.borrow(); let _b = c.borrow();",
+            "let _a = c.borrow(); let _b = c.borrow_mut();",
+            "{ let _a = c.borrow_mut(); } let _b = c.borrow_mut();",
+            "let _a = c.borrow(); let _b = c.try_borrow_mut();",
+        ],
+        1,
+        "A mutable borrow cannot coexist with any other borrow, and `borrow_mut` panics \
+         instead of returning an error. The third option drops the first guard before the \
+         second borrow, and `try_borrow_mut` returns an `Err` instead of panicking.",
+        "refcell#example-6",
+        || {
+            panicking(&[
+                || {
+                    let c = RefCell::new(1);
+                    let _a = c.borrow();
+                    let _b = c.borrow();
+                },
+                || {
+                    let c = RefCell::new(1);
+                    let _a = c.borrow();
+                    let _b = c.borrow_mut();
+                },
+                || {
+                    let c = RefCell::new(1);
+                    {
+                        let _a = c.borrow_mut();
+                    }
+                    let _b = c.borrow_mut();
+                },
+                || {
+                    let c = RefCell::new(1);
+                    let _a = c.borrow();
+                    let _b = c.try_borrow_mut();
+                },
+            ])
+        },
+    )
+}
+
+fn refcell_try_borrow_mut_while_shared() -> Question {
+    question(
+        "refcell_try_borrow_mut_while_shared",
+        Chapter::RefCell,
+        "What does `try_borrow_mut` do while a `borrow()` guard of the same RefCell is alive?",
+        &[
+            "Returns Ok(RefMut)",
+            "Returns Err(BorrowMutError)",
+            "Panics",
+            "Blocks until the guard is dropped",
+        ],
+        1,
+        "The `try_` variants report the conflict as a `Result` instead of panicking. A RefCell \
+         never blocks: it is single-threaded, so nobody else could release the borrow.",
+        "refcell#example-7",
+        || {
+            let c = RefCell::new(1);
+            let _guard = c.borrow();
+            match catch_panic(|| c.try_borrow_mut().is_ok()) {
+                Ok(true) => 0,
+                Ok(false) => 1,
+                Err(_) => 2,
+            }
+        },
+    )
+}
+
+fn refcell_replace_while_borrowed() -> Question {
+    question(
+        "refcell_replace_while_borrowed",
+        Chapter::RefCell,
+        "What happens when you call `replace` on a RefCell while a shared borrow is alive?",
+        &["The value is replaced", "It returns an Err", "It panics", "It blocks"],
+        2,
+        "`replace` needs exclusive access to move the old value out, so it behaves like \
+         `borrow_mut` and panics.",
+        "refcell#example-6",
+        || {
+            let c = RefCell::new(1);
+            let _guard = c.borrow();
+            match catch_panic(|| c.replace(2)) {
+                Ok(_) => 0,
+                Err(_) => 2,
+            }
+        },
+    )
+}
+
+fn refcell_many_shared_borrows() -> Question {
+    question(
+        "refcell_many_shared_borrows",
+        Chapter::RefCell,
+        "How many `borrow()` guards of the same RefCell can be alive at once?",
+        &["Exactly one", "Any number", "Two", "None, `borrow` consumes the RefCell"],
+        1,
+        "Shared borrows only increment the borrow counter; any number of them can coexist as \
+         long as there is no mutable borrow.",
+        "refcell#example-1",
+        || {
+            let c = RefCell::new(1);
+            let guards: Vec<_> = (0..3).map(|_| c.try_borrow()).collect();
+            match guards.iter().filter(|guard| guard.is_ok()).count() {
+                1 => 0,
+                2 => 2,
+                _ => 1,
+            }
+        },
+    )
+}
+
+fn threads_channel_closes_itself() -> Question {
+    question(
+        "threads_channel_closes_itself",
+        Chapter::Threads,
+        "Every `Sender` of an mpsc channel has been dropped and its queue is drained. What does \
+         `receiver.recv()` do? (A `Mutex<VecDeque>` + `Condvar` queue needs a `closed` flag for \
+         the same job.)",
+        &[
+            "Returns `Err(RecvError)`",
+            "Blocks forever",
+            "Returns `Ok` with a default value",
+            "Panics",
+        ],
+        0,
+        "A channel knows how many senders are alive, so it can tell the receiver that nothing \
+         more will come. With shared state, the producer has to set a flag and wake the \
+         consumers itself; forget the `notify_all` and they wait forever.",
+        "producer_consumer",
+        || {
+            let (sender, receiver) = std::sync::mpsc::channel::<u8>();
+            drop(sender);
+            match receiver.recv() {
+                Err(_) => 0,
+                Ok(_) => 2,
+            }
+        },
+    )
+}
+
+/// A question template: produces a fresh variant of a question from a random source.
+pub type Template = fn(&mut Rng) -> Question;
+
+/// Every built-in template. Questions of the [`bank`] that have no values to vary are templates
+/// whose variants only differ in option order.
+pub fn templates() -> Vec<(Chapter, Template)> {
+    vec![
+        (Chapter::Cell, cell_replace_template),
+        (Chapter::Cell, cell_swap_template),
+        (Chapter::Cell, |rng| cell_take_leaves_default().shuffled(rng)),
+        (Chapter::RefCell, refcell_shared_guards_template),
+        (Chapter::RefCell, |rng| refcell_which_panics().shuffled(rng)),
+        (Chapter::RefCell, |rng| refcell_try_borrow_mut_while_shared().shuffled(rng)),
+        (Chapter::RefCell, |rng| refcell_replace_while_borrowed().shuffled(rng)),
+        (Chapter::RefCell, |rng| refcell_many_shared_borrows().shuffled(rng)),
+        (Chapter::Threads, |rng| threads_channel_closes_itself().shuffled(rng)),
+    ]
+}
+
+/// Generates a quiz of `count` randomized questions, optionally restricted to `topic`.
+///
+/// Templates are used round-robin in a seed-dependent order, so a quiz only repeats a template
+/// once all of them have been used.
+pub fn generate(seed: u64, count: usize, topic: Option<Chapter>) -> Quiz {
+    let mut rng = Rng::new(seed);
+    let mut templates: Vec<_> = templates()
+        .into_iter()
+        .filter(|(chapter, _)| topic.is_none_or(|topic| topic == *chapter))
+        .map(|(_, template)| template)
+        .collect();
+    rng.shuffle(&mut templates);
+
+    let questions =
+        templates.iter().cycle().take(count).map(|template| template(&mut rng)).collect();
+    Quiz::new(questions)
+}
+
+/// Three distinct values in `-50..50`.
+fn distinct(rng: &mut Rng) -> (i64, i64, i64) {
+    let a = rng.range(-50..50);
+    let b = loop {
+        let b = rng.range(-50..50);
+        if b != a {
+            break b;
+        }
+    };
+    let c = loop {
+        let c = rng.range(-50..50);
+        if c != a && c != b {
+            break c;
+        }
+    };
+    (a, b, c)
+}
+
+fn cell_replace_template(rng: &mut Rng) -> Question {
+    let (a, b, c) = distinct(rng);
+    question(
+        "cell_replace_returns",
+        Chapter::Cell,
+        &format!("let c = Cell::new({a}); What does `c.replace({b})` return?"),
+        &[&a.to_string(), &b.to_string(), "()", &c.to_string()],
+        0,
+        "`replace` puts the new value in and hands back the old one.",
+        "cell#example-5",
+        move || {
+            let cell = Cell::new(a);
+            match cell.replace(b) {
+                x if x == a => 0,
+                x if x == b => 1,
+                _ => 3,
+            }
+        },
+    )
+    .shuffled(rng)
+}
+
+fn cell_swap_template(rng: &mut Rng) -> Question {
+    let (a, b, c) = distinct(rng);
+    question(
+        "cell_swap",
+        Chapter::Cell,
+        &format!("let x = Cell::new({a}); let y = Cell::new({b}); x.swap(&y); What is `x.get()`?"),
+        &[&b.to_string(), &a.to_string(), &c.to_string(), "It does not compile"],
+        0,
+        "`swap` exchanges the values of two cells through shared references.",
+        "cell#example-5",
+        move || {
+            let x = Cell::new(a);
+            let y = Cell::new(b);
+            x.swap(&y);
+            match x.get() {
+                v if v == b => 0,
+                v if v == a => 1,
+                _ => 2,
+            }
+        },
+    )
+    .shuffled(rng)
+}
+
+fn refcell_shared_guards_template(rng: &mut Rng) -> Question {
+    let guards = 1 + rng.below(5);
+    question(
+        "refcell_shared_guards",
+        Chapter::RefCell,
+        &format!(
+            "While {guards} `borrow()` guard(s) of a RefCell are alive, what does \
+             `try_borrow_mut()` return?"
+        ),
+        &["Ok(RefMut)", "Err(BorrowMutError)", "It panics", "It blocks"],
+        1,
+        "Any number of shared borrows blocks a mutable borrow, and `try_borrow_mut` reports \
+         that as an `Err`.",
+        "refcell#example-7",
+        move || {
+            let cell = RefCell::new(0);
+            let _guards: Vec<_> = (0..guards).map(|_| cell.borrow()).collect();
+            match catch_panic(|| cell.try_borrow_mut().is_ok()) {
+                Ok(true) => 0,
+                Ok(false) => 1,
+                Err(_) => 2,
+            }
+        },
+    )
+    .shuffled(rng)
+}
+
+/// A list of questions that can be answered and graded.
+#[derive(Debug, Clone)]
+pub struct Quiz {
+    questions: Vec<Question>,
+}
+
+impl Quiz {
+    /// Creates a quiz from the given questions.
+    pub fn new(questions: Vec<Question>) -> Self {
+        Self { questions }
+    }
+
+    /// Creates a quiz from every question of the bank about `topic`.
+    pub fn from_topic(topic: Chapter) -> Self {
+        Self::new(bank().into_iter().filter(|question| question.topic == topic).collect())
+    }
+
+    /// The questions, in order.
+    pub fn questions(&self) -> &[Question] {
+        &self.questions
+    }
+
+    /// Grades the answers. `answers[i]` is the option chosen for question `i`, or `None` if it
+    /// was skipped. Missing answers count as skipped.
+    pub fn grade(&self, answers: &[Option<usize>]) -> Grade {
+        let results: Vec<_> = self
+            .questions
+            .iter()
+            .enumerate()
+            .map(|(index, question)| {
+                let given = answers.get(index).copied().flatten();
+                Graded {
+                    question: question.clone(),
+                    given,
+                    correct: given == Some(question.answer),
+                }
+            })
+            .collect();
+
+        Grade {
+            correct: results.iter().filter(|result| result.correct).count(),
+            total: results.len(),
+            results,
+        }
+    }
+}
+
+/// The result of grading a [`Quiz`].
+#[derive(Debug, Clone)]
+pub struct Grade {
+    /// Number of correctly answered questions.
+    pub correct: usize,
+    /// Number of questions in the quiz.
+    pub total: usize,
+    /// One entry per question, in order.
+    pub results: Vec<Graded>,
+}
+
+/// A single graded question.
+#[derive(Debug, Clone)]
+pub struct Graded {
+    /// The question.
+    pub question: Question,
+    /// The option that was chosen, if any.
+    pub given: Option<usize>,
+    /// Whether the chosen option is the correct one.
+    pub correct: bool,
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}/{} correct", self.correct, self.total)?;
+        for result in &self.results {
+            let question = &result.question;
+            let mark = if result.correct { "ok" } else { "wrong" };
+            writeln!(f, "\n[{mark}] {}", question.prompt)?;
+            writeln!(
+                f,
+                "  answer: {}) {}",
+                option_letter(question.answer),
+                question.options[question.answer]
+            )?;
+            writeln!(f, "  {}", question.explanation)?;
+            writeln!(f, "  see {}", question.section)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+//! Builds and runs code snippets, for "edit and re-run this example".
+//!
+//! The lessons' `compile_fail` and `should_panic` examples are most instructive when the
+//! learner can change them and watch the error go away (or move). A [`Runner`] writes a snippet
+//! into a scratch cargo crate, builds it, runs it and reports what happened as an [`Outcome`]:
+//! ```no_run
+//! use learning_cell::runner::{Outcome, Runner};
+//!
+//! let runner = Runner::in_temp_dir();
+//! let outcome = runner.run("let x = 1; x = 2;").unwrap();
+//! assert!(matches!(outcome, Outcome::CompileError(ref message) if message.contains("E0384")));
+//! ```
+//! Like a doctest, a snippet without a `fn main` is wrapped in one, and it can use this crate as
+//! `learning_cell`, taken from the [source](Runner::source) it was built from. Each runner gets a
+//! scratch crate of its own, so runners never overwrite each other's snippets, but they share
+//! one target directory: only the first run on the machine pays for compiling `learning_cell`.
+//!
+//! The runner isolates snippets from the course, not the machine from the snippets: they run as
+//! the learner, with a time limit but no other restrictions.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Environment variable pointing the runners at a `learning_cell` source tree, see
+/// [`Runner::source`].
+pub const SOURCE_VAR: &str = "LEARNING_CELL_SOURCE";
+
+/// Number of runners created by [`Runner::in_temp_dir`] so far in this process.
+static TEMP_RUNNERS: AtomicUsize = AtomicUsize::new(0);
+
+/// What happened to a snippet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The snippet compiled and ran to completion.
+    Ran {
+        /// What it printed to standard output.
+        stdout: String,
+    },
+    /// The compiler rejected the snippet. Holds its diagnostics.
+    CompileError(String),
+    /// The snippet compiled, then panicked. Holds the panic message.
+    Panicked(String),
+    /// The build or the run took longer than the [time limit](Runner::timeout).
+    TimedOut,
+}
+
+/// Builds and runs snippets in a scratch crate.
+#[derive(Debug)]
+pub struct Runner {
+    dir: PathBuf,
+    target_dir: PathBuf,
+    /// Name of the scratch crate, and so of the snippet's binary in `target_dir`.
+    name: String,
+    /// Whether `dir` is ours to delete once the runner is dropped.
+    temporary: bool,
+    source: Option<PathBuf>,
+    timeout: Duration,
+    features: Vec<String>,
+}
+
+impl Runner {
+    /// A runner keeping its scratch crate, and the build artifacts, in `dir`, which is created
+    /// as needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Runner {
+        let dir = dir.into();
+        Runner {
+            target_dir: dir.join("target"),
+            dir,
+            name: "snippet".to_string(),
+            temporary: false,
+            source: default_source(),
+            timeout: Duration::from_secs(60),
+            features: Vec::new(),
+        }
+    }
+
+    /// A runner with a fresh scratch crate in the system's temporary directory, which is deleted
+    /// when the runner is dropped. The build artifacts go to a target directory shared by all
+    /// such runners; cargo's lock on it keeps concurrent builds apart.
+    pub fn in_temp_dir() -> Runner {
+        let temp = std::env::temp_dir();
+        let number = TEMP_RUNNERS.fetch_add(1, Ordering::Relaxed);
+        let id = format!("{}_{number}", std::process::id());
+        let mut runner = Runner::new(temp.join(format!("learning_cell-runner-{id}")));
+        runner.target_dir = temp.join("learning_cell-runner-target");
+        runner.name = format!("snippet_{id}");
+        runner.temporary = true;
+        runner
+    }
+
+    /// Takes `learning_cell` from the source tree at `dir`, instead of the default: the tree
+    /// named by [`$LEARNING_CELL_SOURCE`](SOURCE_VAR), else the one this crate was built from if
+    /// it is still there, else the published release of the same version.
+    pub fn source(mut self, dir: impl Into<PathBuf>) -> Runner {
+        self.source = Some(dir.into());
+        self
+    }
+
+    /// Sets the time limit of the build and of the run, each. Defaults to a minute, which leaves
+    /// time for the first build.
+    pub fn timeout(mut self, timeout: Duration) -> Runner {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Enables `feature` of this crate for the snippets, e.g. `derive`.
+    pub fn feature(mut self, feature: &str) -> Runner {
+        self.features.push(feature.to_string());
+        self
+    }
+
+    /// Builds and runs `snippet`.
+    ///
+    /// Errors are about the runner itself, like `cargo` missing; anything wrong with the snippet
+    /// is an [`Outcome`].
+    pub fn run(&self, snippet: &str) -> io::Result<Outcome> {
+        self.write_crate(snippet)?;
+
+        let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let mut build = Command::new(cargo);
+        build.args(["build", "--quiet", "--color", "never", "--target-dir"]).arg(&self.target_dir);
+        // A local source builds with what is already downloaded; a release may need fetching.
+        if self.source.is_some() {
+            build.arg("--offline");
+        }
+        let build =
+            build.current_dir(&self.dir).stdout(Stdio::null()).stderr(Stdio::piped()).spawn()?;
+        let Some((success, _, stderr)) = wait(build, self.timeout)? else {
+            return Ok(Outcome::TimedOut);
+        };
+        if !success {
+            return Ok(Outcome::CompileError(diagnostics(&stderr)));
+        }
+
+        let binary = self.binary();
+        let run = Command::new(binary)
+            .env("RUST_BACKTRACE", "0")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        Ok(match wait(run, self.timeout)? {
+            None => Outcome::TimedOut,
+            Some((true, stdout, _)) => Outcome::Ran { stdout },
+            Some((false, _, stderr)) => Outcome::Panicked(panic_message(&stderr)),
+        })
+    }
+
+    fn binary(&self) -> PathBuf {
+        let binary = format!("{}{}", self.name, std::env::consts::EXE_SUFFIX);
+        self.target_dir.join("debug").join(binary)
+    }
+
+    fn write_crate(&self, snippet: &str) -> io::Result<()> {
+        let source = match &self.source {
+            Some(dir) => format!("path = {:?}", dir.display().to_string()),
+            None => format!("version = \"={}\"", env!("CARGO_PKG_VERSION")),
+        };
+        let manifest = format!(
+            "[package]\nname = {:?}\nversion = \"0.0.0\"\nedition = \"2021\"\n\n\
+             [dependencies]\nlearning_cell = {{ {source}, features = {:?} }}\n",
+            self.name, self.features
+        );
+        fs::create_dir_all(self.dir.join("src"))?;
+        fs::write(self.dir.join("Cargo.toml"), manifest)?;
+        fs::write(self.dir.join("src").join("main.rs"), program(snippet))
+    }
+}
+
+impl Drop for Runner {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = fs::remove_dir_all(&self.dir);
+            let _ = fs::remove_file(self.binary());
+        }
+    }
+}
+
+/// Where runners take `learning_cell` from by default, see [`Runner::source`]. `None` stands for
+/// the published release.
+fn default_source() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(SOURCE_VAR).filter(|dir| !dir.is_empty()) {
+        return Some(dir.into());
+    }
+    let built_from = Path::new(env!("CARGO_MANIFEST_DIR"));
+    built_from.join("Cargo.toml").is_file().then(|| built_from.to_path_buf())
+}
+
+/// The program [`Runner::run`] builds for `snippet`: the snippet, wrapped in a `fn main` if it
+/// has none, with lints about unused code turned off. The line numbers in the diagnostics of a
+/// [`Outcome::CompileError`] refer to it.
+/// ```
+/// let program = learning_cell::runner::program("let x = 1;");
+/// assert_eq!(program, "#![allow(unused)]\nfn main() {\nlet x = 1;\n}\n");
+/// ```
+pub fn program(snippet: &str) -> String {
+    if snippet.contains("fn main") {
+        format!("#![allow(unused)]\n{snippet}")
+    } else {
+        format!("#![allow(unused)]\nfn main() {{\n{snippet}\n}}\n")
+    }
+}
+
+/// Waits for `child` for at most `timeout`, returning whether it succeeded and its output, or
+/// `None` if it had to be killed.
+fn wait(mut child: Child, timeout: Duration) -> io::Result<Option<(bool, String, String)>> {
+    // Read the pipes on threads, so a chatty child cannot fill them up and block.
+    let reader = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut text = String::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_string(&mut text);
+            }
+            text
+        })
+    };
+    let stdout = reader(child.stdout.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+    let stderr = reader(child.stderr.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > timeout {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    Ok(Some((status.success(), stdout, stderr)))
+}
+
+/// The compiler's diagnostics in cargo's output, without the summary lines cargo adds.
+fn diagnostics(stderr: &str) -> String {
+    let lines = stderr.lines().filter(|line| {
+        !line.starts_with("error: could not compile") && !line.starts_with("warning: `snippet")
+    });
+    lines.map(|line| format!("{line}\n")).collect::<String>().trim_end().to_string()
+}
+
+/// The message of the panic reported in `stderr`, or all of `stderr` if there is none.
+fn panic_message(stderr: &str) -> String {
+    let mut lines = stderr.lines().skip_while(|line| !line.contains("panicked at"));
+    if lines.next().is_none() {
+        return stderr.trim_end().to_string();
+    }
+    let message: Vec<_> = lines.take_while(|line| !line.starts_with("note: ")).collect();
+    message.join("\n")
+}
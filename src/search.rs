@@ -0,0 +1,138 @@
+//! Full-text search over the course.
+//!
+//! The lessons are compiled into the crate (see [`lessons`]) and so are the exercise
+//! descriptions and hints, so the index is built from static data the first time it is needed
+//! and never touches the disk. Each lesson is split into its paragraphs and examples, each
+//! exercise into its description and its hints, and every piece is a [`Section`] with an
+//! anchor pointing back at it:
+//!
+//! - `refcell#paragraph-3`: the third paragraph of the `RefCell` lesson,
+//! - `refcell#example-2`: its second code example, numbered like `learning_cell run` does,
+//! - `interner`: the description of the `interner` exercise,
+//! - `interner#hints`: its hints.
+//!
+//! ```
+//! let hits = learning_cell::search::search("try_borrow_mut");
+//! assert!(hits[0].section.anchor.starts_with("refcell#"));
+//! ```
+
+use std::sync::OnceLock;
+
+use crate::curriculum::Chapter;
+use crate::exercises;
+use crate::lessons::{self, Block};
+
+/// A searchable piece of the course.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Where the text lives, e.g. `refcell#example-2` or `interner#hints`.
+    pub anchor: String,
+    /// The lesson or exercise the section belongs to.
+    pub title: String,
+    /// The searched text.
+    pub text: String,
+}
+
+/// A section matching a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    /// The matching section.
+    pub section: &'static Section,
+    /// Higher is better. Matches in the anchor or title count more than matches in the text.
+    pub score: usize,
+    /// The first line of the section that contains a search term.
+    pub snippet: String,
+}
+
+/// Every section of the course, in course order.
+pub fn sections() -> &'static [Section] {
+    static INDEX: OnceLock<Vec<Section>> = OnceLock::new();
+    INDEX.get_or_init(build)
+}
+
+/// The sections containing every word of `query`, best match first.
+///
+/// Matching ignores case. Sections with the same score keep their course order.
+pub fn search(query: &str) -> Vec<Hit> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let mut hits: Vec<Hit> = sections()
+        .iter()
+        .filter_map(|section| {
+            let text = section.text.to_lowercase();
+            // The anchor holds the exercise id, which learners often search for.
+            let title = format!("{} {}", section.anchor, section.title).to_lowercase();
+            let mut score = 0;
+            for term in &terms {
+                let (in_text, in_title) =
+                    (text.matches(term.as_str()).count(), title.contains(term));
+                if in_text == 0 && !in_title {
+                    return None;
+                }
+                score += in_text + if in_title { 3 } else { 0 };
+            }
+            let snippet = section
+                .text
+                .lines()
+                .find(|line| terms.iter().any(|term| line.to_lowercase().contains(term.as_str())))
+                .unwrap_or(&section.title)
+                .trim()
+                .to_string();
+            Some(Hit { section, score, snippet })
+        })
+        .collect();
+    hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+    hits
+}
+
+fn build() -> Vec<Section> {
+    let mut sections = Vec::new();
+    for chapter in Chapter::ALL {
+        for lesson in lessons::all().into_iter().filter(|lesson| lesson.chapter() == chapter) {
+            let page = lesson.page();
+            let title = format!("{} lesson", lesson.title());
+            let (mut paragraphs, mut examples) = (0, 0);
+            for block in &page.blocks {
+                match block {
+                    Block::Text(text) => {
+                        for paragraph in text.split("\n\n").filter(|p| !p.trim().is_empty()) {
+                            paragraphs += 1;
+                            sections.push(Section {
+                                anchor: format!("{}#paragraph-{paragraphs}", lesson.id()),
+                                title: title.clone(),
+                                text: paragraph.trim().to_string(),
+                            });
+                        }
+                    }
+                    Block::Example(example) => {
+                        examples += 1;
+                        sections.push(Section {
+                            anchor: format!("{}#example-{examples}", lesson.id()),
+                            title: title.clone(),
+                            text: example.visible(),
+                        });
+                    }
+                }
+            }
+        }
+        for exercise in exercises::all().into_iter().filter(|e| e.chapter() == chapter) {
+            let title = format!("{} ({})", exercise.title(), exercise.tags().join(", "));
+            sections.push(Section {
+                anchor: exercise.id().to_string(),
+                title: title.clone(),
+                text: exercise.description().to_string(),
+            });
+            let hints: Vec<String> = exercise.hints().iter().map(|hint| hint.to_string()).collect();
+            if !hints.is_empty() {
+                sections.push(Section {
+                    anchor: format!("{}#hints", exercise.id()),
+                    title,
+                    text: hints.join("\n"),
+                });
+            }
+        }
+    }
+    sections
+}
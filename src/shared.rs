@@ -0,0 +1,26 @@
+//! Names for the two handles the course keeps writing out: `Rc<RefCell<T>>` and `Arc<Mutex<T>>`.
+//!
+//! They are aliases, not new types, so everything the lessons teach about `Rc`, `RefCell`,
+//! `Arc` and `Mutex` applies as is. The [`shared!`](crate::shared!) and
+//! [`shared_sync!`](crate::shared_sync!) macros build them, and [`clone_all!`](crate::clone_all!)
+//! clones several of them before they move into a closure.
+//! ```
+//! use learning_cell::shared::{Shared, SyncShared};
+//! use learning_cell::{shared, shared_sync};
+//!
+//! let log: Shared<Vec<&str>> = shared!(Vec::new());
+//! log.borrow_mut().push("started");
+//!
+//! let hits: SyncShared<u32> = shared_sync!(0);
+//! *hits.lock().unwrap() += 1;
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+/// A value shared by several owners on one thread, each of which may change it.
+pub type Shared<T> = Rc<RefCell<T>>;
+
+/// A value shared by several owners across threads, each of which may change it.
+pub type SyncShared<T> = Arc<Mutex<T>>;
@@ -0,0 +1,288 @@
+//! A model of a `RefCell`'s borrow flag.
+//!
+//! A [`BorrowSimulator`] keeps the borrow bookkeeping of a `RefCell` and nothing else: no value,
+//! no references, just the guards that would be alive and the flag they add up to. That makes
+//! it easy to step through a sequence of borrows, drop guards in any order and ask what the
+//! standard library would do next, as `learning_cell simulate` does.
+//! ```
+//! use learning_cell::simulator::{BorrowSimulator, SimError};
+//!
+//! let mut cell = BorrowSimulator::new();
+//! let first = cell.borrow().unwrap();
+//! let _second = cell.borrow().unwrap();
+//! assert_eq!(cell.flag(), 2);
+//! assert_eq!(cell.borrow_mut(), Err(SimError::AlreadyBorrowed));
+//! assert_eq!(cell.panicking_calls(), ["borrow_mut", "replace", "swap", "take"]);
+//!
+//! cell.drop_guard(first).unwrap();
+//! assert_eq!(cell.flag(), 1);
+//! ```
+//! Operations can also be given as data, an [`Op`] at a time, which is how the `simulate` loop,
+//! the fuzz target (`fuzz/`) and [`compare_with_std`] drive it:
+//! ```
+//! use learning_cell::diagnostics::BorrowState;
+//! use learning_cell::simulator::{BorrowSimulator, Op, SimError};
+//!
+//! let mut cell = BorrowSimulator::new();
+//! let state = cell.apply(Op::BorrowMut).unwrap();
+//! assert_eq!(state.borrow, BorrowState::Exclusive);
+//! assert_eq!(cell.apply(Op::Swap), Err(SimError::AlreadyBorrowed));
+//!
+//! cell.apply(Op::DropGuard(state.guard.unwrap())).unwrap();
+//! assert_eq!(cell.apply(Op::Swap).unwrap().borrow, BorrowState::Unused);
+//! ```
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::fmt;
+
+use crate::diagnostics::{BorrowKind, BorrowState};
+use crate::util::catch_panic;
+
+/// Identifies a guard handed out by the simulator, starting at 1.
+pub type GuardId = usize;
+
+/// Why the simulator refused an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimError {
+    /// A shared borrow was asked for while an exclusive one is alive. `borrow` panics with this
+    /// message, `try_borrow` returns it.
+    AlreadyMutablyBorrowed,
+    /// An exclusive borrow was asked for while any borrow is alive. `borrow_mut` panics with this
+    /// message, `try_borrow_mut` returns it.
+    AlreadyBorrowed,
+    /// There is no live guard with this id.
+    NoSuchGuard(GuardId),
+}
+
+impl fmt::Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimError::AlreadyMutablyBorrowed => f.write_str("already mutably borrowed"),
+            SimError::AlreadyBorrowed => f.write_str("already borrowed"),
+            SimError::NoSuchGuard(id) => write!(f, "there is no guard {id}"),
+        }
+    }
+}
+
+impl std::error::Error for SimError {}
+
+/// An operation on a `RefCell`, as far as its borrow flag is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// `try_borrow`: a shared borrow, whose guard stays alive until dropped.
+    Borrow,
+    /// `try_borrow_mut`: an exclusive borrow, whose guard stays alive until dropped.
+    BorrowMut,
+    /// Dropping the guard with this id.
+    DropGuard(GuardId),
+    /// `replace`: an exclusive borrow that ends at once.
+    Replace,
+    /// `swap` with another `RefCell` that is not borrowed: an exclusive borrow of both that ends
+    /// at once.
+    Swap,
+}
+
+impl Op {
+    /// Reads operations from arbitrary bytes, one per byte, as the fuzz target does. Every byte
+    /// sequence is valid.
+    /// ```
+    /// use learning_cell::simulator::Op;
+    ///
+    /// let ops = Op::decode(&[0, 1, 2, 7]);
+    /// assert_eq!(ops, [Op::Borrow, Op::BorrowMut, Op::DropGuard(1), Op::DropGuard(2)]);
+    /// ```
+    pub fn decode(bytes: &[u8]) -> Vec<Op> {
+        let op = |byte: u8| match byte % 5 {
+            0 => Op::Borrow,
+            1 => Op::BorrowMut,
+            2 => Op::DropGuard(GuardId::from(byte / 5) + 1),
+            3 => Op::Replace,
+            _ => Op::Swap,
+        };
+        bytes.iter().copied().map(op).collect()
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Borrow => f.write_str("try_borrow"),
+            Op::BorrowMut => f.write_str("try_borrow_mut"),
+            Op::DropGuard(id) => write!(f, "drop {id}"),
+            Op::Replace => f.write_str("replace"),
+            Op::Swap => f.write_str("swap"),
+        }
+    }
+}
+
+/// The simulated cell after an [`Op`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    /// Who holds the value.
+    pub borrow: BorrowState,
+    /// The guard handed out by a `Borrow` or `BorrowMut`.
+    pub guard: Option<GuardId>,
+}
+
+/// The borrow bookkeeping of one `RefCell`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BorrowSimulator {
+    guards: Vec<(GuardId, BorrowKind)>,
+    next: GuardId,
+}
+
+impl BorrowSimulator {
+    /// A cell that is not borrowed.
+    pub fn new() -> BorrowSimulator {
+        BorrowSimulator::default()
+    }
+
+    /// Takes a shared borrow, like `RefCell::try_borrow`, returning the id of its guard.
+    pub fn borrow(&mut self) -> Result<GuardId, SimError> {
+        if self.flag() < 0 {
+            return Err(SimError::AlreadyMutablyBorrowed);
+        }
+        Ok(self.push(BorrowKind::Shared))
+    }
+
+    /// Takes an exclusive borrow, like `RefCell::try_borrow_mut`, returning the id of its guard.
+    pub fn borrow_mut(&mut self) -> Result<GuardId, SimError> {
+        if self.flag() != 0 {
+            return Err(SimError::AlreadyBorrowed);
+        }
+        Ok(self.push(BorrowKind::Exclusive))
+    }
+
+    /// Replaces the value, like `RefCell::replace`: a `borrow_mut` whose guard is dropped at once.
+    pub fn replace(&mut self) -> Result<(), SimError> {
+        if self.flag() != 0 {
+            return Err(SimError::AlreadyBorrowed);
+        }
+        Ok(())
+    }
+
+    /// Swaps the value with that of an unborrowed cell, like `RefCell::swap`. To this cell, that
+    /// is the same as a `replace`.
+    pub fn swap(&mut self) -> Result<(), SimError> {
+        self.replace()
+    }
+
+    /// Performs `op` and returns the state it leaves the cell in. A refused operation leaves the
+    /// cell as it was.
+    pub fn apply(&mut self, op: Op) -> Result<State, SimError> {
+        let guard = match op {
+            Op::Borrow => Some(self.borrow()?),
+            Op::BorrowMut => Some(self.borrow_mut()?),
+            Op::DropGuard(id) => self.drop_guard(id).map(|_| None)?,
+            Op::Replace => self.replace().map(|()| None)?,
+            Op::Swap => self.swap().map(|()| None)?,
+        };
+        Ok(State { borrow: self.state(), guard })
+    }
+
+    /// Drops the guard `id`, in any order, like dropping a `Ref` or `RefMut`.
+    pub fn drop_guard(&mut self, id: GuardId) -> Result<BorrowKind, SimError> {
+        let index = self
+            .guards
+            .iter()
+            .position(|(guard, _)| *guard == id)
+            .ok_or(SimError::NoSuchGuard(id))?;
+        Ok(self.guards.remove(index).1)
+    }
+
+    /// The live guards, oldest first.
+    pub fn guards(&self) -> &[(GuardId, BorrowKind)] {
+        &self.guards
+    }
+
+    /// The borrow flag as the standard library keeps it: the number of shared borrows, `-1` for
+    /// an exclusive borrow, `0` when unused.
+    pub fn flag(&self) -> isize {
+        self.state().flag()
+    }
+
+    /// Who holds the value, according to the live guards.
+    pub fn state(&self) -> BorrowState {
+        match self.guards.first() {
+            None => BorrowState::Unused,
+            Some((_, BorrowKind::Exclusive)) => BorrowState::Exclusive,
+            Some((_, BorrowKind::Shared)) => BorrowState::Shared(self.guards.len()),
+        }
+    }
+
+    /// The `RefCell` methods that would panic if called now.
+    pub fn panicking_calls(&self) -> Vec<&'static str> {
+        match self.flag() {
+            0 => Vec::new(),
+            flag if flag > 0 => vec!["borrow_mut", "replace", "swap", "take"],
+            _ => vec!["borrow", "borrow_mut", "replace", "swap", "take"],
+        }
+    }
+
+    fn push(&mut self, kind: BorrowKind) -> GuardId {
+        self.next += 1;
+        self.guards.push((self.next, kind));
+        self.next
+    }
+}
+
+/// Runs `ops` on a simulator and on a real `RefCell` side by side, and describes the first
+/// operation after which they disagree: on whether it succeeded, or on which borrows the cell
+/// would grant next.
+/// ```
+/// use learning_cell::simulator::{self, Op};
+///
+/// let ops = [Op::Borrow, Op::Borrow, Op::Swap, Op::DropGuard(1), Op::BorrowMut, Op::DropGuard(2)];
+/// assert_eq!(simulator::compare_with_std(&ops), Ok(()));
+/// ```
+pub fn compare_with_std(ops: &[Op]) -> Result<(), String> {
+    /// A guard of the real cell, held until the matching `DropGuard`.
+    #[allow(dead_code)]
+    enum Guard<'a> {
+        Shared(Ref<'a, i32>),
+        Exclusive(RefMut<'a, i32>),
+    }
+
+    let (cell, other) = (RefCell::new(0), RefCell::new(1));
+    let mut simulator = BorrowSimulator::new();
+    let mut guards: Vec<(GuardId, Guard<'_>)> = Vec::new();
+    let mut next = 0;
+    for (step, &op) in ops.iter().enumerate() {
+        let simulated = simulator.apply(op).map(|state| state.guard);
+        let real = match op {
+            Op::Borrow => cell.try_borrow().map(|guard| Some(Guard::Shared(guard))).map_err(|_| ()),
+            Op::BorrowMut => {
+                cell.try_borrow_mut().map(|guard| Some(Guard::Exclusive(guard))).map_err(|_| ())
+            }
+            Op::DropGuard(id) => match guards.iter().position(|(guard, _)| *guard == id) {
+                Some(index) => {
+                    guards.remove(index);
+                    Ok(None)
+                }
+                None => Err(()),
+            },
+            Op::Replace => catch_panic(|| cell.replace(2)).map(|_| None).map_err(|_| ()),
+            Op::Swap => catch_panic(|| cell.swap(&other)).map(|()| None).map_err(|_| ()),
+        };
+        if simulated.is_ok() != real.is_ok() {
+            let simulated = simulated.map_or_else(|err| err.to_string(), |_| "ok".to_string());
+            let real = if real.is_ok() { "ok" } else { "refused" };
+            return Err(format!("step {step} ({op}): simulated {simulated}, std {real}"));
+        }
+        if let Ok(Some(guard)) = real {
+            next += 1;
+            guards.push((next, guard));
+        }
+        let state = simulator.state();
+        for kind in [BorrowKind::Shared, BorrowKind::Exclusive] {
+            let granted = match kind {
+                BorrowKind::Shared => cell.try_borrow().is_ok(),
+                BorrowKind::Exclusive => cell.try_borrow_mut().is_ok(),
+            };
+            if state.allows(kind) != granted {
+                return Err(format!("step {step} ({op}): simulated {state}, std disagrees"));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! Plain-data snapshots of the lesson structs.
+//!
+//! Every lesson struct (e.g. [`Cell::Immutable`](crate::Cell::Immutable) and
+//! [`RefCell::Immutable`](crate::RefCell::Immutable)) can produce a `Snapshot` of its current
+//! values and later `diff` itself against that snapshot. Exercises use this to assert exactly
+//! which fields a learner's code mutated, and nothing more.
+//! ```
+//! let a = learning_cell::Cell::Immutable::default();
+//! let before = a.snapshot();
+//!
+//! a.special.set(7);
+//!
+//! let changes = a.diff(&before);
+//! assert_eq!(changes.len(), 1);
+//! assert_eq!(changes[0].field, "special");
+//! assert_eq!(changes[0].to_string(), "special: 42 -> 7");
+//! ```
+
+use std::fmt;
+
+/// A single field that differs between a snapshot and the current value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// Name of the field as it appears in the struct.
+    pub field: &'static str,
+    /// `Debug` representation of the value in the snapshot.
+    pub before: String,
+    /// `Debug` representation of the current value.
+    pub after: String,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.before, self.after)
+    }
+}
+
+/// Pushes a [`FieldChange`] onto `changes` if `before` and `after` differ.
+pub(crate) fn compare<T: PartialEq + fmt::Debug>(
+    changes: &mut Vec<FieldChange>,
+    field: &'static str,
+    before: &T,
+    after: &T,
+) {
+    if before != after {
+        changes.push(FieldChange {
+            field,
+            before: format!("{before:?}"),
+            after: format!("{after:?}"),
+        });
+    }
+}
@@ -0,0 +1,159 @@
+//! The lessons' "does not compile" examples, as data.
+//!
+//! The `compile_fail` examples of the lessons only say that the compiler rejects them; rustdoc
+//! does not check why. Each of them is repeated here as a [`Snippet`] with the error the compiler
+//! is expected to report and why, so tools can check that the example fails for the reason the
+//! lesson gives, and tell the learner when an edit made the error go away or changed it.
+//! ```
+//! use learning_cell::lessons::{self, ExampleKind};
+//! use learning_cell::snippets;
+//!
+//! let page = lessons::find("nested").unwrap().page();
+//! let mut examples = page.examples().filter(|example| example.kind == ExampleKind::CompileFail);
+//! let snippet = snippets::for_example(examples.next().unwrap()).unwrap();
+//! assert_eq!(snippet.expected_error, "error[E0594]");
+//! ```
+//! The code of a snippet is the example as compiled, with its hidden lines, and can be given to
+//! the [runner](crate::runner) as is. The crate's UI tests (`tests/ui/`) pin the full diagnostics
+//! of every snippet.
+
+use crate::lessons::{self, Example, ExampleKind};
+use crate::packs;
+
+/// A lesson example that the compiler rejects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snippet {
+    /// Stable identifier.
+    pub id: &'static str,
+    /// Id of the lesson showing the example.
+    pub lesson: &'static str,
+    /// The example, as compiled.
+    pub code: &'static str,
+    /// Why the compiler rejects the code.
+    pub explanation: &'static str,
+    /// Part of the compiler's diagnostics that shows it failed for that reason.
+    pub expected_error: &'static str,
+}
+
+/// Assigning to the fields of a `Cell` lesson struct through an immutable binding.
+pub const CELL_IMMUTABLE_BINDING: Snippet = Snippet {
+    id: "cell_immutable_binding",
+    lesson: "cell",
+    code: "use std::cell::Cell;\n\
+           let a = learning_cell::Cell::Immutable::default();\n\
+           // Error: cannot mutate immutable variable `a`\n\
+           a.regular += 2;\n\
+           a.special = Cell::new(24);\n",
+    explanation: "`a` is not declared `mut`, so none of its fields can be assigned to, not even \
+                  the ones holding a `Cell`.",
+    expected_error: "error[E0594]",
+};
+
+/// Calling `Cell::get` on a `Cell<String>`.
+pub const CELL_GET_NON_COPY: Snippet = Snippet {
+    id: "cell_get_non_copy",
+    lesson: "cell",
+    code: "use std::cell::Cell;\n\
+           let a = learning_cell::Cell::Immutable::default();\n\
+           // OK\n\
+           let _ = a.special.get();\n\
+           // Error: trait bound `Copy` is not satisfied for String\n\
+           let _ = a.special_nocopy.get();\n",
+    explanation: "`get` copies the value out of the cell, so it only exists for `Copy` types, \
+                  and `String` is not one.",
+    expected_error: "error[E0599]",
+};
+
+/// Calling `Cell::get_mut` through an immutable binding.
+pub const CELL_GET_MUT_IMMUTABLE: Snippet = Snippet {
+    id: "cell_get_mut_immutable",
+    lesson: "cell",
+    code: "use std::cell::Cell;\n\
+           let a = learning_cell::Cell::Immutable::default();\n\
+           // Error: cannot borrow `a.special` as mutable, as `a` is not declared as mutable\n\
+           let _ = a.special.get_mut();\n",
+    explanation: "`get_mut` takes `&mut self`, which an immutable binding cannot lend.",
+    expected_error: "error[E0596]",
+};
+
+/// Assigning to the fields of a `RefCell` lesson struct through an immutable binding.
+pub const REFCELL_IMMUTABLE_BINDING: Snippet = Snippet {
+    id: "refcell_immutable_binding",
+    lesson: "refcell",
+    code: "use std::cell::RefCell;\n\
+           let a = learning_cell::RefCell::Immutable::default();\n\
+           // Error: cannot mutate immutable variable `a`\n\
+           a.regular += 2;\n\
+           a.special = RefCell::new(24);\n",
+    explanation: "`a` is not declared `mut`, so none of its fields can be assigned to, whatever \
+                  their type.",
+    expected_error: "error[E0594]",
+};
+
+/// Assigning to a plain field through a shared borrow of the outer `RefCell`.
+pub const NESTED_ASSIGN_THROUGH_REF: Snippet = Snippet {
+    id: "nested_assign_through_ref",
+    lesson: "nested",
+    code: "let outer = learning_cell::Nested::Outer::default();\n\
+           // Error: cannot assign to data in dereference of `Ref<'_, Immutable>`\n\
+           outer.in_refcell.borrow().regular += 1;\n",
+    explanation: "`borrow` hands out a `Ref`, which only derefs to a shared reference; changing \
+                  a plain field takes `borrow_mut`.",
+    expected_error: "error[E0594]",
+};
+
+/// Copying a non-`Copy` struct out of a `Cell` with `get`.
+pub const NESTED_GET_NON_COPY: Snippet = Snippet {
+    id: "nested_get_non_copy",
+    lesson: "nested",
+    code: "let outer = learning_cell::Nested::Outer::default();\n\
+           // Error: the method `get` exists for `Cell<Immutable>`, but its trait bounds were not \
+           satisfied\n\
+           let inner = outer.in_cell.get();\n",
+    explanation: "The inner struct holds a `String`, so it is not `Copy` and `get` cannot copy \
+                  it out; it has to be moved out with `take` or `replace`.",
+    expected_error: "error[E0599]",
+};
+
+/// Every snippet, in the order of the lessons.
+pub const ALL: &[Snippet] = &[
+    CELL_IMMUTABLE_BINDING,
+    CELL_GET_NON_COPY,
+    CELL_GET_MUT_IMMUTABLE,
+    REFCELL_IMMUTABLE_BINDING,
+    NESTED_ASSIGN_THROUGH_REF,
+    NESTED_GET_NON_COPY,
+];
+
+/// Looks up a snippet by [`id`](Snippet::id).
+pub fn get(id: &str) -> Option<&'static Snippet> {
+    ALL.iter().find(|snippet| snippet.id == id)
+}
+
+/// The snippet repeating `example`, if it is one of the lessons' `compile_fail` examples.
+pub fn for_example(example: &Example) -> Option<&'static Snippet> {
+    if example.kind != ExampleKind::CompileFail {
+        return None;
+    }
+    let code = example.compiled();
+    ALL.iter().find(|snippet| snippet.code == code)
+}
+
+/// The built-in lessons' `compile_fail` examples that no snippet repeats. Empty unless a lesson
+/// changed without its snippets.
+/// ```
+/// assert_eq!(learning_cell::snippets::missing(), Vec::<String>::new());
+/// ```
+pub fn missing() -> Vec<String> {
+    let mut missing = Vec::new();
+    for lesson in lessons::all().into_iter().filter(|lesson| packs::lesson(lesson.id()).is_none()) {
+        let page = lesson.page();
+        let examples = page.examples().enumerate();
+        for (number, example) in examples.filter(|(_, e)| e.kind == ExampleKind::CompileFail) {
+            if for_example(example).is_none() {
+                missing.push(format!("example {} of `{}`", number + 1, lesson.id()));
+            }
+        }
+    }
+    missing
+}
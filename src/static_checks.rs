@@ -0,0 +1,107 @@
+//! The auto traits and copy semantics of the crate's types, checked on every build.
+//!
+//! Whether a type is `Send`, `Sync`, `Copy` or `Clone` is part of what the lessons teach, yet
+//! the compiler derives most of it from the fields: wrapping one more field in a `Cell`, or
+//! swapping a `RefCell` for a `Mutex`, silently changes the answer. The assertions below pin the
+//! answer for every lesson type and every type of [`impls`](crate::impls), so such a change
+//! fails the build here instead of contradicting a lesson. They generate no code.
+//!
+//! | Type | `Send` | `Sync` | `Clone` | `Copy` |
+//! |---|---|---|---|---|
+//! | `Cell::Immutable` | yes | no | yes | no |
+//! | `RefCell::Immutable` | yes | no | yes | no |
+//! | `Nested::Outer` | yes | no | no | no |
+//! | the lessons' snapshots and builders | yes | yes | yes | no |
+//! | `BuildError`, `BorrowCounts` | yes | yes | yes | yes |
+//! | `NonReentrantRefCell`, `TracedRefCell`, `CountingCell`, `HistoryCell` | yes | no | no | no |
+//! | `TracedMutex` | yes | yes | no | no |
+//! | `NonReentrantRefMut`, `Traced` guards of a `RefCell` | no | no | no | no |
+//! | `Traced` guards of a `Mutex` | no | yes | no | no |
+//! | `NonReentrantError`, `ReentrantBorrow` | yes | yes | yes | no |
+//!
+//! The generic types are checked with the parameters the lessons use, `i32` and `String`.
+
+use std::cell::RefMut;
+use std::sync::MutexGuard;
+
+use crate::impls::{
+    BorrowCounts, CountingCell, HistoryCell, NonReentrantError, NonReentrantRefCell,
+    NonReentrantRefMut, ReentrantBorrow, Traced, TracedMutex, TracedRefCell,
+};
+use crate::{assert_not_send, assert_not_sync, assert_send, assert_sync};
+use crate::{Cell as CellLesson, Nested, RefCell as RefCellLesson};
+
+// The lessons: a struct of cells can move between threads, but not be shared by them.
+assert_send!(CellLesson::Immutable, RefCellLesson::Immutable, Nested::Outer);
+assert_not_sync!(CellLesson::Immutable, RefCellLesson::Immutable, Nested::Outer);
+crate::__assert_impl!(Clone: CellLesson::Immutable, RefCellLesson::Immutable);
+crate::__assert_not_impl!(Clone: Nested::Outer);
+crate::__assert_not_impl!(Copy: CellLesson::Immutable, RefCellLesson::Immutable);
+
+// Their plain-data companions hold no cells at all.
+assert_send!(
+    CellLesson::Snapshot,
+    CellLesson::ImmutableBuilder,
+    RefCellLesson::Snapshot,
+    RefCellLesson::ImmutableBuilder,
+    Nested::Snapshot,
+);
+assert_sync!(
+    CellLesson::Snapshot,
+    CellLesson::ImmutableBuilder,
+    RefCellLesson::Snapshot,
+    RefCellLesson::ImmutableBuilder,
+    Nested::Snapshot,
+);
+crate::__assert_impl!(
+    Clone: CellLesson::Snapshot,
+    CellLesson::ImmutableBuilder,
+    RefCellLesson::Snapshot,
+    RefCellLesson::ImmutableBuilder,
+    Nested::Snapshot,
+);
+crate::__assert_not_impl!(
+    Copy: CellLesson::Snapshot,
+    CellLesson::ImmutableBuilder,
+    RefCellLesson::Snapshot,
+    RefCellLesson::ImmutableBuilder,
+    Nested::Snapshot,
+);
+crate::__assert_impl!(Copy: CellLesson::BuildError, BorrowCounts);
+assert_send!(CellLesson::BuildError, BorrowCounts);
+assert_sync!(CellLesson::BuildError, BorrowCounts);
+
+// The single-threaded cells of `impls` behave like the `RefCell` they wrap.
+assert_send!(NonReentrantRefCell<i32>, TracedRefCell<i32>, CountingCell<i32>, HistoryCell<i32>);
+assert_not_sync!(NonReentrantRefCell<i32>, TracedRefCell<i32>, CountingCell<i32>, HistoryCell<i32>);
+crate::__assert_not_impl!(
+    Clone: NonReentrantRefCell<i32>,
+    TracedRefCell<i32>,
+    CountingCell<i32>,
+    HistoryCell<i32>,
+    TracedMutex<i32>,
+);
+
+// `TracedMutex` stays a `Mutex`.
+assert_send!(TracedMutex<i32>);
+assert_sync!(TracedMutex<i32>);
+
+// Guards must be dropped on the thread that took them.
+assert_not_send!(
+    NonReentrantRefMut<'static, i32>,
+    Traced<RefMut<'static, i32>>,
+    Traced<MutexGuard<'static, i32>>,
+);
+assert_not_sync!(NonReentrantRefMut<'static, i32>, Traced<RefMut<'static, i32>>);
+assert_sync!(Traced<MutexGuard<'static, i32>>);
+crate::__assert_not_impl!(
+    Clone: NonReentrantRefMut<'static, i32>,
+    Traced<RefMut<'static, i32>>,
+    Traced<MutexGuard<'static, i32>>,
+);
+
+// Errors can travel to whichever thread reports them.
+assert_send!(NonReentrantError, ReentrantBorrow);
+assert_sync!(NonReentrantError, ReentrantBorrow);
+crate::__assert_impl!(Clone: NonReentrantError, ReentrantBorrow);
+crate::__assert_not_impl!(Copy: NonReentrantError, ReentrantBorrow);
@@ -0,0 +1,358 @@
+//! Small helpers shared by the lessons, exercises and diagnostics.
+
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{
+    AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicU16, AtomicU32,
+    AtomicU64, AtomicU8, AtomicUsize, Ordering,
+};
+use std::sync::{mpsc, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A source of [`Instant`]s.
+///
+/// Anything that measures time (how long a borrow was held, whether an exercise timed out, ...)
+/// asks a `Clock` instead of calling [`Instant::now`] directly. That way tests can swap in a
+/// [`FakeClock`] and get the exact same numbers on every run.
+pub trait Clock {
+    /// Returns the current point in time according to this clock.
+    fn now(&self) -> Instant;
+}
+
+/// The real clock, backed by [`Instant::now`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when you tell it to.
+///
+/// `FakeClock` is itself a tiny `Cell` lesson: `advance` takes `&self`, not `&mut self`, so the
+/// same clock can be shared by reference between the code under test and the test driving it.
+/// The elapsed time lives in a `Cell<Duration>` and, since `Duration` is `Copy`, we can simply
+/// `get` and `set` it.
+/// ```
+/// use std::time::Duration;
+/// use learning_cell::util::{Clock, FakeClock};
+///
+/// let clock = FakeClock::new();
+/// let start = clock.now();
+///
+/// clock.advance(Duration::from_millis(250));
+/// assert_eq!(clock.now() - start, Duration::from_millis(250));
+///
+/// // Time never moves on its own.
+/// assert_eq!(clock.now() - start, Duration::from_millis(250));
+/// ```
+#[derive(Debug)]
+pub struct FakeClock {
+    origin: Instant,
+    elapsed: Cell<Duration>,
+}
+
+impl FakeClock {
+    /// Creates a clock frozen at the moment of its creation.
+    pub fn new() -> Self {
+        Self { origin: Instant::now(), elapsed: Cell::new(Duration::ZERO) }
+    }
+
+    /// Moves the clock forward by `by`. Time can only go forward, just like a real [`Instant`].
+    pub fn advance(&self, by: Duration) {
+        self.elapsed.set(self.elapsed.get() + by);
+    }
+
+    /// Returns how far the clock has been advanced since it was created.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed.get()
+    }
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.origin + self.elapsed.get()
+    }
+}
+
+/// Number of [`catch_panic`] calls currently running, on any thread.
+static CAPTURING: AtomicUsize = AtomicUsize::new(0);
+
+/// Runs `f`, turning a panic into an `Err` holding the panic message.
+///
+/// Panic messages are not printed to stderr while `f` runs. This lets the exercise runner and the
+/// lesson demos show a panic as data instead of as noise in the terminal. Since threads spawned by
+/// `f` may panic too (and their panics usually end up re-raised by `join`), messages are silenced
+/// process-wide for as long as any `catch_panic` is running.
+/// ```
+/// use std::cell::RefCell;
+/// use learning_cell::util::catch_panic;
+///
+/// let cell = RefCell::new(1);
+/// let message = catch_panic(|| {
+///     let _a = cell.borrow_mut();
+///     let _b = cell.borrow_mut();
+/// })
+/// .unwrap_err();
+///
+/// assert!(message.contains("already"));
+/// ```
+pub fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CAPTURING.load(Ordering::SeqCst) == 0 {
+                previous(info);
+            }
+        }));
+    });
+
+    CAPTURING.fetch_add(1, Ordering::SeqCst);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CAPTURING.fetch_sub(1, Ordering::SeqCst);
+
+    result.map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// Joins `handle`, re-raising the thread's panic (with its original message) if it panicked.
+pub fn join<T>(handle: thread::JoinHandle<T>) -> T {
+    match handle.join() {
+        Ok(value) => value,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Runs `f` on a new thread and waits at most `limit` for it to finish.
+///
+/// This is how the exercises detect deadlocks: a thread stuck on a lock never returns, so after
+/// `limit` the caller gets a [`TimedOut`] error instead of hanging forever. The stuck thread
+/// cannot be killed; it is left behind, blocked, until the process exits. A panic inside `f` is
+/// re-raised on the calling thread.
+/// ```
+/// use std::sync::Mutex;
+/// use std::time::Duration;
+/// use learning_cell::util::with_timeout;
+///
+/// assert_eq!(with_timeout(Duration::from_secs(1), || 6 * 7), Ok(42));
+///
+/// let lock: &'static Mutex<()> = Box::leak(Box::new(Mutex::new(())));
+/// let stuck = with_timeout(Duration::from_millis(50), move || {
+///     let _a = lock.lock().unwrap();
+///     let _b = lock.lock().unwrap(); // Waits for itself.
+/// });
+/// assert!(stuck.is_err());
+/// ```
+pub fn with_timeout<R: Send + 'static>(
+    limit: Duration,
+    f: impl FnOnce() -> R + Send + 'static,
+) -> Result<R, TimedOut> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        // The receiver is gone if we timed out; nobody is waiting for the result anymore.
+        let _ = sender.send(result);
+    });
+    match receiver.recv_timeout(limit) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(payload)) => panic::resume_unwind(payload),
+        Err(_) => Err(TimedOut { limit }),
+    }
+}
+
+/// The closure given to [`with_timeout`] did not finish in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut {
+    /// How long we waited.
+    pub limit: Duration,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "did not finish within {:?}, probably deadlocked", self.limit)
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// Extracts the message from a panic payload.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".to_string()
+    }
+}
+
+/// Runs `f` `iterations` times and measures how long that took.
+///
+/// This is a deliberately simple harness: no warm-up, no statistics, one wall-clock measurement
+/// around the whole loop. It is good enough to show that one design is several times faster than
+/// another, not to detect a 5% regression. The result of every call goes through
+/// [`std::hint::black_box`] so the optimizer cannot skip the work.
+/// ```
+/// use learning_cell::util::bench;
+///
+/// let measurement = bench(1000, || (0..100u64).sum::<u64>());
+/// assert_eq!(measurement.iterations, 1000);
+/// println!("summing: {measurement}");
+/// ```
+pub fn bench<R>(iterations: u32, mut f: impl FnMut() -> R) -> Measurement {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        std::hint::black_box(f());
+    }
+    Measurement { iterations, total: start.elapsed() }
+}
+
+/// The result of [`bench`](fn@bench).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Measurement {
+    /// How many times the closure ran.
+    pub iterations: u32,
+    /// Wall-clock time of all iterations together.
+    pub total: Duration,
+}
+
+impl Measurement {
+    /// Average time of one iteration.
+    pub fn per_iteration(&self) -> Duration {
+        self.total / self.iterations.max(1)
+    }
+
+    /// How many times faster `self` is than `other` (below one if it is slower).
+    pub fn speedup_over(&self, other: &Measurement) -> f64 {
+        other.per_iteration().as_secs_f64() / self.per_iteration().as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}/iter ({} iterations)", self.per_iteration(), self.iterations)
+    }
+}
+
+/// A small, seedable pseudo-random number generator (SplitMix64).
+///
+/// Good enough to shuffle quiz options and generate operation sequences, and fully reproducible
+/// from its seed. Not suitable for anything security related.
+/// ```
+/// use learning_cell::util::Rng;
+///
+/// let mut a = Rng::new(7);
+/// let mut b = Rng::new(7);
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// assert!(a.below(10) < 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator from `seed`. The same seed always yields the same sequence.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next pseudo-random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..bound`. `bound` must not be zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Returns a number in `range`.
+    pub fn range(&mut self, range: std::ops::Range<i64>) -> i64 {
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as i64
+    }
+
+    /// Shuffles `items` in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A primitive with an atomic counterpart in `std::sync::atomic`, such as `u32` and `AtomicU32`.
+///
+/// Lets generic code, like the [`interior_mutable!`](crate::interior_mutable) macro, pick the
+/// atomic type for a value type. Every access is `SeqCst`: the lessons care about which operations
+/// need `&mut`, not about orderings.
+/// ```
+/// use learning_cell::util::AtomicValue;
+///
+/// let flag = true.into_atomic();
+/// bool::store(&flag, false);
+/// assert!(!bool::load(&flag));
+/// ```
+pub trait AtomicValue: Copy {
+    /// The atomic type holding a `Self`.
+    type Atomic: Send + Sync;
+
+    /// Wraps the value in its atomic type.
+    fn into_atomic(self) -> Self::Atomic;
+
+    /// Reads the value of `atomic`.
+    fn load(atomic: &Self::Atomic) -> Self;
+
+    /// Overwrites the value of `atomic`.
+    fn store(atomic: &Self::Atomic, value: Self);
+}
+
+macro_rules! atomic_value {
+    ($($value:ty => $atomic:ty),+ $(,)?) => {
+        $(
+            impl AtomicValue for $value {
+                type Atomic = $atomic;
+
+                fn into_atomic(self) -> $atomic {
+                    <$atomic>::new(self)
+                }
+
+                fn load(atomic: &$atomic) -> $value {
+                    atomic.load(Ordering::SeqCst)
+                }
+
+                fn store(atomic: &$atomic, value: $value) {
+                    atomic.store(value, Ordering::SeqCst);
+                }
+            }
+        )+
+    };
+}
+
+atomic_value! {
+    bool => AtomicBool,
+    i8 => AtomicI8,
+    i16 => AtomicI16,
+    i32 => AtomicI32,
+    i64 => AtomicI64,
+    isize => AtomicIsize,
+    u8 => AtomicU8,
+    u16 => AtomicU16,
+    u32 => AtomicU32,
+    u64 => AtomicU64,
+    usize => AtomicUsize,
+}
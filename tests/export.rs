@@ -0,0 +1,82 @@
+//! Exports the course into temporary directories and reads back what was written.
+
+use std::fs;
+use std::path::PathBuf;
+
+use learning_cell::curriculum::Chapter;
+use learning_cell::{exercises, export};
+
+/// A fresh directory for the export called `name`.
+fn out_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join(format!("learning_cell-export-test-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn html_handouts_escape_titles_once() {
+    let dir = out_dir("html");
+    let files = export::html(&dir).unwrap();
+    assert!(files.iter().any(|path| path.ends_with("index.html")));
+
+    let page = fs::read_to_string(dir.join("refcell.html")).unwrap();
+    assert!(page.starts_with("<!DOCTYPE html>\n"));
+    assert!(page.contains("<title>RefCell</title>"));
+    assert!(page.contains("<h2>Memoized Fibonacci with RefCell&lt;HashMap&gt;</h2>"));
+    assert!(page.contains("<details>\n<summary>Hint 1</summary>"));
+    let rc = fs::read_to_string(dir.join("rc.html")).unwrap();
+    assert!(rc.contains("<h2>Build a graph with Rc&lt;RefCell&lt;Node&gt;&gt;</h2>"));
+    assert!(rc.contains("<code>Rc&lt;RefCell&gt;</code>"));
+    for path in &files {
+        let page = fs::read_to_string(path).unwrap();
+        assert!(!page.contains("&amp;lt;"), "{} escapes a `<` twice", path.display());
+    }
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn rustlings_files_hold_the_scaffold_or_the_solution_and_the_tests() {
+    let dir = out_dir("rustlings");
+    export::rustlings(&dir).unwrap();
+    let info = fs::read_to_string(dir.join("info.toml")).unwrap();
+    let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+
+    for exercise in exercises::all() {
+        let id = exercise.id();
+        let number = Chapter::ALL.iter().position(|&chapter| chapter == exercise.chapter());
+        let chapter_dir = format!("{:02}_{}", number.unwrap() + 1, exercise.chapter().id());
+        assert!(info.contains(&format!("name = \"{id}\"\ndir = \"{chapter_dir}\"\n")), "{id}");
+        assert!(manifest.contains(&format!("path = \"exercises/{chapter_dir}/{id}.rs\"")), "{id}");
+
+        let scaffold: String = exercises::scaffold(id)
+            .unwrap()
+            .lines()
+            .filter(|line| *line != "#[cfg(not(feature = \"solutions\"))]")
+            .map(|line| format!("{line}\n"))
+            .collect();
+        for kind in ["exercises", "solutions"] {
+            let path = format!("{kind}/{chapter_dir}/{id}.rs");
+            let file = fs::read_to_string(dir.join(&path)).unwrap();
+            assert!(file.starts_with(&format!("// {}\n", exercise.title())), "{path}");
+            assert!(file.contains("\nfn main() {"), "{path} has no `main`");
+            for test in exercise.tests() {
+                let header = format!("    #[test]\n    fn {}() {{\n", test.name);
+                assert!(file.contains(&header), "{path} lacks the test `{}`", test.name);
+            }
+            for leftover in ["feature = \"solutions\"", "crate::", "StaticExercise", "EXERCISE: "] {
+                assert!(!file.contains(leftover), "{path} still holds `{leftover}`");
+            }
+            let region = format!("// BEGIN EXERCISE\n{scaffold}// END EXERCISE\n");
+            if kind == "exercises" {
+                assert!(file.contains(&region), "{path} does not hold the scaffold");
+            } else {
+                assert!(!file.contains("EXERCISE\n"), "{path} holds exercise markers");
+                assert!(file.contains("// BEGIN SOLUTION\n"), "{path} holds no solution");
+            }
+        }
+    }
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,25 @@
+//! Checks the message catalogs against the lessons they translate.
+
+use learning_cell::lessons::{self, Block};
+use learning_cell::locale;
+
+#[test]
+fn every_paragraph_is_translated() {
+    let pages: Vec<_> =
+        lessons::all().into_iter().map(|lesson| (lesson.id(), lesson.page())).collect();
+    for code in locale::available().into_iter().skip(1) {
+        locale::set_locale(code).unwrap();
+        for (id, page) in &pages {
+            for block in &page.blocks {
+                let Block::Text(prose) = block else { continue };
+                for paragraph in prose.split("\n\n") {
+                    assert_ne!(
+                        locale::translate(paragraph),
+                        paragraph,
+                        "`{code}` has no translation of this paragraph of `{id}`"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Keeps the shipped `exercises.toml` in sync with the compiled-in exercises.
+
+use learning_cell::manifest::{Manifest, Registry};
+use learning_cell::{curriculum, exercises};
+
+#[test]
+fn shipped_manifest_describes_the_builtin_course() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/exercises.toml");
+    let shipped = Manifest::load(path).unwrap();
+    let builtin = Registry::builtin().to_manifest();
+    assert!(shipped == builtin, "exercises.toml is out of date, regenerate it from the registry");
+}
+
+#[test]
+fn installed_manifest_drives_the_course() {
+    let manifest = Manifest::parse(
+        r#"
+        [[exercise]]
+        id = "fix_borrow_panic"
+
+        [[exercise]]
+        id = "cell_intro"
+        prerequisites = ["fix_borrow_panic"]
+        "#,
+    )
+    .unwrap();
+    Registry::from_manifest(&manifest).unwrap().install();
+
+    let ids: Vec<_> = exercises::all().iter().map(|exercise| exercise.id()).collect();
+    assert_eq!(ids, ["fix_borrow_panic", "cell_intro"]);
+    // `cell_intro` waits for its prerequisite, even though its chapter comes first.
+    let next = curriculum::next(|id| id == "cell");
+    assert_ne!(next.map(|item| item.id()), Some("cell_intro"));
+    let next = curriculum::next(|id| ["cell", "fix_borrow_panic"].contains(&id));
+    assert_eq!(next.unwrap().id(), "cell_intro");
+    assert_eq!(exercises::compiled().len(), Registry::builtin().entries().len());
+}
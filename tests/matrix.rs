@@ -0,0 +1,124 @@
+//! Keeps the method matrix honest: every operation a row claims is called here on the real type,
+//! and every operation called here is claimed by the row.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+
+use learning_cell::comparison;
+use learning_cell::matrix::{self, Operation};
+
+fn send<T: Send>(_: &T) {}
+fn sync<T: Sync>(_: &T) {}
+
+/// Performs `operation` on `Cell<i32>`; false if it has no such operation.
+fn cell(operation: Operation) -> bool {
+    let mut cell = Cell::new(1);
+    match operation {
+        Operation::Get => assert_eq!(cell.get(), 1),
+        Operation::Set => cell.set(2),
+        Operation::Replace => assert_eq!(cell.replace(2), 1),
+        Operation::Take => assert_eq!(cell.take(), 1),
+        Operation::GetMut => *cell.get_mut() = 2,
+        Operation::Send => send(&cell),
+        _ => return false,
+    }
+    true
+}
+
+/// Performs `operation` on `RefCell<i32>`; false if it has no such operation.
+fn refcell(operation: Operation) -> bool {
+    let mut cell = RefCell::new(1);
+    match operation {
+        Operation::Replace => assert_eq!(cell.replace(2), 1),
+        Operation::Take => assert_eq!(cell.take(), 1),
+        Operation::GetMut => *cell.get_mut() = 2,
+        Operation::Borrow => assert_eq!(*cell.borrow(), 1),
+        Operation::BorrowMut => *cell.borrow_mut() = 2,
+        Operation::TryBorrow => assert_eq!(*cell.try_borrow().unwrap(), 1),
+        Operation::TryBorrowMut => *cell.try_borrow_mut().unwrap() = 2,
+        Operation::Send => send(&cell),
+        _ => return false,
+    }
+    true
+}
+
+/// Performs `operation` on `Mutex<i32>`; false if it has no such operation.
+fn mutex(operation: Operation) -> bool {
+    let mut mutex = Mutex::new(1);
+    match operation {
+        Operation::GetMut => *mutex.get_mut().unwrap() = 2,
+        Operation::Lock => *mutex.lock().unwrap() = 2,
+        Operation::TryLock => *mutex.try_lock().unwrap() = 2,
+        Operation::Send => send(&mutex),
+        Operation::Sync => sync(&mutex),
+        _ => return false,
+    }
+    true
+}
+
+/// Performs `operation` on `RwLock<i32>`; false if it has no such operation.
+fn rwlock(operation: Operation) -> bool {
+    let mut lock = RwLock::new(1);
+    match operation {
+        Operation::GetMut => *lock.get_mut().unwrap() = 2,
+        Operation::Read => assert_eq!(*lock.read().unwrap(), 1),
+        Operation::Write => *lock.write().unwrap() = 2,
+        Operation::TryRead => assert_eq!(*lock.try_read().unwrap(), 1),
+        Operation::TryWrite => *lock.try_write().unwrap() = 2,
+        Operation::Send => send(&lock),
+        Operation::Sync => sync(&lock),
+        _ => return false,
+    }
+    true
+}
+
+/// Performs `operation` on `Rc<i32>`; false if it has no such operation.
+fn rc(operation: Operation) -> bool {
+    let mut rc = Rc::new(1);
+    match operation {
+        Operation::GetMut => *Rc::get_mut(&mut rc).unwrap() = 2,
+        _ => return false,
+    }
+    true
+}
+
+/// Performs `operation` on `Arc<i32>`; false if it has no such operation.
+fn arc(operation: Operation) -> bool {
+    let mut arc = Arc::new(1);
+    match operation {
+        Operation::GetMut => *Arc::get_mut(&mut arc).unwrap() = 2,
+        Operation::Send => send(&arc),
+        Operation::Sync => sync(&arc),
+        _ => return false,
+    }
+    true
+}
+
+#[test]
+fn every_claimed_operation_can_be_performed() {
+    let rows = [
+        (matrix::CELL, cell as fn(Operation) -> bool),
+        (matrix::REFCELL, refcell),
+        (matrix::MUTEX, mutex),
+        (matrix::RWLOCK, rwlock),
+        (matrix::RC, rc),
+        (matrix::ARC, arc),
+    ];
+    assert_eq!(rows.map(|(row, _)| row), matrix::MATRIX);
+    for (row, perform) in rows {
+        for operation in Operation::ALL {
+            let claimed = row.supports(operation);
+            assert_eq!(perform(operation), claimed, "{}: {operation}", row.name);
+        }
+    }
+}
+
+#[test]
+fn thread_safety_matches_the_comparison_table() {
+    for row in matrix::MATRIX {
+        let info = comparison::lookup(row.name).unwrap();
+        assert_eq!(row.get(Operation::Send), info.send, "{} Send", row.name);
+        assert_eq!(row.get(Operation::Sync), info.sync, "{} Sync", row.name);
+    }
+}
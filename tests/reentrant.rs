@@ -0,0 +1,18 @@
+//! `NonReentrantRefCell` tells re-entrant borrows apart from borrows left behind by other cells.
+
+use std::mem;
+
+use learning_cell::impls::{NonReentrantError, NonReentrantRefCell};
+
+#[test]
+fn a_forgotten_guard_only_blocks_its_own_cell() {
+    let mut slot = NonReentrantRefCell::new(1);
+    mem::forget(slot.borrow_mut());
+    let err = slot.try_borrow_mut().unwrap_err();
+    assert!(matches!(err, NonReentrantError::Reentrant(_)), "{err:?}");
+
+    // A new cell in the same place, so at the address the forgotten borrow was recorded for.
+    slot = NonReentrantRefCell::new(2);
+    *slot.borrow_mut() += 1;
+    assert_eq!(*slot.borrow(), 3);
+}
@@ -0,0 +1,47 @@
+//! Runs real snippets through the scratch crate of the runner.
+
+use learning_cell::runner::{Outcome, Runner};
+
+#[test]
+fn reports_what_happened_to_each_snippet() {
+    let dir =
+        std::env::temp_dir().join(format!("learning_cell-runner-test-{}", std::process::id()));
+    let runner = Runner::new(&dir);
+
+    let outcome = runner.run("let cell = std::cell::Cell::new(1); println!(\"{}\", cell.get());");
+    assert_eq!(outcome.unwrap(), Outcome::Ran { stdout: "1\n".to_string() });
+
+    match runner.run("let x = 1; x = 2;").unwrap() {
+        Outcome::CompileError(message) => assert!(message.contains("E0384"), "{message}"),
+        other => panic!("expected a compile error, got {other:?}"),
+    }
+
+    let snippet = "let cell = std::cell::RefCell::new(1);
+        let _shared = cell.borrow();
+        let _exclusive = cell.borrow_mut();";
+    match runner.run(snippet).unwrap() {
+        Outcome::Panicked(message) => assert!(message.contains("already borrowed"), "{message}"),
+        other => panic!("expected a panic, got {other:?}"),
+    }
+
+    let uses_crate = "use learning_cell::curriculum::Chapter;
+        fn main() { print!(\"{}\", Chapter::RefCell.id()); }";
+    assert_eq!(runner.run(uses_crate).unwrap(), Outcome::Ran { stdout: "refcell".to_string() });
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn temporary_runners_do_not_share_their_snippets() {
+    let threads: Vec<_> = (0..2)
+        .map(|n| {
+            std::thread::spawn(move || {
+                let runner = Runner::in_temp_dir();
+                runner.run(&format!("print!(\"{n}\");")).unwrap()
+            })
+        })
+        .collect();
+    for (n, thread) in threads.into_iter().enumerate() {
+        assert_eq!(thread.join().unwrap(), Outcome::Ran { stdout: n.to_string() });
+    }
+}
@@ -0,0 +1,23 @@
+//! The shipped scaffolds are the code between the exercise markers of the exercise files, so
+//! `learning_cell reset` gives back what the repository holds.
+
+use learning_cell::exercises;
+
+/// The code between the `// BEGIN EXERCISE` and `// END EXERCISE` lines of `source`.
+fn marker_region(source: &str) -> &str {
+    let begin = "// BEGIN EXERCISE\n";
+    let start = source.find(begin).expect("no `// BEGIN EXERCISE`") + begin.len();
+    let end = start + source[start..].find("// END EXERCISE").expect("no `// END EXERCISE`");
+    assert!(!source[end..].contains(begin), "more than one exercise region");
+    &source[start..end]
+}
+
+#[test]
+fn scaffolds_match_the_exercise_files() {
+    for exercise in exercises::all() {
+        let id = exercise.id();
+        let source = exercises::source(id).unwrap();
+        let scaffold = exercises::scaffold(id).unwrap();
+        assert!(scaffold == marker_region(source), "the scaffold of `{id}` is out of date");
+    }
+}
@@ -0,0 +1,60 @@
+//! Every section the course sends the learner to exists: the `see "..."` of the hidden tests'
+//! checks and the sections of the quiz questions name anchors of [`search::sections`].
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use learning_cell::{quiz, search};
+
+fn anchors() -> HashSet<&'static str> {
+    search::sections().iter().map(|section| section.anchor.as_str()).collect()
+}
+
+/// The sections named by `see "..."` in the code of `source`, leaving out comments.
+fn see_targets(source: &str) -> Vec<&str> {
+    source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .flat_map(|line| line.split("see \"").skip(1))
+        .filter_map(|rest| rest.split('"').next())
+        .collect()
+}
+
+#[test]
+fn every_check_points_at_a_section() {
+    let anchors = anchors();
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("exercises");
+    let mut missing = Vec::new();
+    let mut checked = 0;
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "rs") {
+            continue;
+        }
+        let source = fs::read_to_string(&path).unwrap();
+        for target in see_targets(&source) {
+            checked += 1;
+            if !anchors.contains(target) {
+                missing.push(format!("{}: `{target}`", path.display()));
+            }
+        }
+    }
+    assert!(checked > 0, "found no `see` to check");
+    assert!(missing.is_empty(), "no such section:\n{}", missing.join("\n"));
+}
+
+#[test]
+fn every_quiz_question_points_at_a_section() {
+    let anchors = anchors();
+    // One variant of every template, besides the fixed questions of the bank.
+    let generated = quiz::generate(7, quiz::templates().len(), None);
+    for question in quiz::bank().iter().chain(generated.questions()) {
+        assert!(
+            anchors.contains(question.section),
+            "{}: no such section `{}`",
+            question.id,
+            question.section
+        );
+    }
+}
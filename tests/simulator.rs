@@ -0,0 +1,17 @@
+//! Differential tests of the borrow simulator against `std::cell::RefCell`.
+
+use learning_cell::simulator::{self, Op};
+use learning_cell::util::Rng;
+
+#[test]
+fn agrees_with_std_on_random_operations() {
+    let mut rng = Rng::new(0x5EED);
+    for _ in 0..500 {
+        let length = rng.below(40);
+        let bytes: Vec<u8> = (0..length).map(|_| rng.below(40) as u8).collect();
+        let ops = Op::decode(&bytes);
+        if let Err(divergence) = simulator::compare_with_std(&ops) {
+            panic!("{divergence}\noperations: {ops:?}");
+        }
+    }
+}
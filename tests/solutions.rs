@@ -0,0 +1,12 @@
+//! Runs every exercise's hidden tests against its reference solution.
+#![cfg(feature = "solutions")]
+
+use learning_cell::exercises;
+
+#[test]
+fn reference_solutions_pass_hidden_tests() {
+    for exercise in exercises::all() {
+        let report = exercise.run();
+        assert!(report.passed(), "{report}");
+    }
+}
@@ -0,0 +1,28 @@
+//! Guards of traced cells kept in thread locals are dropped while the thread exits, after the
+//! crate's own thread locals may already be gone.
+
+use std::cell::{Ref, RefCell};
+use std::thread;
+
+use learning_cell::diagnostics;
+use learning_cell::impls::{Traced, TracedRefCell};
+
+thread_local! {
+    static GUARD: RefCell<Option<Traced<Ref<'static, i32>>>> = const { RefCell::new(None) };
+}
+
+#[test]
+fn guards_outliving_the_thread_locals_drop_quietly() {
+    thread::spawn(|| {
+        // Touch `GUARD` first: thread locals are usually destroyed in reverse order of first use,
+        // so it is dropped after the ones the borrow below sets up.
+        GUARD.with(|_| {});
+        let cell: &'static TracedRefCell<i32> = Box::leak(Box::new(TracedRefCell::new(1)));
+        let ((), events) = diagnostics::record(|| {
+            GUARD.with(|guard| *guard.borrow_mut() = Some(cell.borrow()));
+        });
+        assert_eq!(events.len(), 1);
+    })
+    .join()
+    .unwrap();
+}
@@ -0,0 +1,68 @@
+//! The borrows of the traced cells, as a `tracing` subscriber sees them.
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use learning_cell::impls::TracedRefCell;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Writes down span openings, events and span closings, in order.
+#[derive(Clone, Default)]
+struct Log(Arc<Mutex<Vec<String>>>);
+
+struct Message<'a>(&'a mut String);
+
+impl Visit for Message<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0.push_str(&format!("{value:?}"));
+        }
+    }
+}
+
+impl Subscriber for Log {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut log = self.0.lock().unwrap();
+        log.push(format!("open {}", span.metadata().name()));
+        Id::from_u64(log.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut message = String::new();
+        event.record(&mut Message(&mut message));
+        let level = event.metadata().level();
+        self.0.lock().unwrap().push(format!("{level} {message}"));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+
+    fn try_close(&self, _: Id) -> bool {
+        self.0.lock().unwrap().push("close".to_string());
+        true
+    }
+}
+
+#[test]
+fn borrows_are_spans_with_events() {
+    let log = Log::default();
+    tracing::subscriber::with_default(log.clone(), || {
+        let cell = TracedRefCell::with_label(1, "counter");
+        let guard = cell.borrow_mut();
+        assert!(cell.try_borrow().is_err());
+        drop(guard);
+    });
+    let log = log.0.lock().unwrap();
+    assert_eq!(*log, ["open borrow", "TRACE acquire", "DEBUG conflict", "TRACE release", "close"]);
+}
@@ -0,0 +1,64 @@
+//! The lessons' `compile_fail` examples, with the compiler's diagnostics pinned by trybuild.
+//!
+//! For every [snippet](learning_cell::snippets), `tests/ui/<id>.rs` is the program the runner
+//! builds and `tests/ui/<id>.stderr` what the compiler reports for it. Run with
+//! `TRYBUILD=overwrite` to write the files anew, for instance after a compiler update, and check
+//! the diff still makes the lesson's point.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use learning_cell::{runner, snippets};
+
+fn ui_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("ui")
+}
+
+fn overwrite() -> bool {
+    std::env::var_os("TRYBUILD").is_some_and(|mode| mode == "overwrite")
+}
+
+/// Checks `path` holds `expected`, or writes it there in overwrite mode. Returns a description
+/// of the mismatch, if any.
+fn compare(path: &Path, expected: &str) -> Option<String> {
+    if overwrite() {
+        fs::write(path, expected).unwrap();
+        return None;
+    }
+    match fs::read_to_string(path) {
+        Ok(actual) if actual == expected => None,
+        Ok(actual) => Some(format!(
+            "{} is out of date, expected:\n{expected}\ngot:\n{actual}",
+            path.display()
+        )),
+        Err(_) => Some(format!("{} is missing, expected:\n{expected}", path.display())),
+    }
+}
+
+#[test]
+fn every_compile_fail_example_has_a_ui_test() {
+    assert_eq!(snippets::missing(), Vec::<String>::new());
+    let mut mismatches = Vec::new();
+    for snippet in snippets::ALL {
+        let path = ui_dir().join(format!("{}.rs", snippet.id));
+        mismatches.extend(compare(&path, &runner::program(snippet.code)));
+        let stderr = fs::read_to_string(path.with_extension("stderr")).unwrap_or_default();
+        if !overwrite() && !stderr.contains(snippet.expected_error) {
+            mismatches
+                .push(format!("`{}` should fail with {}", snippet.id, snippet.expected_error));
+        }
+    }
+    for entry in fs::read_dir(ui_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        let id = path.file_stem().unwrap().to_str().unwrap();
+        if snippets::get(id).is_none() {
+            mismatches.push(format!("{} belongs to no snippet", path.display()));
+        }
+    }
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+#[test]
+fn diagnostics_match_the_pinned_output() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
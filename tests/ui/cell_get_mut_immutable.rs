@@ -0,0 +1,8 @@
+#![allow(unused)]
+fn main() {
+use std::cell::Cell;
+let a = learning_cell::Cell::Immutable::default();
+// Error: cannot borrow `a.special` as mutable, as `a` is not declared as mutable
+let _ = a.special.get_mut();
+
+}
@@ -0,0 +1,10 @@
+#![allow(unused)]
+fn main() {
+use std::cell::Cell;
+let a = learning_cell::Cell::Immutable::default();
+// OK
+let _ = a.special.get();
+// Error: trait bound `Copy` is not satisfied for String
+let _ = a.special_nocopy.get();
+
+}
@@ -0,0 +1,9 @@
+#![allow(unused)]
+fn main() {
+use std::cell::Cell;
+let a = learning_cell::Cell::Immutable::default();
+// Error: cannot mutate immutable variable `a`
+a.regular += 2;
+a.special = Cell::new(24);
+
+}
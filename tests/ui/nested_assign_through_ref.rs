@@ -0,0 +1,7 @@
+#![allow(unused)]
+fn main() {
+let outer = learning_cell::Nested::Outer::default();
+// Error: cannot assign to data in dereference of `Ref<'_, Immutable>`
+outer.in_refcell.borrow().regular += 1;
+
+}
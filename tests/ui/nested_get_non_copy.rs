@@ -0,0 +1,7 @@
+#![allow(unused)]
+fn main() {
+let outer = learning_cell::Nested::Outer::default();
+// Error: the method `get` exists for `Cell<Immutable>`, but its trait bounds were not satisfied
+let inner = outer.in_cell.get();
+
+}
@@ -0,0 +1,9 @@
+#![allow(unused)]
+fn main() {
+use std::cell::RefCell;
+let a = learning_cell::RefCell::Immutable::default();
+// Error: cannot mutate immutable variable `a`
+a.regular += 2;
+a.special = RefCell::new(24);
+
+}